@@ -1,20 +1,23 @@
-use super::{AccountingState, Item, Mode};
-use crate::config::Config;
-use crate::data::currency::{CurrencyValue, VatCalculationResult};
-use crate::data::{InvoiceType, Vat};
-use crate::db::get_date_range_for_settings;
+use super::{
+    AccountingState, Item, Mode, has_valid_file_type, invalid_file_type_msg,
+    is_item_date_out_of_range, selected_date_range,
+};
+use crate::config::{self, Config};
+use crate::data::currency::{CurrencyValue, VatCalculationResult, parse_number};
+use crate::data::{AccountingItem, InvoiceType};
 use crate::messages::Messages;
 use crate::ui::dialog::{self, Dialog, DialogResponse};
-use crate::util::files::{PATH_FOR_FILES, copy_file_and_rename};
+use crate::util::files::{
+    PATH_FOR_FILES, copy_file_and_rename, files_folder_for, remember_recent_folder,
+};
 use crate::util::validation::Field;
-use crate::util::{self, Colors, VALID_FILETYPES};
-use crate::{AppContext, DATE_FORMAT, Event, GuiEvent, State, ui};
+use crate::util::{self, Colors};
+use crate::{AppContext, Event, GuiEvent, State, date_format, ui};
 use eframe::egui::{Align, Context, Grid, Id, RichText, SelectableLabel, TextEdit, Ui};
 use egui_extras_datepicker_fork::DatePickerButton;
+use egui_file::FileDialog;
 use log::info;
-use rust_decimal::Decimal;
-use std::path::Path;
-use std::str::FromStr;
+use std::path::{Path, PathBuf};
 
 fn render_field_errors(field: &Field, state: &AccountingState, ui: &mut Ui) {
     if let Some(errors) = state.item.validation.get_errors(field) {
@@ -36,10 +39,19 @@ fn render_field_warnings(field: &Field, state: &AccountingState, ui: &mut Ui) {
     }
 }
 
+// an open-file dialog for the supported file types, starting at the given path
+fn open_file_dialog(startpoint: Option<PathBuf>) -> FileDialog {
+    let filter = Box::new(|path: &Path| -> bool { has_valid_file_type(path) });
+    let mut dialog = ui::get_localized_open_file_dialog(startpoint, Messages::ChooseFile.msg())
+        .show_files_filter(filter);
+    dialog.open();
+    dialog
+}
+
 pub(super) fn build(
     ctx: &Context,
     state: &mut State,
-    config: &Config,
+    config: &mut Config,
     app_context: &AppContext,
     ui: &mut Ui,
 ) {
@@ -71,6 +83,7 @@ pub(super) fn build(
 
                         if resp.clicked() {
                             accounting_state.item.invoice_type = *invoice_type;
+                            accounting_state.item.invoice_type_changed = true;
                         }
                     });
             });
@@ -84,7 +97,15 @@ pub(super) fn build(
 
             ui.label(Messages::Date);
             ui.horizontal(|ui| {
-                ui.text_edit_singleline(&mut accounting_state.item.date_field);
+                let out_of_range = is_item_date_out_of_range(accounting_state);
+                let mut date_edit = TextEdit::singleline(&mut accounting_state.item.date_field);
+                if out_of_range {
+                    date_edit = date_edit.background_color(Colors::WarningField.col());
+                }
+                let date_field_response = ui.add(date_edit);
+                if out_of_range {
+                    date_field_response.on_hover_text(Messages::DateNotInSelectedDateRange);
+                }
                 let date_response = ui.add(
                     DatePickerButton::new(&mut accounting_state.item.date)
                         .calendar_week(false)
@@ -97,7 +118,7 @@ pub(super) fn build(
                 );
                 if date_response.changed() {
                     accounting_state.item.date_field =
-                        accounting_state.item.date.format(DATE_FORMAT).to_string();
+                        accounting_state.item.date.format(date_format()).to_string();
                     accounting_state
                         .item
                         .validation
@@ -143,16 +164,33 @@ pub(super) fn build(
             ui.end_row();
 
             ui.label(Messages::Category);
-            let cat_response = accounting_state.item.category_autosuggest.ui(
-                ui,
-                &mut accounting_state.item.category,
-                &accounting_state.categories,
-            );
-            if cat_response.changed() {
+            if !accounting_state.item.splits.is_empty() {
+                ui.label(Messages::Splits);
+            } else if accounting_state
+                .item
+                .category_autosuggest
+                .ui(
+                    ui,
+                    &mut accounting_state.item.category,
+                    &accounting_state.categories,
+                )
+                .changed()
+            {
                 accounting_state
                     .item
                     .validation
                     .clear_for_field(&Field::Category);
+                if config.category_defaults
+                    && accounting_state
+                        .categories
+                        .contains(&accounting_state.item.category)
+                {
+                    util::send_event_and_request_repaint(
+                        ctx,
+                        &app_context.background_event_sender,
+                        Event::FetchCategoryDefaults(accounting_state.item.category.clone()),
+                    );
+                }
             }
             render_field_warnings(&Field::Category, accounting_state, ui);
             render_field_errors(&Field::Category, accounting_state, ui);
@@ -182,29 +220,19 @@ pub(super) fn build(
             ui.end_row();
 
             ui.label(Messages::Vat);
-            ui.horizontal(|ui| {
-                [Vat::Zero, Vat::Ten, Vat::Twenty].iter().for_each(|vat| {
-                    if ui
-                        .add(SelectableLabel::new(
-                            accounting_state.item.vat == *vat,
-                            vat.name(),
-                        ))
-                        .clicked()
-                    {
-                        accounting_state.item.vat = *vat;
-                    }
-                });
-            });
+            if ui::vat_combo_box(ui, "item_vat", &mut accounting_state.item.vat) {
+                accounting_state.item.vat_changed = true;
+            }
             ui.end_row();
 
-            let (mut tax, mut gross) =
-                if let Ok(net) = Decimal::from_str(&accounting_state.item.net) {
-                    let VatCalculationResult { tax, gross } = CurrencyValue::new_from_decimal(net)
-                        .calculate_vat(accounting_state.item.vat);
-                    (tax.to_value_string(), gross.to_value_string())
-                } else {
-                    (String::from("0.00"), String::from("0.00"))
-                };
+            let (mut tax, mut gross) = if let Some(net) = parse_number(&accounting_state.item.net) {
+                let VatCalculationResult { tax, gross } =
+                    CurrencyValue::new_from_decimal(net).calculate_vat(accounting_state.item.vat);
+                (tax.to_value_string(), gross.to_value_string())
+            } else {
+                let zero = CurrencyValue::zero().to_value_string();
+                (zero.clone(), zero)
+            };
 
             ui.label(Messages::Tax);
             ui.horizontal(|ui| {
@@ -226,40 +254,55 @@ pub(super) fn build(
             });
             ui.end_row();
 
+            // existing items are edited on their own
+            if accounting_state.mode == Mode::Add {
+                ui.label(Messages::Splits);
+                build_splits(accounting_state, ui);
+                render_field_errors(&Field::Splits, accounting_state, ui);
+                ui.end_row();
+            }
+
+            if !state.recent_folders.is_empty() {
+                ui.label(Messages::RecentFolders);
+                ui.horizontal_wrapped(|ui| {
+                    state.recent_folders.iter().for_each(|folder| {
+                        let name = folder
+                            .file_name()
+                            .map_or_else(|| folder.to_string_lossy(), |n| n.to_string_lossy());
+                        if ui
+                            .button(format!("📁 {name}"))
+                            .on_hover_text(folder.to_string_lossy())
+                            .clicked()
+                        {
+                            accounting_state.item.open_file_dialog =
+                                Some(open_file_dialog(Some(folder.to_owned())));
+                        }
+                    });
+                });
+                ui.end_row();
+            }
+
             ui.label(Messages::File);
             ui.horizontal(|ui| {
                 ui.text_edit_singleline(&mut accounting_state.item.file.to_str().map_or("", |v| v));
                 let file_button_response = ui.button(Messages::Open);
                 if file_button_response.clicked() {
-                    let filter = Box::new({
-                        move |path: &Path| -> bool {
-                            if let Some(ext) = path.extension() {
-                                for t in VALID_FILETYPES.iter() {
-                                    if *t == ext {
-                                        return true;
-                                    }
-                                }
-                            }
-                            false
-                        }
-                    });
-                    let mut dialog = ui::get_localized_open_file_dialog(
+                    accounting_state.item.open_file_dialog = Some(open_file_dialog(
                         if accounting_state.item.file.as_os_str().is_empty() {
                             state.file_picker_startpoint.clone()
                         } else {
                             Some(accounting_state.item.file.to_owned())
                         },
-                        Messages::ChooseFile.msg(),
-                    )
-                    .show_files_filter(filter);
-                    dialog.open();
-                    accounting_state.item.open_file_dialog = Some(dialog);
+                    ));
                 }
 
                 if let Some(dialog) = &mut accounting_state.item.open_file_dialog {
                     if dialog.show(ctx).selected() {
                         if let Some(file) = dialog.path() {
                             state.file_picker_startpoint = Some(file.to_path_buf());
+                            if let Some(folder) = file.parent() {
+                                remember_recent_folder(&mut state.recent_folders, folder);
+                            }
                             accounting_state.item.file = file.to_path_buf();
                         }
                         accounting_state
@@ -268,10 +311,38 @@ pub(super) fn build(
                             .clear_for_field(&Field::File);
                     }
                 }
+                ui.weak(Messages::DropFileHint);
             });
+            // only the first one, if several files are dropped at once
+            if let Some(file) =
+                ctx.input(|i| i.raw.dropped_files.iter().find_map(|f| f.path.clone()))
+            {
+                let validation = &mut accounting_state.item.validation;
+                validation.clear_for_field(&Field::File);
+                if has_valid_file_type(&file) {
+                    if let Some(folder) = file.parent() {
+                        remember_recent_folder(&mut state.recent_folders, folder);
+                    }
+                    state.file_picker_startpoint = Some(file.clone());
+                    accounting_state.item.file = file;
+                } else {
+                    validation.add_error(Field::File, invalid_file_type_msg());
+                }
+            }
             render_field_warnings(&Field::File, accounting_state, ui);
             render_field_errors(&Field::File, accounting_state, ui);
             ui.end_row();
+
+            ui.label(Messages::Note);
+            ui.add(TextEdit::multiline(&mut accounting_state.item.note).desired_rows(2));
+            ui.end_row();
+
+            ui.label(Messages::Private);
+            ui.checkbox(
+                &mut accounting_state.item.excluded,
+                Messages::ExcludeFromSummary,
+            );
+            ui.end_row();
         });
 
         ui.horizontal(|ui| {
@@ -287,7 +358,7 @@ pub(super) fn build(
 
                 if accounting_state.item.validation.is_ok() {
                     save_item(accounting_state, app_context, ctx, config);
-                    accounting_state.item = Item::new();
+                    accounting_state.item = next_item(accounting_state, config);
                 } else if accounting_state.item.validation.has_warnings()
                     && !accounting_state.item.validation.has_errors()
                 {
@@ -302,13 +373,24 @@ pub(super) fn build(
                     ));
                 }
             }
+            if accounting_state.mode == Mode::Add
+                && ui
+                    .checkbox(
+                        &mut config.keep_item_fields,
+                        Messages::KeepDateCompanyCategory,
+                    )
+                    .on_hover_text(Messages::KeepDateCompanyCategoryHint)
+                    .changed()
+            {
+                config::save_config_or_notify(config, &app_context.gui_event_sender);
+            }
         });
         if let Some(ref dialog) = accounting_state.item.save_dialog {
             match dialog::render_dialog(ctx, dialog) {
                 DialogResponse::Ok => {
                     save_item(accounting_state, app_context, ctx, config);
                     accounting_state.item.save_dialog = None;
-                    accounting_state.item = Item::new();
+                    accounting_state.item = next_item(accounting_state, config);
                     info!("save item pressed")
                 }
                 DialogResponse::Cancel => {
@@ -321,21 +403,83 @@ pub(super) fn build(
     }
 }
 
+// keeps date, company and category for the next item, when adding several similar ones
+fn next_item(accounting_state: &AccountingState, config: &Config) -> Item {
+    accounting_state
+        .item
+        .next(config.keep_item_fields && accounting_state.mode == Mode::Add)
+}
+
+fn build_splits(accounting_state: &mut AccountingState, ui: &mut Ui) {
+    ui.vertical(|ui| {
+        let mut to_remove = None;
+        for (idx, split) in accounting_state.item.splits.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                split.category_autosuggest.ui(
+                    ui,
+                    &mut split.category,
+                    &accounting_state.categories,
+                );
+                ui.add(
+                    TextEdit::singleline(&mut split.net)
+                        .desired_width(80.0)
+                        .horizontal_align(Align::Max),
+                );
+                ui.label("€");
+                if ui.button(Messages::Delete).clicked() {
+                    to_remove = Some(idx);
+                }
+            });
+        }
+        if let Some(idx) = to_remove {
+            accounting_state.item.splits.remove(idx);
+            accounting_state
+                .item
+                .validation
+                .clear_for_field(&Field::Splits);
+        }
+        ui.horizontal(|ui| {
+            if ui.button(Messages::Split).clicked() {
+                accounting_state.item.add_split();
+                accounting_state
+                    .item
+                    .validation
+                    .clear_for_field(&Field::Splits);
+            }
+            if !accounting_state.item.splits.is_empty() {
+                if let Some(rest) = accounting_state.item.unsplit_net() {
+                    ui.label(format!(
+                        "{}: {}",
+                        Messages::NotSplitYet,
+                        CurrencyValue::new_from_decimal(rest).to_display_string()
+                    ));
+                }
+            }
+        });
+    });
+}
+
 fn save_item(
     accounting_state: &mut AccountingState,
     app_context: &AppContext,
     ctx: &Context,
     config: &Config,
 ) {
+    if !accounting_state.item.splits.is_empty() {
+        save_split_items(accounting_state, app_context, ctx, config);
+        return;
+    }
     let id = accounting_state.item.id;
+    // the date field is what's saved, it was validated before
+    let date = AccountingItem::from(&accounting_state.item).date;
+    let files_root = config
+        .data_folder
+        .as_ref()
+        .expect("data folder is set")
+        .join(PATH_FOR_FILES);
     match copy_file_and_rename(
         &id.to_string(),
-        config
-            .data_folder
-            .as_ref()
-            .expect("data folder is set")
-            .join(PATH_FOR_FILES)
-            .as_path(),
+        files_folder_for(&files_root, date, config.files_per_year).as_path(),
         &accounting_state.item.file,
     ) {
         Ok(new_path) => {
@@ -349,11 +493,7 @@ fn save_item(
                 &app_context.background_event_sender,
                 Event::SaveItem(
                     (&accounting_state.item).into(),
-                    get_date_range_for_settings(
-                        accounting_state.selected_year,
-                        accounting_state.selected_quarter,
-                        accounting_state.selected_month,
-                    ),
+                    selected_date_range(accounting_state),
                 ),
             )
         }
@@ -365,3 +505,36 @@ fn save_item(
         }
     }
 }
+
+// like recorded invoices, each split item gets its own copy of the file
+fn save_split_items(
+    accounting_state: &mut AccountingState,
+    app_context: &AppContext,
+    ctx: &Context,
+    config: &Config,
+) {
+    let files_root = config
+        .data_folder
+        .as_ref()
+        .expect("data folder is set")
+        .join(PATH_FOR_FILES);
+    let mut items = accounting_state.item.split_items();
+    for item in items.iter_mut() {
+        let files_folder = files_folder_for(&files_root, item.date, config.files_per_year);
+        match copy_file_and_rename(&item.id.to_string(), &files_folder, &item.file) {
+            Ok(new_path) => item.file = new_path,
+            Err(e) => {
+                util::send_gui_event(
+                    &app_context.gui_event_sender,
+                    GuiEvent::ShowErrorNotification(e.to_string()),
+                );
+                return;
+            }
+        }
+    }
+    util::send_event_and_request_repaint(
+        ctx,
+        &app_context.background_event_sender,
+        Event::SaveSplitItems(items, selected_date_range(accounting_state)),
+    );
+}
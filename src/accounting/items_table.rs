@@ -1,91 +1,313 @@
-use super::AccountingState;
+use super::{AccountingState, AmountColumns, NetEdit};
 use crate::{
-    accounting::{Item, Mode},
-    data::currency::VatCalculationResult,
-    db::{get_date_range_for_settings, DB},
+    accounting::{selected_date_range, Item, Mode},
+    config::{self, Config},
+    data::{
+        currency::{parse_number, CurrencyValue, VatCalculationResult},
+        AccountingItem, InvoiceType,
+    },
+    date_format,
+    db::{DateRange, DB},
     messages::Messages,
-    util, AppContext, Event, DATE_FORMAT,
+    ui::dialog::{self, Dialog, DialogResponse},
+    util::{self, Colors},
+    AppContext, Event, GuiEvent,
+};
+use eframe::egui::{
+    Align, Button, Color32, Context, Key, Layout, Modifiers, RichText, SelectableLabel, Sense,
+    Shape, TextEdit, Ui, Vec2,
 };
-use eframe::egui::{Align, Context, Layout, Ui};
 use egui_extras::{Column, TableBuilder};
 use log::info;
+use rust_decimal::Decimal;
+use std::time::Duration;
+use uuid::Uuid;
 
 const ROW_HEIGHT: f32 = 30.0;
+// how long an invalid inline edit is highlighted, in seconds
+const NET_EDIT_FAILED_DURATION: f64 = 1.0;
+const COLUMN_COUNT: usize = 14;
+const MIN_COLUMN_WIDTH: f32 = 20.0;
+const DATE_COLUMN: usize = 3;
+const NET_COLUMN: usize = 7;
+const VAT_COLUMN: usize = 8;
+const TAX_COLUMN: usize = 9;
+const GROSS_COLUMN: usize = 10;
+const LAST_COLUMN: usize = COLUMN_COUNT - 1;
+// name, company, category and the last column share the rest of the table's width, so their
+// widths aren't saved
+const REMAINDER_COLUMNS: [usize; 4] = [4, 5, 6, LAST_COLUMN];
+
+fn is_shown(amount_columns: AmountColumns, column: usize) -> bool {
+    match amount_columns {
+        AmountColumns::All => true,
+        AmountColumns::Net => ![VAT_COLUMN, TAX_COLUMN, GROSS_COLUMN].contains(&column),
+        AmountColumns::Gross => ![NET_COLUMN, VAT_COLUMN, TAX_COLUMN].contains(&column),
+    }
+}
+
+// the saved widths with the measured ones of the shown columns, except the remainder columns -
+// None, if the hidden columns were never measured
+fn merge_widths(saved_widths: &[f32], measured: &[(usize, f32)]) -> Option<Vec<f32>> {
+    let mut widths = if saved_widths.len() == COLUMN_COUNT {
+        saved_widths.to_vec()
+    } else if measured.len() == COLUMN_COUNT {
+        vec![0.0; COLUMN_COUNT]
+    } else {
+        return None;
+    };
+    measured
+        .iter()
+        .filter(|(column, _)| !REMAINDER_COLUMNS.contains(column))
+        .for_each(|&(column, width)| widths[column] = width);
+    Some(widths)
+}
+
+// whether a saved column, which is shown in both, got resized - ignores sub-pixel differences and
+// the remainder columns, which follow the window's width
+fn widths_differ(before: &[(usize, f32)], after: &[(usize, f32)]) -> bool {
+    after.iter().any(|(column, width)| {
+        !REMAINDER_COLUMNS.contains(column)
+            && before
+                .iter()
+                .any(|(c, w)| c == column && (w - width).abs() > 0.5)
+    })
+}
+
+// the default columns, or the widths the user resized them to
+fn columns(saved_widths: &[f32], amount_columns: AmountColumns) -> Vec<Column> {
+    let defaults = [
+        Column::exact(20.0),
+        Column::initial(60.0),
+        Column::initial(30.0),
+        Column::auto(),
+        Column::remainder(),
+        Column::remainder(),
+        Column::remainder(),
+        Column::initial(80.0),
+        Column::initial(30.0),
+        Column::initial(80.0),
+        Column::initial(80.0),
+        Column::initial(25.0),
+        Column::auto(),
+        Column::remainder(),
+    ];
+    // e.g. saved before a column was added
+    let saved_widths = (saved_widths.len() == COLUMN_COUNT).then_some(saved_widths);
+    defaults
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| is_shown(amount_columns, *idx))
+        .map(|(idx, column)| {
+            // the selection checkbox keeps its size, the last column fills the rest
+            if idx == 0 || idx == LAST_COLUMN {
+                return column;
+            }
+            saved_widths
+                .filter(|_| !REMAINDER_COLUMNS.contains(&idx))
+                .map_or(column, |widths| Column::initial(widths[idx]))
+                .at_least(MIN_COLUMN_WIDTH)
+                .clip(true)
+                .resizable(true)
+        })
+        .collect()
+}
 
 pub(super) fn build(
     ctx: &Context,
     state: &mut AccountingState,
+    config: &mut Config,
     app_context: &AppContext,
     ui: &mut Ui,
 ) {
+    let date_range = selected_date_range(state);
+    if state
+        .selected_accounting_sheet
+        .as_ref()
+        .is_some_and(|sheet| sheet.items.is_empty())
+    {
+        if state.items_fetched {
+            render_empty_state(ctx, state, date_range, app_context, ui);
+        }
+        return;
+    }
     if let Some(accounting_sheet) = &mut state.selected_accounting_sheet {
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut state.show_only_problems,
+                Messages::ShowOnlyProblemItems,
+            );
+            ui.separator();
+            render_net_filter(&mut state.net_filter_min, Messages::MinNet, ui);
+            render_net_filter(&mut state.net_filter_max, Messages::MaxNet, ui);
+            ui.separator();
+            // Ctrl+F (Cmd+F on macOS) jumps to the search, unless a dialog is open
+            let shortcut = state.delete_dialog.is_none()
+                && ui.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::F));
+            let button = ui
+                .button("🔍")
+                .on_hover_text(Messages::SearchShortcut.msg());
+            let search = ui.add(
+                TextEdit::singleline(&mut state.search)
+                    .hint_text(Messages::Search.msg())
+                    .desired_width(150.0),
+            );
+            if shortcut || button.clicked() {
+                ui.memory_mut(|m| m.request_focus(search.id));
+            }
+            ui.separator();
+            [
+                (AmountColumns::All, Messages::AllAmounts),
+                (AmountColumns::Net, Messages::Net),
+                (AmountColumns::Gross, Messages::Gross),
+            ]
+            .into_iter()
+            .for_each(|(amount_columns, text)| {
+                if ui
+                    .add(SelectableLabel::new(
+                        state.amount_columns == amount_columns,
+                        text.msg(),
+                    ))
+                    .clicked()
+                {
+                    state.amount_columns = amount_columns;
+                }
+            });
+        });
+        let amount_columns = state.amount_columns;
+        let net_min = parse_number(&state.net_filter_min);
+        let net_max = parse_number(&state.net_filter_max);
+        let search = state.search.trim().to_lowercase();
+        let visible_rows: Vec<usize> = accounting_sheet
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !state.show_only_problems || has_problems(item))
+            .filter(|(_, item)| is_net_in_range(item, net_min, net_max))
+            .filter(|(_, item)| matches_search(item, &search))
+            .map(|(idx, _)| idx)
+            .collect();
+        if state.selected_row.is_some_and(|r| r >= visible_rows.len()) {
+            state.selected_row = None;
+        }
+
+        // only navigate, if no other widget (e.g. a text field) has the keyboard
+        let mut scroll_to_row = None;
+        if !visible_rows.is_empty()
+            && state.delete_dialog.is_none()
+            && ui.memory(|m| m.focused().is_none())
+        {
+            let last_row = visible_rows.len() - 1;
+            let (up, down, enter, delete) = ui.input_mut(|i| {
+                (
+                    i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                    i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                    i.consume_key(Modifiers::NONE, Key::Enter),
+                    i.consume_key(Modifiers::NONE, Key::Delete),
+                )
+            });
+            if up {
+                state.selected_row =
+                    Some(state.selected_row.map_or(last_row, |r| r.saturating_sub(1)));
+                scroll_to_row = state.selected_row;
+            }
+            if down {
+                state.selected_row = Some(state.selected_row.map_or(0, |r| (r + 1).min(last_row)));
+                scroll_to_row = state.selected_row;
+            }
+            if let Some(selected) = state.selected_row {
+                let item = &accounting_sheet.items[visible_rows[selected]];
+                if enter {
+                    state.mode = Mode::Edit;
+                    state.item = Item::from(item);
+                }
+                if delete {
+                    state.delete_dialog = Some((
+                        DB::get_key_for_item(item),
+                        Dialog::new(
+                            Messages::ReallyDeleteItem.msg().to_owned(),
+                            Messages::Delete.msg(),
+                            Messages::Cancel.msg(),
+                        ),
+                    ));
+                }
+            }
+        }
+
+        let totals = Totals::of(visible_rows.iter().map(|&idx| &accounting_sheet.items[idx]));
+
+        // the row colors replace the stripes
         let table = TableBuilder::new(ui)
-            .striped(true)
+            .striped(!state.color_rows)
             .max_scroll_height(200.0)
             .min_scrolled_height(100.0)
             .auto_shrink(true)
             .cell_layout(Layout::left_to_right(Align::Center))
-            .column(Column::exact(60.0))
-            .column(Column::exact(30.0))
-            .column(Column::auto())
-            .column(Column::remainder().clip(true))
-            .column(Column::remainder().clip(true))
-            .column(Column::remainder().clip(true))
-            .column(Column::exact(80.0))
-            .column(Column::exact(30.0))
-            .column(Column::exact(80.0))
-            .column(Column::exact(80.0))
-            .column(Column::exact(25.0))
-            .column(Column::auto())
-            .column(Column::auto());
+            .sense(Sense::click());
+        let mut table = columns(&config.accounting_column_widths, amount_columns)
+            .into_iter()
+            .fold(table, |table, column| table.column(column));
+        if let Some(row) = scroll_to_row {
+            table = table.scroll_to_row(row, None);
+        }
 
+        // the current widths, to save them once the user is done resizing
+        let mut widths = Vec::with_capacity(COLUMN_COUNT);
         table
             .header(ROW_HEIGHT, |mut header| {
-                header.col(|ui| {
-                    ui.strong(Messages::InvoiceType);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::InvoiceNumber);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Date);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Name);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Company);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Category);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Net);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Vat);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Tax);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Gross);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::File);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Edit);
-                });
-                header.col(|ui| {
-                    ui.strong(Messages::Delete);
+                [
+                    ("", false),
+                    (Messages::InvoiceType.msg(), false),
+                    (Messages::InvoiceNumber.msg(), false),
+                    (Messages::Date.msg(), false),
+                    (Messages::Name.msg(), false),
+                    (Messages::Company.msg(), false),
+                    (Messages::Category.msg(), false),
+                    (Messages::Net.msg(), true),
+                    (Messages::Vat.msg(), true),
+                    (Messages::Tax.msg(), true),
+                    (Messages::Gross.msg(), true),
+                    (Messages::File.msg(), false),
+                    (Messages::Edit.msg(), false),
+                    (Messages::Delete.msg(), false),
+                ]
+                .into_iter()
+                .enumerate()
+                .filter(|(idx, _)| is_shown(amount_columns, *idx))
+                .for_each(|(idx, (title, numeric))| {
+                    header.col(|ui| {
+                        widths.push((idx, ui.max_rect().width()));
+                        // like the values below
+                        if numeric {
+                            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                                ui.strong(title);
+                            });
+                        } else {
+                            ui.strong(title);
+                        }
+                    });
                 });
             })
             .body(|body| {
-                body.rows(ROW_HEIGHT, accounting_sheet.items.len(), |mut row| {
-                    let row_index = row.index();
+                body.rows(ROW_HEIGHT, visible_rows.len(), |mut row| {
+                    row.set_selected(state.selected_row == Some(row.index()));
+                    let row_index = visible_rows[row.index()];
                     let invoice_number = row_index + 1;
                     let item = &accounting_sheet.items[row_index];
+                    // reserve a shape below the cell contents, which is filled once the row's
+                    // size is known
+                    let mut background = None;
+                    row.col(|ui| {
+                        background = Some((ui.painter().clone(), ui.painter().add(Shape::Noop)));
+                        let mut selected = state.selected_items.contains(&item.id);
+                        if ui.checkbox(&mut selected, "").changed() {
+                            if selected {
+                                state.selected_items.insert(item.id);
+                            } else {
+                                state.selected_items.remove(&item.id);
+                            }
+                        }
+                    });
                     row.col(|ui| {
                         let text = item.invoice_type.name();
                         ui.label(text);
@@ -95,11 +317,29 @@ pub(super) fn build(
                         ui.label(&text);
                     });
                     row.col(|ui| {
-                        let text = item.date.format(DATE_FORMAT).to_string();
+                        let text = item.date.format(date_format()).to_string();
                         ui.label(&text);
                     });
                     row.col(|ui| {
-                        ui.label(&item.name);
+                        let mut name = RichText::new(&item.name);
+                        if item.excluded {
+                            name = name.strikethrough();
+                        }
+                        if !item.note.is_empty() {
+                            name = name.underline();
+                        }
+                        let hover_text = [
+                            item.excluded.then(|| Messages::ExcludedFromSummary.msg()),
+                            (!item.note.is_empty()).then_some(item.note.as_str()),
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<&str>>()
+                        .join("\n");
+                        let response = ui.label(name);
+                        if !hover_text.is_empty() {
+                            response.on_hover_text(hover_text);
+                        }
                     });
                     row.col(|ui| {
                         ui.label(&item.company.0);
@@ -107,32 +347,48 @@ pub(super) fn build(
                     row.col(|ui| {
                         ui.label(&item.category.0);
                     });
-                    row.col(|ui| {
-                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                            let text = item.net.to_str();
-                            ui.label(text);
+                    if is_shown(amount_columns, NET_COLUMN) {
+                        row.col(|ui| {
+                            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                                render_net_edit(
+                                    ctx,
+                                    &mut state.net_edit,
+                                    &mut state.net_edit_failed,
+                                    item,
+                                    &date_range,
+                                    app_context,
+                                    ui,
+                                );
+                            });
                         });
-                    });
-                    row.col(|ui| {
-                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                            ui.label(item.vat);
+                    }
+                    if is_shown(amount_columns, VAT_COLUMN) {
+                        row.col(|ui| {
+                            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                                ui.label(item.vat);
+                            });
                         });
-                    });
+                    }
                     let VatCalculationResult { tax, gross } = &item.net.calculate_vat(item.vat);
-                    row.col(|ui| {
-                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                            ui.label(tax);
+                    if is_shown(amount_columns, TAX_COLUMN) {
+                        row.col(|ui| {
+                            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                                ui.label(tax.to_str_rounded(config.show_cents));
+                            });
                         });
-                    });
-                    row.col(|ui| {
-                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                            ui.label(gross);
+                    }
+                    if is_shown(amount_columns, GROSS_COLUMN) {
+                        row.col(|ui| {
+                            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                                ui.label(gross.to_str_rounded(config.show_cents));
+                            });
                         });
-                    });
+                    }
                     row.col(|ui| {
                         let file = &item.file;
                         let text = file.to_str().unwrap_or_default();
-                        if ui.link(Messages::Link).on_hover_text(text).clicked() {
+                        let link = ui.link(Messages::Link).on_hover_text(text);
+                        if link.clicked() {
                             info!("clicked link: {}", text);
                             util::send_event_and_request_repaint(
                                 ctx,
@@ -140,6 +396,40 @@ pub(super) fn build(
                                 Event::OpenFile(text.to_owned()),
                             );
                         }
+                        link.context_menu(|ui| {
+                            if ui
+                                .add_enabled(!text.is_empty(), Button::new(Messages::CopyPath))
+                                .clicked()
+                            {
+                                ctx.copy_text(text.to_owned());
+                                util::send_gui_event(
+                                    &app_context.gui_event_sender,
+                                    GuiEvent::ShowInfoNotification(String::from(
+                                        Messages::PathCopied.msg(),
+                                    )),
+                                );
+                                ui.close_menu();
+                            }
+                            let folder = file
+                                .parent()
+                                .filter(|p| !p.as_os_str().is_empty() && p.exists());
+                            if ui
+                                .add_enabled(
+                                    folder.is_some(),
+                                    Button::new(Messages::OpenContainingFolder),
+                                )
+                                .clicked()
+                            {
+                                if let Some(folder) = folder {
+                                    util::send_event_and_request_repaint(
+                                        ctx,
+                                        &app_context.background_event_sender,
+                                        Event::OpenFolder(folder.to_path_buf()),
+                                    );
+                                }
+                                ui.close_menu();
+                            }
+                        });
                     });
                     row.col(|ui| {
                         ui.horizontal(|ui| {
@@ -159,17 +449,266 @@ pub(super) fn build(
                                     &app_context.background_event_sender,
                                     Event::RemoveItem(
                                         DB::get_key_for_item(item),
-                                        get_date_range_for_settings(
-                                            state.selected_year,
-                                            state.selected_quarter,
-                                            state.selected_month,
-                                        ),
+                                        date_range.clone(),
                                     ),
                                 );
                             }
                         });
                     });
+                    if let (Some((painter, shape)), Some(fill)) =
+                        (background, row_fill(state.color_rows, item.invoice_type))
+                    {
+                        painter.set(shape, Shape::rect_filled(row.response().rect, 0.0, fill));
+                    }
+                    if row.response().clicked() {
+                        state.selected_row = Some(row.index());
+                    }
                 });
             });
+
+        // below the table, so it stays visible while the items are scrolled
+        render_totals_row(&totals, &widths, config.show_cents, ui);
+
+        // the widths change while a header is dragged, they're saved once it's released
+        if ui.input(|i| i.pointer.primary_down()) {
+            if widths_differ(&state.column_widths, &widths) {
+                state.resizing_columns = true;
+            }
+        } else if std::mem::take(&mut state.resizing_columns) {
+            if let Some(widths) = merge_widths(&config.accounting_column_widths, &widths) {
+                config.accounting_column_widths = widths;
+                config::save_config_or_notify(config, &app_context.gui_event_sender);
+            }
+        }
+        state.column_widths = widths;
+
+        // updates live while rows are (de-)selected
+        let selected: Vec<&AccountingItem> = accounting_sheet
+            .items
+            .iter()
+            .filter(|item| state.selected_items.contains(&item.id))
+            .collect();
+        if !selected.is_empty() {
+            let count = selected.len();
+            let selected_totals = Totals::of(selected.into_iter());
+            ui.label(format!(
+                "{} ({}): {} {}, {} {}",
+                Messages::SelectedItems,
+                count,
+                Messages::Net,
+                selected_totals.net.to_str_rounded(config.show_cents),
+                Messages::Gross,
+                selected_totals.gross.to_str_rounded(config.show_cents)
+            ));
+        }
+
+        if let Some((ref key, ref dialog)) = state.delete_dialog {
+            match dialog::render_dialog(ctx, dialog) {
+                DialogResponse::Ok => {
+                    util::send_event_and_request_repaint(
+                        ctx,
+                        &app_context.background_event_sender,
+                        Event::RemoveItem(key.to_owned(), date_range.clone()),
+                    );
+                    state.delete_dialog = None;
+                }
+                DialogResponse::Cancel => {
+                    state.delete_dialog = None;
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+// sums of the net, tax and gross columns
+struct Totals {
+    net: CurrencyValue,
+    tax: CurrencyValue,
+    gross: CurrencyValue,
+}
+
+impl Totals {
+    // like the summary, without excluded items
+    fn of<'a>(items: impl Iterator<Item = &'a AccountingItem>) -> Self {
+        items.filter(|item| !item.excluded).fold(
+            Totals {
+                net: CurrencyValue::zero(),
+                tax: CurrencyValue::zero(),
+                gross: CurrencyValue::zero(),
+            },
+            |totals, item| {
+                let VatCalculationResult { tax, gross } = item.net.calculate_vat(item.vat);
+                Totals {
+                    net: totals.net + item.net.clone(),
+                    tax: totals.tax + tax,
+                    gross: totals.gross + gross,
+                }
+            },
+        )
+    }
+}
+
+// laid out with the measured widths of the table's columns, so the sums are below their columns
+fn render_totals_row(totals: &Totals, widths: &[(usize, f32)], show_cents: bool, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        widths.iter().for_each(|&(column, width)| {
+            let sum = match column {
+                NET_COLUMN => Some(&totals.net),
+                TAX_COLUMN => Some(&totals.tax),
+                GROSS_COLUMN => Some(&totals.gross),
+                _ => None,
+            };
+            let layout = if sum.is_some() {
+                Layout::right_to_left(Align::Center)
+            } else {
+                Layout::left_to_right(Align::Center)
+            };
+            ui.allocate_ui_with_layout(Vec2::new(width, ROW_HEIGHT), layout, |ui| {
+                ui.set_min_width(width);
+                match sum {
+                    Some(sum) => {
+                        ui.strong(sum.to_str_rounded(show_cents));
+                    }
+                    None if column == DATE_COLUMN => {
+                        ui.strong(Messages::Total);
+                    }
+                    None => {}
+                }
+            });
+        });
+    });
+}
+
+// instead of an empty table, so a wrong selection isn't mistaken for missing data
+fn render_empty_state(
+    ctx: &Context,
+    state: &AccountingState,
+    date_range: DateRange,
+    app_context: &AppContext,
+    ui: &mut Ui,
+) {
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(Messages::NoItemsForThisPeriod).italics());
+        if !state.selected_all_time && ui.button(Messages::JumpToPreviousPeriodWithItems).clicked()
+        {
+            util::send_event_and_request_repaint(
+                ctx,
+                &app_context.background_event_sender,
+                Event::FindPeriodWithItemsBefore(date_range),
+            );
+        }
+    });
+}
+
+fn row_fill(color_rows: bool, invoice_type: InvoiceType) -> Option<Color32> {
+    match (color_rows, invoice_type) {
+        (true, InvoiceType::Out) => Some(Colors::OutgoingRow.col()),
+        _ => None,
+    }
+}
+
+// empty or invalid bounds don't filter, invalid ones are highlighted
+fn render_net_filter(value: &mut String, hint: Messages, ui: &mut Ui) {
+    let invalid = !value.trim().is_empty() && parse_number(value).is_none();
+    let mut text_edit = TextEdit::singleline(value)
+        .hint_text(hint.msg())
+        .desired_width(70.0);
+    if invalid {
+        text_edit = text_edit.text_color(Colors::Error.col());
+    }
+    ui.add(text_edit);
+}
+
+fn is_net_in_range(item: &AccountingItem, min: Option<Decimal>, max: Option<Decimal>) -> bool {
+    min.is_none_or(|min| item.net.value >= min) && max.is_none_or(|max| item.net.value <= max)
+}
+
+// case-insensitive, the search is expected in lower case already
+fn matches_search(item: &AccountingItem, search: &str) -> bool {
+    search.is_empty()
+        || [
+            item.name.as_str(),
+            item.company.0.as_str(),
+            item.category.0.as_str(),
+            item.note.as_str(),
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(search))
+}
+
+// items with a missing file or empty fields, e.g. after imports
+fn has_problems(item: &AccountingItem) -> bool {
+    !item.file.exists()
+        || item.name.trim().is_empty()
+        || item.company.trim().is_empty()
+        || item.category.trim().is_empty()
+}
+
+// edits the net in place - only saved on Enter or focus loss, invalid input is reverted
+fn render_net_edit(
+    ctx: &Context,
+    net_edit: &mut Option<NetEdit>,
+    net_edit_failed: &mut Option<(Uuid, f64)>,
+    item: &AccountingItem,
+    date_range: &DateRange,
+    app_context: &AppContext,
+    ui: &mut Ui,
+) {
+    let now = ui.input(|i| i.time);
+    let mut failed = false;
+    if let Some((id, since)) = *net_edit_failed {
+        if id == item.id {
+            if now - since < NET_EDIT_FAILED_DURATION {
+                failed = true;
+                ctx.request_repaint_after(Duration::from_secs_f64(NET_EDIT_FAILED_DURATION));
+            } else {
+                *net_edit_failed = None;
+            }
+        }
+    }
+
+    let is_editing = net_edit.as_ref().is_some_and(|e| e.id == item.id);
+    let mut current_value = item.net.to_value_string();
+    let buffer = match net_edit {
+        Some(e) if e.id == item.id => &mut e.value,
+        _ => &mut current_value,
+    };
+    let mut text_edit = TextEdit::singleline(buffer)
+        .horizontal_align(Align::Max)
+        .desired_width(ui.available_width());
+    if failed {
+        text_edit = text_edit.text_color(Colors::Error.col());
+    }
+    let response = ui.add(text_edit);
+
+    if response.gained_focus() && !is_editing {
+        *net_edit = Some(NetEdit {
+            id: item.id,
+            value: item.net.to_value_string(),
+        });
+    }
+
+    if response.lost_focus() && is_editing {
+        let value = net_edit.take().map(|e| e.value).unwrap_or_default();
+        if ui.input(|i| i.key_pressed(Key::Escape)) {
+            return;
+        }
+        match parse_number(&value) {
+            Some(net) => {
+                if net != item.net.value {
+                    let mut updated = item.clone();
+                    updated.net = CurrencyValue::new_from_decimal(net);
+                    util::send_event_and_request_repaint(
+                        ctx,
+                        &app_context.background_event_sender,
+                        Event::SaveItem(updated, date_range.clone()),
+                    );
+                }
+            }
+            None => {
+                *net_edit_failed = Some((item.id, now));
+            }
+        }
     }
 }
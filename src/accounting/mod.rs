@@ -1,34 +1,57 @@
 use crate::{
-    AppContext, DATE_FORMAT, Event, GuiEvent, State,
-    config::Config,
+    AppContext, Event, GuiEvent, State,
+    config::{self, AccountingPeriod, Config, PageSize},
     data::{
-        AccountingItem, AccountingSheet, Category, Company, InvoiceType, Vat,
-        currency::CurrencyValue,
+        AccountingItem, AccountingSheet, Category, CategoryDefaults, Company, ExportLogEntry,
+        ExportType, InvoiceType, Vat,
+        currency::{CurrencyValue, format_quantity, parse_number},
     },
-    db::get_date_range_for_settings,
+    date_format,
+    db::{DB, DateRange, get_date_range_for_all_time, get_date_range_for_settings},
     messages::Messages,
-    ui::{self, autosuggest::AutoSuggest, dialog::Dialog},
+    ui::{
+        self,
+        autosuggest::AutoSuggest,
+        dialog::{self, Dialog, DialogResponse},
+    },
     util::{
-        self, MONTHS, Month, QUARTERS, Quarter,
-        export::accounting::{CreatePDFResult, create_accounting_pdf},
-        files::{build_file_name_suggestion, copy_file_and_rename, delete_file_and_folder},
+        self, Colors, MONTHS, Month, QUARTERS, Quarter, VALID_FILETYPES,
+        export::accounting::{
+            AccountingSummary, CreatePDFResult, calculate_summary, create_accounting_pdf,
+            csv::{SubtotalPeriod, export_accounting_csv_with_subtotals},
+            estimate_accounting_pages, period_name,
+        },
+        files::{
+            self, apply_file_name_template, build_file_name_suggestion, copy_file_and_rename,
+            delete_file_and_folder, unique_file_name,
+        },
+        parse_flexible_date,
         validation::{Field, ValidationResult, is_date_in_selected_time_span},
     },
 };
 use chrono::{Datelike, NaiveDate};
-use eframe::egui::{ComboBox, Context, Grid, RichText, SelectableLabel, Ui};
+use eframe::egui::{
+    Button, CollapsingHeader, ComboBox, Context, Grid, Key, RichText, ScrollArea, SelectableLabel,
+    TextEdit, Ui,
+};
 use egui_file::FileDialog;
 use log::info;
 use rust_decimal::Decimal;
 use std::{
+    collections::HashSet,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
-    str::FromStr,
+    sync::mpsc::Sender,
 };
 use uuid::Uuid;
 
 mod add_edit;
 mod items_table;
 
+const MANY_PAGES_WARNING_THRESHOLD: usize = 20;
+// typed years outside of it are most likely typos, and dates that far out can't be represented
+const SELECTABLE_YEARS: RangeInclusive<i32> = 1900..=2200;
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum Mode {
     Add,
@@ -40,37 +63,140 @@ pub(crate) struct AccountingState {
     pub(crate) selected_year: i32,
     pub(crate) selected_quarter: Option<Quarter>,
     pub(crate) selected_month: Option<Month>,
+    pub(crate) selected_all_time: bool,
     pub(crate) selected_accounting_sheet: Option<AccountingSheet>,
+    pub(crate) fiscal_year_start_month: u32,
+    pub(crate) color_rows: bool,
     quarter_selector_selected: Option<Quarter>,
     month_selector_selected: Option<Month>,
     year_selector_selected: i32,
+    year_input: String,
+    year_input_invalid: bool,
+    all_time_selector_selected: bool,
     item: Item,
     mode: Mode,
     export_state: ExportState,
     pub(crate) names: Vec<String>,
     pub(crate) companies: Vec<String>,
     pub(crate) categories: Vec<String>,
+    selected_items: HashSet<Uuid>,
+    copy_dialog: Option<Dialog>,
+    net_edit: Option<NetEdit>,
+    net_edit_failed: Option<(Uuid, f64)>,
+    show_only_problems: bool,
+    amount_columns: AmountColumns,
+    net_filter_min: String,
+    net_filter_max: String,
+    search: String,
+    selected_row: Option<usize>,
+    // the widths of the items table's columns in the last frame, to notice them being resized
+    column_widths: Vec<(usize, f32)>,
+    resizing_columns: bool,
+    delete_dialog: Option<(String, Dialog)>,
+    summary_by_type: bool,
+    csv_subtotal_period: SubtotalPeriod,
+    validation_report: Option<Vec<ItemProblems>>,
+    pub(crate) duplicates: Option<Vec<Vec<AccountingItem>>>,
+    // the duplicate to be deleted, once confirmed
+    duplicate_delete_dialog: Option<(Uuid, Dialog)>,
+    merge: Option<Merge>,
+    // the sheet's items arrived, so an empty sheet really is empty
+    items_fetched: bool,
+    // the date of an earlier item, whose period is selected next
+    pub(crate) jump_to_date: Option<NaiveDate>,
+}
+
+// the currency columns shown in the items table, e.g. only gross on narrow screens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AmountColumns {
+    All,
+    Net,
+    Gross,
+}
+
+// an in-place edit of an item's net value in the items table
+#[derive(Debug)]
+struct NetEdit {
+    id: Uuid,
+    value: String,
 }
 
 impl AccountingState {
-    pub(crate) fn new() -> Self {
-        let now = chrono::Local::now();
-        let month = now.month();
+    pub(crate) fn new(config: &Config) -> Self {
+        let today = chrono::Local::now().date_naive();
+        let fiscal_year = util::fiscal_year_of(today, config.fiscal_year_start_month);
 
-        Self {
-            selected_year: now.year(),
+        let mut state = Self {
+            selected_year: fiscal_year,
             selected_quarter: None,
             selected_month: None,
+            selected_all_time: false,
             selected_accounting_sheet: None,
-            quarter_selector_selected: Some(Quarter::from_month(month)),
+            fiscal_year_start_month: config.fiscal_year_start_month,
+            color_rows: config.color_rows,
+            quarter_selector_selected: Some(Quarter::from_month(
+                today.month(),
+                config.fiscal_year_start_month,
+            )),
             month_selector_selected: None,
-            year_selector_selected: now.year(),
+            year_selector_selected: fiscal_year,
+            year_input: String::default(),
+            year_input_invalid: false,
+            all_time_selector_selected: false,
             item: Item::new().hidden(),
             mode: Mode::Add,
             export_state: ExportState::new(),
             names: vec![],
             companies: vec![],
             categories: vec![],
+            selected_items: HashSet::new(),
+            copy_dialog: None,
+            net_edit: None,
+            net_edit_failed: None,
+            show_only_problems: false,
+            amount_columns: AmountColumns::All,
+            net_filter_min: String::default(),
+            net_filter_max: String::default(),
+            search: String::default(),
+            selected_row: None,
+            column_widths: vec![],
+            resizing_columns: false,
+            delete_dialog: None,
+            summary_by_type: false,
+            csv_subtotal_period: SubtotalPeriod::Month,
+            validation_report: None,
+            duplicates: None,
+            duplicate_delete_dialog: None,
+            merge: None,
+            items_fetched: false,
+            jump_to_date: None,
+        };
+        if let Some(period) = config
+            .accounting_period
+            .as_ref()
+            .filter(|p| SELECTABLE_YEARS.contains(&p.year))
+        {
+            state.selected_year = period.year;
+            state.selected_quarter = period.quarter;
+            state.selected_month = period.month;
+            state.selected_all_time = period.all_time;
+            state.year_selector_selected = period.year;
+            state.quarter_selector_selected = period.quarter;
+            state.month_selector_selected = period.month;
+            state.all_time_selector_selected = period.all_time;
+        }
+        state
+    }
+
+    // the sheet is only replaced by selecting a range - switching screens keeps it, and items
+    // fetched for a range that isn't selected anymore are dropped
+    pub(crate) fn set_fetched_items(&mut self, date_range: &DateRange, items: Vec<AccountingItem>) {
+        if selected_date_range(self) != *date_range {
+            return;
+        }
+        if let Some(ref mut sheet) = self.selected_accounting_sheet {
+            sheet.items = items;
+            self.items_fetched = true;
         }
     }
 }
@@ -79,6 +205,9 @@ impl AccountingState {
 struct ExportState {
     open_file_dialog: Option<FileDialog>,
     selected_path: Option<PathBuf>,
+    page_count_dialog: Option<Dialog>,
+    open_folder_dialog: Option<FileDialog>,
+    open_csv_dialog: Option<FileDialog>,
 }
 
 impl ExportState {
@@ -86,16 +215,53 @@ impl ExportState {
         Self {
             open_file_dialog: None,
             selected_path: None,
+            page_count_dialog: None,
+            open_folder_dialog: None,
+            open_csv_dialog: None,
         }
     }
 }
 
+// everything needed to export each month of a year into its own PDF on the background thread
+#[derive(Debug)]
+pub(crate) struct MonthlyExport {
+    pub(crate) folder: PathBuf,
+    pub(crate) year: i32,
+    pub(crate) fiscal_year_start_month: u32,
+    pub(crate) page_size: PageSize,
+    pub(crate) files_suffix: String,
+    pub(crate) file_name_template: String,
+    pub(crate) author: String,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MonthlyExportSummary {
+    pub(crate) exports: Vec<ExportLogEntry>,
+    pub(crate) skipped: usize,
+    pub(crate) failed: usize,
+}
+
+// the problems of a stored item, found by validating the whole sheet
+#[derive(Debug)]
+struct ItemProblems {
+    nr: usize,
+    name: String,
+    problems: Vec<String>,
+}
+
+#[derive(Debug)]
+enum SheetExportError {
+    PdfNotCreated,
+    FilesNotCopied(usize),
+}
+
 #[derive(Debug)]
 struct Item {
     id: Uuid,
     show: bool,
     focus_first_element: bool,
     invoice_type: InvoiceType,
+    invoice_type_changed: bool,
     date: NaiveDate,
     date_field: String,
     name: String,
@@ -106,12 +272,35 @@ struct Item {
     category_autosuggest: AutoSuggest,
     net: String,
     vat: Vat,
+    vat_changed: bool,
     file: PathBuf,
+    note: String,
+    excluded: bool,
+    // one receipt split across categories - each split is saved as its own item
+    splits: Vec<Split>,
     open_file_dialog: Option<FileDialog>,
     validation: ValidationResult,
     save_dialog: Option<Dialog>,
 }
 
+// a part of a split item's net, booked on its own category
+#[derive(Debug)]
+struct Split {
+    category: String,
+    category_autosuggest: AutoSuggest,
+    net: String,
+}
+
+impl Split {
+    fn new(category: String, net: String) -> Self {
+        Self {
+            category,
+            category_autosuggest: AutoSuggest::new(),
+            net,
+        }
+    }
+}
+
 impl From<&AccountingItem> for Item {
     fn from(item: &AccountingItem) -> Self {
         Self {
@@ -119,8 +308,9 @@ impl From<&AccountingItem> for Item {
             show: true,
             focus_first_element: true,
             invoice_type: item.invoice_type,
+            invoice_type_changed: true,
             date: item.date,
-            date_field: item.date.format(DATE_FORMAT).to_string(),
+            date_field: item.date.format(date_format()).to_string(),
             name: item.name.to_owned(),
             name_autosuggest: AutoSuggest::new(),
             company: item.company.0.to_owned(),
@@ -129,7 +319,11 @@ impl From<&AccountingItem> for Item {
             category_autosuggest: AutoSuggest::new(),
             net: item.net.to_value_string(),
             vat: item.vat,
+            vat_changed: true,
             file: item.file.to_path_buf(),
+            note: item.note.to_owned(),
+            excluded: item.excluded,
+            splits: vec![],
             open_file_dialog: None,
             validation: ValidationResult::new(),
             save_dialog: None,
@@ -142,15 +336,17 @@ impl From<&Item> for AccountingItem {
         AccountingItem {
             invoice_type: val.invoice_type,
             id: val.id,
-            date: NaiveDate::parse_from_str(&val.date_field, DATE_FORMAT).expect("was validated"),
+            date: parse_flexible_date(&val.date_field).expect("was validated"),
             name: val.name.to_owned(),
             company: Company(val.company.to_owned()),
             category: Category(val.category.to_owned()),
             net: CurrencyValue::new_from_decimal(
-                Decimal::from_str(&val.net).expect("is a valid number"),
+                parse_number(&val.net).expect("is a valid number"),
             ),
             vat: val.vat,
             file: val.file.to_owned(),
+            note: val.note.trim().to_owned(),
+            excluded: val.excluded,
         }
     }
 }
@@ -163,17 +359,22 @@ impl Item {
             show: true,
             focus_first_element: true,
             invoice_type: InvoiceType::In,
+            invoice_type_changed: false,
             date: now,
-            date_field: now.format(DATE_FORMAT).to_string(),
+            date_field: now.format(date_format()).to_string(),
             name: String::default(),
             name_autosuggest: AutoSuggest::new(),
             company: String::default(),
             company_autosuggest: AutoSuggest::new(),
             category: String::default(),
             category_autosuggest: AutoSuggest::new(),
-            net: String::from("0.00"),
+            net: CurrencyValue::zero().to_value_string(),
             vat: Vat::Zero,
+            vat_changed: false,
             file: PathBuf::default(),
+            note: String::default(),
+            excluded: false,
+            splits: vec![],
             open_file_dialog: None,
             validation: ValidationResult::new(),
             save_dialog: None,
@@ -185,68 +386,509 @@ impl Item {
         self
     }
 
+    // an empty form for the next item, optionally with the fields, which are likely the same for
+    // a batch of similar items
+    fn next(&self, keep_fields: bool) -> Self {
+        let next = Item::new();
+        if !keep_fields {
+            return next;
+        }
+        Self {
+            date: self.date,
+            date_field: self.date_field.clone(),
+            company: self.company.clone(),
+            category: self.category.clone(),
+            ..next
+        }
+    }
+
+    // starts with the whole net on the current category, to be split from there
+    fn add_split(&mut self) {
+        if self.splits.is_empty() {
+            self.splits
+                .push(Split::new(self.category.clone(), self.net.clone()));
+        }
+        self.splits.push(Split::new(
+            String::default(),
+            CurrencyValue::zero().to_value_string(),
+        ));
+    }
+
+    // the net, which isn't assigned to a split yet
+    fn unsplit_net(&self) -> Option<Decimal> {
+        let net = parse_number(&self.net)?;
+        self.splits
+            .iter()
+            .try_fold(net, |rest, split| Some(rest - parse_number(&split.net)?))
+    }
+
+    // one item per split, with fresh ids - the file is copied for each of them when saving
+    fn split_items(&self) -> Vec<AccountingItem> {
+        let item = AccountingItem::from(self);
+        self.splits
+            .iter()
+            .map(|split| AccountingItem {
+                id: Uuid::now_v7(),
+                category: Category(split.category.trim().to_owned()),
+                net: CurrencyValue::new_from_decimal(
+                    parse_number(&split.net).expect("was validated"),
+                ),
+                ..item.clone()
+            })
+            .collect()
+    }
+
     fn validate(&self, state: &AccountingState) -> ValidationResult {
         let mut validation_result = ValidationResult::new();
-        if let Ok(date) = NaiveDate::parse_from_str(&self.date_field, DATE_FORMAT) {
-            if !is_date_in_selected_time_span(
-                date,
-                state.selected_year,
-                state.selected_quarter,
-                state.selected_month,
-            ) {
-                validation_result.add_warning(
-                    Field::Date,
-                    Messages::DateNotInSelectedDateRange.msg().to_owned(),
-                );
-            }
+        if let Some(date) = parse_flexible_date(&self.date_field) {
+            validate_date(date, state, &mut validation_result);
         } else {
             validation_result.add_error(Field::Date, Messages::DateNotValid.msg().to_owned());
         }
-
-        if self.name.is_empty() {
-            validation_result.add_error(
-                Field::Name,
-                format!("{} {}", Messages::Name, Messages::CanNotBeEmpty),
-            );
+        validate_fields(
+            &self.name,
+            &self.company,
+            // split items have a category per split
+            self.splits.is_empty().then_some(self.category.as_str()),
+            &self.file,
+            &mut validation_result,
+        );
+        if !self.file.as_os_str().is_empty() && !has_valid_file_type(&self.file) {
+            validation_result.add_error(Field::File, invalid_file_type_msg());
         }
-
-        if self.company.is_empty() {
+        if parse_number(&self.net).is_none() {
             validation_result.add_error(
-                Field::Company,
-                format!("{} {}", Messages::Company, Messages::CanNotBeEmpty),
+                Field::Net,
+                format!("{} {}", Messages::Net, Messages::NotANumber),
             );
         }
+        self.validate_splits(&mut validation_result);
+        validation_result
+    }
 
-        if self.category.is_empty() {
+    fn validate_splits(&self, validation_result: &mut ValidationResult) {
+        if self.splits.is_empty() {
+            return;
+        }
+        if self.splits.iter().any(|s| s.category.trim().is_empty()) {
             validation_result.add_error(
-                Field::Category,
+                Field::Splits,
                 format!("{} {}", Messages::Category, Messages::CanNotBeEmpty),
             );
         }
-
-        if let Err(_e) = Decimal::from_str(&self.net) {
+        if self.splits.iter().any(|s| parse_number(&s.net).is_none()) {
             validation_result.add_error(
-                Field::Net,
+                Field::Splits,
                 format!("{} {}", Messages::Net, Messages::NotANumber),
             );
-        }
-        if self.file.as_os_str().is_empty() {
+        } else if self.unsplit_net().is_some_and(|rest| !rest.is_zero()) {
             validation_result.add_error(
-                Field::File,
-                format!("{} {}", Messages::File, Messages::CanNotBeEmpty),
+                Field::Splits,
+                Messages::SplitsDoNotAddUpToNet.msg().to_owned(),
             );
         }
-        validation_result
     }
 }
 
+// the file picker only offers these, but the path can also be typed or pasted
+pub(super) fn has_valid_file_type(file: &Path) -> bool {
+    file.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            VALID_FILETYPES
+                .iter()
+                .any(|valid| valid.eq_ignore_ascii_case(ext))
+        })
+}
+
+// e.g. "File type not supported, use one of pdf, png, jpg, jpeg, gif"
+pub(super) fn invalid_file_type_msg() -> String {
+    format!(
+        "{} {}",
+        Messages::InvalidFileType,
+        VALID_FILETYPES.join(", ")
+    )
+}
+
+// the live hint on the item form's date, the warning on save stays the actual check - checked
+// every frame, so it follows both the field and the selected period
+fn is_item_date_out_of_range(state: &AccountingState) -> bool {
+    parse_flexible_date(&state.item.date_field)
+        .is_some_and(|date| is_out_of_selected_range(date, state))
+}
+
+fn is_out_of_selected_range(date: NaiveDate, state: &AccountingState) -> bool {
+    !state.selected_all_time
+        && !is_date_in_selected_time_span(
+            date,
+            state.selected_year,
+            state.selected_quarter,
+            state.selected_month,
+            state.fiscal_year_start_month,
+        )
+}
+
+// checks shared by the item form and stored items
+fn validate_date(
+    date: NaiveDate,
+    state: &AccountingState,
+    validation_result: &mut ValidationResult,
+) {
+    if is_out_of_selected_range(date, state) {
+        validation_result.add_warning(
+            Field::Date,
+            Messages::DateNotInSelectedDateRange.msg().to_owned(),
+        );
+    }
+    // most likely a typo, but still possible, e.g. for scheduled payments
+    if date > chrono::Local::now().date_naive() {
+        validation_result.add_warning(Field::Date, Messages::DateInFuture.msg().to_owned());
+    }
+}
+
+fn validate_fields(
+    name: &str,
+    company: &str,
+    category: Option<&str>,
+    file: &Path,
+    validation_result: &mut ValidationResult,
+) {
+    if name.trim().is_empty() {
+        validation_result.add_error(
+            Field::Name,
+            format!("{} {}", Messages::Name, Messages::CanNotBeEmpty),
+        );
+    }
+
+    if company.trim().is_empty() {
+        validation_result.add_error(
+            Field::Company,
+            format!("{} {}", Messages::Company, Messages::CanNotBeEmpty),
+        );
+    }
+
+    if category.is_some_and(|c| c.trim().is_empty()) {
+        validation_result.add_error(
+            Field::Category,
+            format!("{} {}", Messages::Category, Messages::CanNotBeEmpty),
+        );
+    }
+
+    if file.as_os_str().is_empty() {
+        validation_result.add_error(
+            Field::File,
+            format!("{} {}", Messages::File, Messages::CanNotBeEmpty),
+        );
+    }
+}
+
+// the net of a stored item is always a number, but its file might have been moved or deleted since
+fn validate_stored_item(item: &AccountingItem, state: &AccountingState) -> ValidationResult {
+    let mut validation_result = ValidationResult::new();
+    validate_date(item.date, state, &mut validation_result);
+    validate_fields(
+        &item.name,
+        &item.company.0,
+        Some(&item.category.0),
+        &item.file,
+        &mut validation_result,
+    );
+    if !item.file.as_os_str().is_empty() && !item.file.exists() {
+        validation_result.add_error(Field::File, Messages::FileDoesNotExist.msg().to_owned());
+    }
+    validation_result
+}
+
+// dry run over all items of the selected sheet, numbered like in the table
+fn validate_sheet(state: &AccountingState) -> Vec<ItemProblems> {
+    let Some(ref sheet) = state.selected_accounting_sheet else {
+        return vec![];
+    };
+    sheet
+        .items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| {
+            let validation_result = validate_stored_item(item, state);
+            if validation_result.is_ok() {
+                return None;
+            }
+            Some(ItemProblems {
+                nr: idx + 1,
+                name: item.name.to_owned(),
+                problems: validation_result
+                    .messages()
+                    .into_iter()
+                    .map(|m| m.to_owned())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn render_validation_report(ctx: &Context, state: &mut AccountingState) {
+    let Some(ref report) = state.validation_report else {
+        return;
+    };
+    let mut close = false;
+    dialog::modal_window("validation_report", [500.0, 300.0]).show(ctx, |ui| {
+        if report.is_empty() {
+            ui.label(Messages::AllItemsValid);
+        } else {
+            ui.strong(Messages::ItemsWithProblems);
+            ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                report.iter().for_each(|item| {
+                    ui.label(format!(
+                        "{}. {}: {}",
+                        item.nr,
+                        item.name,
+                        item.problems.join(", ")
+                    ));
+                });
+            });
+        }
+        ui.vertical_centered(|ui| {
+            if ui.button(Messages::Close).clicked() {
+                close = true;
+            }
+        });
+    });
+    if close {
+        state.validation_report = None;
+    }
+}
+
+// lists the duplicate groups over all periods, so extras can be deleted right away
+fn render_duplicates(ctx: &Context, state: &mut AccountingState, app_context: &AppContext) {
+    if let Some((id, ref dialog)) = state.duplicate_delete_dialog {
+        match dialog::render_dialog(ctx, dialog) {
+            DialogResponse::Ok => {
+                remove_duplicate(ctx, state, &id, app_context);
+                state.duplicate_delete_dialog = None;
+            }
+            DialogResponse::Cancel => state.duplicate_delete_dialog = None,
+            DialogResponse::None => {}
+        }
+        return;
+    }
+    let Some(ref groups) = state.duplicates else {
+        return;
+    };
+    let mut close = false;
+    let mut delete = None;
+    dialog::modal_window("duplicates", [500.0, 300.0]).show(ctx, |ui| {
+        if groups.is_empty() {
+            ui.label(Messages::NoDuplicatesFound);
+        } else {
+            ui.strong(Messages::DuplicateItems);
+            ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                groups.iter().for_each(|group| {
+                    ui.separator();
+                    group.iter().for_each(|item| {
+                        ui.horizontal(|ui| {
+                            if ui.button(Messages::Delete.msg()).clicked() {
+                                delete = Some(item.id);
+                            }
+                            ui.label(format!(
+                                "{} {} - {} - {} {}",
+                                item.date.format(date_format()),
+                                item.company.0,
+                                item.name,
+                                item.net,
+                                item.note
+                            ));
+                        });
+                    });
+                });
+            });
+        }
+        ui.vertical_centered(|ui| {
+            if ui.button(Messages::Close).clicked() {
+                close = true;
+            }
+        });
+    });
+    if let Some(id) = delete {
+        state.duplicate_delete_dialog = Some((
+            id,
+            Dialog::new(
+                Messages::ReallyDeleteItem.msg().to_owned(),
+                Messages::Delete.msg(),
+                Messages::Cancel.msg(),
+            ),
+        ));
+    }
+    if close {
+        state.duplicates = None;
+    }
+}
+
+// merges a category or company into another one, e.g. "Buero" into "Büro"
+#[derive(Debug, Default)]
+struct Merge {
+    companies: bool,
+    from: String,
+    to: String,
+    // asks with the number of affected items, once they're counted
+    confirm_dialog: Option<Dialog>,
+}
+
+impl AccountingState {
+    pub(crate) fn confirm_merge(&mut self, count: usize) {
+        if let Some(ref mut merge) = self.merge {
+            merge.confirm_dialog = Some(Dialog::new(
+                format!("{} {}", count, Messages::ItemsWillBeMerged.msg()),
+                Messages::Merge.msg(),
+                Messages::Cancel.msg(),
+            ));
+        }
+    }
+
+    // the form's date in the newly configured date format
+    pub(crate) fn reformat_dates(&mut self, from: &str, to: &str) {
+        util::reformat_date_field(&mut self.item.date_field, from, to);
+    }
+}
+
+fn render_merge(
+    ctx: &Context,
+    state: &mut AccountingState,
+    config: &Config,
+    app_context: &AppContext,
+) {
+    let date_range = selected_date_range(state);
+    let Some(ref mut merge) = state.merge else {
+        return;
+    };
+    if let Some(ref dialog) = merge.confirm_dialog {
+        match dialog::render_dialog(ctx, dialog) {
+            DialogResponse::Ok => {
+                if files::auto_backup_or_notify(config, &app_context.gui_event_sender) {
+                    let (from, to) = (merge.from.clone(), merge.to.clone());
+                    let event = if merge.companies {
+                        Event::MergeCompanies(from, to, date_range)
+                    } else {
+                        Event::MergeCategories(from, to, date_range)
+                    };
+                    util::send_event_and_request_repaint(
+                        ctx,
+                        &app_context.background_event_sender,
+                        event,
+                    );
+                }
+                state.merge = None;
+            }
+            DialogResponse::Cancel => merge.confirm_dialog = None,
+            DialogResponse::None => {}
+        }
+        return;
+    }
+    let options = if merge.companies {
+        &state.companies
+    } else {
+        &state.categories
+    };
+    let mut close = false;
+    let mut switched = false;
+    dialog::modal_window("merge", [400.0, 150.0]).show(ctx, |ui| {
+        ui.strong(Messages::MergeCategoriesOrCompanies);
+        ui.horizontal(|ui| {
+            switched |= ui
+                .radio_value(&mut merge.companies, false, Messages::Category.msg())
+                .changed();
+            switched |= ui
+                .radio_value(&mut merge.companies, true, Messages::Company.msg())
+                .changed();
+        });
+        Grid::new("merge_grid").num_columns(2).show(ui, |ui| {
+            ui.label(Messages::From);
+            render_merge_selector("merge_from", &mut merge.from, options, ui);
+            ui.end_row();
+            ui.label(Messages::MergeInto);
+            render_merge_selector("merge_into", &mut merge.to, options, ui);
+            ui.end_row();
+        });
+        ui.label(Messages::MergeHint);
+        ui.horizontal(|ui| {
+            let can_merge =
+                !merge.from.is_empty() && !merge.to.is_empty() && merge.from != merge.to;
+            if ui
+                .add_enabled(can_merge, Button::new(Messages::Merge.msg()))
+                .clicked()
+            {
+                util::send_event_and_request_repaint(
+                    ctx,
+                    &app_context.background_event_sender,
+                    Event::CountItemsToMerge(merge.companies, merge.from.clone()),
+                );
+            }
+            if ui.button(Messages::Close).clicked() {
+                close = true;
+            }
+        });
+    });
+    if switched {
+        merge.from.clear();
+        merge.to.clear();
+    }
+    if close {
+        state.merge = None;
+    }
+}
+
+fn render_merge_selector(id: &str, selected: &mut String, options: &[String], ui: &mut Ui) {
+    ComboBox::from_id_salt(id)
+        .selected_text(selected.as_str())
+        .width(250.0)
+        .show_ui(ui, |ui| {
+            options.iter().for_each(|option| {
+                ui.selectable_value(selected, option.clone(), option);
+            });
+        });
+}
+
+// deletes the item and drops groups, which aren't duplicates anymore
+fn remove_duplicate(
+    ctx: &Context,
+    state: &mut AccountingState,
+    id: &Uuid,
+    app_context: &AppContext,
+) {
+    let date_range = selected_date_range(state);
+    let Some(ref mut groups) = state.duplicates else {
+        return;
+    };
+    if let Some(item) = groups.iter().flatten().find(|item| item.id == *id) {
+        util::send_event_and_request_repaint(
+            ctx,
+            &app_context.background_event_sender,
+            Event::RemoveItem(DB::get_key_for_item(item), date_range),
+        );
+    }
+    groups
+        .iter_mut()
+        .for_each(|group| group.retain(|item| item.id != *id));
+    groups.retain(|group| group.len() > 1);
+}
+
 pub(crate) fn build(
     ctx: &Context,
     state: &mut State,
-    config: &Config,
+    config: &mut Config,
     app_context: &AppContext,
     ui: &mut Ui,
 ) {
+    if let Some(date) = state.accounting.jump_to_date.take() {
+        // keeps the kind of period, e.g. a quarter stays a quarter
+        let period = if state.accounting.selected_quarter.is_some() {
+            CurrentPeriod::Quarter
+        } else if state.accounting.selected_month.is_some() {
+            CurrentPeriod::Month
+        } else {
+            CurrentPeriod::Year
+        };
+        select_period_of(period, date, state, config, app_context, ctx);
+    }
     ui.label(RichText::new(Messages::Accounting).strong());
     ui.separator();
     ui.vertical(|ui| {
@@ -254,12 +896,17 @@ pub(crate) fn build(
             .num_columns(3)
             .show(ui, |ui| {
                 ui.label(Messages::Year);
-                ComboBox::from_id_salt("year_selector")
-                    .selected_text(format!("{}", state.accounting.year_selector_selected))
-                    .show_ui(ui, |ui| {
-                        ((state.accounting.year_selector_selected - 100)
-                            ..=chrono::Local::now().year())
-                            .rev()
+                ui.horizontal(|ui| {
+                    let mut picked_year = None;
+                    ComboBox::from_id_salt("year_selector")
+                        .selected_text(format!("{}", state.accounting.year_selector_selected))
+                        .show_ui(ui, |ui| {
+                            selectable_years(
+                                state.accounting.year_selector_selected,
+                                chrono::Local::now().year(),
+                                config.year_history_span,
+                            )
+                            .into_iter()
                             .for_each(|year| {
                                 if ui
                                     .add(SelectableLabel::new(
@@ -268,12 +915,51 @@ pub(crate) fn build(
                                     ))
                                     .clicked()
                                 {
-                                    state.accounting.year_selector_selected = year;
-                                    state.accounting.quarter_selector_selected = None;
-                                    state.accounting.month_selector_selected = None;
+                                    picked_year = Some(year);
                                 }
                             });
-                    });
+                        });
+                    // years outside of the span can be typed in
+                    let mut year_edit = TextEdit::singleline(&mut state.accounting.year_input)
+                        .hint_text(Messages::OtherYear.msg())
+                        .desired_width(60.0);
+                    if state.accounting.year_input_invalid {
+                        year_edit = year_edit.text_color(Colors::Error.col());
+                    }
+                    let mut response = ui.add(year_edit);
+                    if state.accounting.year_input_invalid {
+                        response = response.on_hover_text(Messages::YearOutOfRange);
+                    }
+                    if response.changed() {
+                        state.accounting.year_input_invalid = false;
+                    }
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                        match parse_year(&state.accounting.year_input) {
+                            Some(year) => {
+                                picked_year = Some(year);
+                                state.accounting.year_input.clear();
+                            }
+                            None => state.accounting.year_input_invalid = true,
+                        }
+                    }
+                    if let Some(year) = picked_year {
+                        state.accounting.year_selector_selected = year;
+                        state.accounting.quarter_selector_selected = None;
+                        state.accounting.month_selector_selected = None;
+                        state.accounting.all_time_selector_selected = false;
+                    }
+                });
+                if ui
+                    .add(SelectableLabel::new(
+                        state.accounting.all_time_selector_selected,
+                        Messages::AllTime.msg(),
+                    ))
+                    .clicked()
+                {
+                    state.accounting.all_time_selector_selected = true;
+                    state.accounting.quarter_selector_selected = None;
+                    state.accounting.month_selector_selected = None;
+                }
                 ui.end_row();
 
                 ui.label(Messages::Quarter);
@@ -289,6 +975,7 @@ pub(crate) fn build(
                         {
                             state.accounting.quarter_selector_selected = Some(quarter.to_owned());
                             state.accounting.month_selector_selected = None;
+                            state.accounting.all_time_selector_selected = false;
                         }
                     });
                 });
@@ -306,6 +993,7 @@ pub(crate) fn build(
                         {
                             state.accounting.month_selector_selected = Some(month.to_owned());
                             state.accounting.quarter_selector_selected = None;
+                            state.accounting.all_time_selector_selected = false;
                         }
                     });
                 });
@@ -313,12 +1001,40 @@ pub(crate) fn build(
                     state.accounting.selected_year = state.accounting.year_selector_selected;
                     state.accounting.selected_month = state.accounting.month_selector_selected;
                     state.accounting.selected_quarter = state.accounting.quarter_selector_selected;
+                    state.accounting.selected_all_time =
+                        state.accounting.all_time_selector_selected;
+                    save_accounting_period(&state.accounting, config, app_context);
                     select_date_range(state, app_context, ctx);
                 }
                 ui.end_row();
+
+                ui.label("");
+                ui.horizontal(|ui| {
+                    for (period, text) in [
+                        (CurrentPeriod::Month, Messages::ThisMonth),
+                        (CurrentPeriod::Quarter, Messages::ThisQuarter),
+                        (CurrentPeriod::Year, Messages::ThisYear),
+                    ] {
+                        if ui.button(text).clicked() {
+                            select_period_of(
+                                period,
+                                chrono::Local::now().date_naive(),
+                                state,
+                                config,
+                                app_context,
+                                ctx,
+                            );
+                        }
+                    }
+                });
+                ui.end_row();
             });
         ui.separator();
         ui.horizontal(|ui| {
+            if state.accounting.selected_all_time {
+                ui.label(Messages::AllTime);
+                return;
+            }
             ui.label(format!(
                 "{}: {}",
                 Messages::Year,
@@ -332,19 +1048,150 @@ pub(crate) fn build(
             }
         });
 
-        add_button(ui, state);
-        items_table::build(ctx, &mut state.accounting, app_context, ui);
+        ui.horizontal(|ui| {
+            add_button(ui, state);
+            if ui.button(Messages::Refresh).clicked() {
+                refresh(state, app_context, ctx);
+            }
+            if ui
+                .add_enabled(
+                    state.accounting.selected_accounting_sheet.is_some(),
+                    Button::new(Messages::ValidateItems.msg()),
+                )
+                .clicked()
+            {
+                state.accounting.validation_report = Some(validate_sheet(&state.accounting));
+            }
+            if ui.button(Messages::FindDuplicates).clicked() {
+                util::send_event_and_request_repaint(
+                    ctx,
+                    &app_context.background_event_sender,
+                    Event::FindDuplicates(),
+                );
+            }
+            if ui.button(Messages::MergeCategoriesOrCompanies).clicked() {
+                state.accounting.merge = Some(Merge::default());
+            }
+        });
+        render_validation_report(ctx, &mut state.accounting);
+        render_duplicates(ctx, &mut state.accounting, app_context);
+        render_merge(ctx, &mut state.accounting, config, app_context);
+        build_summary(&mut state.accounting, config.show_cents, ui);
+        items_table::build(ctx, &mut state.accounting, config, app_context, ui);
 
         add_edit::build(ctx, state, config, app_context, ui);
-        if ui.button(Messages::Export.msg()).clicked() {
-            let name_suggestion = build_file_name_suggestion(&state.accounting);
-            let mut dialog = ui::get_localized_save_file_dialog(
-                state.file_picker_startpoint.clone(),
-                Messages::SaveFile.msg(),
-            )
-            .default_filename(name_suggestion.unwrap_or_default());
-            dialog.open();
-            state.accounting.export_state.open_file_dialog = Some(dialog);
+        let selected_count = count_selected_items(&state.accounting);
+        let export_text = if selected_count > 0 {
+            format!("{} ({})", Messages::ExportSelected, selected_count)
+        } else {
+            Messages::Export.msg().to_owned()
+        };
+        ui.horizontal(|ui| {
+            if ui.button(export_text).clicked() {
+                let pages = estimate_export_pages(&state.accounting, config.page_size);
+                if pages > MANY_PAGES_WARNING_THRESHOLD {
+                    state.accounting.export_state.page_count_dialog = Some(Dialog::new(
+                        format!("{} {}", pages, Messages::PagesWillBeExported.msg()),
+                        Messages::Export.msg(),
+                        Messages::Cancel.msg(),
+                    ));
+                } else {
+                    open_export_file_dialog(state, config.accounting_export_template());
+                }
+            }
+            if ui
+                .add_enabled(
+                    !state.accounting.selected_all_time,
+                    Button::new(Messages::ExportAllMonths.msg()),
+                )
+                .clicked()
+            {
+                let mut dialog = ui::get_localized_select_folder_dialog(
+                    state.file_picker_startpoint.clone(),
+                    Messages::SelectFolder.msg(),
+                );
+                dialog.open();
+                state.accounting.export_state.open_folder_dialog = Some(dialog);
+            }
+            ui.separator();
+            ui.label(Messages::SubtotalsPer);
+            for (period, text) in [
+                (SubtotalPeriod::Month, Messages::Month),
+                (SubtotalPeriod::Quarter, Messages::Quarter),
+            ] {
+                if ui
+                    .add(SelectableLabel::new(
+                        state.accounting.csv_subtotal_period == period,
+                        text.msg(),
+                    ))
+                    .clicked()
+                {
+                    state.accounting.csv_subtotal_period = period;
+                }
+            }
+            if ui.button(Messages::ExportCSV).clicked() {
+                let name_suggestion = build_file_name_suggestion(
+                    &state.accounting,
+                    config.accounting_export_template(),
+                )
+                .map(|name| PathBuf::from(name).with_extension("csv"))
+                .map(|path| path.to_string_lossy().into_owned());
+                let mut dialog = ui::get_localized_save_file_dialog(
+                    state.file_picker_startpoint.clone(),
+                    Messages::SaveFile.msg(),
+                )
+                .default_filename(name_suggestion.unwrap_or_default());
+                dialog.open();
+                state.accounting.export_state.open_csv_dialog = Some(dialog);
+            }
+            ui.separator();
+            if selected_count > 0 && ui.button(Messages::ClearSelection).clicked() {
+                state.accounting.selected_items.clear();
+            }
+            if selected_count > 0
+                && ui
+                    .button(format!("{} ({})", Messages::CopyToNextYear, selected_count))
+                    .clicked()
+            {
+                // dry run - show how many items would be copied before doing it
+                state.accounting.copy_dialog = Some(Dialog::new(
+                    format!(
+                        "{} {} {}",
+                        selected_count,
+                        Messages::ItemsWillBeCopiedToNextYear.msg(),
+                        Messages::AttachedFilesAreReferenced.msg()
+                    ),
+                    Messages::Copy.msg(),
+                    Messages::Cancel.msg(),
+                ));
+            }
+        });
+        if let Some(ref dialog) = state.accounting.copy_dialog {
+            match dialog::render_dialog(ctx, dialog) {
+                DialogResponse::Ok => {
+                    if files::auto_backup_or_notify(config, &app_context.gui_event_sender) {
+                        copy_selected_items_to_next_year(&state.accounting, app_context, ctx);
+                    }
+                    state.accounting.selected_items.clear();
+                    state.accounting.copy_dialog = None;
+                }
+                DialogResponse::Cancel => {
+                    state.accounting.copy_dialog = None;
+                }
+                _ => (),
+            }
+        }
+        if let Some(ref dialog) = state.accounting.export_state.page_count_dialog {
+            match dialog::render_dialog(ctx, dialog) {
+                DialogResponse::Ok => {
+                    state.accounting.export_state.page_count_dialog = None;
+                    open_export_file_dialog(state, config.accounting_export_template());
+                }
+                DialogResponse::Cancel => {
+                    state.accounting.export_state.page_count_dialog = None;
+                }
+                _ => (),
+            }
         }
         if let Some(dialog) = &mut state.accounting.export_state.open_file_dialog {
             if dialog.show(ctx).selected() {
@@ -368,58 +1215,344 @@ pub(crate) fn build(
             }
         }
 
+        if let Some(dialog) = &mut state.accounting.export_state.open_folder_dialog {
+            if dialog.show(ctx).selected() {
+                if let Some(folder) = dialog.path() {
+                    util::send_event_and_request_repaint(
+                        ctx,
+                        &app_context.background_event_sender,
+                        Event::ExportMonths(MonthlyExport {
+                            folder: folder.to_path_buf(),
+                            year: state.accounting.selected_year,
+                            fiscal_year_start_month: state.accounting.fiscal_year_start_month,
+                            page_size: config.page_size,
+                            files_suffix: config.files_suffix().to_owned(),
+                            file_name_template: config.file_name_template().to_owned(),
+                            author: config.pdf_author.to_owned(),
+                        }),
+                    );
+                }
+                state.accounting.export_state.open_folder_dialog = None;
+            }
+        }
+
+        if let Some(dialog) = &mut state.accounting.export_state.open_csv_dialog {
+            if dialog.show(ctx).selected() {
+                if let (Some(file), Some(accounting_sheet)) =
+                    (dialog.path(), &state.accounting.selected_accounting_sheet)
+                {
+                    let path_buf = file.with_extension("csv");
+                    let selected_sheet = build_sheet_from_selection(
+                        accounting_sheet,
+                        &state.accounting.selected_items,
+                    );
+                    create_csv(
+                        &path_buf,
+                        selected_sheet.as_ref().unwrap_or(accounting_sheet),
+                        &state.accounting,
+                        app_context,
+                    );
+                    state.file_picker_startpoint = Some(path_buf);
+                }
+                state.accounting.export_state.open_csv_dialog = None;
+            }
+        }
+
         if let Some(ref path_buf) = state.accounting.export_state.selected_path {
             if let Some(ref accounting_sheet) = state.accounting.selected_accounting_sheet {
-                create_pdf(path_buf, accounting_sheet, app_context);
+                match build_sheet_from_selection(accounting_sheet, &state.accounting.selected_items)
+                {
+                    Some(selected_sheet) => {
+                        create_pdf(path_buf, &selected_sheet, config, app_context, ctx)
+                    }
+                    None => create_pdf(path_buf, accounting_sheet, config, app_context, ctx),
+                }
                 state.accounting.export_state.selected_path = None;
             }
         }
     });
 }
 
-fn create_pdf(path_buf: &Path, accounting_sheet: &AccountingSheet, app_context: &AppContext) {
-    match create_accounting_pdf(path_buf, accounting_sheet) {
-        Ok(CreatePDFResult { file, files_folder }) => {
-            info!("created pdf!");
-            let mut results = accounting_sheet
+// net, tax and gross of the selected period, either combined or by invoice type
+fn build_summary(state: &mut AccountingState, show_cents: bool, ui: &mut Ui) {
+    let Some(ref sheet) = state.selected_accounting_sheet else {
+        return;
+    };
+    let summary = calculate_summary(sheet);
+    let rows: Vec<(&str, AccountingSummary)> = if state.summary_by_type {
+        [
+            (Messages::Ingoing.msg(), InvoiceType::In),
+            (Messages::Outgoing.msg(), InvoiceType::Out),
+        ]
+        .into_iter()
+        .filter_map(|(label, invoice_type)| {
+            summary
+                .accounting
+                .get(&invoice_type)
+                .map(|s| (label, s.clone()))
+        })
+        .collect()
+    } else {
+        vec![(Messages::Total.msg(), summary.combined())]
+    };
+    ui.horizontal(|ui| {
+        [(false, Messages::Combined), (true, Messages::ByType)]
+            .into_iter()
+            .for_each(|(by_type, label)| {
+                if ui
+                    .add(SelectableLabel::new(
+                        state.summary_by_type == by_type,
+                        label.msg(),
+                    ))
+                    .clicked()
+                {
+                    state.summary_by_type = by_type;
+                }
+            });
+    });
+    Grid::new("accounting_summary")
+        .num_columns(4)
+        .show(ui, |ui| {
+            ui.label("");
+            ui.label(RichText::new(Messages::Net).strong());
+            ui.label(RichText::new(Messages::Tax).strong());
+            ui.label(RichText::new(Messages::Gross).strong());
+            ui.end_row();
+            rows.iter().for_each(|(label, sums)| {
+                ui.label(*label);
+                ui.label(sums.net.to_str_rounded(show_cents));
+                ui.label(sums.tax.to_str_rounded(show_cents));
+                ui.label(sums.gross.to_str_rounded(show_cents));
+                ui.end_row();
+            });
+        });
+    if summary.revenue_by_company.is_empty() {
+        return;
+    }
+    CollapsingHeader::new(Messages::RevenueByCompany.msg())
+        .id_salt("revenue_by_company")
+        .show(ui, |ui| {
+            Grid::new("revenue_by_company_grid")
+                .num_columns(3)
+                .show(ui, |ui| {
+                    ui.label(RichText::new(Messages::Company).strong());
+                    ui.label(RichText::new(Messages::Net).strong());
+                    ui.label(RichText::new(Messages::Share).strong());
+                    ui.end_row();
+                    summary
+                        .revenue_by_company
+                        .iter()
+                        .for_each(|(company, net)| {
+                            ui.label(&company.0);
+                            ui.label(net.to_str_rounded(show_cents));
+                            ui.label(
+                                summary
+                                    .revenue_share(net)
+                                    .map_or_else(String::new, |share| {
+                                        format!("{} %", format_quantity(&share, 1))
+                                    }),
+                            );
+                            ui.end_row();
+                        });
+                });
+        });
+}
+
+// a typed year, if it's within the selectable range
+fn parse_year(input: &str) -> Option<i32> {
+    input
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|year| SELECTABLE_YEARS.contains(year))
+}
+
+// the years offered in the year selector, newest first - the selected year is always included
+fn selectable_years(selected: i32, current: i32, span: u32) -> Vec<i32> {
+    let mut years: Vec<i32> = (current.saturating_sub_unsigned(span)..=current).collect();
+    if !years.contains(&selected) {
+        years.push(selected);
+    }
+    years.sort_unstable_by(|a, b| b.cmp(a));
+    years
+}
+
+fn count_selected_items(state: &AccountingState) -> usize {
+    state
+        .selected_accounting_sheet
+        .as_ref()
+        .map(|sheet| {
+            sheet
                 .items
                 .iter()
-                .enumerate()
-                .map(|(idx, item)| {
-                    let invoce_number = idx + 1;
-
-                    copy_file_and_rename(
-                        &invoce_number.to_string(),
-                        files_folder.as_path(),
-                        &item.file,
-                    )
-                    .map(|_| ())
-                });
-            if results.any(|r| r.is_err()) {
-                let error_count = results.filter(|x| x.is_err()).count();
-                info!(
-                    "Errors while copying invoices for PDF creation: {error_count} - rolling back pdf and files folder creation"
-                );
-                // rollback pdf and files folder creation
-                delete_file_and_folder(file.as_path(), files_folder.as_path());
+                .filter(|item| state.selected_items.contains(&item.id))
+                .count()
+        })
+        .unwrap_or(0)
+}
 
-                util::send_gui_event(
-                    &app_context.gui_event_sender,
-                    GuiEvent::ShowErrorNotification(format!(
-                        "{} {}",
-                        error_count,
-                        Messages::PDFFilesCopyFailed.msg(),
-                    )),
-                );
-            } else {
-                util::send_gui_event(
-                    &app_context.gui_event_sender,
-                    GuiEvent::ShowInfoNotification(String::from(Messages::PDFCreated.msg())),
+fn open_export_file_dialog(state: &mut State, template: &str) {
+    let name_suggestion = build_file_name_suggestion(&state.accounting, template);
+    let mut dialog = ui::get_localized_save_file_dialog(
+        state.file_picker_startpoint.clone(),
+        Messages::SaveFile.msg(),
+    )
+    .default_filename(name_suggestion.unwrap_or_default());
+    dialog.open();
+    state.accounting.export_state.open_file_dialog = Some(dialog);
+}
+
+fn estimate_export_pages(state: &AccountingState, page_size: PageSize) -> usize {
+    state
+        .selected_accounting_sheet
+        .as_ref()
+        .map(
+            |sheet| match build_sheet_from_selection(sheet, &state.selected_items) {
+                Some(selected_sheet) => estimate_accounting_pages(&selected_sheet, page_size),
+                None => estimate_accounting_pages(sheet, page_size),
+            },
+        )
+        .unwrap_or(0)
+}
+
+fn copy_selected_items_to_next_year(
+    state: &AccountingState,
+    app_context: &AppContext,
+    ctx: &Context,
+) {
+    if let Some(ref sheet) = state.selected_accounting_sheet {
+        let keys: Vec<String> = sheet
+            .items
+            .iter()
+            .filter(|item| state.selected_items.contains(&item.id))
+            .map(DB::get_key_for_item)
+            .collect();
+        util::send_event_and_request_repaint(
+            ctx,
+            &app_context.background_event_sender,
+            Event::CopyItemsToNextYear(keys, selected_date_range(state)),
+        );
+    }
+}
+
+// builds a transient sheet containing only the selected items, if there are any
+fn build_sheet_from_selection(
+    sheet: &AccountingSheet,
+    selected_items: &HashSet<Uuid>,
+) -> Option<AccountingSheet> {
+    let items: Vec<AccountingItem> = sheet
+        .items
+        .iter()
+        .filter(|item| selected_items.contains(&item.id))
+        .cloned()
+        .collect();
+    if items.is_empty() {
+        return None;
+    }
+    Some(AccountingSheet {
+        year: sheet.year,
+        quarter: sheet.quarter,
+        month: sheet.month,
+        all_time: sheet.all_time,
+        items,
+    })
+}
+
+// writes the PDF and copies the item files beside it - rolls both back, if copying fails
+fn export_sheet(
+    path_buf: &Path,
+    accounting_sheet: &AccountingSheet,
+    page_size: PageSize,
+    files_suffix: &str,
+    file_name_template: &str,
+    author: &str,
+) -> Result<PathBuf, SheetExportError> {
+    let CreatePDFResult { file, files_folder } =
+        create_accounting_pdf(path_buf, accounting_sheet, page_size, files_suffix, author)
+            .map_err(|e| {
+                log::error!("PDF was not created: {}", e);
+                SheetExportError::PdfNotCreated
+            })?;
+    info!("created pdf!");
+    let error_count = accounting_sheet
+        .items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let invoce_number = idx + 1;
+
+            let name = apply_file_name_template(file_name_template, invoce_number, item);
+            copy_file_and_rename(
+                &unique_file_name(files_folder.as_path(), &name, &item.file),
+                files_folder.as_path(),
+                &item.file,
+            )
+        })
+        .filter(|r| r.is_err())
+        .count();
+    if error_count > 0 {
+        info!(
+            "Errors while copying invoices for PDF creation: {error_count} - rolling back pdf and files folder creation"
+        );
+        // rollback pdf and files folder creation
+        delete_file_and_folder(file.as_path(), files_folder.as_path());
+        return Err(SheetExportError::FilesNotCopied(error_count));
+    }
+    Ok(file)
+}
+
+fn create_pdf(
+    path_buf: &Path,
+    accounting_sheet: &AccountingSheet,
+    config: &Config,
+    app_context: &AppContext,
+    ctx: &Context,
+) {
+    match export_sheet(
+        path_buf,
+        accounting_sheet,
+        config.page_size,
+        config.files_suffix(),
+        config.file_name_template(),
+        &config.pdf_author,
+    ) {
+        Ok(file) => {
+            util::send_gui_event(
+                &app_context.gui_event_sender,
+                GuiEvent::ShowInfoNotification(String::from(Messages::PDFCreated.msg())),
+            );
+            util::send_gui_event(
+                &app_context.gui_event_sender,
+                GuiEvent::SetLastExport(file.clone()),
+            );
+            util::send_event_and_request_repaint(
+                ctx,
+                &app_context.background_event_sender,
+                Event::RecordExport(ExportLogEntry::new(
+                    ExportType::Accounting,
+                    file.clone(),
+                    period_name(accounting_sheet),
+                )),
+            );
+            if config.open_pdf_after_export {
+                util::send_event_and_request_repaint(
+                    ctx,
+                    &app_context.background_event_sender,
+                    Event::OpenFile(file.to_string_lossy().into_owned()),
                 );
             }
         }
-        Err(e) => {
-            log::error!("PDF was not created: {}", e);
+        Err(SheetExportError::FilesNotCopied(error_count)) => {
+            util::send_gui_event(
+                &app_context.gui_event_sender,
+                GuiEvent::ShowErrorNotification(format!(
+                    "{} {}",
+                    error_count,
+                    Messages::PDFFilesCopyFailed.msg(),
+                )),
+            );
+        }
+        Err(SheetExportError::PdfNotCreated) => {
             util::send_gui_event(
                 &app_context.gui_event_sender,
                 GuiEvent::ShowErrorNotification(String::from(Messages::PDFNotCreated.msg())),
@@ -428,25 +1561,240 @@ fn create_pdf(path_buf: &Path, accounting_sheet: &AccountingSheet, app_context:
     }
 }
 
-fn select_date_range(state: &mut State, app_context: &AppContext, ctx: &Context) {
-    let date_range = get_date_range_for_settings(
-        state.accounting.selected_year,
-        state.accounting.selected_quarter,
-        state.accounting.selected_month,
-    );
+fn create_csv(
+    path_buf: &Path,
+    accounting_sheet: &AccountingSheet,
+    state: &AccountingState,
+    app_context: &AppContext,
+) {
+    match export_accounting_csv_with_subtotals(
+        path_buf,
+        accounting_sheet,
+        state.csv_subtotal_period,
+        state.fiscal_year_start_month,
+    ) {
+        Ok(()) => {
+            util::send_gui_event(
+                &app_context.gui_event_sender,
+                GuiEvent::ShowInfoNotification(String::from(Messages::CSVCreated.msg())),
+            );
+        }
+        Err(e) => {
+            log::error!("CSV was not created: {e}");
+            util::send_gui_event(
+                &app_context.gui_event_sender,
+                GuiEvent::ShowErrorNotification(String::from(Messages::CSVNotCreated.msg())),
+            );
+        }
+    }
+}
+
+// the months of the fiscal year in order, each with its first day
+fn fiscal_months(year: i32, fiscal_year_start_month: u32) -> Vec<(Month, NaiveDate)> {
+    let start: u32 = Month::from(fiscal_year_start_month).into();
+    (0..12)
+        .map(|offset| {
+            let month = Month::from((start - 1 + offset) % 12 + 1);
+            let (from, _) = util::time_span(year, None, Some(month), fiscal_year_start_month);
+            (month, from)
+        })
+        .collect()
+}
 
+// exports every month of the year with items as "<year>-<month>.pdf" into the folder
+pub(crate) fn export_months(
+    export: &MonthlyExport,
+    db: &DB,
+    sender: &Sender<GuiEvent>,
+) -> MonthlyExportSummary {
+    let mut summary = MonthlyExportSummary::default();
+    for (month, from) in fiscal_months(export.year, export.fiscal_year_start_month) {
+        let date_range = get_date_range_for_settings(
+            export.year,
+            None,
+            Some(month),
+            export.fiscal_year_start_month,
+        );
+        let items = match db.get_accounting_items_for_range(&date_range) {
+            Ok(items) => items,
+            Err(e) => {
+                log::error!("Could not fetch items for {}: {e}", month.name());
+                summary.failed += 1;
+                continue;
+            }
+        };
+        if items.is_empty() {
+            summary.skipped += 1;
+            continue;
+        }
+        let sheet = AccountingSheet {
+            year: export.year,
+            quarter: None,
+            month: Some(month),
+            all_time: false,
+            items,
+        };
+        let file_name = format!("{}.pdf", from.format("%Y-%m"));
+        match export_sheet(
+            &export.folder.join(&file_name),
+            &sheet,
+            export.page_size,
+            &export.files_suffix,
+            &export.file_name_template,
+            &export.author,
+        ) {
+            Ok(file) => {
+                util::send_gui_event(
+                    sender,
+                    GuiEvent::ShowInfoNotification(format!(
+                        "{} {}",
+                        file_name,
+                        Messages::MonthExported.msg()
+                    )),
+                );
+                summary.exports.push(ExportLogEntry::new(
+                    ExportType::Accounting,
+                    file,
+                    period_name(&sheet),
+                ));
+            }
+            Err(e) => {
+                log::error!("Could not export {file_name}: {e:?}");
+                summary.failed += 1;
+            }
+        }
+    }
+    summary
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CurrentPeriod {
+    Month,
+    Quarter,
+    Year,
+}
+
+// the selection for the period containing the given day, within its fiscal year
+fn current_period(
+    period: CurrentPeriod,
+    date: NaiveDate,
+    fiscal_year_start_month: u32,
+) -> (i32, Option<Quarter>, Option<Month>) {
+    let year = util::fiscal_year_of(date, fiscal_year_start_month);
+    match period {
+        CurrentPeriod::Month => (year, None, Some(Month::from(date.month()))),
+        CurrentPeriod::Quarter => (
+            year,
+            Some(Quarter::from_month(date.month(), fiscal_year_start_month)),
+            None,
+        ),
+        CurrentPeriod::Year => (year, None, None),
+    }
+}
+
+// selects the period containing the date, in the selectors as well
+fn select_period_of(
+    period: CurrentPeriod,
+    date: NaiveDate,
+    state: &mut State,
+    config: &mut Config,
+    app_context: &AppContext,
+    ctx: &Context,
+) {
+    let (year, quarter, month) =
+        current_period(period, date, state.accounting.fiscal_year_start_month);
+    let accounting = &mut state.accounting;
+    accounting.year_selector_selected = year;
+    accounting.quarter_selector_selected = quarter;
+    accounting.month_selector_selected = month;
+    accounting.all_time_selector_selected = false;
+    accounting.selected_year = year;
+    accounting.selected_quarter = quarter;
+    accounting.selected_month = month;
+    accounting.selected_all_time = false;
+    save_accounting_period(&state.accounting, config, app_context);
+    select_date_range(state, app_context, ctx);
+}
+
+fn save_accounting_period(state: &AccountingState, config: &mut Config, app_context: &AppContext) {
+    config.accounting_period = Some(AccountingPeriod {
+        year: state.selected_year,
+        quarter: state.selected_quarter,
+        month: state.selected_month,
+        all_time: state.selected_all_time,
+    });
+    config::save_config_or_notify(config, &app_context.gui_event_sender);
+}
+
+// the date range used for re-fetching items after changes
+pub(crate) fn selected_date_range(state: &AccountingState) -> DateRange {
+    if state.selected_all_time {
+        return get_date_range_for_all_time();
+    }
+    get_date_range_for_settings(
+        state.selected_year,
+        state.selected_quarter,
+        state.selected_month,
+        state.fiscal_year_start_month,
+    )
+}
+
+// only pre-fill values the user hasn't set explicitly
+pub(crate) fn apply_category_defaults(
+    state: &mut AccountingState,
+    category: &str,
+    defaults: CategoryDefaults,
+) {
+    let item = &mut state.item;
+    if item.category != category {
+        return;
+    }
+    if !item.invoice_type_changed {
+        item.invoice_type = defaults.invoice_type;
+    }
+    if !item.vat_changed {
+        item.vat = defaults.vat;
+    }
+}
+
+pub(crate) fn select_date_range(state: &mut State, app_context: &AppContext, ctx: &Context) {
+    state.accounting.selected_items.clear();
+    state.accounting.items_fetched = false;
     state.accounting.selected_accounting_sheet = Some(AccountingSheet {
         year: state.accounting.selected_year,
         quarter: state.accounting.selected_quarter,
         month: state.accounting.selected_month,
+        all_time: state.accounting.selected_all_time,
         items: vec![],
     });
 
-    util::send_event_and_request_repaint(
-        ctx,
-        &app_context.background_event_sender,
-        Event::FetchItems(date_range),
-    );
+    let event = if state.accounting.selected_all_time {
+        Event::FetchAllItems()
+    } else {
+        Event::FetchItems(selected_date_range(&state.accounting))
+    };
+    util::send_event_and_request_repaint(ctx, &app_context.background_event_sender, event);
+}
+
+// re-fetches the current sheet and the suggestions, e.g. after external changes
+fn refresh(state: &State, app_context: &AppContext, ctx: &Context) {
+    if state.accounting.selected_accounting_sheet.is_some() {
+        let event = if state.accounting.selected_all_time {
+            Event::FetchAllItems()
+        } else {
+            Event::FetchItems(selected_date_range(&state.accounting))
+        };
+        util::send_event_and_request_repaint(ctx, &app_context.background_event_sender, event);
+    }
+    [
+        Event::FetchNames(),
+        Event::FetchCompanies(),
+        Event::FetchCategories(),
+    ]
+    .into_iter()
+    .for_each(|event| {
+        util::send_event_and_request_repaint(ctx, &app_context.background_event_sender, event);
+    });
 }
 
 fn add_button(ui: &mut Ui, state: &mut State) {
@@ -460,3 +1808,311 @@ fn add_button(ui: &mut Ui, state: &mut State) {
         state.accounting.mode = Mode::Add;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Days;
+
+    fn state() -> AccountingState {
+        let config: Config = toml::from_str("language = \"en\"").unwrap();
+        let mut state = AccountingState::new(&config);
+        state.selected_all_time = true;
+        state
+    }
+
+    #[test]
+    fn future_date_is_a_warning() {
+        let state = state();
+        let mut item = Item::new();
+        let tomorrow = chrono::Local::now().date_naive() + Days::new(1);
+        item.date_field = tomorrow.format(date_format()).to_string();
+        item.name = String::from("Rent");
+        item.company = String::from("Landlord");
+        item.category = String::from("Office");
+        item.file = PathBuf::from("invoice.pdf");
+
+        let result = item.validate(&state);
+        assert!(!result.has_errors());
+        assert_eq!(
+            result.get_warnings(&Field::Date),
+            Some(&vec![Messages::DateInFuture.msg().to_owned()])
+        );
+
+        item.date_field = chrono::Local::now()
+            .date_naive()
+            .format(date_format())
+            .to_string();
+        assert!(item.validate(&state).get_warnings(&Field::Date).is_none());
+    }
+
+    #[test]
+    fn date_out_of_range_follows_field_and_period() {
+        let mut state = state();
+        state.selected_all_time = false;
+        state.selected_year = 2024;
+        state.selected_quarter = Some(Quarter::Q1);
+        state.fiscal_year_start_month = 1;
+        state.item.date_field = String::from("15.02.2024");
+        assert!(!is_item_date_out_of_range(&state));
+
+        state.item.date_field = String::from("15.05.2024");
+        assert!(is_item_date_out_of_range(&state));
+
+        // switching the period while the form is open
+        state.selected_quarter = Some(Quarter::Q2);
+        assert!(!is_item_date_out_of_range(&state));
+        state.selected_year = 2025;
+        assert!(is_item_date_out_of_range(&state));
+        state.selected_all_time = true;
+        assert!(!is_item_date_out_of_range(&state));
+
+        // unparseable dates are an error on save instead
+        state.selected_all_time = false;
+        state.item.date_field = String::from("15.05.");
+        assert!(!is_item_date_out_of_range(&state));
+    }
+
+    #[test]
+    fn next_item_keeps_date_company_and_category() {
+        let mut item = Item::new();
+        item.date_field = String::from("01.02.2024");
+        item.name = String::from("Taxi");
+        item.company = String::from("Cab Co");
+        item.category = String::from("Travel");
+        item.net = String::from("25");
+        item.note = String::from("airport");
+
+        let next = item.next(true);
+        assert_ne!(next.id, item.id);
+        assert_eq!(next.date_field, "01.02.2024");
+        assert_eq!(next.company, "Cab Co");
+        assert_eq!(next.category, "Travel");
+        assert!(next.name.is_empty());
+        assert!(next.note.is_empty());
+        assert_eq!(next.net, Item::new().net);
+
+        let next = item.next(false);
+        assert!(next.company.is_empty());
+        assert!(next.category.is_empty());
+        assert_eq!(next.date_field, Item::new().date_field);
+    }
+
+    #[test]
+    fn unsupported_file_type_is_an_error() {
+        let state = state();
+        let mut item = Item::new();
+        item.name = String::from("Rent");
+        item.company = String::from("Landlord");
+        item.category = String::from("Office");
+        item.net = String::from("100");
+        item.file = PathBuf::from("invoice.PDF");
+        assert!(item.validate(&state).get_errors(&Field::File).is_none());
+
+        item.file = PathBuf::from("invoice.docx");
+        assert!(item.validate(&state).get_errors(&Field::File).is_some());
+        item.file = PathBuf::from("invoice");
+        assert!(item.validate(&state).get_errors(&Field::File).is_some());
+    }
+
+    #[test]
+    fn sheet_validation_lists_problem_rows() {
+        let mut state = state();
+        let mut item = Item::new();
+        item.name = String::from("Rent");
+        item.company = String::from("Landlord");
+        item.category = String::from("Office");
+        item.file = PathBuf::from(file!());
+        let valid = AccountingItem::from(&item);
+        let mut missing_file = valid.clone();
+        missing_file.name = String::from("Phone");
+        missing_file.file = PathBuf::from("does/not/exist.pdf");
+        let mut no_company = valid.clone();
+        no_company.company = Company(String::from(" "));
+        state.selected_accounting_sheet = Some(AccountingSheet {
+            year: state.selected_year,
+            quarter: None,
+            month: None,
+            all_time: true,
+            items: vec![valid, missing_file, no_company],
+        });
+
+        let report = validate_sheet(&state);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].nr, 2);
+        assert_eq!(report[0].name, "Phone");
+        assert_eq!(report[0].problems, vec![Messages::FileDoesNotExist.msg()]);
+        assert_eq!(report[1].nr, 3);
+        assert_eq!(
+            report[1].problems,
+            vec![format!("{} {}", Messages::Company, Messages::CanNotBeEmpty)]
+        );
+    }
+
+    fn receipt() -> Item {
+        let mut item = Item::new();
+        item.name = String::from("Supermarket");
+        item.company = String::from("Shop");
+        item.category = String::from("Groceries");
+        item.net = String::from("100.00");
+        item.file = PathBuf::from("receipt.pdf");
+        item
+    }
+
+    #[test]
+    fn splits_have_to_add_up_to_the_net() {
+        let state = state();
+        let mut item = receipt();
+        item.add_split();
+        assert_eq!(item.splits.len(), 2);
+        assert_eq!(item.splits[0].category, "Groceries");
+        assert_eq!(item.unsplit_net(), Some(Decimal::ZERO));
+        // the second split has no category yet
+        assert!(item.validate(&state).get_errors(&Field::Splits).is_some());
+
+        item.splits[0].net = String::from("70.00");
+        item.splits[1].category = String::from("Office");
+        item.splits[1].net = String::from("20.00");
+        assert_eq!(item.unsplit_net(), Some(Decimal::new(10, 0)));
+        assert_eq!(
+            item.validate(&state).get_errors(&Field::Splits),
+            Some(&vec![Messages::SplitsDoNotAddUpToNet.msg().to_owned()])
+        );
+
+        item.splits[1].net = String::from("30.00");
+        // the category of the item itself isn't used anymore
+        item.category = String::default();
+        assert!(item.validate(&state).is_ok());
+
+        item.splits[1].net = String::from("abc");
+        assert_eq!(item.unsplit_net(), None);
+        assert_eq!(
+            item.validate(&state).get_errors(&Field::Splits),
+            Some(&vec![format!("{} {}", Messages::Net, Messages::NotANumber)])
+        );
+    }
+
+    #[test]
+    fn split_items_share_everything_but_category_and_net() {
+        let mut item = receipt();
+        item.add_split();
+        item.splits[0].net = String::from("70.00");
+        item.splits[1].category = String::from(" Office ");
+        item.splits[1].net = String::from("30.00");
+
+        let items = item.split_items();
+        assert_eq!(items.len(), 2);
+        assert_ne!(items[0].id, items[1].id);
+        assert!(items.iter().all(|i| i.id != item.id));
+        assert!(
+            items
+                .iter()
+                .all(|i| i.file == item.file && i.name == item.name)
+        );
+        assert_eq!(items[0].category.0, "Groceries");
+        assert_eq!(items[0].net.to_value_string(), "70.00");
+        assert_eq!(items[1].category.0, "Office");
+        assert_eq!(items[1].net.to_value_string(), "30.00");
+    }
+
+    #[test]
+    fn fetched_items_only_replace_the_selected_sheet() {
+        let mut state = state();
+        let item = AccountingItem::from(&Item::new());
+        let all_time = get_date_range_for_all_time();
+
+        // nothing selected yet
+        state.set_fetched_items(&all_time, vec![item.clone()]);
+        assert!(state.selected_accounting_sheet.is_none());
+
+        state.selected_accounting_sheet = Some(AccountingSheet {
+            year: state.selected_year,
+            quarter: None,
+            month: None,
+            all_time: true,
+            items: vec![],
+        });
+        state.set_fetched_items(&all_time, vec![item.clone()]);
+        assert_eq!(
+            state
+                .selected_accounting_sheet
+                .as_ref()
+                .unwrap()
+                .items
+                .len(),
+            1
+        );
+
+        // a late response for a range that was selected before
+        let stale = get_date_range_for_settings(2020, None, None, 1);
+        state.set_fetched_items(&stale, vec![]);
+        assert_eq!(
+            state.selected_accounting_sheet.as_ref().unwrap().items,
+            vec![item]
+        );
+    }
+
+    #[test]
+    fn fiscal_months_start_with_the_fiscal_year() {
+        let months = fiscal_months(2024, 7);
+        assert_eq!(months.len(), 12);
+        assert_eq!(
+            months[0],
+            (Month::July, NaiveDate::from_ymd_opt(2024, 7, 1).unwrap())
+        );
+        assert_eq!(
+            months[11],
+            (Month::June, NaiveDate::from_ymd_opt(2025, 6, 1).unwrap())
+        );
+        assert_eq!(fiscal_months(2024, 1)[0].0, Month::January);
+    }
+
+    #[test]
+    fn current_period_follows_the_fiscal_year() {
+        let today = NaiveDate::from_ymd_opt(2025, 2, 14).unwrap();
+        assert_eq!(
+            current_period(CurrentPeriod::Month, today, 1),
+            (2025, None, Some(Month::February))
+        );
+        assert_eq!(
+            current_period(CurrentPeriod::Quarter, today, 1),
+            (2025, Some(Quarter::Q1), None)
+        );
+        assert_eq!(
+            current_period(CurrentPeriod::Year, today, 1),
+            (2025, None, None)
+        );
+
+        let fiscal_year = util::fiscal_year_of(today, 7);
+        assert_eq!(
+            current_period(CurrentPeriod::Quarter, today, 7),
+            (fiscal_year, Some(Quarter::Q3), None)
+        );
+        // the range contains the given day
+        let (year, quarter, month) = current_period(CurrentPeriod::Month, today, 7);
+        let (from, to) = util::time_span(year, quarter, month, 7);
+        assert!(from <= today && today <= to);
+    }
+
+    #[test]
+    fn year_selection_covers_span_and_selected_year() {
+        assert_eq!(
+            selectable_years(2024, 2025, 3),
+            vec![2025, 2024, 2023, 2022]
+        );
+        assert_eq!(
+            selectable_years(1990, 2025, 2),
+            vec![2025, 2024, 2023, 1990]
+        );
+        assert_eq!(selectable_years(2025, 2025, 0), vec![2025]);
+    }
+
+    #[test]
+    fn typed_years_have_to_be_in_range() {
+        assert_eq!(parse_year(" 1999 "), Some(1999));
+        assert_eq!(parse_year("2200"), Some(2200));
+        assert_eq!(parse_year("999999"), None);
+        assert_eq!(parse_year("-300000"), None);
+        assert_eq!(parse_year("next"), None);
+    }
+}
@@ -3,40 +3,418 @@ use serde::{Deserialize, Serialize};
 
 use std::fs::{create_dir_all, File};
 use std::io::{Read, Write};
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use std::sync::mpsc::Sender;
 
-use crate::messages::Language;
-use crate::update_language;
+use crate::data::currency::NumberLocale;
+use crate::data::BankAccount;
+use crate::messages::{Language, Messages};
+use crate::ui::autosuggest::{SuggestionLimits, DEFAULT_MAX_SUGGESTIONS};
+use crate::util::files::{
+    DEFAULT_ACCOUNTING_EXPORT_TEMPLATE, DEFAULT_AUTO_BACKUP_COUNT, DEFAULT_FILE_NAME_TEMPLATE,
+    DEFAULT_INVOICE_EXPORT_TEMPLATE, SUFFIX_FOR_FILES,
+};
+use crate::util::{self, Month, Quarter};
+use crate::{
+    update_date_format, update_language, update_number_format, update_suggestion_limits, GuiEvent,
+};
 
 const APP_NAME: &str = "helferlein";
 const CONFIG_FILE: &str = "config.toml";
 
+pub(crate) const MAX_SUGGESTIONS_RANGE: RangeInclusive<usize> = 1..=500;
+pub(crate) const MIN_WINDOW_SIZE_RANGE: RangeInclusive<f32> = 200.0..=2000.0;
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub(crate) struct Config {
     pub(crate) data_folder: Option<PathBuf>,
     pub(crate) file_open_command: Option<String>,
     pub(crate) language: String,
+    #[serde(default)]
+    pub(crate) page_size: PageSize,
+    #[serde(default)]
+    pub(crate) accounting_period: Option<AccountingPeriod>,
+    #[serde(default)]
+    pub(crate) category_defaults: bool,
+    #[serde(default)]
+    pub(crate) open_pdf_after_export: bool,
+    // suffix of the folder the item files are copied to on export
+    #[serde(default)]
+    pub(crate) files_suffix: Option<String>,
+    // name of the exported item files, e.g. "{nr}_{company}"
+    #[serde(default)]
+    pub(crate) file_name_template: Option<String>,
+    // suggested names of exported files, empty for the default, see util::files for the placeholders
+    #[serde(default)]
+    pub(crate) accounting_export_template: String,
+    #[serde(default)]
+    pub(crate) invoice_export_template: String,
+    // the month the fiscal year starts in, quarters and years are computed from it
+    #[serde(default = "default_fiscal_year_start_month")]
+    pub(crate) fiscal_year_start_month: u32,
+    #[serde(default)]
+    pub(crate) number_format: NumberFormat,
+    #[serde(default)]
+    pub(crate) date_format: DateFormat,
+    // decimal places of item quantities on exported invoices
+    #[serde(default = "default_quantity_decimals")]
+    pub(crate) quantity_decimals: u32,
+    // shifts the invoice content down, e.g. below a pre-printed letterhead
+    #[serde(default)]
+    pub(crate) top_margin_offset_mm: f32,
+    // how many years back the year selector goes
+    #[serde(default = "default_year_history_span")]
+    pub(crate) year_history_span: u32,
+    // background of accounting rows by invoice type
+    #[serde(default)]
+    pub(crate) color_rows: bool,
+    #[serde(default)]
+    pub(crate) always_on_top: bool,
+    // the window can't be made smaller than this
+    #[serde(default = "default_min_window_width")]
+    pub(crate) min_window_width: f32,
+    #[serde(default = "default_min_window_height")]
+    pub(crate) min_window_height: f32,
+    // item files are copied to a subfolder per year of the item's date
+    #[serde(default)]
+    pub(crate) files_per_year: bool,
+    // author in the metadata of accounting PDFs - invoices use their sender
+    #[serde(default)]
+    pub(crate) pdf_author: String,
+    // an EPC payment QR code on invoices, from the IBAN and BIC in the bank data
+    #[serde(default)]
+    pub(crate) epc_qr_code: bool,
+    // the VAT amount of each line in the invoice table
+    #[serde(default)]
+    pub(crate) invoice_vat_column: bool,
+    // pre-filled on new invoices
+    #[serde(default)]
+    pub(crate) bank_account: BankAccount,
+    // ask before closing the app with an unsaved invoice
+    #[serde(default = "default_confirm_exit")]
+    pub(crate) confirm_exit: bool,
+    #[serde(default = "default_max_suggestions")]
+    pub(crate) max_suggestions: usize,
+    // characters to type, before names, companies and categories are suggested
+    #[serde(default)]
+    pub(crate) min_suggestion_input: usize,
+    // as resized by the user, empty for the default widths
+    #[serde(default)]
+    pub(crate) accounting_column_widths: Vec<f32>,
+    // otherwise, totals and summaries are rounded to whole units
+    #[serde(default = "default_show_cents")]
+    pub(crate) show_cents: bool,
+    // once it's exported, a loaded draft is most likely done
+    #[serde(default = "default_delete_draft_after_export")]
+    pub(crate) delete_draft_after_export: bool,
+    // copies the database to the backups folder before risky operations, keeping the newest ones
+    #[serde(default)]
+    pub(crate) auto_backup: bool,
+    #[serde(default = "default_auto_backup_count")]
+    pub(crate) auto_backup_count: usize,
+    // keeps date, company and category in the item form after saving, for batch entry
+    #[serde(default)]
+    pub(crate) keep_item_fields: bool,
+    // shows the activity log in the settings
+    #[serde(default)]
+    pub(crate) developer_mode: bool,
+}
+
+fn non_empty_or<'a>(value: &'a str, default: &'a str) -> &'a str {
+    if value.trim().is_empty() {
+        default
+    } else {
+        value
+    }
+}
+
+fn default_fiscal_year_start_month() -> u32 {
+    1
+}
+
+fn default_quantity_decimals() -> u32 {
+    2
+}
+
+fn default_year_history_span() -> u32 {
+    10
+}
+
+fn default_min_window_width() -> f32 {
+    640.0
+}
+
+fn default_min_window_height() -> f32 {
+    480.0
+}
+
+fn default_confirm_exit() -> bool {
+    true
+}
+
+fn default_show_cents() -> bool {
+    true
+}
+
+fn default_delete_draft_after_export() -> bool {
+    true
+}
+
+fn default_auto_backup_count() -> usize {
+    DEFAULT_AUTO_BACKUP_COUNT
+}
+
+fn default_max_suggestions() -> usize {
+    DEFAULT_MAX_SUGGESTIONS
+}
+
+impl Config {
+    pub(crate) fn suggestion_limits(&self) -> SuggestionLimits {
+        SuggestionLimits {
+            max_results: self.max_suggestions,
+            min_input_length: self.min_suggestion_input,
+        }
+    }
+
+    pub(crate) fn files_suffix(&self) -> &str {
+        self.files_suffix.as_deref().unwrap_or(SUFFIX_FOR_FILES)
+    }
+
+    pub(crate) fn file_name_template(&self) -> &str {
+        self.file_name_template
+            .as_deref()
+            .unwrap_or(DEFAULT_FILE_NAME_TEMPLATE)
+    }
+
+    pub(crate) fn accounting_export_template(&self) -> &str {
+        non_empty_or(
+            &self.accounting_export_template,
+            DEFAULT_ACCOUNTING_EXPORT_TEMPLATE,
+        )
+    }
+
+    pub(crate) fn invoice_export_template(&self) -> &str {
+        non_empty_or(
+            &self.invoice_export_template,
+            DEFAULT_INVOICE_EXPORT_TEMPLATE,
+        )
+    }
+}
+
+// the last selected accounting period, restored on startup
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct AccountingPeriod {
+    pub(crate) year: i32,
+    pub(crate) quarter: Option<Quarter>,
+    pub(crate) month: Option<Month>,
+    #[serde(default)]
+    pub(crate) all_time: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) enum PageSize {
+    #[default]
+    A4,
+    Letter,
+}
+
+impl PageSize {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            PageSize::A4 => "A4",
+            PageSize::Letter => "Letter",
+        }
+    }
+
+    // width and height in portrait orientation in mm
+    pub(crate) fn dimensions(&self) -> (f32, f32) {
+        match self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+// problems found in the config on load, shown as notifications on startup
+#[derive(Debug, PartialEq)]
+pub(crate) enum ConfigWarning {
+    DataFolderMissing(PathBuf),
+    UnknownLanguage(String),
+    // the key of the setting and the value it was clamped to
+    OutOfRange(&'static str, String),
+}
+
+impl ConfigWarning {
+    pub(crate) fn msg(&self) -> String {
+        match self {
+            ConfigWarning::DataFolderMissing(path) => {
+                format!("{}: {}", Messages::DataFolderMissing, path.display())
+            }
+            ConfigWarning::UnknownLanguage(language) => {
+                format!("{}: {}", Messages::UnknownLanguage, language)
+            }
+            ConfigWarning::OutOfRange(key, value) => {
+                format!("{}: {} = {}", Messages::SettingOutOfRange, key, value)
+            }
+        }
+    }
+}
+
+// how numbers are displayed and parsed - by default the same as the language
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) enum NumberFormat {
+    #[default]
+    FollowLanguage,
+    DeDe,
+    EnUs,
 }
 
-pub(crate) fn load_config() -> Result<Config> {
+impl NumberFormat {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            NumberFormat::FollowLanguage => Messages::FollowLanguage.msg(),
+            NumberFormat::DeDe => "1.234,56",
+            NumberFormat::EnUs => "1,234.56",
+        }
+    }
+
+    pub(crate) fn locale(&self, language: Language) -> NumberLocale {
+        match self {
+            NumberFormat::DeDe => NumberLocale::DeDe,
+            NumberFormat::EnUs => NumberLocale::EnUs,
+            NumberFormat::FollowLanguage => match language {
+                Language::EN => NumberLocale::EnUs,
+                Language::DE | Language::ES | Language::IT => NumberLocale::DeDe,
+            },
+        }
+    }
+}
+
+// how dates are displayed and entered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) enum DateFormat {
+    #[default]
+    DayMonthYear,
+    MonthDayYear,
+    Iso,
+}
+
+impl DateFormat {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            DateFormat::DayMonthYear => "31.12.2024",
+            DateFormat::MonthDayYear => "12/31/2024",
+            DateFormat::Iso => "2024-12-31",
+        }
+    }
+
+    pub(crate) fn pattern(&self) -> &'static str {
+        match self {
+            DateFormat::DayMonthYear => "%d.%m.%Y",
+            DateFormat::MonthDayYear => "%m/%d/%Y",
+            DateFormat::Iso => "%Y-%m-%d",
+        }
+    }
+}
+
+pub(crate) fn load_config() -> Result<(Config, Vec<ConfigWarning>)> {
     let config_file = check_config_exists()?;
     let mut file = File::open(&config_file)?;
     let mut buf = String::default();
     File::read_to_string(&mut file, &mut buf)?;
-    let res: Config = toml::from_str(&buf)?;
+    let mut res: Config = toml::from_str(&buf)?;
+    let warnings = validate_config(&mut res);
     update_language(&res.language);
-    Ok(res)
+    update_number_format(res.number_format);
+    update_date_format(res.date_format);
+    update_suggestion_limits(res.suggestion_limits());
+    Ok((res, warnings))
+}
+
+// resets invalid values, so the app can start with a usable config
+fn validate_config(config: &mut Config) -> Vec<ConfigWarning> {
+    let mut warnings = vec![];
+    if let Some(ref data_folder) = config.data_folder {
+        if !data_folder.is_dir() {
+            log::warn!("data folder {} does not exist", data_folder.display());
+            warnings.push(ConfigWarning::DataFolderMissing(data_folder.clone()));
+            // triggers the folder picker
+            config.data_folder = None;
+        }
+    }
+    if Language::from(config.language.as_str()).name() != config.language {
+        log::warn!("unknown language {} in config", config.language);
+        warnings.push(ConfigWarning::UnknownLanguage(config.language.clone()));
+        config.language = Language::EN.name().into();
+    }
+    clamp_setting(
+        "max_suggestions",
+        &mut config.max_suggestions,
+        &MAX_SUGGESTIONS_RANGE,
+        &mut warnings,
+    );
+    clamp_setting(
+        "min_window_width",
+        &mut config.min_window_width,
+        &MIN_WINDOW_SIZE_RANGE,
+        &mut warnings,
+    );
+    clamp_setting(
+        "min_window_height",
+        &mut config.min_window_height,
+        &MIN_WINDOW_SIZE_RANGE,
+        &mut warnings,
+    );
+    warnings
+}
+
+// values outside of the range are set to its closest end, NaN to its start
+fn clamp_setting<T: PartialOrd + Copy + std::fmt::Display>(
+    key: &'static str,
+    value: &mut T,
+    range: &RangeInclusive<T>,
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    if range.contains(value) {
+        return;
+    }
+    log::warn!("{key} = {value} in config is out of range");
+    *value = if *value > *range.end() {
+        *range.end()
+    } else {
+        *range.start()
+    };
+    warnings.push(ConfigWarning::OutOfRange(key, value.to_string()));
 }
 
 pub(crate) fn save_config(config: &Config) -> Result<()> {
     let config_file = check_config_exists()?;
-    let serialized = toml::to_string(&config)?;
+    let serialized = toml::to_string_pretty(&config)?;
     let mut file = File::create(&config_file)?;
     file.write_all(serialized.as_bytes())?;
     update_language(&config.language);
+    update_number_format(config.number_format);
+    update_date_format(config.date_format);
+    update_suggestion_limits(config.suggestion_limits());
     Ok(())
 }
 
+// saves the config and shows an error notification if that fails - returns whether it was saved
+pub(crate) fn save_config_or_notify(config: &Config, sender: &Sender<GuiEvent>) -> bool {
+    match save_config(config) {
+        Ok(()) => true,
+        Err(e) => {
+            log::error!("Could not save config: {e}");
+            util::send_gui_event(
+                sender,
+                GuiEvent::ShowErrorNotification(Messages::CouldNotSaveConfig.msg().to_owned()),
+            );
+            false
+        }
+    }
+}
+
 fn check_config_exists() -> Result<PathBuf> {
     let mut dir: PathBuf = dirs::config_dir().unwrap_or_else(|| "./".into());
     dir.push(APP_NAME);
@@ -51,9 +429,143 @@ fn check_config_exists() -> Result<PathBuf> {
             data_folder: None,
             file_open_command: None,
             language: Language::EN.name().into(),
+            page_size: PageSize::default(),
+            accounting_period: None,
+            category_defaults: false,
+            open_pdf_after_export: false,
+            files_suffix: None,
+            file_name_template: None,
+            accounting_export_template: String::new(),
+            invoice_export_template: String::new(),
+            fiscal_year_start_month: default_fiscal_year_start_month(),
+            number_format: NumberFormat::default(),
+            date_format: DateFormat::default(),
+            quantity_decimals: default_quantity_decimals(),
+            top_margin_offset_mm: 0.0,
+            year_history_span: default_year_history_span(),
+            color_rows: false,
+            always_on_top: false,
+            min_window_width: default_min_window_width(),
+            min_window_height: default_min_window_height(),
+            files_per_year: false,
+            pdf_author: String::new(),
+            epc_qr_code: false,
+            invoice_vat_column: false,
+            bank_account: BankAccount::default(),
+            confirm_exit: default_confirm_exit(),
+            max_suggestions: default_max_suggestions(),
+            min_suggestion_input: 0,
+            accounting_column_widths: vec![],
+            show_cents: default_show_cents(),
+            delete_draft_after_export: default_delete_draft_after_export(),
+            auto_backup: false,
+            auto_backup_count: default_auto_backup_count(),
+            keep_item_fields: false,
+            developer_mode: false,
         };
         let serialized = toml::to_string(&default_config)?;
         fd.write_all(serialized.as_bytes())?;
     }
     Ok(dir)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> Config {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn valid_config_has_no_warnings() {
+        let mut cfg = config("language = \"de\"");
+        assert!(validate_config(&mut cfg).is_empty());
+        assert_eq!(cfg.language, "de");
+
+        let mut cfg = config(&format!(
+            "language = \"en\"\ndata_folder = \"{}\"",
+            std::env::temp_dir().display()
+        ));
+        assert!(validate_config(&mut cfg).is_empty());
+        assert!(cfg.data_folder.is_some());
+    }
+
+    #[test]
+    fn missing_data_folder_is_unset() {
+        let mut cfg = config("language = \"en\"\ndata_folder = \"/this/folder/does/not/exist\"");
+        assert_eq!(
+            validate_config(&mut cfg),
+            vec![ConfigWarning::DataFolderMissing(PathBuf::from(
+                "/this/folder/does/not/exist"
+            ))]
+        );
+        assert!(cfg.data_folder.is_none());
+    }
+
+    #[test]
+    fn unknown_language_defaults_to_en() {
+        let mut cfg = config("language = \"klingon\"");
+        assert_eq!(
+            validate_config(&mut cfg),
+            vec![ConfigWarning::UnknownLanguage(String::from("klingon"))]
+        );
+        assert_eq!(cfg.language, "en");
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        let mut cfg = config(
+            "language = \"en\"\nmax_suggestions = 0\nmin_window_width = 50000.0\nmin_window_height = 480.0",
+        );
+        assert_eq!(
+            validate_config(&mut cfg),
+            vec![
+                ConfigWarning::OutOfRange("max_suggestions", String::from("1")),
+                ConfigWarning::OutOfRange("min_window_width", String::from("2000")),
+            ]
+        );
+        assert_eq!(cfg.max_suggestions, 1);
+        assert_eq!(cfg.min_window_width, 2000.0);
+        assert_eq!(cfg.min_window_height, 480.0);
+
+        let mut cfg = config("language = \"en\"\nmax_suggestions = 501");
+        validate_config(&mut cfg);
+        assert_eq!(cfg.max_suggestions, 500);
+    }
+
+    #[test]
+    fn dates_round_trip_in_every_format() {
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 7).unwrap();
+        for (format, expected) in [
+            (DateFormat::DayMonthYear, "07.03.2024"),
+            (DateFormat::MonthDayYear, "03/07/2024"),
+            (DateFormat::Iso, "2024-03-07"),
+        ] {
+            let formatted = date.format(format.pattern()).to_string();
+            assert_eq!(formatted, expected);
+            assert_eq!(
+                chrono::NaiveDate::parse_from_str(&formatted, format.pattern()),
+                Ok(date)
+            );
+        }
+        // day and month are swapped between the formats
+        assert!(chrono::NaiveDate::parse_from_str(
+            "31.12.2024",
+            DateFormat::MonthDayYear.pattern()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn date_format_defaults_to_day_month_year() {
+        assert_eq!(
+            config("language = \"en\"").date_format,
+            DateFormat::DayMonthYear
+        );
+        assert_eq!(
+            config("language = \"en\"\ndate_format = \"Iso\"").date_format,
+            DateFormat::Iso
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use super::Vat;
 use eframe::egui::{RichText, WidgetText};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::iter::Sum;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
 
 pub const SCALE: u32 = 2;
 
@@ -24,6 +27,7 @@ pub(crate) struct VatCalculationResult {
 pub(crate) struct CurrencyValue {
     pub(crate) value: Decimal,
     currency: Currency,
+    // only kept for the stored layout - values are formatted on demand, in the configured locale
     formatted: String,
     formatted_value: String,
 }
@@ -53,6 +57,22 @@ impl CurrencyValue {
         }
     }
 
+    pub fn zero() -> Self {
+        Self::new_from_decimal(default_currency_value())
+    }
+
+    pub fn checked_add(&self, other: &CurrencyValue) -> Option<CurrencyValue> {
+        self.value
+            .checked_add(other.value)
+            .map(Self::new_from_decimal)
+    }
+
+    pub fn checked_sub(&self, other: &CurrencyValue) -> Option<CurrencyValue> {
+        self.value
+            .checked_sub(other.value)
+            .map(Self::new_from_decimal)
+    }
+
     pub fn calculate_vat(&self, vat: Vat) -> VatCalculationResult {
         let tax = Self::new_from_decimal(
             self.value
@@ -68,24 +88,56 @@ impl CurrencyValue {
         VatCalculationResult { tax, gross }
     }
 
-    pub fn to_str(&self) -> &str {
-        &self.formatted
+    // splits a gross value, which already includes the given VAT, into its tax and gross part
+    pub fn calculate_included_vat(&self, vat: Vat) -> VatCalculationResult {
+        let net = self
+            .value
+            .checked_div(Decimal::ONE + vat.value())
+            .unwrap_or_else(default_currency_value)
+            .round_dp_with_strategy(SCALE, RoundingStrategy::MidpointAwayFromZero);
+        let tax = Self::new_from_decimal(
+            self.value
+                .checked_sub(net)
+                .unwrap_or_else(default_currency_value),
+        );
+
+        VatCalculationResult {
+            tax,
+            gross: self.clone(),
+        }
     }
 
-    pub fn to_euro_str(&self) -> String {
+    // Values are formatted in the configured number locale in one of two ways:
+    // - to_display_string: grouped, with the currency symbol, e.g. "1.234,56 €" - for the UI and PDFs
+    // - to_value_string: without grouping and symbol, e.g. "1234,56" - for input fields and CSV
+
+    pub fn to_display_string(&self) -> String {
         format!(
             "{} {}",
-            default_currency().to_str(),
-            format_euro_string(&self.value)
+            crate::get_number_locale().format(&self.value),
+            self.currency.to_str()
         )
     }
 
-    pub fn _to_value_str(&self) -> &str {
-        &self.formatted_value
+    // like to_display_string, but rounded to whole units without cents, e.g. "1,235 €"
+    pub fn to_str_rounded(&self, show_cents: bool) -> String {
+        if show_cents {
+            return self.to_display_string();
+        }
+        let rounded = self
+            .value
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero);
+        format!(
+            "{} {}",
+            crate::get_number_locale().format_grouped(&rounded, 0),
+            self.currency.to_str()
+        )
     }
 
     pub fn to_value_string(&self) -> String {
-        self.formatted_value.clone()
+        let mut scaled_value = self.value;
+        scaled_value.rescale(SCALE);
+        crate::get_number_locale().format_plain(&scaled_value)
     }
 }
 
@@ -101,38 +153,155 @@ impl Ord for CurrencyValue {
     }
 }
 
-fn format_euro_string(num: &Decimal) -> String {
-    let mut scaled_value = num.to_owned();
-    scaled_value.rescale(SCALE);
-    let input = scaled_value.to_string();
-    let parts: Vec<&str> = input.split('.').collect();
-    let with_minus = input.starts_with('-');
-    let cut_off = if with_minus { 4 } else { 3 };
+// saturates at the bounds of Decimal instead of overflowing
+impl Add for CurrencyValue {
+    type Output = CurrencyValue;
+
+    fn add(self, other: CurrencyValue) -> CurrencyValue {
+        CurrencyValue::new_from_decimal(self.value.saturating_add(other.value))
+    }
+}
 
-    let int_part = parts[0];
-    let dec_part = parts[1];
-    let int_formatted = if int_part.len() > cut_off {
-        let mut result = String::new();
-        let chars: Vec<char> = int_part.chars().rev().collect();
+impl Sub for CurrencyValue {
+    type Output = CurrencyValue;
+
+    fn sub(self, other: CurrencyValue) -> CurrencyValue {
+        CurrencyValue::new_from_decimal(self.value.saturating_sub(other.value))
+    }
+}
+
+impl Sum for CurrencyValue {
+    fn sum<I: Iterator<Item = CurrencyValue>>(iter: I) -> Self {
+        iter.fold(CurrencyValue::zero(), |acc, v| acc + v)
+    }
+}
 
-        for (i, c) in chars.iter().enumerate() {
-            if i > 0 && i % 3 == 0 {
-                result.push('.');
+// rounds to the nearest multiple of step, half-way cases away from zero - None on overflow
+pub fn round_to_step(value: Decimal, step: Decimal) -> Option<Decimal> {
+    if step.is_zero() {
+        return Some(value);
+    }
+    let steps = value
+        .checked_div(step)?
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero);
+    let mut rounded = steps.checked_mul(step)?;
+    rounded.rescale(SCALE);
+    Some(rounded)
+}
+
+// decimal and grouping separators, used to display and parse numbers
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum NumberLocale {
+    DeDe,
+    EnUs,
+}
+
+impl NumberLocale {
+    fn separators(&self) -> (char, char) {
+        match self {
+            NumberLocale::DeDe => (',', '.'),
+            NumberLocale::EnUs => ('.', ','),
+        }
+    }
+
+    // with grouping and SCALE decimal places, e.g. 1.234,56
+    pub(crate) fn format(&self, num: &Decimal) -> String {
+        self.format_grouped(num, SCALE)
+    }
+
+    // with grouping and the given decimal places, e.g. 1.234,5 - without a separator for 0
+    pub(crate) fn format_grouped(&self, num: &Decimal, decimals: u32) -> String {
+        let (decimal_separator, grouping_separator) = self.separators();
+        let mut scaled_value = num.to_owned();
+        scaled_value.rescale(decimals);
+        let input = scaled_value.abs().to_string();
+        let (int_part, dec_part) = input.split_once('.').unwrap_or((&input, ""));
+
+        let mut int_formatted = String::new();
+        for (i, c) in int_part.chars().enumerate() {
+            if i > 0 && (int_part.len() - i) % 3 == 0 {
+                int_formatted.push(grouping_separator);
             }
-            result.push(*c);
+            int_formatted.push(c);
+        }
+
+        let sign = if scaled_value.is_sign_negative() && !scaled_value.is_zero() {
+            "-"
+        } else {
+            ""
+        };
+        if dec_part.is_empty() {
+            return format!("{sign}{int_formatted}");
         }
-        result.chars().rev().collect()
-    } else {
-        int_part.to_string()
-    };
+        format!("{sign}{int_formatted}{decimal_separator}{dec_part}")
+    }
+
+    // without grouping, e.g. 1234,56
+    pub(crate) fn format_plain(&self, num: &Decimal) -> String {
+        let (decimal_separator, _) = self.separators();
+        num.to_string().replace('.', &decimal_separator.to_string())
+    }
 
-    if with_minus {
-        format!("- {},{}", &int_formatted[1..], dec_part)
-    } else {
-        format!("{},{}", int_formatted, dec_part)
+    // without grouping and with a fixed number of decimal places, e.g. 1234,50
+    pub(crate) fn format_fixed(&self, num: &Decimal, decimals: u32) -> String {
+        let mut rounded =
+            num.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero);
+        rounded.rescale(decimals);
+        self.format_plain(&rounded)
+    }
+
+    // accepts both grouped and plain input, whitespace is ignored - a grouping separator outside of
+    // thousands positions is only accepted as the decimal point, e.g. a typed 12.50 in German format
+    pub(crate) fn parse(&self, input: &str) -> Option<Decimal> {
+        let (decimal_separator, grouping_separator) = self.separators();
+        let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+        let (int_part, dec_part) = match input.split_once(decimal_separator) {
+            Some((int_part, dec_part)) => (int_part, Some(dec_part)),
+            None => (input.as_str(), None),
+        };
+        let int_part = if is_grouped(int_part, grouping_separator) {
+            int_part.replace(grouping_separator, "")
+        } else if dec_part.is_none() && int_part.matches(grouping_separator).count() == 1 {
+            int_part.replace(grouping_separator, ".")
+        } else if int_part.contains(grouping_separator) {
+            return None;
+        } else {
+            int_part.to_owned()
+        };
+        let normalized = match dec_part {
+            Some(dec_part) => format!("{int_part}.{dec_part}"),
+            None => int_part,
+        };
+        Decimal::from_str(&normalized).ok()
     }
 }
 
+// digits in groups of three, e.g. 1.234.567 - the first group can be shorter
+fn is_grouped(int_part: &str, grouping_separator: char) -> bool {
+    let digits = int_part.strip_prefix('-').unwrap_or(int_part);
+    let mut groups = digits.split(grouping_separator);
+    let first = groups.next().unwrap_or_default();
+    digits.contains(grouping_separator)
+        && (1..=3).contains(&first.len())
+        && first.chars().all(|c| c.is_ascii_digit())
+        && groups.all(|g| g.len() == 3 && g.chars().all(|c| c.is_ascii_digit()))
+}
+
+// parses user input in the configured number locale
+pub(crate) fn parse_number(input: &str) -> Option<Decimal> {
+    crate::get_number_locale().parse(input)
+}
+
+// formats a number for input fields in the configured number locale
+pub(crate) fn format_number(num: &Decimal) -> String {
+    crate::get_number_locale().format_plain(num)
+}
+
+// formats an item quantity for exports in the configured number locale
+pub(crate) fn format_quantity(num: &Decimal, decimals: u32) -> String {
+    crate::get_number_locale().format_fixed(num, decimals)
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub(crate) enum Currency {
     Euro,
@@ -148,25 +317,25 @@ impl Currency {
 
 impl From<CurrencyValue> for WidgetText {
     fn from(val: CurrencyValue) -> Self {
-        WidgetText::from(val.to_str())
+        WidgetText::from(val.to_display_string())
     }
 }
 
 impl From<&CurrencyValue> for WidgetText {
     fn from(val: &CurrencyValue) -> Self {
-        WidgetText::from(val.to_str())
+        WidgetText::from(val.to_display_string())
     }
 }
 
 impl From<CurrencyValue> for RichText {
     fn from(val: CurrencyValue) -> Self {
-        RichText::from(val.to_str())
+        RichText::from(val.to_display_string())
     }
 }
 
 impl From<&CurrencyValue> for RichText {
     fn from(val: &CurrencyValue) -> Self {
-        RichText::from(val.to_str())
+        RichText::from(val.to_display_string())
     }
 }
 
@@ -178,6 +347,228 @@ impl From<CurrencyValue> for String {
 
 impl std::fmt::Display for CurrencyValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_str())
+        write!(f, "{}", self.to_display_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn round_to_five_cents() {
+        let step = dec("0.05");
+        assert_eq!(round_to_step(dec("10.02"), step), Some(dec("10.00")));
+        assert_eq!(round_to_step(dec("10.03"), step), Some(dec("10.05")));
+        assert_eq!(round_to_step(dec("10.025"), step), Some(dec("10.05")));
+        assert_eq!(round_to_step(dec("10.07"), step), Some(dec("10.05")));
+        assert_eq!(round_to_step(dec("10.08"), step), Some(dec("10.10")));
+        assert_eq!(round_to_step(dec("10.05"), step), Some(dec("10.05")));
+    }
+
+    #[test]
+    fn round_to_ten_cents() {
+        let step = dec("0.10");
+        assert_eq!(round_to_step(dec("10.04"), step), Some(dec("10.00")));
+        assert_eq!(round_to_step(dec("10.05"), step), Some(dec("10.10")));
+        assert_eq!(round_to_step(dec("10.15"), step), Some(dec("10.20")));
+        assert_eq!(round_to_step(dec("0.00"), step), Some(dec("0.00")));
+    }
+
+    #[test]
+    fn round_negatives() {
+        assert_eq!(
+            round_to_step(dec("-10.02"), dec("0.05")),
+            Some(dec("-10.00"))
+        );
+        assert_eq!(
+            round_to_step(dec("-10.03"), dec("0.05")),
+            Some(dec("-10.05"))
+        );
+        assert_eq!(
+            round_to_step(dec("-10.05"), dec("0.10")),
+            Some(dec("-10.10"))
+        );
+        assert_eq!(
+            round_to_step(dec("-10.04"), dec("0.10")),
+            Some(dec("-10.00"))
+        );
+    }
+
+    #[test]
+    fn round_zero_step() {
+        assert_eq!(round_to_step(dec("10.03"), dec("0")), Some(dec("10.03")));
+    }
+
+    #[test]
+    fn round_overflow() {
+        assert_eq!(round_to_step(Decimal::MAX, dec("0.05")), None);
+        assert_eq!(round_to_step(Decimal::MIN, dec("0.10")), None);
+    }
+
+    #[test]
+    fn add_and_sub() {
+        let a = CurrencyValue::new(1050);
+        let b = CurrencyValue::new(225);
+        assert_eq!((a.clone() + b.clone()).value, dec("12.75"));
+        assert_eq!((a.clone() - b.clone()).value, dec("8.25"));
+        assert_eq!((b - a).to_display_string(), "-8.25 €");
+    }
+
+    #[test]
+    fn sum_values() {
+        let values = vec![
+            CurrencyValue::new(100),
+            CurrencyValue::new(250),
+            CurrencyValue::new(-50),
+        ];
+        assert_eq!(values.into_iter().sum::<CurrencyValue>().value, dec("3.00"));
+        assert_eq!(
+            std::iter::empty::<CurrencyValue>()
+                .sum::<CurrencyValue>()
+                .to_display_string(),
+            "0.00 €"
+        );
+    }
+
+    #[test]
+    fn checked_overflow() {
+        let max = CurrencyValue::new_from_decimal(Decimal::MAX);
+        let one = CurrencyValue::new(100);
+        assert!(max.checked_add(&one).is_none());
+        assert_eq!(one.checked_add(&one).map(|v| v.value), Some(dec("2.00")));
+        let min = CurrencyValue::new_from_decimal(Decimal::MIN);
+        assert!(min.checked_sub(&one).is_none());
+    }
+
+    #[test]
+    fn saturating_overflow() {
+        let max = CurrencyValue::new_from_decimal(Decimal::MAX);
+        let min = CurrencyValue::new_from_decimal(Decimal::MIN);
+        let one = CurrencyValue::new(100);
+        assert_eq!((max.clone() + one.clone()).value, Decimal::MAX);
+        assert_eq!((min - one).value, Decimal::MIN);
+        assert_eq!(
+            vec![max.clone(), max]
+                .into_iter()
+                .sum::<CurrencyValue>()
+                .value,
+            Decimal::MAX
+        );
+    }
+
+    #[test]
+    fn format_de() {
+        let de = NumberLocale::DeDe;
+        assert_eq!(de.format(&dec("1234.56")), "1.234,56");
+        assert_eq!(de.format(&dec("1234567.8")), "1.234.567,80");
+        assert_eq!(de.format(&dec("123")), "123,00");
+        assert_eq!(de.format(&dec("-1234.5")), "-1.234,50");
+        assert_eq!(de.format(&dec("-0.00")), "0,00");
+        assert_eq!(de.format_plain(&dec("1234.56")), "1234,56");
+    }
+
+    #[test]
+    fn format_en() {
+        let en = NumberLocale::EnUs;
+        assert_eq!(en.format(&dec("1234.56")), "1,234.56");
+        assert_eq!(en.format(&dec("1234567.8")), "1,234,567.80");
+        assert_eq!(en.format(&dec("999.99")), "999.99");
+        assert_eq!(en.format(&dec("-123456")), "-123,456.00");
+        assert_eq!(en.format_plain(&dec("1234.56")), "1234.56");
+    }
+
+    #[test]
+    fn format_fixed_decimals() {
+        let de = NumberLocale::DeDe;
+        assert_eq!(de.format_fixed(&dec("0.25"), 2), "0,25");
+        assert_eq!(de.format_fixed(&dec("10"), 2), "10,00");
+        assert_eq!(de.format_fixed(&dec("100.5"), 2), "100,50");
+        assert_eq!(de.format_fixed(&dec("0.25"), 1), "0,3");
+        assert_eq!(de.format_fixed(&dec("100.5"), 0), "101");
+        assert_eq!(
+            NumberLocale::EnUs.format_fixed(&dec("1234.5"), 3),
+            "1234.500"
+        );
+    }
+
+    #[test]
+    fn parse_de() {
+        let de = NumberLocale::DeDe;
+        assert_eq!(de.parse("1.234,56"), Some(dec("1234.56")));
+        assert_eq!(de.parse(" 1234,5 "), Some(dec("1234.5")));
+        assert_eq!(de.parse("-12"), Some(dec("-12")));
+        assert_eq!(de.parse("12,3,4"), None);
+        assert_eq!(de.parse("abc"), None);
+        // a dot outside of thousands positions is the decimal point
+        assert_eq!(de.parse("12.50"), Some(dec("12.5")));
+        assert_eq!(de.parse("1.234"), Some(dec("1234")));
+        assert_eq!(de.parse("-1.234.567,8"), Some(dec("-1234567.8")));
+        assert_eq!(de.parse("12.50,5"), None);
+        assert_eq!(de.parse("1.2.3"), None);
+    }
+
+    #[test]
+    fn parse_en() {
+        let en = NumberLocale::EnUs;
+        assert_eq!(en.parse("1,234.56"), Some(dec("1234.56")));
+        assert_eq!(en.parse("1234.5"), Some(dec("1234.5")));
+        assert_eq!(en.parse("12.3.4"), None);
+        assert_eq!(en.parse("12,50"), Some(dec("12.5")));
+        assert_eq!(en.parse("1,234"), Some(dec("1234")));
+        assert_eq!(en.parse(""), None);
+    }
+
+    #[test]
+    fn display_and_value_strings() {
+        // in the default number locale
+        let value = CurrencyValue::new(123456);
+        assert_eq!(value.to_display_string(), "1,234.56 €");
+        assert_eq!(value.to_value_string(), "1234.56");
+        assert_eq!(value.to_string(), value.to_display_string());
+
+        let negative = CurrencyValue::new(-1050);
+        assert_eq!(negative.to_display_string(), "-10.50 €");
+        assert_eq!(negative.to_value_string(), "-10.50");
+
+        // always with two decimal places
+        let whole = CurrencyValue::new_from_decimal(dec("7"));
+        assert_eq!(whole.to_display_string(), "7.00 €");
+        assert_eq!(whole.to_value_string(), "7.00");
+    }
+
+    #[test]
+    fn rounded_strings() {
+        // in the default number locale
+        let value = CurrencyValue::new(123499);
+        assert_eq!(value.to_str_rounded(true), "1,234.99 €");
+        assert_eq!(value.to_str_rounded(false), "1,235 €");
+        assert_eq!(CurrencyValue::new(99).to_str_rounded(false), "1 €");
+        assert_eq!(CurrencyValue::new(1049).to_str_rounded(false), "10 €");
+        assert_eq!(CurrencyValue::new(1050).to_str_rounded(false), "11 €");
+        assert_eq!(CurrencyValue::new(-199).to_str_rounded(false), "-2 €");
+        assert_eq!(CurrencyValue::new(-49).to_str_rounded(false), "0 €");
+        // only the display is rounded
+        assert_eq!(value.value, dec("1234.99"));
+
+        assert_eq!(
+            NumberLocale::DeDe.format_grouped(&dec("1234567"), 0),
+            "1.234.567"
+        );
+    }
+
+    #[test]
+    fn format_parse_round_trip() {
+        for locale in [NumberLocale::DeDe, NumberLocale::EnUs] {
+            for value in ["0.00", "1.05", "-7.50", "1234.56", "-9876543.21"] {
+                let value = dec(value);
+                assert_eq!(locale.parse(&locale.format(&value)), Some(value));
+                assert_eq!(locale.parse(&locale.format_plain(&value)), Some(value));
+            }
+        }
     }
 }
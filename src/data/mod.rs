@@ -1,7 +1,10 @@
 use crate::messages::Messages;
-use crate::util::{Month, Quarter};
-use chrono::NaiveDate;
-use currency::{CurrencyValue, SCALE};
+use crate::util::{
+    Month, Quarter,
+    validation::{format_iban, strip_label, validate_iban},
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use currency::{CurrencyValue, SCALE, VatCalculationResult, default_currency_value};
 use eframe::egui::{RichText, WidgetText};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -11,7 +14,7 @@ use uuid::Uuid;
 
 pub(crate) mod currency;
 
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub(crate) struct Invoice {
     pub(crate) id: Uuid,
     pub(crate) date: NaiveDate,
@@ -23,13 +26,140 @@ pub(crate) struct Invoice {
     pub(crate) invoice_number: String,
     pub(crate) pre_text: String,
     pub(crate) post_text: String,
+    // free text, used if there is no structured bank account
     pub(crate) bank_data: String,
     pub(crate) items: Vec<InvoiceItem>,
+    // stored separately, so templates saved before it existed can still be read
+    #[serde(skip)]
+    pub(crate) kind: DocumentKind,
+    // stored separately as well
+    #[serde(skip)]
+    pub(crate) adjustments: Vec<Adjustment>,
+    // PDFs appended to the export, e.g. timesheets - they change per invoice, so they aren't stored
+    #[serde(skip)]
+    pub(crate) attachments: Vec<PathBuf>,
+    // stored separately, like the kind
+    #[serde(skip)]
+    pub(crate) bank_account: BankAccount,
+    // whether prices are gross - stored separately for templates, drafts keep it themselves
+    #[serde(skip)]
+    pub(crate) prices_include_vat: bool,
+    // stored separately as well
+    #[serde(skip)]
+    pub(crate) rounding_step: RoundingStep,
+}
+
+impl Invoice {
+    // a new template with the same content - the fresh id gives it its own key
+    pub(crate) fn duplicate(&self) -> Invoice {
+        Invoice {
+            id: Uuid::now_v7(),
+            name: format!("{} {}", self.name, Messages::CopySuffix),
+            ..self.clone()
+        }
+    }
+
+    // the invoice as a draft, which keeps the settings of the form
+    // the fields, which aren't part of the invoice's stored layout, are moved to the draft
+    pub(crate) fn into_draft(mut self) -> Draft {
+        Draft {
+            kind: std::mem::take(&mut self.kind),
+            adjustments: std::mem::take(&mut self.adjustments),
+            attachments: std::mem::take(&mut self.attachments),
+            bank_account: std::mem::take(&mut self.bank_account),
+            rounding_step: std::mem::take(&mut self.rounding_step),
+            prices_include_vat: std::mem::take(&mut self.prices_include_vat),
+            invoice: self,
+        }
+    }
+
+    // the structured bank account, falling back to the free text for older templates
+    pub(crate) fn bank_data_lines(&self) -> Vec<String> {
+        if self.bank_account.is_empty() {
+            return self.bank_data.lines().map(format_iban_line).collect();
+        }
+        let account = &self.bank_account;
+        let mut lines = vec![];
+        if !account.account_holder.trim().is_empty() {
+            lines.push(account.account_holder.trim().to_owned());
+        }
+        if !account.iban.trim().is_empty() {
+            lines.push(format!("IBAN: {}", account.formatted_iban()));
+        }
+        if !account.bic.trim().is_empty() {
+            lines.push(format!("BIC: {}", account.bic.trim().to_uppercase()));
+        }
+        lines
+    }
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BankAccount {
+    #[serde(default)]
+    pub(crate) account_holder: String,
+    #[serde(default)]
+    pub(crate) iban: String,
+    #[serde(default)]
+    pub(crate) bic: String,
+}
+
+impl BankAccount {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.account_holder.trim().is_empty()
+            && self.iban.trim().is_empty()
+            && self.bic.trim().is_empty()
+    }
+
+    // in groups of four, e.g. "AT61 1904 3002 3457 3201"
+    pub(crate) fn formatted_iban(&self) -> String {
+        format_iban(&self.iban)
+    }
+}
+
+// a valid IBAN in a free text line like "IBAN: at611904..." is printed in groups of four
+fn format_iban_line(line: &str) -> String {
+    match strip_label(line.trim(), "IBAN") {
+        Some(iban) if validate_iban(iban) => format!("IBAN: {}", format_iban(iban)),
+        _ => line.to_owned(),
+    }
+}
+
+// a discount (negative amount) or surcharge (positive amount) on the whole invoice
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Adjustment {
+    pub(crate) description: String,
+    pub(crate) amount: CurrencyValue,
+    pub(crate) vat: Vat,
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) enum DocumentKind {
+    #[default]
+    Invoice,
+    Quote,
+}
+
+impl DocumentKind {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            DocumentKind::Invoice => Messages::Invoice.msg(),
+            DocumentKind::Quote => Messages::Quote.msg(),
+        }
+    }
+
+    pub(crate) fn short(&self) -> &'static str {
+        match self {
+            DocumentKind::Invoice => Messages::InvoiceShort.msg(),
+            DocumentKind::Quote => Messages::QuoteShort.msg(),
+        }
+    }
 }
 
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub(crate) struct InvoiceItem {
-    pub(crate) nr: u64,
+    // e.g. "1", or "1.2" for a sub-item
+    #[serde(with = "position")]
+    pub(crate) nr: String,
     pub(crate) description: String,
     pub(crate) unit: Unit,
     pub(crate) amount: Decimal,
@@ -37,6 +167,116 @@ pub(crate) struct InvoiceItem {
     pub(crate) vat: Vat,
 }
 
+// stored as the whole position, like before there were sub-items, so older templates can still be
+// read - sub-positions are stored separately
+mod position {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(nr: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(super::whole_position(nr))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<String, D::Error> {
+        u64::deserialize(deserializer).map(|nr| nr.to_string())
+    }
+}
+
+// e.g. 1 for "1.2"
+pub(crate) fn whole_position(nr: &str) -> u64 {
+    nr.trim()
+        .split('.')
+        .next()
+        .and_then(|whole| whole.parse().ok())
+        .unwrap_or_default()
+}
+
+// the positions of the items, if any of them is a sub-position, which doesn't survive storing
+pub(crate) fn sub_positions(items: &[InvoiceItem]) -> Option<Vec<String>> {
+    items
+        .iter()
+        .any(|item| item.nr.contains('.'))
+        .then(|| items.iter().map(|item| item.nr.clone()).collect())
+}
+
+// positions stored for other items, e.g. from before the template was changed, are ignored
+pub(crate) fn attach_sub_positions(items: &mut [InvoiceItem], positions: Vec<String>) {
+    if positions.len() == items.len() {
+        items
+            .iter_mut()
+            .zip(positions)
+            .for_each(|(item, nr)| item.nr = nr);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SumData {
+    pub(crate) net: CurrencyValue,
+    pub(crate) tax: CurrencyValue,
+    pub(crate) total: CurrencyValue,
+    pub(crate) rounding: Option<CurrencyValue>,
+    pub(crate) prices_include_vat: bool,
+}
+
+// sums up the net, tax and total of the given invoice items and adjustments
+// if prices include VAT, the item prices are gross and net and tax are derived from them
+pub(crate) fn calculate_sum(
+    items: &[InvoiceItem],
+    adjustments: &[Adjustment],
+    prices_include_vat: bool,
+) -> SumData {
+    let mut net_sum = CurrencyValue::zero();
+    let mut tax_sum = CurrencyValue::zero();
+    let mut total_sum = CurrencyValue::zero();
+
+    let item_prices = items.iter().map(|item| {
+        (
+            CurrencyValue::new_from_decimal(
+                item.price_per_unit
+                    .value
+                    .checked_mul(item.amount)
+                    .unwrap_or_else(default_currency_value),
+            ),
+            item.vat,
+        )
+    });
+    let adjustment_prices = adjustments.iter().map(|a| (a.amount.clone(), a.vat));
+
+    item_prices
+        .chain(adjustment_prices)
+        .for_each(|(price, vat)| {
+            let (net, VatCalculationResult { tax, gross }) = if prices_include_vat {
+                let result = price.calculate_included_vat(vat);
+                let net = result
+                    .gross
+                    .checked_sub(&result.tax)
+                    .unwrap_or_else(CurrencyValue::zero);
+                (net, result)
+            } else {
+                let result = price.calculate_vat(vat);
+                (price, result)
+            };
+            net_sum = net_sum
+                .checked_add(&net)
+                .unwrap_or_else(CurrencyValue::zero);
+            tax_sum = tax_sum
+                .checked_add(&tax)
+                .unwrap_or_else(CurrencyValue::zero);
+            total_sum = total_sum
+                .checked_add(&gross)
+                .unwrap_or_else(CurrencyValue::zero);
+        });
+
+    SumData {
+        net: net_sum,
+        tax: tax_sum,
+        total: total_sum,
+        rounding: None,
+        prices_include_vat,
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub(crate) struct Address {
     pub(crate) name: String,
@@ -92,10 +332,11 @@ pub(crate) struct AccountingSheet {
     pub(crate) year: i32,
     pub(crate) quarter: Option<Quarter>,
     pub(crate) month: Option<Month>,
+    pub(crate) all_time: bool,
     pub(crate) items: Vec<AccountingItem>,
 }
 
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub(crate) struct AccountingItem {
     pub(crate) invoice_type: InvoiceType,
     pub(crate) id: Uuid,
@@ -106,6 +347,12 @@ pub(crate) struct AccountingItem {
     pub(crate) net: CurrencyValue,
     pub(crate) vat: Vat,
     pub(crate) file: PathBuf,
+    // stored in a separate table, so items saved before notes existed can still be read
+    #[serde(skip)]
+    pub(crate) note: String,
+    // private, e.g. a mixed expense - listed, but left out of the sums, stored separately as well
+    #[serde(skip)]
+    pub(crate) excluded: bool,
 }
 
 impl PartialOrd for AccountingItem {
@@ -136,7 +383,7 @@ impl Ord for AccountingItem {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Default, Hash, Serialize, Deserialize)]
 pub(crate) struct Company(pub(crate) String);
 
 impl std::ops::Deref for Company {
@@ -158,6 +405,72 @@ impl std::ops::Deref for Category {
     }
 }
 
+// a half-finished invoice - unlike templates, drafts store every field, since there are no old ones
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Draft {
+    invoice: Invoice,
+    kind: DocumentKind,
+    adjustments: Vec<Adjustment>,
+    attachments: Vec<PathBuf>,
+    bank_account: BankAccount,
+    pub(crate) rounding_step: RoundingStep,
+    pub(crate) prices_include_vat: bool,
+}
+
+impl Draft {
+    // the invoice including the fields, which aren't part of its stored layout
+    pub(crate) fn invoice(&self) -> Invoice {
+        Invoice {
+            kind: self.kind,
+            adjustments: self.adjustments.clone(),
+            attachments: self.attachments.clone(),
+            bank_account: self.bank_account.clone(),
+            prices_include_vat: self.prices_include_vat,
+            rounding_step: self.rounding_step,
+            ..self.invoice.clone()
+        }
+    }
+
+    pub(crate) fn id(&self) -> Uuid {
+        self.invoice.id
+    }
+
+    pub(crate) fn items(&self) -> &[InvoiceItem] {
+        &self.invoice.items
+    }
+
+    pub(crate) fn items_mut(&mut self) -> &mut [InvoiceItem] {
+        &mut self.invoice.items
+    }
+}
+
+// rounding of an invoice's total, e.g. for cash payments
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub(crate) enum RoundingStep {
+    #[default]
+    None,
+    FiveCents,
+    TenCents,
+}
+
+impl RoundingStep {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            RoundingStep::None => "-",
+            RoundingStep::FiveCents => "0.05",
+            RoundingStep::TenCents => "0.10",
+        }
+    }
+
+    pub(crate) fn step(&self) -> Option<Decimal> {
+        match self {
+            RoundingStep::None => None,
+            RoundingStep::FiveCents => Some(Decimal::new(5, 2)),
+            RoundingStep::TenCents => Some(Decimal::new(10, 2)),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub(crate) enum Vat {
     Zero,
@@ -211,6 +524,68 @@ impl Vat {
             Vat::Twenty => Decimal::new(20, SCALE),
         }
     }
+
+    // the next higher rate, or the highest one, used for arrow-key selection
+    pub(crate) fn next(&self) -> Self {
+        match self {
+            Vat::Zero => Vat::Ten,
+            Vat::Ten | Vat::Twenty => Vat::Twenty,
+        }
+    }
+
+    pub(crate) fn previous(&self) -> Self {
+        match self {
+            Vat::Zero | Vat::Ten => Vat::Zero,
+            Vat::Twenty => Vat::Ten,
+        }
+    }
+}
+
+pub(crate) const VATS: &[Vat] = &[Vat::Zero, Vat::Ten, Vat::Twenty];
+
+// the last used invoice type and VAT for a category, used to pre-fill new items
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct CategoryDefaults {
+    pub(crate) invoice_type: InvoiceType,
+    pub(crate) vat: Vat,
+}
+
+// an entry of the export log, written after every successful PDF export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ExportLogEntry {
+    pub(crate) id: Uuid,
+    pub(crate) timestamp: NaiveDateTime,
+    pub(crate) export_type: ExportType,
+    pub(crate) path: PathBuf,
+    // the exported period, or the invoice number
+    pub(crate) reference: String,
+}
+
+impl ExportLogEntry {
+    pub(crate) fn new(export_type: ExportType, path: PathBuf, reference: String) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            timestamp: chrono::Local::now().naive_local(),
+            export_type,
+            path,
+            reference,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub(crate) enum ExportType {
+    Invoice,
+    Accounting,
+}
+
+impl ExportType {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            ExportType::Invoice => Messages::Invoice.msg(),
+            ExportType::Accounting => Messages::Accounting.msg(),
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Serialize, Deserialize)]
@@ -227,3 +602,134 @@ impl InvoiceType {
         }
     }
 }
+
+// fixtures for tests, adjusted with struct update syntax where needed
+#[cfg(test)]
+impl AccountingItem {
+    pub(crate) fn test_item() -> Self {
+        Self {
+            invoice_type: InvoiceType::In,
+            id: Uuid::now_v7(),
+            date: NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            name: String::from("Rent"),
+            company: Company(String::from("Landlord")),
+            category: Category(String::from("Office")),
+            net: CurrencyValue::new(1000),
+            vat: Vat::Twenty,
+            file: PathBuf::from("/tmp/file.pdf"),
+            note: String::default(),
+            excluded: false,
+        }
+    }
+}
+
+#[cfg(test)]
+impl InvoiceItem {
+    pub(crate) fn test_item() -> Self {
+        Self {
+            nr: String::from("1"),
+            description: String::from("Work"),
+            unit: Unit::Hour,
+            amount: Decimal::ONE,
+            price_per_unit: CurrencyValue::new(10000),
+            vat: Vat::Twenty,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vat_steps_stop_at_the_ends() {
+        assert_eq!(Vat::Zero.next(), Vat::Ten);
+        assert_eq!(Vat::Ten.next(), Vat::Twenty);
+        assert_eq!(Vat::Twenty.next(), Vat::Twenty);
+        assert_eq!(Vat::Twenty.previous(), Vat::Ten);
+        assert_eq!(Vat::Zero.previous(), Vat::Zero);
+    }
+
+    fn item(amount: i64, price_per_unit: i64, vat: Vat) -> InvoiceItem {
+        InvoiceItem {
+            amount: Decimal::new(amount, 0),
+            price_per_unit: CurrencyValue::new(price_per_unit),
+            vat,
+            ..InvoiceItem::test_item()
+        }
+    }
+
+    #[test]
+    fn sum_with_prices_excluding_vat() {
+        let items = vec![item(2, 10000, Vat::Twenty), item(1, 11000, Vat::Ten)];
+        let sum = calculate_sum(&items, &[], false);
+        assert_eq!(sum.net.value, Decimal::new(31000, SCALE));
+        assert_eq!(sum.tax.value, Decimal::new(5100, SCALE));
+        assert_eq!(sum.total.value, Decimal::new(36100, SCALE));
+    }
+
+    #[test]
+    fn sum_with_prices_including_vat() {
+        let items = vec![item(2, 10000, Vat::Twenty), item(1, 11000, Vat::Ten)];
+        let sum = calculate_sum(&items, &[], true);
+        assert_eq!(sum.net.value, Decimal::new(26667, SCALE));
+        assert_eq!(sum.tax.value, Decimal::new(4333, SCALE));
+        assert_eq!(sum.total.value, Decimal::new(31000, SCALE));
+        assert!(sum.prices_include_vat);
+    }
+
+    fn adjustment(amount: i64) -> Adjustment {
+        Adjustment {
+            description: String::from("Adjustment"),
+            amount: CurrencyValue::new(amount),
+            vat: Vat::Twenty,
+        }
+    }
+
+    #[test]
+    fn sum_with_discount() {
+        let items = vec![item(2, 10000, Vat::Twenty)];
+        let sum = calculate_sum(&items, &[adjustment(-5000)], false);
+        assert_eq!(sum.net.value, Decimal::new(15000, SCALE));
+        assert_eq!(sum.tax.value, Decimal::new(3000, SCALE));
+        assert_eq!(sum.total.value, Decimal::new(18000, SCALE));
+    }
+
+    #[test]
+    fn sum_with_surcharge() {
+        let items = vec![item(2, 10000, Vat::Twenty)];
+        let sum = calculate_sum(&items, &[adjustment(10000)], false);
+        assert_eq!(sum.net.value, Decimal::new(30000, SCALE));
+        assert_eq!(sum.tax.value, Decimal::new(6000, SCALE));
+        assert_eq!(sum.total.value, Decimal::new(36000, SCALE));
+
+        let sum = calculate_sum(&items, &[adjustment(12000)], true);
+        assert_eq!(sum.total.value, Decimal::new(32000, SCALE));
+    }
+
+    #[test]
+    fn iban_is_formatted_in_groups_of_four() {
+        let account = BankAccount {
+            account_holder: String::from(" Jane Doe "),
+            iban: String::from("at611904300234573201"),
+            bic: String::from("bkauatww"),
+        };
+        assert_eq!(account.formatted_iban(), "AT61 1904 3002 3457 3201");
+        assert!(BankAccount::default().is_empty());
+        assert!(!account.is_empty());
+    }
+
+    #[test]
+    fn valid_ibans_in_free_text_are_formatted() {
+        assert_eq!(
+            format_iban_line("iban: at611904300234573201"),
+            "IBAN: AT61 1904 3002 3457 3201"
+        );
+        // invalid IBANs and other lines stay as they are
+        assert_eq!(
+            format_iban_line("IBAN: AT621904300234573201"),
+            "IBAN: AT621904300234573201"
+        );
+        assert_eq!(format_iban_line("My Bank"), "My Bank");
+    }
+}
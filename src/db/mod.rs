@@ -1,14 +1,23 @@
-use crate::data::Invoice;
-use crate::util::{self, Month, Quarter};
-use crate::{GuiError, data::AccountingItem};
-use chrono::{Datelike, NaiveDate};
+use crate::data::{
+    Address, Adjustment, BankAccount, CategoryDefaults, DocumentKind, Draft, ExportLogEntry,
+    Invoice, InvoiceItem, RoundingStep, attach_sub_positions, sub_positions,
+};
+use crate::util::{self, Month, Quarter, files};
+use crate::{
+    GuiError,
+    data::{AccountingItem, Category, Company},
+};
+use chrono::{Months, NaiveDate};
 use redb::{Database, ReadableTable, TableDefinition, TypeName, Value, WriteTransaction};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::any::type_name;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
-const DB_FILE: &str = "helferlein.redb";
+pub(crate) const DB_FILE: &str = "helferlein.redb";
 
 const ACCOUNTING_ITEMS_TABLE: TableDefinition<&str, Bincode<AccountingItem>> =
     TableDefinition::new("accounting_items");
@@ -18,6 +27,37 @@ const COMPANIES_TABLE: TableDefinition<&str, Bincode<Vec<String>>> =
 const CATEGORIES_TABLE: TableDefinition<&str, Bincode<Vec<String>>> =
     TableDefinition::new("categories");
 const INVOICES_TABLE: TableDefinition<&str, Bincode<Invoice>> = TableDefinition::new("invoices");
+const CATEGORY_DEFAULTS_TABLE: TableDefinition<&str, Bincode<CategoryDefaults>> =
+    TableDefinition::new("category_defaults");
+// notes of accounting items, by item id
+const NOTES_TABLE: TableDefinition<&str, &str> = TableDefinition::new("notes");
+// ids of accounting items excluded from the sums
+const EXCLUDED_ITEMS_TABLE: TableDefinition<&str, ()> = TableDefinition::new("excluded_items");
+const EXPORT_LOG_TABLE: TableDefinition<&str, Bincode<ExportLogEntry>> =
+    TableDefinition::new("export_log");
+// document kinds of invoice templates, by template key - missing entries are invoices
+const DOCUMENT_KINDS_TABLE: TableDefinition<&str, Bincode<DocumentKind>> =
+    TableDefinition::new("document_kinds");
+// whole-invoice discounts and surcharges of invoice templates, by template key
+const ADJUSTMENTS_TABLE: TableDefinition<&str, Bincode<Vec<Adjustment>>> =
+    TableDefinition::new("adjustments");
+// keys of invoice templates with gross prices
+const PRICES_INCLUDE_VAT_TABLE: TableDefinition<&str, ()> =
+    TableDefinition::new("prices_include_vat");
+// rounding steps of invoice templates, by template key - missing entries aren't rounded
+const ROUNDING_STEPS_TABLE: TableDefinition<&str, Bincode<RoundingStep>> =
+    TableDefinition::new("rounding_steps");
+// structured bank accounts of invoice templates, by template key
+const BANK_ACCOUNTS_TABLE: TableDefinition<&str, Bincode<BankAccount>> =
+    TableDefinition::new("bank_accounts");
+// the last used recipient address of invoices, by company name
+const COMPANY_ADDRESSES_TABLE: TableDefinition<&str, Bincode<Address>> =
+    TableDefinition::new("company_addresses");
+// keyed by the id only, so saving a draft again replaces it, even if its date changed
+const DRAFTS_TABLE: TableDefinition<&str, Bincode<Draft>> = TableDefinition::new("drafts");
+// positions of template and draft items with sub-items, e.g. "1.2", by template or draft key
+const POSITIONS_TABLE: TableDefinition<&str, Bincode<Vec<String>>> =
+    TableDefinition::new("positions");
 
 /// This can only be called once
 fn get_db(data_folder: &Path) -> Database {
@@ -25,95 +65,77 @@ fn get_db(data_folder: &Path) -> Database {
     let path = data_folder.join(db_file);
 
     let db = Database::create(path).expect("can create/open db file");
+    create_tables(&db);
+    db
+}
+
+fn create_tables(db: &Database) {
     if let Ok(write_txn) = db.begin_write() {
-        let _ = write_txn.open_table(NAMES_TABLE);
-        let _ = write_txn.open_table(COMPANIES_TABLE);
-        let _ = write_txn.open_table(CATEGORIES_TABLE);
-        let _ = write_txn.open_table(INVOICES_TABLE);
-        let _ = write_txn.open_table(ACCOUNTING_ITEMS_TABLE);
+        let _ = open_tables(&write_txn);
         let _ = write_txn.commit();
     }
+}
 
-    db
+// opening creates the tables, if they don't exist yet
+fn open_tables(write_txn: &WriteTransaction) -> Result<(), redb::TableError> {
+    write_txn.open_table(NAMES_TABLE)?;
+    write_txn.open_table(COMPANIES_TABLE)?;
+    write_txn.open_table(CATEGORIES_TABLE)?;
+    write_txn.open_table(INVOICES_TABLE)?;
+    write_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
+    write_txn.open_table(CATEGORY_DEFAULTS_TABLE)?;
+    write_txn.open_table(EXPORT_LOG_TABLE)?;
+    write_txn.open_table(NOTES_TABLE)?;
+    write_txn.open_table(EXCLUDED_ITEMS_TABLE)?;
+    write_txn.open_table(DOCUMENT_KINDS_TABLE)?;
+    write_txn.open_table(ADJUSTMENTS_TABLE)?;
+    write_txn.open_table(BANK_ACCOUNTS_TABLE)?;
+    write_txn.open_table(COMPANY_ADDRESSES_TABLE)?;
+    write_txn.open_table(DRAFTS_TABLE)?;
+    write_txn.open_table(POSITIONS_TABLE)?;
+    write_txn.open_table(PRICES_INCLUDE_VAT_TABLE)?;
+    write_txn.open_table(ROUNDING_STEPS_TABLE)?;
+    Ok(())
 }
-#[derive(Debug, Clone)]
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DateRange {
     pub from: String,
     pub to: String,
 }
 
+// covers every possible key, used to re-fetch all items after changes
+pub fn get_date_range_for_all_time() -> DateRange {
+    DateRange {
+        from: String::from("0000-01-01"),
+        to: String::from("9999-12-31"),
+    }
+}
+
 pub fn get_date_range_for_settings(
     year: i32,
     quarter: Option<Quarter>,
     month: Option<Month>,
+    fiscal_year_start_month: u32,
 ) -> DateRange {
-    let range_from = match quarter {
-        None => match month {
-            None => {
-                format!("{year}-01-01")
-            }
-            Some(m) => {
-                let month_num: u32 = m.into();
-                let date_from = NaiveDate::from_ymd_opt(year, month_num, 1);
-                match date_from {
-                    None => {
-                        format!("{year}-01-01")
-                    }
-                    Some(date) => date.format(KEY_DATE_FORMAT).to_string(),
-                }
-            }
-        },
-        Some(q) => {
-            let (from, _) = q.start_and_end_months();
-            let date_from = NaiveDate::from_ymd_opt(year, from, 1);
-            match date_from {
-                None => {
-                    format!("{year}-01-01")
-                }
-                Some(date) => date.format(KEY_DATE_FORMAT).to_string(),
-            }
-        }
-    };
-    let range_to = match quarter {
-        None => match month {
-            None => {
-                format!("{year}-12-31")
-            }
-            Some(m) => {
-                let month_num: u32 = m.into();
-                let date_from = NaiveDate::from_ymd_opt(year, month_num, 1);
-                match date_from {
-                    None => {
-                        format!("{year}-12-31")
-                    }
-                    Some(date) => {
-                        let last_day = util::last_day_of_month(date.year(), date.month());
-                        last_day.format(KEY_DATE_FORMAT).to_string()
-                    }
-                }
-            }
-        },
-        Some(q) => {
-            let (_, to) = q.start_and_end_months();
-            let date_to = NaiveDate::from_ymd_opt(year, to, 1);
-            match date_to {
-                None => {
-                    format!("{year}-12-31")
-                }
-                Some(date) => {
-                    let last_day = util::last_day_of_month(date.year(), date.month());
-                    last_day.format(KEY_DATE_FORMAT).to_string()
-                }
-            }
-        }
-    };
+    let (from, to) = util::time_span(year, quarter, month, fiscal_year_start_month);
     DateRange {
-        from: range_from,
-        to: range_to,
+        from: from.format(KEY_DATE_FORMAT).to_string(),
+        to: to.format(KEY_DATE_FORMAT).to_string(),
     }
 }
 
+// which suggestion lists got a new entry by saving an item, so only those have to be re-fetched
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct NewSuggestions {
+    pub(crate) name: bool,
+    pub(crate) category: bool,
+    pub(crate) company: bool,
+}
+
 pub(crate) const KEY_DATE_FORMAT: &str = "%Y-%m-%d";
+// sortable, so the export log is in chronological order
+const EXPORT_LOG_KEY_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6f";
 
 #[derive(Debug)]
 pub(crate) struct DB {
@@ -128,6 +150,28 @@ impl DB {
         }
     }
 
+    // keeps everything in memory, so DB methods can be tested without touching the filesystem
+    #[cfg(test)]
+    pub(crate) fn new_in_memory() -> Self {
+        let db = Database::builder()
+            .create_with_backend(redb::backends::InMemoryBackend::new())
+            .expect("can create in-memory db");
+        create_tables(&db);
+        Self { db }
+    }
+
+    // deletes all data at once and recreates the empty tables, e.g. before a handover
+    pub(crate) fn clear_all(&self) -> Result<(), GuiError> {
+        let write_txn = self.db.begin_write()?;
+        let tables: Vec<_> = write_txn.list_tables()?.collect();
+        for table in tables {
+            write_txn.delete_table(table)?;
+        }
+        open_tables(&write_txn)?;
+        write_txn.commit()?;
+        Ok(())
+    }
+
     pub(crate) fn get_key_for_item(item: &AccountingItem) -> String {
         format!("{}_{}", item.date.format(KEY_DATE_FORMAT), item.id)
     }
@@ -136,54 +180,249 @@ impl DB {
         format!("{}_{}", invoice.date.format(KEY_DATE_FORMAT), invoice.id)
     }
 
+    pub(crate) fn get_key_for_draft(id: Uuid) -> String {
+        id.to_string()
+    }
+
     // ACCOUNTING ITEMS
     pub(crate) fn get_accounting_items_for_range(
         &self,
         date_range: &DateRange,
     ) -> Result<Vec<AccountingItem>, GuiError> {
-        let table = self
-            .db
-            .begin_read()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?
-            .open_table(ACCOUNTING_ITEMS_TABLE)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
 
         // add \x7f, because it compares bit-wise, so date{something} doesn't match date_a324
         let iter = table
-            .range(date_range.from.as_str()..=format!("{}\x7f", date_range.to.as_str()).as_str())
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            .range(date_range.from.as_str()..=format!("{}\x7f", date_range.to.as_str()).as_str())?;
+
+        let mut items: Vec<AccountingItem> =
+            iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect();
+        let notes = read_txn.open_table(NOTES_TABLE)?;
+        attach_notes(&mut items, &notes)?;
+        let excluded = read_txn.open_table(EXCLUDED_ITEMS_TABLE)?;
+        attach_excluded(&mut items, &excluded)?;
+        Ok(items)
+    }
+
+    pub(crate) fn get_all_accounting_items(&self) -> Result<Vec<AccountingItem>, GuiError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
+
+        let iter = table.iter()?;
+
+        let mut items: Vec<AccountingItem> =
+            iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect();
+        let notes = read_txn.open_table(NOTES_TABLE)?;
+        attach_notes(&mut items, &notes)?;
+        let excluded = read_txn.open_table(EXCLUDED_ITEMS_TABLE)?;
+        attach_excluded(&mut items, &excluded)?;
+        Ok(items)
+    }
+
+    // the date of the latest item before the range, which lies in the latest period with items
+    pub(crate) fn latest_nonempty_period_before(
+        &self,
+        date_range: &DateRange,
+    ) -> Result<Option<NaiveDate>, GuiError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
+        let latest = table
+            .range(..date_range.from.as_str())?
+            .next_back()
+            .transpose()?;
+        Ok(latest.map(|(_, item)| item.value().date))
+    }
+
+    // groups of items with the same date, company and net amount, ordered by date
+    pub(crate) fn find_duplicate_items(&self) -> Result<Vec<Vec<AccountingItem>>, GuiError> {
+        let mut groups: BTreeMap<(NaiveDate, String, Decimal), Vec<AccountingItem>> =
+            BTreeMap::new();
+        for item in self.get_all_accounting_items()? {
+            groups
+                .entry((item.date, item.company.0.to_owned(), item.net.value))
+                .or_default()
+                .push(item);
+        }
+        Ok(groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect())
+    }
+
+    // moves the item files into, or out of, per-year subfolders of the files folder and updates
+    // their paths - returns how many were moved
+    // a file shared by several items, e.g. of copied items, is moved once, by the date of its
+    // earliest item - the paths are committed first and everything is rolled back, if a move fails
+    pub(crate) fn relocate_item_files(
+        &self,
+        files_root: &Path,
+        per_year: bool,
+    ) -> Result<usize, GuiError> {
+        let mut by_file: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+        let mut moves: Vec<(PathBuf, PathBuf)> = vec![];
+        {
+            let table = self.db.begin_read()?.open_table(ACCOUNTING_ITEMS_TABLE)?;
+            // keys start with the date, so the earliest item of a file comes first
+            for (key, item) in table
+                .iter()?
+                .filter_map(|r| r.map(|(k, v)| (k.value().to_owned(), v.value())).ok())
+            {
+                let keys = by_file.entry(item.file.clone()).or_default();
+                if keys.is_empty() && item.file.exists() {
+                    if let Some(target) =
+                        files::relocated_file_path(files_root, &item.file, item.date, per_year)
+                    {
+                        moves.push((item.file.clone(), target));
+                    }
+                }
+                keys.push(key);
+            }
+        }
+
+        self.update_item_files(&moves, &by_file)?;
+        for (done, (from, to)) in moves.iter().enumerate() {
+            if let Err(e) = files::move_file(from, to) {
+                log::error!("Could not move {from:?} to {to:?}: {e} - rolling back");
+                for (from, to) in moves[..done].iter().rev() {
+                    if let Err(e) = files::move_file(to, from) {
+                        log::error!("Could not move {to:?} back to {from:?}: {e}");
+                    }
+                }
+                let reverted: Vec<(PathBuf, PathBuf)> = moves
+                    .iter()
+                    .map(|(from, to)| (to.clone(), from.clone()))
+                    .collect();
+                let by_target = moves
+                    .iter()
+                    .filter_map(|(from, to)| Some((to.clone(), by_file.get(from)?.clone())))
+                    .collect();
+                self.update_item_files(&reverted, &by_target)?;
+                return Err(GuiError::Io(e));
+            }
+        }
+        Ok(moves.len())
+    }
+
+    // points all items of each moved file to its new path
+    fn update_item_files(
+        &self,
+        moves: &[(PathBuf, PathBuf)],
+        keys_by_file: &BTreeMap<PathBuf, Vec<String>>,
+    ) -> Result<(), GuiError> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
+            for (from, to) in moves {
+                for key in keys_by_file.get(from).into_iter().flatten() {
+                    let item = table.get(key.as_str())?.map(|v| v.value());
+                    if let Some(mut item) = item {
+                        item.file = to.clone();
+                        table.insert(key.as_str(), item)?;
+                    }
+                }
+            }
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    // counts the accounting items, whose file doesn't exist (anymore)
+    pub(crate) fn check_file_integrity(&self, files_root: &Path) -> Result<usize, GuiError> {
+        if !files_root.exists() {
+            log::warn!("files folder {files_root:?} does not exist");
+        }
+        let table = self.db.begin_read()?.open_table(ACCOUNTING_ITEMS_TABLE)?;
+
+        let iter = table.iter()?;
+
+        Ok(iter
+            .filter_map(|r| r.map(|v| v.1.value()).ok())
+            .filter(|item| {
+                let file = if item.file.is_absolute() {
+                    item.file.to_path_buf()
+                } else {
+                    files_root.join(&item.file)
+                };
+                !file.exists()
+            })
+            .count())
+    }
+
+    // EXPORT LOG
+    pub(crate) fn record_export(&self, entry: ExportLogEntry) -> Result<(), GuiError> {
+        let key = format!(
+            "{}_{}",
+            entry.timestamp.format(EXPORT_LOG_KEY_FORMAT),
+            entry.id
+        );
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(EXPORT_LOG_TABLE)?;
+
+            table.insert(key.as_str(), entry)?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    pub(crate) fn get_export_log(&self) -> Result<Vec<ExportLogEntry>, GuiError> {
+        let table = self.db.begin_read()?.open_table(EXPORT_LOG_TABLE)?;
+
+        let iter = table.iter()?;
 
         Ok(iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect())
     }
 
+    // INVOICE TEMPLATES
     fn fetch_invoice_templates(
         &self,
         write_txn: &WriteTransaction,
     ) -> Result<Vec<Invoice>, GuiError> {
-        let table = write_txn
-            .open_table(INVOICES_TABLE)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let table = write_txn.open_table(INVOICES_TABLE)?;
 
-        let iter = table
-            .iter()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let iter = table.iter()?;
 
-        Ok(iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect())
+        let mut invoices: Vec<Invoice> = iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect();
+        let kinds = write_txn.open_table(DOCUMENT_KINDS_TABLE)?;
+        let adjustments = write_txn.open_table(ADJUSTMENTS_TABLE)?;
+        let bank_accounts = write_txn.open_table(BANK_ACCOUNTS_TABLE)?;
+        let positions = write_txn.open_table(POSITIONS_TABLE)?;
+        let prices_include_vat = write_txn.open_table(PRICES_INCLUDE_VAT_TABLE)?;
+        let rounding_steps = write_txn.open_table(ROUNDING_STEPS_TABLE)?;
+        attach_template_extras(
+            &mut invoices,
+            &kinds,
+            &adjustments,
+            &bank_accounts,
+            &positions,
+        )?;
+        attach_template_settings(&mut invoices, &prices_include_vat, &rounding_steps)?;
+        Ok(invoices)
     }
 
     pub(crate) fn get_invoice_templates(&self) -> Result<Vec<Invoice>, GuiError> {
-        let table = self
-            .db
-            .begin_read()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?
-            .open_table(INVOICES_TABLE)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(INVOICES_TABLE)?;
 
-        let iter = table
-            .iter()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let iter = table.iter()?;
 
-        Ok(iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect())
+        let mut invoices: Vec<Invoice> = iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect();
+        let kinds = read_txn.open_table(DOCUMENT_KINDS_TABLE)?;
+        let adjustments = read_txn.open_table(ADJUSTMENTS_TABLE)?;
+        let bank_accounts = read_txn.open_table(BANK_ACCOUNTS_TABLE)?;
+        let positions = read_txn.open_table(POSITIONS_TABLE)?;
+        let prices_include_vat = read_txn.open_table(PRICES_INCLUDE_VAT_TABLE)?;
+        let rounding_steps = read_txn.open_table(ROUNDING_STEPS_TABLE)?;
+        attach_template_extras(
+            &mut invoices,
+            &kinds,
+            &adjustments,
+            &bank_accounts,
+            &positions,
+        )?;
+        attach_template_settings(&mut invoices, &prices_include_vat, &rounding_steps)?;
+        Ok(invoices)
     }
 
     pub(crate) fn create_invoice_template_and_refetch(
@@ -191,26 +430,53 @@ impl DB {
         invoice: &Invoice,
     ) -> Result<Vec<Invoice>, GuiError> {
         let key = DB::get_key_for_invoice(invoice);
-        let write_txn = self
-            .db
-            .begin_write()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let write_txn = self.db.begin_write()?;
         {
-            let mut table = write_txn
-                .open_table(INVOICES_TABLE)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            let mut table = write_txn.open_table(INVOICES_TABLE)?;
+
+            table.insert(key.as_str(), invoice)?;
+
+            let mut kinds = write_txn.open_table(DOCUMENT_KINDS_TABLE)?;
+            if invoice.kind == DocumentKind::default() {
+                kinds.remove(key.as_str())?;
+            } else {
+                kinds.insert(key.as_str(), invoice.kind)?;
+            }
+
+            let mut adjustments = write_txn.open_table(ADJUSTMENTS_TABLE)?;
+            if invoice.adjustments.is_empty() {
+                adjustments.remove(key.as_str())?;
+            } else {
+                adjustments.insert(key.as_str(), &invoice.adjustments)?;
+            }
+
+            let mut bank_accounts = write_txn.open_table(BANK_ACCOUNTS_TABLE)?;
+            if invoice.bank_account.is_empty() {
+                bank_accounts.remove(key.as_str())?;
+            } else {
+                bank_accounts.insert(key.as_str(), &invoice.bank_account)?;
+            }
+
+            let mut prices_include_vat = write_txn.open_table(PRICES_INCLUDE_VAT_TABLE)?;
+            if invoice.prices_include_vat {
+                prices_include_vat.insert(key.as_str(), ())?;
+            } else {
+                prices_include_vat.remove(key.as_str())?;
+            }
+
+            let mut rounding_steps = write_txn.open_table(ROUNDING_STEPS_TABLE)?;
+            if invoice.rounding_step == RoundingStep::default() {
+                rounding_steps.remove(key.as_str())?;
+            } else {
+                rounding_steps.insert(key.as_str(), invoice.rounding_step)?;
+            }
 
-            table
-                .insert(key.as_str(), invoice)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            update_positions(&key, &invoice.items, &write_txn)?;
         }
-        let res = self
-            .fetch_invoice_templates(&write_txn)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        self.update_company_address(&invoice.to, &write_txn)?;
+        let res = self.fetch_invoice_templates(&write_txn)?;
 
-        write_txn
-            .commit()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        write_txn.commit()?;
         Ok(res)
     }
 
@@ -218,26 +484,63 @@ impl DB {
         &self,
         key: &str,
     ) -> Result<Vec<Invoice>, GuiError> {
-        let write_txn = self
-            .db
-            .begin_write()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let write_txn = self.db.begin_write()?;
         {
-            let mut table = write_txn
-                .open_table(INVOICES_TABLE)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            let mut table = write_txn.open_table(INVOICES_TABLE)?;
+
+            table.remove(key)?;
 
-            table
-                .remove(key)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            write_txn.open_table(DOCUMENT_KINDS_TABLE)?.remove(key)?;
+            write_txn.open_table(ADJUSTMENTS_TABLE)?.remove(key)?;
+            write_txn.open_table(BANK_ACCOUNTS_TABLE)?.remove(key)?;
+            write_txn.open_table(POSITIONS_TABLE)?.remove(key)?;
+            write_txn
+                .open_table(PRICES_INCLUDE_VAT_TABLE)?
+                .remove(key)?;
+            write_txn.open_table(ROUNDING_STEPS_TABLE)?.remove(key)?;
         }
-        let res = self
-            .fetch_invoice_templates(&write_txn)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let res = self.fetch_invoice_templates(&write_txn)?;
 
-        write_txn
-            .commit()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        write_txn.commit()?;
+        Ok(res)
+    }
+
+    // DRAFTS
+    pub(crate) fn get_drafts(&self) -> Result<Vec<Draft>, GuiError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(DRAFTS_TABLE)?;
+
+        let iter = table.iter()?;
+
+        let mut drafts: Vec<Draft> = iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect();
+        attach_draft_positions(&mut drafts, &read_txn.open_table(POSITIONS_TABLE)?)?;
+        Ok(drafts)
+    }
+
+    // creates or replaces the draft and returns all drafts
+    pub(crate) fn save_draft(&self, draft: &Draft) -> Result<Vec<Draft>, GuiError> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let key = DB::get_key_for_draft(draft.id());
+            write_txn
+                .open_table(DRAFTS_TABLE)?
+                .insert(key.as_str(), draft)?;
+            update_positions(&key, draft.items(), &write_txn)?;
+        }
+        let res = fetch_drafts(&write_txn)?;
+        write_txn.commit()?;
+        Ok(res)
+    }
+
+    // returns the remaining drafts
+    pub(crate) fn delete_draft(&self, key: &str) -> Result<Vec<Draft>, GuiError> {
+        let write_txn = self.db.begin_write()?;
+        {
+            write_txn.open_table(DRAFTS_TABLE)?.remove(key)?;
+            write_txn.open_table(POSITIONS_TABLE)?.remove(key)?;
+        }
+        let res = fetch_drafts(&write_txn)?;
+        write_txn.commit()?;
         Ok(res)
     }
 
@@ -245,33 +548,105 @@ impl DB {
         &self,
         item: &AccountingItem,
         date_range: &DateRange,
-    ) -> Result<Vec<AccountingItem>, GuiError> {
+    ) -> Result<(Vec<AccountingItem>, NewSuggestions), GuiError> {
         let key = DB::get_key_for_item(item);
-        let write_txn = self
-            .db
-            .begin_write()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let write_txn = self.db.begin_write()?;
+        let new_suggestions = {
+            let mut table = write_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
+
+            let new_suggestions = NewSuggestions {
+                name: self.create_or_update_name(&item.name, key.clone(), &write_txn)?,
+                category: self.create_or_update_category(
+                    &item.category,
+                    key.clone(),
+                    &write_txn,
+                )?,
+                company: self.create_or_update_company(&item.company, key.clone(), &write_txn)?,
+            };
+            self.update_category_defaults(item, &write_txn)?;
+            self.update_note(&item.id, &item.note, &write_txn)?;
+            self.update_excluded(&item.id, item.excluded, &write_txn)?;
+
+            table.insert(key.as_str(), item)?;
+            new_suggestions
+        };
+
+        let res = self.fetch_accounting_items_by_range(&write_txn, date_range)?;
+
+        write_txn.commit()?;
+        Ok((res, new_suggestions))
+    }
+
+    // stores several new items at once, e.g. the items recorded for an invoice
+    pub(crate) fn create_accounting_items_and_refetch(
+        &self,
+        items: &[AccountingItem],
+        date_range: &DateRange,
+    ) -> Result<Vec<AccountingItem>, GuiError> {
+        let write_txn = self.db.begin_write()?;
         {
-            let mut table = write_txn
-                .open_table(ACCOUNTING_ITEMS_TABLE)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            let mut table = write_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
 
-            self.create_or_update_name(&item.name, key.clone(), &write_txn)?;
-            self.create_or_update_category(&item.category, key.clone(), &write_txn)?;
-            self.create_or_update_company(&item.company, key.clone(), &write_txn)?;
+            for item in items {
+                let key = DB::get_key_for_item(item);
+                self.create_or_update_name(&item.name, key.clone(), &write_txn)?;
+                self.create_or_update_category(&item.category, key.clone(), &write_txn)?;
+                self.create_or_update_company(&item.company, key.clone(), &write_txn)?;
+                self.update_note(&item.id, &item.note, &write_txn)?;
+                self.update_excluded(&item.id, item.excluded, &write_txn)?;
 
-            table
-                .insert(key.as_str(), item)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+                table.insert(key.as_str(), item)?;
+            }
         }
 
-        let res = self
-            .fetch_accounting_items_by_range(&write_txn, date_range)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let res = self.fetch_accounting_items_by_range(&write_txn, date_range)?;
 
-        write_txn
-            .commit()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        write_txn.commit()?;
+        Ok(res)
+    }
+
+    // re-inserts the given items with new ids and their dates shifted by `shift`
+    // attached files are referenced, not copied - they're copied to the new id once the item is saved again
+    pub(crate) fn clone_items_to_period(
+        &self,
+        keys: &[String],
+        shift: Months,
+        date_range: &DateRange,
+    ) -> Result<Vec<AccountingItem>, GuiError> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
+
+            for key in keys {
+                let mut item = table.get(key.as_str())?.map(|v| v.value()).ok_or_else(|| {
+                    GuiError::DatabaseError(format!(
+                        "Item {key} does not exist and can't be copied."
+                    ))
+                })?;
+
+                let old_id = item.id;
+                item.id = Uuid::now_v7();
+                item.date = item.date.checked_add_months(shift).ok_or_else(|| {
+                    GuiError::DatabaseError(format!("Date of item {key} can't be shifted."))
+                })?;
+                let new_key = DB::get_key_for_item(&item);
+
+                self.create_or_update_name(&item.name, new_key.clone(), &write_txn)?;
+                self.create_or_update_category(&item.category, new_key.clone(), &write_txn)?;
+                self.create_or_update_company(&item.company, new_key.clone(), &write_txn)?;
+
+                let note = self.get_note(&old_id, &write_txn)?;
+                self.update_note(&item.id, &note, &write_txn)?;
+                let excluded = self.is_excluded(&old_id, &write_txn)?;
+                self.update_excluded(&item.id, excluded, &write_txn)?;
+
+                table.insert(new_key.as_str(), item)?;
+            }
+        }
+
+        let res = self.fetch_accounting_items_by_range(&write_txn, date_range)?;
+
+        write_txn.commit()?;
         Ok(res)
     }
 
@@ -280,16 +655,68 @@ impl DB {
         write_txn: &WriteTransaction,
         date_range: &DateRange,
     ) -> Result<Vec<AccountingItem>, GuiError> {
-        let table = write_txn
-            .open_table(ACCOUNTING_ITEMS_TABLE)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let table = write_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
 
         // add \x7f, because it compares bit-wise, so date{something} doesn't match date_a324
         let iter = table
-            .range(date_range.from.as_str()..=format!("{}\x7f", date_range.to.as_str()).as_str())
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            .range(date_range.from.as_str()..=format!("{}\x7f", date_range.to.as_str()).as_str())?;
 
-        Ok(iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect())
+        let mut items: Vec<AccountingItem> =
+            iter.filter_map(|r| r.map(|v| v.1.value()).ok()).collect();
+        let notes = write_txn.open_table(NOTES_TABLE)?;
+        attach_notes(&mut items, &notes)?;
+        let excluded = write_txn.open_table(EXCLUDED_ITEMS_TABLE)?;
+        attach_excluded(&mut items, &excluded)?;
+        Ok(items)
+    }
+
+    // NOTES
+    fn get_note(&self, id: &Uuid, write_txn: &WriteTransaction) -> Result<String, GuiError> {
+        let table = write_txn.open_table(NOTES_TABLE)?;
+        Ok(table
+            .get(id.to_string().as_str())?
+            .map(|v| v.value().to_owned())
+            .unwrap_or_default())
+    }
+
+    // empty notes are removed
+    fn update_note(
+        &self,
+        id: &Uuid,
+        note: &str,
+        write_txn: &WriteTransaction,
+    ) -> Result<(), GuiError> {
+        let mut table = write_txn.open_table(NOTES_TABLE)?;
+        let key = id.to_string();
+        if note.is_empty() {
+            table.remove(key.as_str())?;
+        } else {
+            table.insert(key.as_str(), note)?;
+        }
+        Ok(())
+    }
+
+    // EXCLUDED ITEMS
+    fn is_excluded(&self, id: &Uuid, write_txn: &WriteTransaction) -> Result<bool, GuiError> {
+        let table = write_txn.open_table(EXCLUDED_ITEMS_TABLE)?;
+        Ok(table.get(id.to_string().as_str())?.is_some())
+    }
+
+    // only excluded items are stored
+    fn update_excluded(
+        &self,
+        id: &Uuid,
+        excluded: bool,
+        write_txn: &WriteTransaction,
+    ) -> Result<(), GuiError> {
+        let mut table = write_txn.open_table(EXCLUDED_ITEMS_TABLE)?;
+        let key = id.to_string();
+        if excluded {
+            table.insert(key.as_str(), ())?;
+        } else {
+            table.remove(key.as_str())?;
+        }
+        Ok(())
     }
 
     pub(crate) fn delete_accounting_item_and_refetch(
@@ -297,20 +724,12 @@ impl DB {
         key: &str,
         date_range: &DateRange,
     ) -> Result<Vec<AccountingItem>, GuiError> {
-        let write_txn = self
-            .db
-            .begin_write()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let write_txn = self.db.begin_write()?;
 
         {
-            let mut table = write_txn
-                .open_table(ACCOUNTING_ITEMS_TABLE)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            let mut table = write_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
 
-            let res = table
-                .get(key)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?
-                .map(|v| v.value());
+            let res = table.get(key)?.map(|v| v.value());
 
             let value = match res {
                 None => {
@@ -322,20 +741,16 @@ impl DB {
             };
 
             self.remove_name(&value.name, key, &write_txn)?;
-            self.remove_category(&value.name, key, &write_txn)?;
-            self.remove_company(&value.name, key, &write_txn)?;
+            self.remove_category(&value.category, key, &write_txn)?;
+            self.remove_company(&value.company, key, &write_txn)?;
+            self.update_note(&value.id, "", &write_txn)?;
+            self.update_excluded(&value.id, false, &write_txn)?;
 
-            table
-                .remove(key)
-                .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+            table.remove(key)?;
         }
 
-        let res = self
-            .fetch_accounting_items_by_range(&write_txn, date_range)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
-        write_txn
-            .commit()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let res = self.fetch_accounting_items_by_range(&write_txn, date_range)?;
+        write_txn.commit()?;
         Ok(res)
     }
 
@@ -352,32 +767,173 @@ impl DB {
         self.get_all(CATEGORIES_TABLE)
     }
 
+    // moves all items of the category `from` to `to`, e.g. to fix a typo - returns how many items
+    // were changed
+    pub(crate) fn merge_category(&self, from: &str, to: &str) -> Result<usize, GuiError> {
+        if from == to || to.trim().is_empty() {
+            return Ok(0);
+        }
+        let write_txn = self.db.begin_write()?;
+        let merged = {
+            let merged = self.merge(from, to, &write_txn, CATEGORIES_TABLE, |item, to| {
+                item.category = Category(to.to_owned())
+            })?;
+            // the target's own defaults win
+            let mut defaults = write_txn.open_table(CATEGORY_DEFAULTS_TABLE)?;
+            let from_defaults = defaults.remove(from)?.map(|v| v.value());
+            if let Some(from_defaults) = from_defaults {
+                if defaults.get(to)?.is_none() {
+                    defaults.insert(to, from_defaults)?;
+                }
+            }
+            merged
+        };
+        write_txn.commit()?;
+        Ok(merged)
+    }
+
+    // moves all items of the company `from` to `to` - returns how many items were changed
+    pub(crate) fn merge_company(&self, from: &str, to: &str) -> Result<usize, GuiError> {
+        if from == to || to.trim().is_empty() {
+            return Ok(0);
+        }
+        let write_txn = self.db.begin_write()?;
+        let merged = self.merge(from, to, &write_txn, COMPANIES_TABLE, |item, to| {
+            item.company = Company(to.to_owned())
+        })?;
+        write_txn.commit()?;
+        Ok(merged)
+    }
+
+    pub(crate) fn count_category_items(&self, category: &str) -> Result<usize, GuiError> {
+        self.count_items(category, CATEGORIES_TABLE)
+    }
+
+    pub(crate) fn count_company_items(&self, company: &str) -> Result<usize, GuiError> {
+        self.count_items(company, COMPANIES_TABLE)
+    }
+
+    fn count_items(
+        &self,
+        key: &str,
+        table: TableDefinition<&str, Bincode<Vec<String>>>,
+    ) -> Result<usize, GuiError> {
+        let table = self.db.begin_read()?.open_table(table)?;
+        Ok(table.get(key)?.map(|v| v.value().len()).unwrap_or_default())
+    }
+
+    fn merge(
+        &self,
+        from: &str,
+        to: &str,
+        write_txn: &WriteTransaction,
+        table: TableDefinition<&str, Bincode<Vec<String>>>,
+        rename: fn(&mut AccountingItem, &str),
+    ) -> Result<usize, GuiError> {
+        let keys = write_txn
+            .open_table(table)?
+            .remove(from)?
+            .map(|v| v.value())
+            .unwrap_or_default();
+        let mut items = write_txn.open_table(ACCOUNTING_ITEMS_TABLE)?;
+        let mut merged = 0;
+        for key in keys {
+            let Some(mut item) = items.get(key.as_str())?.map(|v| v.value()) else {
+                continue;
+            };
+            rename(&mut item, to);
+            items.insert(key.as_str(), item)?;
+            self.create_or_update(to, key, write_txn, table)?;
+            merged += 1;
+        }
+        Ok(merged)
+    }
+
     fn get_all(
         &self,
         table: TableDefinition<&str, Bincode<Vec<String>>>,
     ) -> Result<Vec<String>, GuiError> {
-        let table = self
-            .db
-            .begin_read()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?
-            .open_table(table)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let table = self.db.begin_read()?.open_table(table)?;
 
-        let iter = table
-            .iter()
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+        let iter = table.iter()?;
+
+        Ok(iter
+            .filter_map(|r| r.map(|v| v.0.value().to_owned()).ok())
+            .collect())
+    }
+
+    // COMPANY ADDRESSES
+    pub(crate) fn save_company_address(&self, address: &Address) -> Result<(), GuiError> {
+        let write_txn = self.db.begin_write()?;
+        self.update_company_address(address, &write_txn)?;
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    pub(crate) fn get_address_for_company(
+        &self,
+        company: &str,
+    ) -> Result<Option<Address>, GuiError> {
+        let table = self.db.begin_read()?.open_table(COMPANY_ADDRESSES_TABLE)?;
+
+        Ok(table.get(company.trim())?.map(|v| v.value()))
+    }
+
+    pub(crate) fn get_address_companies(&self) -> Result<Vec<String>, GuiError> {
+        let table = self.db.begin_read()?.open_table(COMPANY_ADDRESSES_TABLE)?;
 
+        let iter = table.iter()?;
         Ok(iter
             .filter_map(|r| r.map(|v| v.0.value().to_owned()).ok())
             .collect())
     }
 
+    // remembers the address for its company, unless it has no name
+    fn update_company_address(
+        &self,
+        address: &Address,
+        write_txn: &WriteTransaction,
+    ) -> Result<(), GuiError> {
+        let company = address.name.trim();
+        if company.is_empty() {
+            return Ok(());
+        }
+        let mut table = write_txn.open_table(COMPANY_ADDRESSES_TABLE)?;
+        table.insert(company, address)?;
+        Ok(())
+    }
+
+    pub(crate) fn get_category_defaults(
+        &self,
+        category: &str,
+    ) -> Result<Option<CategoryDefaults>, GuiError> {
+        let table = self.db.begin_read()?.open_table(CATEGORY_DEFAULTS_TABLE)?;
+
+        Ok(table.get(category)?.map(|v| v.value()))
+    }
+
+    fn update_category_defaults(
+        &self,
+        item: &AccountingItem,
+        write_txn: &WriteTransaction,
+    ) -> Result<(), GuiError> {
+        let mut table = write_txn.open_table(CATEGORY_DEFAULTS_TABLE)?;
+        table.insert(
+            item.category.0.as_str(),
+            CategoryDefaults {
+                invoice_type: item.invoice_type,
+                vat: item.vat,
+            },
+        )?;
+        Ok(())
+    }
+
     fn create_or_update_name(
         &self,
         key: &str,
         accounting_item_key: String,
         write_txn: &WriteTransaction,
-    ) -> Result<(), GuiError> {
+    ) -> Result<bool, GuiError> {
         self.create_or_update(key, accounting_item_key, write_txn, NAMES_TABLE)
     }
 
@@ -386,7 +942,7 @@ impl DB {
         key: &str,
         accounting_item_key: String,
         write_txn: &WriteTransaction,
-    ) -> Result<(), GuiError> {
+    ) -> Result<bool, GuiError> {
         self.create_or_update(key, accounting_item_key, write_txn, CATEGORIES_TABLE)
     }
 
@@ -395,41 +951,34 @@ impl DB {
         key: &str,
         accounting_item_key: String,
         write_txn: &WriteTransaction,
-    ) -> Result<(), GuiError> {
+    ) -> Result<bool, GuiError> {
         self.create_or_update(key, accounting_item_key, write_txn, COMPANIES_TABLE)
     }
 
+    // returns whether the key is new
     fn create_or_update(
         &self,
         key: &str,
         accounting_item_key: String,
         write_txn: &WriteTransaction,
         table: TableDefinition<&str, Bincode<Vec<String>>>,
-    ) -> Result<(), GuiError> {
-        let mut table = write_txn
-            .open_table(table)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
-        let item = table
-            .get(key)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?
-            .map(|v| v.value());
+    ) -> Result<bool, GuiError> {
+        let mut table = write_txn.open_table(table)?;
+        let item = table.get(key)?.map(|v| v.value());
         match item {
             None => {
                 let accounting_item_keys = vec![accounting_item_key];
-                table
-                    .insert(key, accounting_item_keys)
-                    .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+                table.insert(key, accounting_item_keys)?;
+                Ok(true)
             }
             Some(mut v) => {
                 if !v.contains(&accounting_item_key) {
                     v.push(accounting_item_key);
-                    table
-                        .insert(key, v)
-                        .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+                    table.insert(key, v)?;
                 }
+                Ok(false)
             }
-        };
-        Ok(())
+        }
     }
 
     fn remove_name(
@@ -466,14 +1015,9 @@ impl DB {
         write_txn: &WriteTransaction,
         table: TableDefinition<&str, Bincode<Vec<String>>>,
     ) -> Result<(), GuiError> {
-        let mut table = write_txn
-            .open_table(table)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
-
-        let res = table
-            .get(key)
-            .map_err(|e| GuiError::DatabaseError(e.to_string()))?
-            .map(|v| v.value());
+        let mut table = write_txn.open_table(table)?;
+
+        let res = table.get(key)?.map(|v| v.value());
         match res {
             None => Ok(()),
             Some(mut v) => match v.iter().position(|v| *v == accounting_item_key) {
@@ -482,13 +1026,9 @@ impl DB {
                     v.remove(found);
                     // if it's the last item, remove the entire entry
                     if v.is_empty() {
-                        table
-                            .remove(key)
-                            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+                        table.remove(key)?;
                     } else {
-                        table
-                            .insert(key, v)
-                            .map_err(|e| GuiError::DatabaseError(e.to_string()))?;
+                        table.insert(key, v)?;
                     }
                     Ok(())
                 }
@@ -497,6 +1037,106 @@ impl DB {
     }
 }
 
+fn attach_notes(
+    items: &mut [AccountingItem],
+    notes: &impl ReadableTable<&'static str, &'static str>,
+) -> Result<(), GuiError> {
+    for item in items.iter_mut() {
+        if let Some(note) = notes.get(item.id.to_string().as_str())? {
+            item.note = note.value().to_owned();
+        }
+    }
+    Ok(())
+}
+
+fn attach_excluded(
+    items: &mut [AccountingItem],
+    excluded: &impl ReadableTable<&'static str, ()>,
+) -> Result<(), GuiError> {
+    for item in items.iter_mut() {
+        item.excluded = excluded.get(item.id.to_string().as_str())?.is_some();
+    }
+    Ok(())
+}
+
+// sets the fields of the templates, which are stored in separate tables
+fn attach_template_extras(
+    invoices: &mut [Invoice],
+    kinds: &impl ReadableTable<&'static str, Bincode<DocumentKind>>,
+    adjustments: &impl ReadableTable<&'static str, Bincode<Vec<Adjustment>>>,
+    bank_accounts: &impl ReadableTable<&'static str, Bincode<BankAccount>>,
+    positions: &impl ReadableTable<&'static str, Bincode<Vec<String>>>,
+) -> Result<(), GuiError> {
+    for invoice in invoices.iter_mut() {
+        let key = DB::get_key_for_invoice(invoice);
+        if let Some(positions) = positions.get(key.as_str())? {
+            attach_sub_positions(&mut invoice.items, positions.value());
+        }
+        if let Some(kind) = kinds.get(key.as_str())? {
+            invoice.kind = kind.value();
+        }
+        if let Some(adjustments) = adjustments.get(key.as_str())? {
+            invoice.adjustments = adjustments.value();
+        }
+        if let Some(bank_account) = bank_accounts.get(key.as_str())? {
+            invoice.bank_account = bank_account.value();
+        }
+    }
+    Ok(())
+}
+
+// the settings of the invoice form, which are stored with templates
+fn attach_template_settings(
+    invoices: &mut [Invoice],
+    prices_include_vat: &impl ReadableTable<&'static str, ()>,
+    rounding_steps: &impl ReadableTable<&'static str, Bincode<RoundingStep>>,
+) -> Result<(), GuiError> {
+    for invoice in invoices.iter_mut() {
+        let key = DB::get_key_for_invoice(invoice);
+        invoice.prices_include_vat = prices_include_vat.get(key.as_str())?.is_some();
+        if let Some(rounding_step) = rounding_steps.get(key.as_str())? {
+            invoice.rounding_step = rounding_step.value();
+        }
+    }
+    Ok(())
+}
+
+fn attach_draft_positions(
+    drafts: &mut [Draft],
+    positions: &impl ReadableTable<&'static str, Bincode<Vec<String>>>,
+) -> Result<(), GuiError> {
+    for draft in drafts.iter_mut() {
+        if let Some(positions) = positions.get(DB::get_key_for_draft(draft.id()).as_str())? {
+            attach_sub_positions(draft.items_mut(), positions.value());
+        }
+    }
+    Ok(())
+}
+
+fn fetch_drafts(write_txn: &WriteTransaction) -> Result<Vec<Draft>, GuiError> {
+    let table = write_txn.open_table(DRAFTS_TABLE)?;
+    let mut drafts: Vec<Draft> = table
+        .iter()?
+        .filter_map(|r| r.map(|v| v.1.value()).ok())
+        .collect();
+    attach_draft_positions(&mut drafts, &write_txn.open_table(POSITIONS_TABLE)?)?;
+    Ok(drafts)
+}
+
+// only items with sub-positions need them stored
+fn update_positions(
+    key: &str,
+    items: &[InvoiceItem],
+    write_txn: &WriteTransaction,
+) -> Result<(), GuiError> {
+    let mut table = write_txn.open_table(POSITIONS_TABLE)?;
+    match sub_positions(items) {
+        Some(positions) => table.insert(key, positions)?,
+        None => table.remove(key)?,
+    };
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct Bincode<T>(pub T);
 
@@ -536,3 +1176,741 @@ where
         TypeName::new(&format!("Bincode<{}>", type_name::<T>()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Address, ExportType, ServicePeriod};
+    use crate::data::{Category, Company, Vat, currency::CurrencyValue};
+    use chrono::{NaiveDate, NaiveDateTime};
+    use redb::ReadableTableMetadata;
+    use std::path::PathBuf;
+
+    fn item(date: &str, name: &str, company: &str, category: &str) -> AccountingItem {
+        AccountingItem {
+            date: NaiveDate::parse_from_str(date, KEY_DATE_FORMAT).unwrap(),
+            name: name.to_owned(),
+            company: Company(company.to_owned()),
+            category: Category(category.to_owned()),
+            ..AccountingItem::test_item()
+        }
+    }
+
+    fn q1_2024() -> DateRange {
+        get_date_range_for_settings(2024, Some(Quarter::Q1), None, 1)
+    }
+
+    #[test]
+    fn create_and_fetch_items() {
+        let db = DB::new_in_memory();
+        let first = item("2024-01-15", "Rent", "Landlord", "Office");
+        let (res, new_suggestions) = db
+            .create_or_update_accounting_item_and_refetch(&first, &q1_2024())
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id, first.id);
+        assert_eq!(
+            new_suggestions,
+            NewSuggestions {
+                name: true,
+                category: true,
+                company: true,
+            }
+        );
+
+        let mut updated = first.clone();
+        updated.name = String::from("Office Rent");
+        let (res, new_suggestions) = db
+            .create_or_update_accounting_item_and_refetch(&updated, &q1_2024())
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].name, "Office Rent");
+        // only the name is new, the company and category are already known
+        assert_eq!(
+            new_suggestions,
+            NewSuggestions {
+                name: true,
+                ..NewSuggestions::default()
+            }
+        );
+
+        assert_eq!(db.get_all_companies().unwrap(), vec!["Landlord"]);
+        assert_eq!(db.get_all_categories().unwrap(), vec!["Office"]);
+    }
+
+    #[test]
+    fn range_includes_boundary_days() {
+        let db = DB::new_in_memory();
+        let range = q1_2024();
+        for date in ["2023-12-31", "2024-01-01", "2024-03-31", "2024-04-01"] {
+            db.create_or_update_accounting_item_and_refetch(
+                &item(date, "Name", "Company", "Category"),
+                &range,
+            )
+            .unwrap();
+        }
+
+        // the last day's keys have a uuid suffix, which the \x7f upper bound has to include
+        let items = db.get_accounting_items_for_range(&range).unwrap();
+        let dates: Vec<String> = items
+            .iter()
+            .map(|i| i.date.format(KEY_DATE_FORMAT).to_string())
+            .collect();
+        assert_eq!(dates, vec!["2024-01-01", "2024-03-31"]);
+    }
+
+    #[test]
+    fn fiscal_year_range_spans_calendar_years() {
+        let db = DB::new_in_memory();
+        let range = get_date_range_for_settings(2024, None, None, 4);
+        assert_eq!(range.from, "2024-04-01");
+        assert_eq!(range.to, "2025-03-31");
+        for date in ["2024-03-31", "2024-04-01", "2025-03-31", "2025-04-01"] {
+            db.create_or_update_accounting_item_and_refetch(
+                &item(date, "Rent", "Landlord", "Office"),
+                &range,
+            )
+            .unwrap();
+        }
+
+        let dates = |range: &DateRange| -> Vec<String> {
+            db.get_accounting_items_for_range(range)
+                .unwrap()
+                .iter()
+                .map(|i| i.date.format(KEY_DATE_FORMAT).to_string())
+                .collect()
+        };
+        assert_eq!(dates(&range), vec!["2024-04-01", "2025-03-31"]);
+
+        let q4 = get_date_range_for_settings(2024, Some(Quarter::Q4), None, 4);
+        assert_eq!(q4.from, "2025-01-01");
+        assert_eq!(q4.to, "2025-03-31");
+        assert_eq!(dates(&q4), vec!["2025-03-31"]);
+
+        let march = get_date_range_for_settings(2023, None, Some(Month::March), 4);
+        assert_eq!(dates(&march), vec!["2024-03-31"]);
+    }
+
+    #[test]
+    fn names_are_cleaned_up_on_delete() {
+        let db = DB::new_in_memory();
+        let range = q1_2024();
+        let first = item("2024-01-15", "Rent", "Landlord", "Office");
+        let second = item("2024-02-15", "Rent", "Landlord", "Office");
+        db.create_or_update_accounting_item_and_refetch(&first, &range)
+            .unwrap();
+        db.create_or_update_accounting_item_and_refetch(&second, &range)
+            .unwrap();
+        assert_eq!(db.get_all_names().unwrap(), vec!["Rent"]);
+
+        // still referenced by the second item
+        let res = db
+            .delete_accounting_item_and_refetch(&DB::get_key_for_item(&first), &range)
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(db.get_all_names().unwrap(), vec!["Rent"]);
+
+        let res = db
+            .delete_accounting_item_and_refetch(&DB::get_key_for_item(&second), &range)
+            .unwrap();
+        assert!(res.is_empty());
+        assert!(db.get_all_names().unwrap().is_empty());
+    }
+
+    #[test]
+    fn categories_and_companies_are_cleaned_up_on_delete() {
+        let db = DB::new_in_memory();
+        let range = q1_2024();
+        let first = item("2024-01-15", "Rent", "Landlord", "Office");
+        db.create_or_update_accounting_item_and_refetch(&first, &range)
+            .unwrap();
+        assert_eq!(db.get_all_categories().unwrap(), vec!["Office"]);
+        assert_eq!(db.get_all_companies().unwrap(), vec!["Landlord"]);
+
+        db.delete_accounting_item_and_refetch(&DB::get_key_for_item(&first), &range)
+            .unwrap();
+        assert!(db.get_all_categories().unwrap().is_empty());
+        assert!(db.get_all_companies().unwrap().is_empty());
+    }
+
+    #[test]
+    fn notes_are_stored_copied_and_deleted() {
+        let db = DB::new_in_memory();
+        let all_time = get_date_range_for_all_time();
+        let mut first = item("2024-01-15", "Rent", "Landlord", "Office");
+        first.note = String::from("reimbursed by client X");
+        let (items, _) = db
+            .create_or_update_accounting_item_and_refetch(&first, &all_time)
+            .unwrap();
+        assert_eq!(items[0].note, "reimbursed by client X");
+
+        let items = db
+            .clone_items_to_period(&[DB::get_key_for_item(&first)], Months::new(12), &all_time)
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|i| i.note == "reimbursed by client X"));
+
+        db.delete_accounting_item_and_refetch(&DB::get_key_for_item(&first), &all_time)
+            .unwrap();
+        let notes = db.db.begin_read().unwrap().open_table(NOTES_TABLE).unwrap();
+        assert_eq!(notes.len().unwrap(), 1);
+        let items = db.get_all_accounting_items().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_ne!(items[0].id, first.id);
+        assert_eq!(items[0].note, "reimbursed by client X");
+    }
+
+    #[test]
+    fn latest_period_with_items_before_a_range() {
+        let db = DB::new_in_memory();
+        let all_time = get_date_range_for_all_time();
+        assert_eq!(db.latest_nonempty_period_before(&q1_2024()).unwrap(), None);
+
+        for date in ["2023-05-10", "2023-11-20", "2024-02-01"] {
+            db.create_or_update_accounting_item_and_refetch(
+                &item(date, "Rent", "Landlord", "Office"),
+                &all_time,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            db.latest_nonempty_period_before(&q1_2024()).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 11, 20)
+        );
+        let q3_2023 = get_date_range_for_settings(2023, Some(Quarter::Q3), None, 1);
+        assert_eq!(
+            db.latest_nonempty_period_before(&q3_2023).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 5, 10)
+        );
+    }
+
+    #[test]
+    fn excluded_flag_is_stored_copied_and_deleted() {
+        let db = DB::new_in_memory();
+        let all_time = get_date_range_for_all_time();
+        let mut first = item("2024-01-15", "Groceries", "Shop", "Private");
+        first.excluded = true;
+        let (items, _) = db
+            .create_or_update_accounting_item_and_refetch(&first, &all_time)
+            .unwrap();
+        assert!(items[0].excluded);
+
+        let items = db
+            .clone_items_to_period(&[DB::get_key_for_item(&first)], Months::new(12), &all_time)
+            .unwrap();
+        assert!(items.iter().all(|i| i.excluded));
+
+        first.excluded = false;
+        db.create_or_update_accounting_item_and_refetch(&first, &all_time)
+            .unwrap();
+        let items = db.get_all_accounting_items().unwrap();
+        assert_eq!(items.iter().filter(|i| i.excluded).count(), 1);
+
+        db.delete_accounting_item_and_refetch(&DB::get_key_for_item(&items[1]), &all_time)
+            .unwrap();
+        let excluded = db
+            .db
+            .begin_read()
+            .unwrap()
+            .open_table(EXCLUDED_ITEMS_TABLE)
+            .unwrap();
+        assert!(excluded.is_empty().unwrap());
+    }
+
+    fn invoice(name: &str, kind: DocumentKind) -> Invoice {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        Invoice {
+            id: Uuid::now_v7(),
+            date,
+            city: String::from("Vienna"),
+            name: name.to_owned(),
+            from: Address::new(),
+            to: Address::new(),
+            service_period: ServicePeriod {
+                from: date,
+                from_field: String::default(),
+                to: date,
+                to_field: String::default(),
+            },
+            invoice_number: String::default(),
+            pre_text: String::default(),
+            post_text: String::default(),
+            bank_data: String::default(),
+            items: vec![],
+            kind,
+            adjustments: vec![],
+            attachments: vec![],
+            bank_account: BankAccount::default(),
+            prices_include_vat: false,
+            rounding_step: RoundingStep::None,
+        }
+    }
+
+    #[test]
+    fn create_several_items_at_once() {
+        let db = DB::new_in_memory();
+        let mut first = item("2024-02-01", "Consulting", "Client", "Revenue");
+        first.note = String::from("INV-1");
+        let second = item("2024-05-01", "Hosting", "Client", "Revenue");
+        let res = db
+            .create_accounting_items_and_refetch(&[first.clone(), second], &q1_2024())
+            .unwrap();
+        // only the first one is in the fetched range
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id, first.id);
+        assert_eq!(res[0].note, "INV-1");
+        assert_eq!(db.get_all_names().unwrap().len(), 2);
+        assert_eq!(
+            db.get_all_companies().unwrap(),
+            vec![String::from("Client")]
+        );
+    }
+
+    #[test]
+    fn duplicates_are_grouped_by_date_company_and_net() {
+        let db = DB::new_in_memory();
+        let original = item("2024-02-01", "Hosting", "Provider", "IT");
+        // imported again, with a different name
+        let imported = item("2024-02-01", "Server", "Provider", "IT");
+        let other_company = item("2024-02-01", "Hosting", "Other", "IT");
+        let mut other_net = item("2024-02-01", "Hosting", "Provider", "IT");
+        other_net.net = CurrencyValue::new(2000);
+        let other_day = item("2024-02-02", "Hosting", "Provider", "IT");
+        let earlier = item("2024-01-10", "Rent", "Landlord", "Office");
+        let earlier_again = item("2024-01-10", "Rent", "Landlord", "Office");
+        db.create_accounting_items_and_refetch(
+            &[
+                original.clone(),
+                imported.clone(),
+                other_company,
+                other_net,
+                other_day,
+                earlier.clone(),
+                earlier_again.clone(),
+            ],
+            &q1_2024(),
+        )
+        .unwrap();
+
+        let groups = db.find_duplicate_items().unwrap();
+        let ids: Vec<Vec<Uuid>> = groups
+            .iter()
+            .map(|group| group.iter().map(|item| item.id).collect())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                vec![earlier.id, earlier_again.id],
+                vec![original.id, imported.id]
+            ]
+        );
+    }
+
+    #[test]
+    fn templates_keep_their_document_kind() {
+        let db = DB::new_in_memory();
+        let quote = invoice("Quote", DocumentKind::Quote);
+        db.create_invoice_template_and_refetch(&quote).unwrap();
+        // stored without a kind, like templates from before quotes existed
+        let plain = invoice("Invoice", DocumentKind::Invoice);
+        let templates = db.create_invoice_template_and_refetch(&plain).unwrap();
+        let kind_of = |templates: &[Invoice], name: &str| {
+            templates.iter().find(|t| t.name == name).map(|t| t.kind)
+        };
+        assert_eq!(kind_of(&templates, "Quote"), Some(DocumentKind::Quote));
+        assert_eq!(kind_of(&templates, "Invoice"), Some(DocumentKind::Invoice));
+        assert_eq!(db.get_invoice_templates().unwrap(), templates);
+
+        let templates = db
+            .delete_invoice_template_and_refetch(&DB::get_key_for_invoice(&quote))
+            .unwrap();
+        assert_eq!(templates.len(), 1);
+        let kinds = db
+            .db
+            .begin_read()
+            .unwrap()
+            .open_table(DOCUMENT_KINDS_TABLE)
+            .unwrap();
+        assert!(kinds.is_empty().unwrap());
+    }
+
+    #[test]
+    fn templates_keep_their_adjustments() {
+        let db = DB::new_in_memory();
+        let mut template = invoice("Rush", DocumentKind::Invoice);
+        template.adjustments = vec![Adjustment {
+            description: String::from("Rush surcharge"),
+            amount: CurrencyValue::new(10000),
+            vat: Vat::Twenty,
+        }];
+        let templates = db.create_invoice_template_and_refetch(&template).unwrap();
+        assert_eq!(templates[0].adjustments, template.adjustments);
+
+        let templates = db
+            .delete_invoice_template_and_refetch(&DB::get_key_for_invoice(&template))
+            .unwrap();
+        assert!(templates.is_empty());
+        let adjustments = db
+            .db
+            .begin_read()
+            .unwrap()
+            .open_table(ADJUSTMENTS_TABLE)
+            .unwrap();
+        assert!(adjustments.is_empty().unwrap());
+    }
+
+    #[test]
+    fn duplicated_templates_get_their_own_key() {
+        let db = DB::new_in_memory();
+        let mut template = invoice("Monthly", DocumentKind::Quote);
+        template.adjustments = vec![Adjustment {
+            description: String::from("Discount"),
+            amount: CurrencyValue::new(-1000),
+            vat: Vat::Twenty,
+        }];
+        db.create_invoice_template_and_refetch(&template).unwrap();
+
+        let copy = template.duplicate();
+        assert_ne!(
+            DB::get_key_for_invoice(&copy),
+            DB::get_key_for_invoice(&template)
+        );
+        let templates = db.create_invoice_template_and_refetch(&copy).unwrap();
+        assert_eq!(templates.len(), 2);
+        let stored_copy = templates.iter().find(|t| t.id == copy.id).unwrap();
+        assert_eq!(stored_copy.name, "Monthly (copy)");
+        assert_eq!(stored_copy.kind, DocumentKind::Quote);
+        assert_eq!(stored_copy.adjustments, template.adjustments);
+        assert!(templates.contains(&template));
+    }
+
+    #[test]
+    fn clear_all_leaves_empty_usable_tables() {
+        let db = DB::new_in_memory();
+        db.create_or_update_accounting_item_and_refetch(
+            &item("2024-01-15", "Rent", "Landlord", "Office"),
+            &q1_2024(),
+        )
+        .unwrap();
+        let mut template = invoice("Monthly", DocumentKind::Quote);
+        template.to.name = String::from("ACME");
+        db.create_invoice_template_and_refetch(&template).unwrap();
+        assert!(!db.get_address_companies().unwrap().is_empty());
+
+        db.clear_all().unwrap();
+        assert!(db.get_all_accounting_items().unwrap().is_empty());
+        assert!(db.get_all_names().unwrap().is_empty());
+        assert!(db.get_all_companies().unwrap().is_empty());
+        assert!(db.get_invoice_templates().unwrap().is_empty());
+        assert!(db.get_address_companies().unwrap().is_empty());
+        let kinds = db
+            .db
+            .begin_read()
+            .unwrap()
+            .open_table(DOCUMENT_KINDS_TABLE)
+            .unwrap();
+        assert!(kinds.is_empty().unwrap());
+
+        // the tables are still there
+        let (items, _) = db
+            .create_or_update_accounting_item_and_refetch(
+                &item("2024-02-01", "Phone", "Telco", "Office"),
+                &q1_2024(),
+            )
+            .unwrap();
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn drafts_are_restored_exactly() {
+        let db = DB::new_in_memory();
+        let mut invoice = invoice("Half done", DocumentKind::Invoice);
+        invoice.invoice_number = String::from("RE-7");
+        invoice.attachments = vec![PathBuf::from("/some/timesheet.pdf")];
+        invoice.adjustments = vec![Adjustment {
+            description: String::from("Discount"),
+            amount: CurrencyValue::new(-1000),
+            vat: Vat::Twenty,
+        }];
+        invoice.prices_include_vat = true;
+        invoice.rounding_step = RoundingStep::FiveCents;
+        let draft = invoice.clone().into_draft();
+        assert!(draft.prices_include_vat);
+        assert_eq!(draft.rounding_step, RoundingStep::FiveCents);
+        assert_eq!(db.save_draft(&draft).unwrap(), vec![draft.clone()]);
+
+        // saving again replaces the draft
+        invoice.date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        invoice.prices_include_vat = false;
+        invoice.rounding_step = RoundingStep::None;
+        let changed = invoice.clone().into_draft();
+        db.save_draft(&changed).unwrap();
+        let drafts = db.get_drafts().unwrap();
+        assert_eq!(drafts, vec![changed.clone()]);
+        assert_eq!(drafts[0].invoice(), invoice);
+
+        // drafts aren't templates
+        assert!(db.get_invoice_templates().unwrap().is_empty());
+
+        assert!(
+            db.delete_draft(&DB::get_key_for_draft(changed.id()))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn sub_positions_survive_templates_and_drafts() {
+        let db = DB::new_in_memory();
+        let mut template = invoice("Project", DocumentKind::Invoice);
+        template.items = ["1", "1.1", "1.2", "2"]
+            .iter()
+            .map(|nr| InvoiceItem {
+                nr: String::from(*nr),
+                ..InvoiceItem::test_item()
+            })
+            .collect();
+        let templates = db.create_invoice_template_and_refetch(&template).unwrap();
+        assert_eq!(templates, vec![template.clone()]);
+
+        let draft = template.clone().into_draft();
+        assert_eq!(db.get_drafts().unwrap(), vec![]);
+        db.save_draft(&draft).unwrap();
+        assert_eq!(db.get_drafts().unwrap()[0].items(), template.items);
+
+        // whole positions don't need to be stored separately
+        template.items.truncate(1);
+        db.create_invoice_template_and_refetch(&template).unwrap();
+        db.delete_draft(&DB::get_key_for_draft(draft.id())).unwrap();
+        let positions = db
+            .db
+            .begin_read()
+            .unwrap()
+            .open_table(POSITIONS_TABLE)
+            .unwrap();
+        assert!(positions.is_empty().unwrap());
+    }
+
+    #[test]
+    fn merging_rewrites_items_and_suggestions() {
+        let db = DB::new_in_memory();
+        for item in [
+            item("2024-01-15", "Paper", "Shop", "Buero"),
+            item("2024-02-15", "Pens", "Shop Inc.", "Buero"),
+            item("2024-03-15", "Desk", "Shop", "Büro"),
+            item("2024-03-20", "Taxi", "Cab Co", "Travel"),
+        ] {
+            db.create_or_update_accounting_item_and_refetch(&item, &q1_2024())
+                .unwrap();
+        }
+
+        assert_eq!(db.count_category_items("Buero").unwrap(), 2);
+        assert_eq!(db.merge_category("Buero", "Büro").unwrap(), 2);
+        assert_eq!(db.count_category_items("Buero").unwrap(), 0);
+        assert_eq!(db.get_all_categories().unwrap(), vec!["Büro", "Travel"]);
+        let items = db.get_all_accounting_items().unwrap();
+        assert_eq!(items.iter().filter(|i| i.category.0 == "Büro").count(), 3);
+        assert!(db.get_category_defaults("Buero").unwrap().is_none());
+        assert!(db.get_category_defaults("Büro").unwrap().is_some());
+
+        assert_eq!(db.count_company_items("Shop Inc.").unwrap(), 1);
+        assert_eq!(db.merge_company("Shop Inc.", "Shop").unwrap(), 1);
+        assert_eq!(db.get_all_companies().unwrap(), vec!["Cab Co", "Shop"]);
+        assert_eq!(db.merge_company("Shop", "Shop").unwrap(), 0);
+        assert_eq!(db.merge_company("Unknown", "Shop").unwrap(), 0);
+
+        // the merged suggestion still tracks its items, e.g. for deleting them
+        let desk = items.iter().find(|i| i.name == "Desk").unwrap();
+        db.delete_accounting_item_and_refetch(&DB::get_key_for_item(desk), &q1_2024())
+            .unwrap();
+        let pens = items.iter().find(|i| i.name == "Pens").unwrap();
+        db.delete_accounting_item_and_refetch(&DB::get_key_for_item(pens), &q1_2024())
+            .unwrap();
+        assert_eq!(db.get_all_categories().unwrap(), vec!["Büro", "Travel"]);
+        let paper = items.iter().find(|i| i.name == "Paper").unwrap();
+        db.delete_accounting_item_and_refetch(&DB::get_key_for_item(paper), &q1_2024())
+            .unwrap();
+        assert_eq!(db.get_all_categories().unwrap(), vec!["Travel"]);
+    }
+
+    #[test]
+    fn shared_files_are_relocated_once_and_rolled_back_on_failure() {
+        let root = std::env::temp_dir().join(format!("helferlein-relocate-{}", Uuid::now_v7()));
+        let files_root = root.join("files");
+        std::fs::create_dir_all(&files_root).unwrap();
+        let shared = files_root.join("shared.pdf");
+        std::fs::write(&shared, "pdf").unwrap();
+        let db = DB::new_in_memory();
+        let mut first = item("2024-01-15", "Phone", "Telco", "Office");
+        first.file = shared.clone();
+        let mut copy = item("2025-01-15", "Phone", "Telco", "Office");
+        copy.file = shared.clone();
+        db.create_accounting_items_and_refetch(&[first, copy], &q1_2024())
+            .unwrap();
+
+        assert_eq!(db.relocate_item_files(&files_root, true).unwrap(), 1);
+        let moved = files_root.join("2024").join("shared.pdf");
+        assert!(moved.exists());
+        assert!(
+            db.get_all_accounting_items()
+                .unwrap()
+                .iter()
+                .all(|i| i.file == moved)
+        );
+
+        // a blocked target rolls back the files moved before
+        let mut other = item("2025-02-01", "Desk", "Shop", "Office");
+        other.file = files_root.join("2025").join("other.pdf");
+        std::fs::create_dir_all(files_root.join("2025")).unwrap();
+        std::fs::write(&other.file, "pdf").unwrap();
+        std::fs::create_dir_all(files_root.join("other.pdf").join("blocked")).unwrap();
+        db.create_or_update_accounting_item_and_refetch(&other, &q1_2024())
+            .unwrap();
+        assert!(db.relocate_item_files(&files_root, false).is_err());
+        assert!(moved.exists());
+        assert!(other.file.exists());
+        let files: Vec<PathBuf> = db
+            .get_all_accounting_items()
+            .unwrap()
+            .into_iter()
+            .map(|i| i.file)
+            .collect();
+        assert_eq!(files, vec![moved.clone(), moved, other.file]);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn templates_keep_gross_prices_and_rounding() {
+        let db = DB::new_in_memory();
+        let mut gross = invoice("Gross", DocumentKind::Invoice);
+        gross.prices_include_vat = true;
+        gross.rounding_step = RoundingStep::TenCents;
+        let net = invoice("Net", DocumentKind::Invoice);
+        db.create_invoice_template_and_refetch(&gross).unwrap();
+        let templates = db.create_invoice_template_and_refetch(&net).unwrap();
+        assert!(templates.contains(&gross));
+        assert!(templates.contains(&net));
+
+        // saved again as net, without rounding
+        gross.prices_include_vat = false;
+        gross.rounding_step = RoundingStep::None;
+        let templates = db.create_invoice_template_and_refetch(&gross).unwrap();
+        assert!(templates.iter().all(|t| !t.prices_include_vat));
+        assert!(
+            templates
+                .iter()
+                .all(|t| t.rounding_step == RoundingStep::None)
+        );
+
+        db.delete_invoice_template_and_refetch(&DB::get_key_for_invoice(&gross))
+            .unwrap();
+        let stored = db
+            .db
+            .begin_read()
+            .unwrap()
+            .open_table(PRICES_INCLUDE_VAT_TABLE)
+            .unwrap();
+        assert!(stored.is_empty().unwrap());
+        let stored = db
+            .db
+            .begin_read()
+            .unwrap()
+            .open_table(ROUNDING_STEPS_TABLE)
+            .unwrap();
+        assert!(stored.is_empty().unwrap());
+    }
+
+    #[test]
+    fn templates_keep_their_bank_account() {
+        let db = DB::new_in_memory();
+        let mut template = invoice("Structured", DocumentKind::Invoice);
+        template.bank_account = BankAccount {
+            account_holder: String::from("Jane Doe"),
+            iban: String::from("AT611904300234573201"),
+            bic: String::from("BKAUATWW"),
+        };
+        db.create_invoice_template_and_refetch(&invoice("Free text", DocumentKind::Invoice))
+            .unwrap();
+        let templates = db.create_invoice_template_and_refetch(&template).unwrap();
+        let account_of = |name: &str| {
+            templates
+                .iter()
+                .find(|t| t.name == name)
+                .map(|t| t.bank_account.clone())
+        };
+        assert_eq!(
+            account_of("Structured"),
+            Some(template.bank_account.clone())
+        );
+        assert_eq!(account_of("Free text"), Some(BankAccount::default()));
+
+        db.delete_invoice_template_and_refetch(&DB::get_key_for_invoice(&template))
+            .unwrap();
+        let bank_accounts = db
+            .db
+            .begin_read()
+            .unwrap()
+            .open_table(BANK_ACCOUNTS_TABLE)
+            .unwrap();
+        assert!(bank_accounts.is_empty().unwrap());
+    }
+
+    #[test]
+    fn templates_remember_the_company_address() {
+        let db = DB::new_in_memory();
+        assert_eq!(db.get_address_for_company("ACME").unwrap(), None);
+
+        let mut template = invoice("March", DocumentKind::Invoice);
+        template.to.name = String::from("ACME");
+        template.to.city = String::from("Vienna");
+        db.create_invoice_template_and_refetch(&template).unwrap();
+        assert_eq!(
+            db.get_address_for_company(" ACME ").unwrap(),
+            Some(template.to.clone())
+        );
+
+        // the last used address wins
+        let moved = Address {
+            city: String::from("Graz"),
+            ..template.to.clone()
+        };
+        db.save_company_address(&moved).unwrap();
+        assert_eq!(db.get_address_for_company("ACME").unwrap(), Some(moved));
+        // addresses without a name are not remembered
+        db.save_company_address(&Address::new()).unwrap();
+        assert_eq!(db.get_address_companies().unwrap(), vec!["ACME"]);
+    }
+
+    #[test]
+    fn export_log_is_chronological() {
+        let db = DB::new_in_memory();
+        assert!(db.get_export_log().unwrap().is_empty());
+
+        let entry = |time: &str, export_type, reference: &str| ExportLogEntry {
+            timestamp: NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S").unwrap(),
+            ..ExportLogEntry::new(
+                export_type,
+                PathBuf::from(format!("{reference}.pdf")),
+                reference.to_owned(),
+            )
+        };
+        // recorded out of order
+        let second = entry("2024-05-01 10:00:00", ExportType::Invoice, "RE-42");
+        let third = entry("2024-05-01 10:00:01", ExportType::Accounting, "2024 Q1");
+        let first = entry("2024-04-30 23:59:59", ExportType::Accounting, "2024 March");
+        for e in [&second, &third, &first] {
+            db.record_export(e.clone()).unwrap();
+        }
+
+        assert_eq!(db.get_export_log().unwrap(), vec![first, second, third]);
+    }
+
+    #[test]
+    fn delete_missing_item_fails() {
+        let db = DB::new_in_memory();
+        assert!(
+            db.delete_accounting_item_and_refetch("2024-01-01_missing", &q1_2024())
+                .is_err()
+        );
+    }
+}
@@ -1,10 +1,109 @@
-use super::InvoiceState;
-use crate::messages::Messages;
-use eframe::egui::{Align, Layout, Ui};
-use egui_extras::{Column, TableBuilder};
+use super::{InvoiceState, Item};
+use crate::{
+    Colors,
+    data::{Unit, VATS},
+    messages::Messages,
+    util::validation::{Field, ValidationResult},
+};
+use eframe::egui::{Align, ComboBox, Key, Layout, Response, TextEdit, Ui};
+use egui_extras::{Column, TableBuilder, TableRow};
 use log::info;
 
 const ROW_HEIGHT: f32 = 30.0;
+const QUICK_ADD_FIELDS: &[Field] = &[
+    Field::Nr,
+    Field::Description,
+    Field::Amount,
+    Field::PricePerUnit,
+];
+
+// whether the user finished editing a quick-add field with enter
+fn submitted(response: &Response, ui: &Ui) -> bool {
+    response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter))
+}
+
+fn quick_add_text_field(
+    row: &mut TableRow,
+    value: &mut String,
+    field: &Field,
+    validation: &mut ValidationResult,
+    submit: &mut bool,
+) {
+    row.col(|ui| {
+        let response = ui.add(TextEdit::singleline(value).desired_width(f32::INFINITY));
+        if response.changed() {
+            validation.clear_for_field(field);
+        }
+        *submit |= submitted(&response, ui);
+    });
+}
+
+// inline row for adding items without the detailed form
+fn render_quick_add_row(state: &mut InvoiceState, row: &mut TableRow) {
+    let mut submit = false;
+    let item = &mut state.quick_add_item;
+    let validation = &mut state.quick_add_validation;
+    quick_add_text_field(row, &mut item.nr, &Field::Nr, validation, &mut submit);
+    quick_add_text_field(
+        row,
+        &mut item.decription,
+        &Field::Description,
+        validation,
+        &mut submit,
+    );
+    row.col(|ui| {
+        ComboBox::from_id_salt("quick_add_unit")
+            .width(ui.available_width())
+            .selected_text(item.unit.name())
+            .show_ui(ui, |ui| {
+                [Unit::Hour, Unit::Day, Unit::None].iter().for_each(|unit| {
+                    ui.selectable_value(&mut item.unit, *unit, unit.name());
+                });
+            });
+    });
+    quick_add_text_field(
+        row,
+        &mut item.amount,
+        &Field::Amount,
+        validation,
+        &mut submit,
+    );
+    quick_add_text_field(
+        row,
+        &mut item.price_per_unit,
+        &Field::PricePerUnit,
+        validation,
+        &mut submit,
+    );
+    row.col(|ui| {
+        ComboBox::from_id_salt("quick_add_vat")
+            .width(ui.available_width())
+            .selected_text(item.vat.name())
+            .show_ui(ui, |ui| {
+                VATS.iter().for_each(|vat| {
+                    ui.selectable_value(&mut item.vat, *vat, vat.name());
+                });
+            });
+    });
+    row.col(|ui| {
+        submit |= ui.button(Messages::Add.msg()).clicked();
+    });
+    row.col(|_| {});
+
+    if submit {
+        state.quick_add_validation = state.quick_add_item.validate();
+        if state.quick_add_validation.is_ok() {
+            let item = std::mem::take(&mut state.quick_add_item);
+            // keep unit and vat, since consecutive items usually share them
+            state.quick_add_item = Item {
+                unit: item.unit,
+                vat: item.vat,
+                ..Item::default()
+            };
+            state.upsert_item(item);
+        }
+    }
+}
 
 pub(super) fn build(state: &mut InvoiceState, ui: &mut Ui) {
     let mut item_to_remove: Option<usize> = None;
@@ -45,8 +144,12 @@ pub(super) fn build(state: &mut InvoiceState, ui: &mut Ui) {
             });
         })
         .body(|body| {
-            body.rows(ROW_HEIGHT, state.items.len(), |mut row| {
-                let row_index = row.index();
+            body.rows(ROW_HEIGHT, state.items.len() + 1, |mut row| {
+                if row.index() == 0 {
+                    render_quick_add_row(state, &mut row);
+                    return;
+                }
+                let row_index = row.index() - 1;
                 let item = &state.items[row_index].clone();
                 row.col(|ui| {
                     ui.label(&item.nr);
@@ -86,5 +189,13 @@ pub(super) fn build(state: &mut InvoiceState, ui: &mut Ui) {
         });
     if let Some(index) = item_to_remove {
         state.items.remove(index);
+        state.dirty = true;
     }
+    QUICK_ADD_FIELDS.iter().for_each(|field| {
+        if let Some(errors) = state.quick_add_validation.get_errors(field) {
+            errors.iter().for_each(|e| {
+                ui.colored_label(Colors::Error.col(), format!("❎ {}", e));
+            });
+        }
+    });
 }
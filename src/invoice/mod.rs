@@ -1,26 +1,41 @@
 use crate::{
-    data::{currency::CurrencyValue, Address, Invoice, InvoiceItem, ServicePeriod, Unit, Vat},
-    db::DB,
+    accounting::selected_date_range,
+    config::Config,
+    data::{
+        calculate_sum,
+        currency::{format_number, parse_number, CurrencyValue},
+        AccountingItem, Address, Adjustment, BankAccount, Category, Company, DocumentKind, Draft,
+        ExportLogEntry, ExportType, Invoice, InvoiceItem, InvoiceType, RoundingStep, ServicePeriod,
+        Unit, Vat, VATS,
+    },
+    date_format,
+    db::{DateRange, DB},
     messages::Messages,
-    ui,
+    ui::{self, autosuggest::AutoSuggest},
     util::{
         self,
-        export::invoice::{create_invoice_pdf, CreatePDFResult, MAX_ITEMS},
-        files::build_invoice_file_name,
-        validation::{Field, ValidationResult},
+        export::invoice::{
+            attachments::is_pdf, create_invoice_pdf, text::render_invoice_text, CreatePDFResult,
+            InvoiceOptions,
+        },
+        files::{
+            build_invoice_file_name, copy_file_and_rename, delete_file, files_folder_for,
+            PATH_FOR_FILES,
+        },
+        parse_flexible_date,
+        validation::{
+            is_valid_position, labelled_ibans, validate_iban, validate_vat_id, Field,
+            ValidationResult,
+        },
     },
-    AppContext, Colors, Event, GuiEvent, State, DATE_FORMAT,
+    AppContext, Colors, Event, GuiError, GuiEvent, State,
 };
 use chrono::NaiveDate;
-use eframe::egui::{Context, Grid, RichText, ScrollArea, SelectableLabel, TextEdit, Ui};
+use eframe::egui::{Button, Context, Grid, RichText, ScrollArea, SelectableLabel, TextEdit, Ui};
 use egui_extras::{Size, StripBuilder};
 use egui_extras_datepicker_fork::DatePickerButton;
 use egui_file::FileDialog;
-use rust_decimal::Decimal;
-use std::{
-    path::{Path, PathBuf},
-    str::FromStr,
-};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 mod items_table;
@@ -35,13 +50,109 @@ fn render_field_errors(field: &Field, validation_result: &ValidationResult, ui:
     }
 }
 
-fn export_pdf(path_buf: &Path, app_context: &AppContext, invoice: &Invoice) {
-    match create_invoice_pdf(path_buf, invoice) {
-        Ok(CreatePDFResult { .. }) => {
+// right away while typing, the error on export blocks it
+fn render_iban_warning(iban: Option<&str>, ui: &mut Ui) {
+    if iban.is_some_and(|iban| !iban.trim().is_empty() && !validate_iban(iban)) {
+        ui.end_row();
+        ui.label(""); // workaround because we can't span columns in a grid
+        ui.colored_label(
+            Colors::Warning.col(),
+            format!("⚠ {}", Messages::IbanNotValid),
+        );
+    }
+}
+
+fn render_vat_id_warning(country: &str, vat: &str, ui: &mut Ui) {
+    if !validate_vat_id(country, vat) {
+        ui.end_row();
+        ui.label(""); // workaround because we can't span columns in a grid
+        ui.colored_label(
+            Colors::Warning.col(),
+            format!("⚠ {}", Messages::VatIdDoesNotMatchCountry),
+        );
+    }
+}
+
+// None, if the form can't be converted - the error is shown as a notification
+fn invoice_or_notify(state: &InvoiceState, app_context: &AppContext) -> Option<Invoice> {
+    match Invoice::try_from(state) {
+        Ok(invoice) => Some(invoice),
+        Err(e) => {
+            log::error!("could not convert the invoice form: {e}");
+            util::send_gui_event(
+                &app_context.gui_event_sender,
+                GuiEvent::ShowErrorNotification(e.to_string()),
+            );
+            None
+        }
+    }
+}
+
+fn export_pdf(
+    path_buf: &Path,
+    app_context: &AppContext,
+    ctx: &Context,
+    invoice: &Invoice,
+    config: &Config,
+    options: &InvoiceOptions,
+) -> bool {
+    match create_invoice_pdf(path_buf, invoice, config.page_size, options) {
+        Ok(CreatePDFResult {
+            skipped_attachments,
+            missing_qr_code,
+        }) => {
             util::send_gui_event(
                 &app_context.gui_event_sender,
                 GuiEvent::ShowInfoNotification(String::from(Messages::PDFCreated.msg())),
             );
+            if !skipped_attachments.is_empty() {
+                util::send_gui_event(
+                    &app_context.gui_event_sender,
+                    GuiEvent::ShowWarningNotification(format!(
+                        "{} {}",
+                        Messages::AttachmentsSkipped.msg(),
+                        skipped_attachments
+                            .iter()
+                            .map(|a| a.to_string_lossy())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )),
+                );
+            }
+            if missing_qr_code {
+                util::send_gui_event(
+                    &app_context.gui_event_sender,
+                    GuiEvent::ShowWarningNotification(String::from(
+                        Messages::QRCodeMissingIBAN.msg(),
+                    )),
+                );
+            }
+            util::send_event_and_request_repaint(
+                ctx,
+                &app_context.background_event_sender,
+                Event::SaveCompanyAddress(invoice.to.clone()),
+            );
+            util::send_gui_event(
+                &app_context.gui_event_sender,
+                GuiEvent::SetLastExport(path_buf.to_path_buf()),
+            );
+            util::send_event_and_request_repaint(
+                ctx,
+                &app_context.background_event_sender,
+                Event::RecordExport(ExportLogEntry::new(
+                    ExportType::Invoice,
+                    path_buf.to_path_buf(),
+                    invoice.invoice_number.clone(),
+                )),
+            );
+            if config.open_pdf_after_export {
+                util::send_event_and_request_repaint(
+                    ctx,
+                    &app_context.background_event_sender,
+                    Event::OpenFile(path_buf.to_string_lossy().into_owned()),
+                );
+            }
+            true
         }
         Err(e) => {
             log::error!("PDF was not created: {}", e);
@@ -49,6 +160,7 @@ fn export_pdf(path_buf: &Path, app_context: &AppContext, invoice: &Invoice) {
                 &app_context.gui_event_sender,
                 GuiEvent::ShowErrorNotification(String::from(Messages::PDFNotCreated.msg())),
             );
+            false
         }
     }
 }
@@ -58,27 +170,67 @@ pub(crate) struct InvoiceState {
     pub(crate) metadata: Metadata,
     items: Vec<Item>,
     item_to_add: Item,
+    quick_add_item: Item,
     validation: ValidationResult,
     item_validation: ValidationResult,
+    quick_add_validation: ValidationResult,
+    adjustments: Vec<AdjustmentInput>,
+    adjustment_to_add: AdjustmentInput,
+    adjustment_validation: ValidationResult,
     export_state: ExportState,
     pub(crate) templates: Vec<Invoice>,
+    pub(crate) drafts: Vec<Draft>,
+    // the draft the form was loaded from, which is replaced when saving the draft again
+    draft_id: Option<Uuid>,
+    // companies, for which a recipient address is known
+    pub(crate) address_companies: Vec<String>,
+    to_name_autosuggest: AutoSuggest,
+    record_dialog: Option<RecordDialog>,
+    attachments: Vec<PathBuf>,
+    // changed since the last export, or since it was saved as a template or draft
+    dirty: bool,
 }
 
 #[derive(Debug)]
 struct ExportState {
     open_file_dialog: Option<FileDialog>,
+    attachment_dialog: Option<FileDialog>,
     selected_path: Option<PathBuf>,
+    // the last exported invoice, which can be recorded in accounting
+    last_export: Option<LastExport>,
 }
 
 impl ExportState {
     pub fn new() -> Self {
         Self {
             open_file_dialog: None,
+            attachment_dialog: None,
             selected_path: None,
+            last_export: None,
         }
     }
 }
 
+#[derive(Debug)]
+struct LastExport {
+    file: PathBuf,
+    invoice: Invoice,
+    prices_include_vat: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RecordMode {
+    PerItem,
+    SummarizedByVat,
+}
+
+#[derive(Debug)]
+struct RecordDialog {
+    mode: RecordMode,
+    category: String,
+    category_autosuggest: AutoSuggest,
+}
+
 impl InvoiceState {
     pub fn new() -> Self {
         let now = chrono::Local::now().date_naive();
@@ -88,26 +240,169 @@ impl InvoiceState {
                 from: Address::new(),
                 to: Address::new(),
                 date: now,
-                date_field: now.format(DATE_FORMAT).to_string(),
+                date_field: now.format(date_format()).to_string(),
                 city: String::default(),
                 invoice_number: String::default(),
                 service_period: ServicePeriod {
                     from: now,
-                    from_field: now.format(DATE_FORMAT).to_string(),
+                    from_field: now.format(date_format()).to_string(),
                     to: now,
-                    to_field: now.format(DATE_FORMAT).to_string(),
+                    to_field: now.format(date_format()).to_string(),
                 },
                 pretext: String::default(),
                 posttext: String::default(),
                 bank_data: String::default(),
+                bank_account: BankAccount::default(),
+                rounding_step: RoundingStep::default(),
+                prices_include_vat: false,
+                kind: DocumentKind::default(),
             },
             items: vec![],
             item_to_add: Item::default(),
+            quick_add_item: Item::default(),
             validation: ValidationResult::new(),
             item_validation: ValidationResult::new(),
+            quick_add_validation: ValidationResult::new(),
+            adjustments: vec![],
+            adjustment_to_add: AdjustmentInput::default(),
+            adjustment_validation: ValidationResult::new(),
             export_state: ExportState::new(),
             templates: vec![],
+            drafts: vec![],
+            draft_id: None,
+            address_companies: vec![],
+            to_name_autosuggest: AutoSuggest::new(),
+            record_dialog: None,
+            attachments: vec![],
+            dirty: false,
+        }
+    }
+
+    pub(crate) fn has_unsaved_changes(&self) -> bool {
+        self.dirty
+    }
+
+    // once the template or draft was stored
+    pub(crate) fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+
+    // e.g. "Too many items for PDF export. 30/25", if the rows don't fit on the PDF
+    fn too_many_rows(&self, config: &Config) -> Option<String> {
+        let rows = self.items.len() + self.adjustments.len();
+        let max_items = self.options(config).max_items();
+        (rows > max_items).then(|| {
+            format!(
+                "{} {}/{}",
+                Messages::TooManyItemsForPDFExport.msg(),
+                rows,
+                max_items
+            )
+        })
+    }
+
+    fn fill_from_template(&mut self, template: &Invoice) {
+        self.metadata = Metadata {
+            name: template.name.clone(),
+            from: template.from.clone(),
+            to: template.to.clone(),
+            date: template.date,
+            date_field: template.date.format(date_format()).to_string(),
+            city: template.city.clone(),
+            invoice_number: template.invoice_number.clone(),
+            service_period: template.service_period.clone(),
+            pretext: template.pre_text.clone(),
+            posttext: template.post_text.clone(),
+            bank_data: template.bank_data.clone(),
+            bank_account: template.bank_account.clone(),
+            rounding_step: template.rounding_step,
+            prices_include_vat: template.prices_include_vat,
+            kind: template.kind,
+        };
+        self.items = template
+            .items
+            .iter()
+            .map(|i| Item {
+                id: Uuid::now_v7(),
+                nr: i.nr.clone(),
+                decription: i.description.clone(),
+                unit: i.unit,
+                amount: format_number(&i.amount),
+                price_per_unit: i.price_per_unit.to_value_string(),
+                vat: i.vat,
+            })
+            .collect();
+        self.adjustments = template
+            .adjustments
+            .iter()
+            .map(|a| AdjustmentInput {
+                id: Uuid::now_v7(),
+                description: a.description.clone(),
+                amount: a.amount.to_value_string(),
+                vat: a.vat,
+            })
+            .collect();
+        // a new invoice, even if a draft was loaded before
+        self.draft_id = None;
+        self.dirty = true;
+    }
+
+    // unlike a template, a draft restores the whole form
+    fn load_draft(&mut self, draft: &Draft) {
+        let invoice = draft.invoice();
+        self.fill_from_template(&invoice);
+        self.attachments = invoice.attachments;
+        self.validation = ValidationResult::new();
+        self.draft_id = Some(draft.id());
+        self.dirty = false;
+    }
+
+    // the form's dates in the newly configured date format
+    pub(crate) fn reformat_dates(&mut self, from: &str, to: &str) {
+        let metadata = &mut self.metadata;
+        [
+            &mut metadata.date_field,
+            &mut metadata.service_period.from_field,
+            &mut metadata.service_period.to_field,
+        ]
+        .into_iter()
+        .for_each(|field| util::reformat_date_field(field, from, to));
+    }
+
+    pub(crate) fn fill_to_address(&mut self, address: Address) {
+        self.metadata.to = address;
+        self.dirty = true;
+        self.validation.clear_for_field(&Field::ToName);
+        self.validation.clear_for_field(&Field::ToAddress);
+        self.validation.clear_for_field(&Field::ToZip);
+        self.validation.clear_for_field(&Field::ToCity);
+    }
+
+    // pre-fills the bank account of new invoices
+    pub(crate) fn with_bank_account(mut self, bank_account: &BankAccount) -> Self {
+        self.metadata.bank_account = bank_account.clone();
+        self
+    }
+
+    fn options(&self, config: &Config) -> InvoiceOptions {
+        InvoiceOptions {
+            rounding_step: self.metadata.rounding_step,
+            prices_include_vat: self.metadata.prices_include_vat,
+            quantity_decimals: config.quantity_decimals,
+            top_margin_offset_mm: config.top_margin_offset_mm,
+            epc_qr_code: config.epc_qr_code,
+            vat_column: config.invoice_vat_column,
+        }
+    }
+
+    // replaces the item with the same id, or adds it at the end
+    fn upsert_item(&mut self, item: Item) {
+        self.dirty = true;
+        match self.items.iter_mut().find(|i| i.id == item.id) {
+            Some(existing) => *existing = item,
+            None => self.items.push(item),
         }
+        self.validation.clear_for_field(&Field::Items);
     }
 
     pub fn validate(&self) -> ValidationResult {
@@ -162,7 +457,7 @@ impl InvoiceState {
             );
         }
 
-        if NaiveDate::parse_from_str(&self.metadata.date_field, DATE_FORMAT).is_err() {
+        if parse_flexible_date(&self.metadata.date_field).is_none() {
             validation_result.add_error(Field::Date, Messages::DateNotValid.msg().to_owned());
         }
 
@@ -180,62 +475,129 @@ impl InvoiceState {
             );
         }
 
-        if self.metadata.invoice_number.is_empty() {
+        // quotes are sent before there is an invoice number
+        if self.metadata.invoice_number.is_empty() && self.metadata.kind == DocumentKind::Invoice {
             validation_result.add_error(
                 Field::Nr,
                 format!("{} {}", Messages::Nr, Messages::CanNotBeEmpty),
             );
         }
 
-        if NaiveDate::parse_from_str(&self.metadata.service_period.from_field, DATE_FORMAT).is_err()
-        {
+        if parse_flexible_date(&self.metadata.service_period.from_field).is_none() {
             validation_result.add_error(
                 Field::ServicePeriodFrom,
                 Messages::DateNotValid.msg().to_owned(),
             );
         }
 
-        if NaiveDate::parse_from_str(&self.metadata.service_period.to_field, DATE_FORMAT).is_err() {
+        if parse_flexible_date(&self.metadata.service_period.to_field).is_none() {
             validation_result.add_error(
                 Field::ServicePeriodTo,
                 Messages::DateNotValid.msg().to_owned(),
             );
         }
 
+        if self.items.is_empty() {
+            validation_result.add_error(Field::Items, Messages::NoInvoiceItems.msg().to_owned());
+        }
+
+        let iban = &self.metadata.bank_account.iban;
+        if !iban.trim().is_empty() && !validate_iban(iban) {
+            validation_result.add_error(Field::Iban, Messages::IbanNotValid.msg().to_owned());
+        }
+
+        validation_result
+    }
+
+    // the attachments are only needed for the PDF, not for templates or the text version
+    pub fn validate_for_export(&self) -> ValidationResult {
+        let mut validation_result = self.validate();
+        self.attachments
+            .iter()
+            .filter(|a| !a.exists())
+            .for_each(|a| {
+                validation_result.add_error(
+                    Field::Attachments,
+                    format!("{}: {}", Messages::FileDoesNotExist, a.to_string_lossy()),
+                );
+            });
         validation_result
     }
 }
 
-impl From<&InvoiceState> for Invoice {
-    fn from(value: &InvoiceState) -> Self {
-        Invoice {
+// the form is validated before, but a failed conversion shouldn't take down the app
+fn invalid_number(field: Messages, value: &str) -> GuiError {
+    GuiError::InvalidInvoice(format!("{field} \"{value}\" {}", Messages::NotANumber))
+}
+
+impl TryFrom<&InvoiceState> for Invoice {
+    type Error = GuiError;
+
+    fn try_from(value: &InvoiceState) -> Result<Self, Self::Error> {
+        let items = value
+            .items
+            .iter()
+            .cloned()
+            .map(|i| {
+                if !is_valid_position(&i.nr) {
+                    return Err(invalid_number(Messages::Nr, &i.nr));
+                }
+                Ok(InvoiceItem {
+                    nr: i.nr.trim().to_owned(),
+                    amount: parse_number(&i.amount)
+                        .ok_or_else(|| invalid_number(Messages::Amount, &i.amount))?,
+                    price_per_unit: CurrencyValue::new_from_decimal(
+                        parse_number(&i.price_per_unit).ok_or_else(|| {
+                            invalid_number(Messages::PricePerUnit, &i.price_per_unit)
+                        })?,
+                    ),
+                    description: i.decription,
+                    unit: i.unit,
+                    vat: i.vat,
+                })
+            })
+            .collect::<Result<Vec<InvoiceItem>, GuiError>>()?;
+        let adjustments = value
+            .adjustments
+            .iter()
+            .map(|a| {
+                Ok(Adjustment {
+                    description: a.description.clone(),
+                    amount: CurrencyValue::new_from_decimal(
+                        parse_number(&a.amount)
+                            .ok_or_else(|| invalid_number(Messages::Amount, &a.amount))?,
+                    ),
+                    vat: a.vat,
+                })
+            })
+            .collect::<Result<Vec<Adjustment>, GuiError>>()?;
+        let service_period = &value.metadata.service_period;
+        Ok(Invoice {
             id: Uuid::now_v7(),
-            date: value.metadata.date.to_owned(),
+            // typed dates win over the date pickers, which keep the fields in sync
+            date: parse_flexible_date(&value.metadata.date_field).unwrap_or(value.metadata.date),
             city: value.metadata.city.to_owned(),
             name: value.metadata.name.to_owned(),
             from: value.metadata.from.to_owned(),
             to: value.metadata.to.to_owned(),
-            service_period: value.metadata.service_period.to_owned(),
+            service_period: ServicePeriod {
+                from: parse_flexible_date(&service_period.from_field)
+                    .unwrap_or(service_period.from),
+                to: parse_flexible_date(&service_period.to_field).unwrap_or(service_period.to),
+                ..service_period.to_owned()
+            },
             invoice_number: value.metadata.invoice_number.to_owned(),
             pre_text: value.metadata.pretext.to_owned(),
             post_text: value.metadata.posttext.to_owned(),
             bank_data: value.metadata.bank_data.to_owned(),
-            items: value
-                .items
-                .iter()
-                .cloned()
-                .map(|i| InvoiceItem {
-                    nr: i.nr.parse::<u64>().expect("is a valid number"),
-                    description: i.decription,
-                    unit: i.unit,
-                    amount: Decimal::from_str(&i.amount).expect("is a valid number"),
-                    price_per_unit: CurrencyValue::new_from_decimal(
-                        Decimal::from_str(&i.price_per_unit).expect("is a valid number"),
-                    ),
-                    vat: i.vat,
-                })
-                .collect(),
-        }
+            bank_account: value.metadata.bank_account.to_owned(),
+            kind: value.metadata.kind,
+            items,
+            adjustments,
+            attachments: value.attachments.clone(),
+            prices_include_vat: value.metadata.prices_include_vat,
+            rounding_step: value.metadata.rounding_step,
+        })
     }
 }
 
@@ -243,15 +605,19 @@ impl From<&InvoiceState> for Invoice {
 pub(crate) struct Metadata {
     pub(crate) name: String,
     from: Address,
-    to: Address,
+    pub(crate) to: Address,
     date: NaiveDate,
     date_field: String,
     city: String,
-    invoice_number: String,
+    pub(crate) invoice_number: String,
     service_period: ServicePeriod,
     pretext: String,
     posttext: String,
     bank_data: String,
+    bank_account: BankAccount,
+    rounding_step: RoundingStep,
+    prices_include_vat: bool,
+    pub(crate) kind: DocumentKind,
 }
 
 #[derive(Debug, Clone)]
@@ -279,14 +645,53 @@ impl Default for Item {
     }
 }
 
+// a discount or surcharge on the whole invoice, as entered in the form
+#[derive(Debug, Clone)]
+struct AdjustmentInput {
+    id: Uuid,
+    description: String,
+    amount: String,
+    vat: Vat,
+}
+
+impl Default for AdjustmentInput {
+    fn default() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            description: Default::default(),
+            amount: Default::default(),
+            vat: Vat::Twenty,
+        }
+    }
+}
+
+impl AdjustmentInput {
+    fn validate(&self) -> ValidationResult {
+        let mut validation_result = ValidationResult::new();
+        if self.description.is_empty() {
+            validation_result.add_error(
+                Field::Description,
+                format!("{} {}", Messages::Description, Messages::CanNotBeEmpty),
+            );
+        }
+        if parse_number(&self.amount).is_none() {
+            validation_result.add_error(
+                Field::Amount,
+                format!("{} {}", Messages::Amount, Messages::NotANumber),
+            );
+        }
+        validation_result
+    }
+}
+
 impl Item {
     pub fn validate(&self) -> ValidationResult {
         let mut validation_result = ValidationResult::new();
 
-        if self.nr.parse::<u64>().is_err() {
+        if !is_valid_position(&self.nr) {
             validation_result.add_error(
                 Field::Nr,
-                format!("{} {}", Messages::Nr, Messages::NotANumber),
+                format!("{} {}", Messages::Nr, Messages::NotAPosition),
             );
         }
 
@@ -296,14 +701,14 @@ impl Item {
                 format!("{} {}", Messages::Description, Messages::CanNotBeEmpty),
             );
         }
-        if Decimal::from_str(&self.amount).is_err() {
+        if parse_number(&self.amount).is_none() {
             validation_result.add_error(
                 Field::Amount,
                 format!("{} {}", Messages::Amount, Messages::NotANumber),
             );
         }
 
-        if Decimal::from_str(&self.price_per_unit).is_err() {
+        if parse_number(&self.price_per_unit).is_none() {
             validation_result.add_error(
                 Field::PricePerUnit,
                 format!("{} {}", Messages::PricePerUnit, Messages::NotANumber),
@@ -314,7 +719,13 @@ impl Item {
     }
 }
 
-pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext, ui: &mut Ui) {
+pub(crate) fn build(
+    ctx: &Context,
+    state: &mut State,
+    config: &Config,
+    app_context: &AppContext,
+    ui: &mut Ui,
+) {
     ui.label(RichText::new(Messages::Invoice).strong());
     ui.separator();
     StripBuilder::new(ui)
@@ -334,7 +745,9 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 ui.label(RichText::new(Messages::From).strong());
                                 ui.end_row();
                                 ui.label(Messages::Name);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.from.name);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.from.name)
+                                    .changed();
                                 render_field_errors(
                                     &Field::FromName,
                                     &state.invoice.validation,
@@ -342,9 +755,11 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 );
                                 ui.end_row();
                                 ui.label(Messages::PostalAddress);
-                                ui.text_edit_singleline(
-                                    &mut state.invoice.metadata.from.postal_address,
-                                );
+                                state.invoice.dirty |= ui
+                                    .text_edit_multiline(
+                                        &mut state.invoice.metadata.from.postal_address,
+                                    )
+                                    .changed();
                                 render_field_errors(
                                     &Field::FromAddress,
                                     &state.invoice.validation,
@@ -352,11 +767,15 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 );
                                 ui.end_row();
                                 ui.label(Messages::Zip);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.from.zip);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.from.zip)
+                                    .changed();
                                 render_field_errors(&Field::FromZip, &state.invoice.validation, ui);
                                 ui.end_row();
                                 ui.label(Messages::City);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.from.city);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.from.city)
+                                    .changed();
                                 render_field_errors(
                                     &Field::FromCity,
                                     &state.invoice.validation,
@@ -364,7 +783,9 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 );
                                 ui.end_row();
                                 ui.label(Messages::Country);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.from.country);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.from.country)
+                                    .changed();
                                 render_field_errors(
                                     &Field::FromCountry,
                                     &state.invoice.validation,
@@ -372,11 +793,20 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 );
                                 ui.end_row();
                                 ui.label(Messages::VatNr);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.from.vat);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.from.vat)
+                                    .changed();
                                 render_field_errors(&Field::FromVat, &state.invoice.validation, ui);
+                                render_vat_id_warning(
+                                    &state.invoice.metadata.from.country,
+                                    &state.invoice.metadata.from.vat,
+                                    ui,
+                                );
                                 ui.end_row();
                                 ui.label(Messages::Misc);
-                                ui.text_edit_multiline(&mut state.invoice.metadata.from.misc);
+                                state.invoice.dirty |= ui
+                                    .text_edit_multiline(&mut state.invoice.metadata.from.misc)
+                                    .changed();
                                 render_field_errors(
                                     &Field::FromMisc,
                                     &state.invoice.validation,
@@ -391,13 +821,35 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 ui.label(RichText::new(Messages::To).strong());
                                 ui.end_row();
                                 ui.label(Messages::Name);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.to.name);
+                                ui.horizontal(|ui| {
+                                    state.invoice.dirty |= state
+                                        .invoice
+                                        .to_name_autosuggest
+                                        .ui(
+                                            ui,
+                                            &mut state.invoice.metadata.to.name,
+                                            &state.invoice.address_companies,
+                                        )
+                                        .changed();
+                                    let company = state.invoice.metadata.to.name.trim();
+                                    if state.invoice.address_companies.iter().any(|c| c == company)
+                                        && ui.button(Messages::FillAddress).clicked()
+                                    {
+                                        util::send_event_and_request_repaint(
+                                            ctx,
+                                            &app_context.background_event_sender,
+                                            Event::FetchCompanyAddress(company.to_owned()),
+                                        );
+                                    }
+                                });
                                 render_field_errors(&Field::ToName, &state.invoice.validation, ui);
                                 ui.end_row();
                                 ui.label(Messages::PostalAddress);
-                                ui.text_edit_singleline(
-                                    &mut state.invoice.metadata.to.postal_address,
-                                );
+                                state.invoice.dirty |= ui
+                                    .text_edit_multiline(
+                                        &mut state.invoice.metadata.to.postal_address,
+                                    )
+                                    .changed();
                                 render_field_errors(
                                     &Field::ToAddress,
                                     &state.invoice.validation,
@@ -405,15 +857,21 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 );
                                 ui.end_row();
                                 ui.label(Messages::Zip);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.to.zip);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.to.zip)
+                                    .changed();
                                 render_field_errors(&Field::ToZip, &state.invoice.validation, ui);
                                 ui.end_row();
                                 ui.label(Messages::City);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.to.city);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.to.city)
+                                    .changed();
                                 render_field_errors(&Field::ToCity, &state.invoice.validation, ui);
                                 ui.end_row();
                                 ui.label(Messages::Country);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.to.country);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.to.country)
+                                    .changed();
                                 render_field_errors(
                                     &Field::ToCountry,
                                     &state.invoice.validation,
@@ -421,11 +879,20 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 );
                                 ui.end_row();
                                 ui.label(Messages::VatNr);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.to.vat);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.to.vat)
+                                    .changed();
                                 render_field_errors(&Field::ToVat, &state.invoice.validation, ui);
+                                render_vat_id_warning(
+                                    &state.invoice.metadata.to.country,
+                                    &state.invoice.metadata.to.vat,
+                                    ui,
+                                );
                                 ui.end_row();
                                 ui.label(Messages::Misc);
-                                ui.text_edit_multiline(&mut state.invoice.metadata.to.misc);
+                                state.invoice.dirty |= ui
+                                    .text_edit_multiline(&mut state.invoice.metadata.to.misc)
+                                    .changed();
                                 render_field_errors(&Field::ToMisc, &state.invoice.validation, ui);
                                 ui.end_row();
                             });
@@ -440,18 +907,44 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                             .show(ui, |ui| {
                                 ui.label(RichText::new(Messages::General).strong());
                                 ui.end_row();
+                                ui.label(Messages::DocumentKind);
+                                ui.horizontal(|ui| {
+                                    [DocumentKind::Invoice, DocumentKind::Quote]
+                                        .iter()
+                                        .for_each(|kind| {
+                                            if ui
+                                                .add(SelectableLabel::new(
+                                                    state.invoice.metadata.kind == *kind,
+                                                    kind.name(),
+                                                ))
+                                                .clicked()
+                                            {
+                                                state.invoice.metadata.kind = *kind;
+                                                state.invoice.dirty = true;
+                                                state
+                                                    .invoice
+                                                    .validation
+                                                    .clear_for_field(&Field::Nr);
+                                            }
+                                        });
+                                });
+                                ui.end_row();
                                 ui.label(Messages::Name);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.name);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.name)
+                                    .changed();
                                 render_field_errors(&Field::Name, &state.invoice.validation, ui);
                                 ui.end_row();
                                 ui.label(Messages::Date);
                                 ui.horizontal(|ui| {
-                                    ui.add(
-                                        TextEdit::singleline(
-                                            &mut state.invoice.metadata.date_field,
+                                    state.invoice.dirty |= ui
+                                        .add(
+                                            TextEdit::singleline(
+                                                &mut state.invoice.metadata.date_field,
+                                            )
+                                            .desired_width(65.0),
                                         )
-                                        .desired_width(65.0),
-                                    );
+                                        .changed();
                                     let date_response = ui.add(
                                         DatePickerButton::new(&mut state.invoice.metadata.date)
                                             .id_salt("metadata_date")
@@ -464,11 +957,12 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                             .highlight_weekends(false),
                                     );
                                     if date_response.changed() {
+                                        state.invoice.dirty = true;
                                         state.invoice.metadata.date_field = state
                                             .invoice
                                             .metadata
                                             .date
-                                            .format(DATE_FORMAT)
+                                            .format(date_format())
                                             .to_string();
                                         state.invoice.validation.clear_for_field(&Field::Date);
                                     }
@@ -476,24 +970,100 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 render_field_errors(&Field::Date, &state.invoice.validation, ui);
                                 ui.end_row();
                                 ui.label(Messages::City);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.city);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(&mut state.invoice.metadata.city)
+                                    .changed();
                                 render_field_errors(&Field::City, &state.invoice.validation, ui);
                                 ui.end_row();
                                 ui.label(Messages::Nr);
-                                ui.text_edit_singleline(&mut state.invoice.metadata.invoice_number);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(
+                                        &mut state.invoice.metadata.invoice_number,
+                                    )
+                                    .changed();
                                 render_field_errors(&Field::Nr, &state.invoice.validation, ui);
                                 ui.end_row();
                                 ui.end_row();
                                 ui.label(RichText::new(Messages::Misc).strong());
                                 ui.end_row();
                                 ui.label(Messages::PreText);
-                                ui.text_edit_multiline(&mut state.invoice.metadata.pretext);
+                                state.invoice.dirty |= ui
+                                    .text_edit_multiline(&mut state.invoice.metadata.pretext)
+                                    .changed();
                                 ui.end_row();
                                 ui.label(Messages::PostText);
-                                ui.text_edit_multiline(&mut state.invoice.metadata.posttext);
+                                state.invoice.dirty |= ui
+                                    .text_edit_multiline(&mut state.invoice.metadata.posttext)
+                                    .changed();
                                 ui.end_row();
                                 ui.label(Messages::BankData);
-                                ui.text_edit_multiline(&mut state.invoice.metadata.bank_data);
+                                state.invoice.dirty |= ui
+                                    .text_edit_multiline(&mut state.invoice.metadata.bank_data)
+                                    .changed();
+                                render_iban_warning(
+                                    labelled_ibans(&state.invoice.metadata.bank_data)
+                                        .find(|iban| !validate_iban(iban)),
+                                    ui,
+                                );
+                                ui.end_row();
+                                ui.label(Messages::AccountHolder);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(
+                                        &mut state.invoice.metadata.bank_account.account_holder,
+                                    )
+                                    .changed();
+                                ui.end_row();
+                                ui.label(Messages::Iban);
+                                if ui
+                                    .text_edit_singleline(
+                                        &mut state.invoice.metadata.bank_account.iban,
+                                    )
+                                    .changed()
+                                {
+                                    state.invoice.dirty = true;
+                                    state.invoice.validation.clear_for_field(&Field::Iban);
+                                }
+                                render_field_errors(&Field::Iban, &state.invoice.validation, ui);
+                                if state.invoice.validation.get_errors(&Field::Iban).is_none() {
+                                    render_iban_warning(
+                                        Some(&state.invoice.metadata.bank_account.iban),
+                                        ui,
+                                    );
+                                }
+                                ui.end_row();
+                                ui.label(Messages::Bic);
+                                state.invoice.dirty |= ui
+                                    .text_edit_singleline(
+                                        &mut state.invoice.metadata.bank_account.bic,
+                                    )
+                                    .changed();
+                                ui.end_row();
+                                ui.label(Messages::RoundTotal);
+                                ui.horizontal(|ui| {
+                                    [
+                                        RoundingStep::None,
+                                        RoundingStep::FiveCents,
+                                        RoundingStep::TenCents,
+                                    ]
+                                    .iter()
+                                    .for_each(|step| {
+                                        if ui
+                                            .add(SelectableLabel::new(
+                                                state.invoice.metadata.rounding_step == *step,
+                                                step.name(),
+                                            ))
+                                            .clicked()
+                                        {
+                                            state.invoice.metadata.rounding_step = *step;
+                                            state.invoice.dirty = true;
+                                        }
+                                    });
+                                });
+                                ui.end_row();
+                                ui.label(Messages::PricesIncludeVat);
+                                state.invoice.dirty |= ui
+                                    .checkbox(&mut state.invoice.metadata.prices_include_vat, "")
+                                    .changed();
                                 ui.end_row();
                             });
                         Grid::new("invoice_add_grid_service_period")
@@ -504,12 +1074,18 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 ui.end_row();
                                 ui.label(Messages::From);
                                 ui.horizontal(|ui| {
-                                    ui.add(
-                                        TextEdit::singleline(
-                                            &mut state.invoice.metadata.service_period.from_field,
+                                    state.invoice.dirty |= ui
+                                        .add(
+                                            TextEdit::singleline(
+                                                &mut state
+                                                    .invoice
+                                                    .metadata
+                                                    .service_period
+                                                    .from_field,
+                                            )
+                                            .desired_width(65.0),
                                         )
-                                        .desired_width(65.0),
-                                    );
+                                        .changed();
                                     let date_response_from = ui.add(
                                         DatePickerButton::new(
                                             &mut state.invoice.metadata.service_period.from,
@@ -524,12 +1100,13 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                         .highlight_weekends(false),
                                     );
                                     if date_response_from.changed() {
+                                        state.invoice.dirty = true;
                                         state.invoice.metadata.service_period.from_field = state
                                             .invoice
                                             .metadata
                                             .service_period
                                             .from
-                                            .format(DATE_FORMAT)
+                                            .format(date_format())
                                             .to_string();
                                         state
                                             .invoice
@@ -545,12 +1122,14 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 ui.end_row();
                                 ui.label(Messages::To);
                                 ui.horizontal(|ui| {
-                                    ui.add(
-                                        TextEdit::singleline(
-                                            &mut state.invoice.metadata.service_period.to_field,
+                                    state.invoice.dirty |= ui
+                                        .add(
+                                            TextEdit::singleline(
+                                                &mut state.invoice.metadata.service_period.to_field,
+                                            )
+                                            .desired_width(65.0),
                                         )
-                                        .desired_width(65.0),
-                                    );
+                                        .changed();
                                     let date_response_to = ui.add(
                                         DatePickerButton::new(
                                             &mut state.invoice.metadata.service_period.to,
@@ -565,12 +1144,13 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                         .highlight_weekends(false),
                                     );
                                     if date_response_to.changed() {
+                                        state.invoice.dirty = true;
                                         state.invoice.metadata.service_period.to_field = state
                                             .invoice
                                             .metadata
                                             .service_period
                                             .to
-                                            .format(DATE_FORMAT)
+                                            .format(date_format())
                                             .to_string();
                                         state
                                             .invoice
@@ -659,39 +1239,18 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 );
                                 ui.end_row();
                                 ui.label(Messages::Vat);
-                                ui.horizontal(|ui| {
-                                    [Vat::Zero, Vat::Ten, Vat::Twenty].iter().for_each(|vat| {
-                                        if ui
-                                            .add(SelectableLabel::new(
-                                                state.invoice.item_to_add.vat == *vat,
-                                                vat.name(),
-                                            ))
-                                            .clicked()
-                                        {
-                                            state.invoice.item_to_add.vat = *vat;
-                                        }
-                                    });
-                                });
+                                ui::vat_combo_box(
+                                    ui,
+                                    "invoice_item_vat",
+                                    &mut state.invoice.item_to_add.vat,
+                                );
                                 ui.end_row();
                                 if ui.button(Messages::Save).clicked() {
                                     state.invoice.item_validation =
                                         state.invoice.item_to_add.validate();
                                     if state.invoice.item_validation.is_ok() {
-                                        match state
-                                            .invoice
-                                            .items
-                                            .iter_mut()
-                                            .find(|i| i.id == state.invoice.item_to_add.id)
-                                        {
-                                            Some(item) => {
-                                                *item = state.invoice.item_to_add.clone();
-                                            }
-                                            None => state
-                                                .invoice
-                                                .items
-                                                .push(state.invoice.item_to_add.clone()),
-                                        }
-                                        state.invoice.item_to_add = Item::default();
+                                        let item = std::mem::take(&mut state.invoice.item_to_add);
+                                        state.invoice.upsert_item(item);
                                     }
                                 }
                             });
@@ -699,26 +1258,40 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                 ui.separator();
                 ui.label(Messages::Items);
                 items_table::build(&mut state.invoice, ui);
+                if let Some(errors) = state.invoice.validation.get_errors(&Field::Items) {
+                    errors.iter().for_each(|e| {
+                        ui.colored_label(Colors::Error.col(), format!("❎ {}", e));
+                    });
+                }
+                ui.separator();
+                ui.label(Messages::Adjustments);
+                build_adjustments(&mut state.invoice, ui);
+                ui.separator();
+                ui.label(Messages::Attachments);
+                build_attachments(
+                    ctx,
+                    &mut state.invoice,
+                    &mut state.file_picker_startpoint,
+                    ui,
+                );
                 ui.separator();
                 ui.horizontal(|ui| {
                     if ui.button(Messages::Export).clicked() {
-                        state.invoice.validation = state.invoice.validate();
-                        if state.invoice.items.len() > MAX_ITEMS {
+                        state.invoice.validation = state.invoice.validate_for_export();
+                        if let Some(too_many_rows) = state.invoice.too_many_rows(config) {
                             util::send_gui_event(
                                 &app_context.gui_event_sender,
-                                GuiEvent::ShowErrorNotification(format!(
-                                    "{} {}/{}",
-                                    Messages::TooManyItemsForPDFExport.msg(),
-                                    state.invoice.items.len(),
-                                    MAX_ITEMS
-                                )),
+                                GuiEvent::ShowErrorNotification(too_many_rows),
                             );
                         } else if state.invoice.validation.is_ok() {
                             let mut dialog = ui::get_localized_save_file_dialog(
                                 state.file_picker_startpoint.clone(),
                                 Messages::SaveFile.msg(),
                             )
-                            .default_filename(build_invoice_file_name(&state.invoice));
+                            .default_filename(build_invoice_file_name(
+                                &state.invoice,
+                                config.invoice_export_template(),
+                            ));
                             dialog.open();
                             state.invoice.export_state.open_file_dialog = Some(dialog);
                         }
@@ -743,16 +1316,87 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 state.invoice.export_state.selected_path = Some(path_buf);
                             }
                         }
-                        if let Some(ref path_buf) = state.invoice.export_state.selected_path {
-                            let invoice: Invoice = Invoice::from(&state.invoice);
-                            export_pdf(path_buf, app_context, &invoice);
-                            state.invoice.export_state.selected_path = None;
+                        if let Some(path_buf) = state.invoice.export_state.selected_path.take() {
+                            if let Some(invoice) = invoice_or_notify(&state.invoice, app_context) {
+                                let options = state.invoice.options(config);
+                                if export_pdf(
+                                    &path_buf,
+                                    app_context,
+                                    ctx,
+                                    &invoice,
+                                    config,
+                                    &options,
+                                ) {
+                                    state.invoice.dirty = false;
+                                    if config.delete_draft_after_export {
+                                        if let Some(id) = state.invoice.draft_id.take() {
+                                            util::send_event_and_request_repaint(
+                                                ctx,
+                                                &app_context.background_event_sender,
+                                                Event::RemoveDraft(DB::get_key_for_draft(id)),
+                                            );
+                                        }
+                                    }
+                                    // quotes aren't revenue
+                                    state.invoice.export_state.last_export = (invoice.kind
+                                        == DocumentKind::Invoice)
+                                        .then_some(LastExport {
+                                            file: path_buf,
+                                            invoice,
+                                            prices_include_vat: options.prices_include_vat,
+                                        });
+                                }
+                            }
+                        }
+                    }
+                    if ui
+                        .add_enabled(
+                            state.invoice.export_state.last_export.is_some(),
+                            Button::new(Messages::RecordInAccounting.msg()),
+                        )
+                        .clicked()
+                    {
+                        state.invoice.record_dialog = Some(RecordDialog {
+                            mode: RecordMode::PerItem,
+                            category: String::default(),
+                            category_autosuggest: AutoSuggest::new(),
+                        });
+                    }
+                    if ui.button(Messages::CopyAsText).clicked() {
+                        state.invoice.validation = state.invoice.validate();
+                        let valid_invoice = state
+                            .invoice
+                            .validation
+                            .is_ok()
+                            .then(|| invoice_or_notify(&state.invoice, app_context))
+                            .flatten();
+                        if let Some(invoice) = valid_invoice {
+                            match render_invoice_text(&invoice, &state.invoice.options(config)) {
+                                Ok(text) => {
+                                    ctx.copy_text(text);
+                                    util::send_gui_event(
+                                        &app_context.gui_event_sender,
+                                        GuiEvent::ShowInfoNotification(String::from(
+                                            Messages::InvoiceCopied.msg(),
+                                        )),
+                                    );
+                                }
+                                Err(e) => util::send_gui_event(
+                                    &app_context.gui_event_sender,
+                                    GuiEvent::ShowErrorNotification(e.to_string()),
+                                ),
+                            }
                         }
                     }
                     if ui.button(Messages::SaveAsTemplate).clicked() {
                         state.invoice.validation = state.invoice.validate();
-                        if state.invoice.validation.is_ok() {
-                            let invoice: Invoice = Invoice::from(&state.invoice);
+                        let valid_invoice = state
+                            .invoice
+                            .validation
+                            .is_ok()
+                            .then(|| invoice_or_notify(&state.invoice, app_context))
+                            .flatten();
+                        if let Some(invoice) = valid_invoice {
                             util::send_event_and_request_repaint(
                                 ctx,
                                 &app_context.background_event_sender,
@@ -760,56 +1404,52 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                             )
                         }
                     }
+                    // not validated, drafts are unfinished - only the numbers have to be valid
+                    if ui.button(Messages::SaveDraft).clicked() {
+                        if let Some(mut invoice) = invoice_or_notify(&state.invoice, app_context) {
+                            invoice.id = *state.invoice.draft_id.get_or_insert(invoice.id);
+                            util::send_event_and_request_repaint(
+                                ctx,
+                                &app_context.background_event_sender,
+                                Event::SaveDraft(Box::new(invoice.into_draft())),
+                            )
+                        }
+                    }
                 });
+                build_record_dialog(ctx, state, config, app_context);
             });
             strip.cell(|ui| {
                 ui.label(Messages::Templates);
                 ui.separator();
+                let mut template_to_fill = None;
                 ScrollArea::vertical()
+                    .id_salt("invoice_templates_scroll")
                     .max_height(200.0)
                     .auto_shrink(false)
                     .show(ui, |ui| {
                         Grid::new("invoice_templates")
-                            .num_columns(3)
+                            .num_columns(4)
                             .show(ui, |ui| {
                                 state.invoice.templates.iter().for_each(|t| {
                                     ui.label(t.name.chars().take(25).collect::<String>());
-                                    ui.label(t.date.format(DATE_FORMAT).to_string());
+                                    ui.label(t.date.format(date_format()).to_string());
+                                    ui.label(
+                                        calculate_sum(
+                                            &t.items,
+                                            &t.adjustments,
+                                            t.prices_include_vat,
+                                        )
+                                        .total,
+                                    );
                                     ui.horizontal(|ui| {
                                         if ui.button(Messages::Fill.msg()).clicked() {
-                                            state.invoice.metadata = Metadata {
-                                                name: t.name.clone(),
-                                                from: t.from.clone(),
-                                                to: t.to.clone(),
-                                                date: t.date,
-                                                date_field: t.date.format(DATE_FORMAT).to_string(),
-                                                city: t.city.clone(),
-                                                invoice_number: t.invoice_number.clone(),
-                                                service_period: t.service_period.clone(),
-                                                pretext: t.pre_text.clone(),
-                                                posttext: t.post_text.clone(),
-                                                bank_data: t.bank_data.clone(),
-                                            };
-                                            state.invoice.items = t
-                                                .items
-                                                .iter()
-                                                .map(|i| Item {
-                                                    id: Uuid::now_v7(),
-                                                    nr: i.nr.to_string(),
-                                                    decription: i.description.clone(),
-                                                    unit: i.unit,
-                                                    amount: i.amount.to_string(),
-                                                    price_per_unit: i
-                                                        .price_per_unit
-                                                        .to_value_string(),
-                                                    vat: i.vat,
-                                                })
-                                                .collect();
-                                            util::send_gui_event(
-                                                &app_context.gui_event_sender,
-                                                GuiEvent::ShowInfoNotification(String::from(
-                                                    Messages::InvoiceTemplateFilled.msg(),
-                                                )),
+                                            template_to_fill = Some(t.clone());
+                                        }
+                                        if ui.button(Messages::Duplicate.msg()).clicked() {
+                                            util::send_event_and_request_repaint(
+                                                ctx,
+                                                &app_context.background_event_sender,
+                                                Event::SaveInvoiceTemplate(Box::new(t.duplicate())),
                                             );
                                         }
                                         if ui.button(Messages::Delete.msg()).clicked() {
@@ -826,6 +1466,607 @@ pub(crate) fn build(ctx: &Context, state: &mut State, app_context: &AppContext,
                                 });
                             });
                     });
+                if let Some(template) = template_to_fill {
+                    state.invoice.fill_from_template(&template);
+                    util::send_gui_event(
+                        &app_context.gui_event_sender,
+                        GuiEvent::ShowInfoNotification(String::from(
+                            Messages::InvoiceTemplateFilled.msg(),
+                        )),
+                    );
+                    warn_about_too_many_rows(&state.invoice, config, app_context);
+                }
+                build_drafts(ctx, state, config, app_context, ui);
             });
         });
 }
+
+// right away, instead of only on export
+fn warn_about_too_many_rows(state: &InvoiceState, config: &Config, app_context: &AppContext) {
+    if let Some(too_many_rows) = state.too_many_rows(config) {
+        util::send_gui_event(
+            &app_context.gui_event_sender,
+            GuiEvent::ShowWarningNotification(too_many_rows),
+        );
+    }
+}
+
+fn build_drafts(
+    ctx: &Context,
+    state: &mut State,
+    config: &Config,
+    app_context: &AppContext,
+    ui: &mut Ui,
+) {
+    if state.invoice.drafts.is_empty() {
+        return;
+    }
+    ui.add_space(10.0);
+    ui.label(Messages::Drafts);
+    ui.separator();
+    let mut draft_to_load = None;
+    ScrollArea::vertical()
+        .id_salt("invoice_drafts_scroll")
+        .max_height(150.0)
+        .auto_shrink(false)
+        .show(ui, |ui| {
+            Grid::new("invoice_drafts").num_columns(4).show(ui, |ui| {
+                state.invoice.drafts.iter().for_each(|d| {
+                    let invoice = d.invoice();
+                    // the invoice number identifies a real invoice best
+                    let label = if invoice.invoice_number.trim().is_empty() {
+                        &invoice.name
+                    } else {
+                        &invoice.invoice_number
+                    };
+                    let loaded = state.invoice.draft_id == Some(d.id());
+                    let label = label.chars().take(25).collect::<String>();
+                    if loaded {
+                        ui.strong(label);
+                    } else {
+                        ui.label(label);
+                    }
+                    ui.label(invoice.date.format(date_format()).to_string());
+                    ui.label(
+                        calculate_sum(&invoice.items, &invoice.adjustments, d.prices_include_vat)
+                            .total,
+                    );
+                    ui.horizontal(|ui| {
+                        if ui.button(Messages::Load.msg()).clicked() {
+                            draft_to_load = Some(d.clone());
+                        }
+                        if ui.button(Messages::Delete.msg()).clicked() {
+                            util::send_event_and_request_repaint(
+                                ctx,
+                                &app_context.background_event_sender,
+                                Event::RemoveDraft(DB::get_key_for_draft(d.id())),
+                            );
+                        }
+                    });
+                    ui.end_row();
+                });
+            });
+        });
+    if let Some(draft) = draft_to_load {
+        state.invoice.load_draft(&draft);
+        util::send_gui_event(
+            &app_context.gui_event_sender,
+            GuiEvent::ShowInfoNotification(String::from(Messages::DraftLoaded.msg())),
+        );
+        warn_about_too_many_rows(&state.invoice, config, app_context);
+    }
+}
+
+// lets the user pick the category and how to split the last exported invoice before recording it
+fn build_record_dialog(
+    ctx: &Context,
+    state: &mut State,
+    config: &Config,
+    app_context: &AppContext,
+) {
+    let date_range = selected_date_range(&state.accounting);
+    let (Some(record_dialog), Some(last_export)) = (
+        &mut state.invoice.record_dialog,
+        &state.invoice.export_state.last_export,
+    ) else {
+        return;
+    };
+    let mut close = false;
+    let mut recorded = false;
+    ui::dialog::modal_window("record_invoice", [400.0, 150.0]).show(ctx, |ui| {
+        ui.label(RichText::new(Messages::RecordInAccounting).strong());
+        ui.horizontal(|ui| {
+            for (mode, text) in [
+                (RecordMode::PerItem, Messages::PerItem),
+                (RecordMode::SummarizedByVat, Messages::SummarizedByVat),
+            ] {
+                if ui
+                    .add(SelectableLabel::new(record_dialog.mode == mode, text.msg()))
+                    .clicked()
+                {
+                    record_dialog.mode = mode;
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(Messages::Category);
+            record_dialog.category_autosuggest.ui(
+                ui,
+                &mut record_dialog.category,
+                &state.accounting.categories,
+            );
+        });
+        let items = accounting_items_for_invoice(
+            &last_export.invoice,
+            last_export.prices_include_vat,
+            record_dialog.mode,
+            record_dialog.category.trim(),
+        );
+        ui.label(format!(
+            "{} {}",
+            items.len(),
+            Messages::ItemsWillBeRecorded.msg()
+        ));
+        ui.horizontal(|ui| {
+            if ui.button(Messages::Cancel).clicked() {
+                close = true;
+            }
+            if ui
+                .add_enabled(
+                    !record_dialog.category.trim().is_empty(),
+                    Button::new(Messages::Record.msg()),
+                )
+                .clicked()
+            {
+                recorded = record_invoice(
+                    items,
+                    &last_export.file,
+                    date_range,
+                    config,
+                    app_context,
+                    ctx,
+                );
+                close = true;
+            }
+        });
+    });
+    if close {
+        state.invoice.record_dialog = None;
+    }
+    // an export is only recorded once
+    if recorded {
+        state.invoice.export_state.last_export = None;
+    }
+}
+
+// the recorded items get their own copy of the exported PDF, like items added by hand - returns
+// whether they were sent to be recorded
+fn record_invoice(
+    mut items: Vec<AccountingItem>,
+    file: &PathBuf,
+    date_range: DateRange,
+    config: &Config,
+    app_context: &AppContext,
+    ctx: &Context,
+) -> bool {
+    let files_root = config
+        .data_folder
+        .as_ref()
+        .expect("data folder is set")
+        .join(PATH_FOR_FILES);
+    for i in 0..items.len() {
+        let files_folder = files_folder_for(&files_root, items[i].date, config.files_per_year);
+        match copy_file_and_rename(&items[i].id.to_string(), &files_folder, file) {
+            Ok(new_path) => items[i].file = new_path,
+            Err(e) => {
+                // don't leave the copies for the previous items behind
+                items[..i].iter().for_each(|item| delete_file(&item.file));
+                util::send_gui_event(
+                    &app_context.gui_event_sender,
+                    GuiEvent::ShowErrorNotification(e.to_string()),
+                );
+                return false;
+            }
+        }
+    }
+    util::send_event_and_request_repaint(
+        ctx,
+        &app_context.background_event_sender,
+        Event::RecordInvoice(items, date_range),
+    );
+    true
+}
+
+// outgoing accounting items for an invoice, either one per invoice item and adjustment, or one
+// per VAT rate summing up the whole invoice
+fn accounting_items_for_invoice(
+    invoice: &Invoice,
+    prices_include_vat: bool,
+    mode: RecordMode,
+    category: &str,
+) -> Vec<AccountingItem> {
+    let name = if invoice.invoice_number.trim().is_empty() {
+        invoice.name.clone()
+    } else {
+        invoice.invoice_number.clone()
+    };
+    let item = |name: String, net: CurrencyValue, vat: Vat, note: String| AccountingItem {
+        invoice_type: InvoiceType::Out,
+        id: Uuid::now_v7(),
+        date: invoice.date,
+        name,
+        company: Company(invoice.to.name.clone()),
+        category: Category(category.to_owned()),
+        net,
+        vat,
+        file: PathBuf::default(),
+        note,
+        excluded: false,
+    };
+    match mode {
+        RecordMode::PerItem => {
+            let items = invoice.items.iter().map(|i| {
+                let net = calculate_sum(std::slice::from_ref(i), &[], prices_include_vat).net;
+                item(i.description.clone(), net, i.vat, name.clone())
+            });
+            let adjustments = invoice.adjustments.iter().map(|a| {
+                let net = calculate_sum(&[], std::slice::from_ref(a), prices_include_vat).net;
+                item(a.description.clone(), net, a.vat, name.clone())
+            });
+            items.chain(adjustments).collect()
+        }
+        RecordMode::SummarizedByVat => VATS
+            .iter()
+            .copied()
+            .filter_map(|vat| {
+                let items: Vec<InvoiceItem> = invoice
+                    .items
+                    .iter()
+                    .filter(|i| i.vat == vat)
+                    .cloned()
+                    .collect();
+                let adjustments: Vec<Adjustment> = invoice
+                    .adjustments
+                    .iter()
+                    .filter(|a| a.vat == vat)
+                    .cloned()
+                    .collect();
+                if items.is_empty() && adjustments.is_empty() {
+                    return None;
+                }
+                let net = calculate_sum(&items, &adjustments, prices_include_vat).net;
+                Some(item(name.clone(), net, vat, String::default()))
+            })
+            .collect(),
+    }
+}
+
+// lists the discounts and surcharges on the whole invoice, with a row to add a new one
+fn build_adjustments(state: &mut InvoiceState, ui: &mut Ui) {
+    let mut to_remove = None;
+    Grid::new("invoice_adjustments")
+        .num_columns(4)
+        .show(ui, |ui| {
+            state.adjustments.iter().for_each(|a| {
+                ui.label(&a.description);
+                ui.label(&a.amount);
+                ui.label(a.vat.name());
+                if ui.button(Messages::Delete).clicked() {
+                    to_remove = Some(a.id);
+                }
+                ui.end_row();
+            });
+            ui.add(
+                TextEdit::singleline(&mut state.adjustment_to_add.description)
+                    .hint_text(Messages::Description.msg()),
+            );
+            ui.add(
+                TextEdit::singleline(&mut state.adjustment_to_add.amount)
+                    .hint_text(Messages::AdjustmentAmount.msg())
+                    .desired_width(80.0),
+            );
+            ui::vat_combo_box(ui, "adjustment_vat", &mut state.adjustment_to_add.vat);
+            if ui.button(Messages::Add).clicked() {
+                state.adjustment_validation = state.adjustment_to_add.validate();
+                if state.adjustment_validation.is_ok() {
+                    let adjustment = std::mem::take(&mut state.adjustment_to_add);
+                    state.adjustments.push(adjustment);
+                    state.dirty = true;
+                }
+            }
+            ui.end_row();
+        });
+    if let Some(id) = to_remove {
+        state.adjustments.retain(|a| a.id != id);
+        state.dirty = true;
+    }
+    [Field::Description, Field::Amount]
+        .iter()
+        .for_each(|field| {
+            if let Some(errors) = state.adjustment_validation.get_errors(field) {
+                errors.iter().for_each(|e| {
+                    ui.colored_label(Colors::Error.col(), format!("❎ {}", e));
+                });
+            }
+        });
+}
+
+// PDFs appended to the exported invoice, other files are skipped on export
+fn build_attachments(
+    ctx: &Context,
+    state: &mut InvoiceState,
+    file_picker_startpoint: &mut Option<PathBuf>,
+    ui: &mut Ui,
+) {
+    let mut to_remove = None;
+    Grid::new("invoice_attachments")
+        .num_columns(2)
+        .show(ui, |ui| {
+            state.attachments.iter().enumerate().for_each(|(idx, a)| {
+                ui.horizontal(|ui| {
+                    ui.label(a.to_string_lossy());
+                    if !a.exists() {
+                        ui.colored_label(
+                            Colors::Error.col(),
+                            format!("❎ {}", Messages::FileDoesNotExist),
+                        );
+                    } else if !is_pdf(a) {
+                        ui.colored_label(
+                            Colors::Warning.col(),
+                            format!("⚠ {}", Messages::NotAPDFWillBeSkipped),
+                        );
+                    }
+                });
+                if ui.button(Messages::Delete).clicked() {
+                    to_remove = Some(idx);
+                }
+                ui.end_row();
+            });
+            if ui.button(Messages::Add).clicked() {
+                let mut dialog = ui::get_localized_open_file_dialog(
+                    file_picker_startpoint.clone(),
+                    Messages::ChooseFile.msg(),
+                );
+                dialog.open();
+                state.export_state.attachment_dialog = Some(dialog);
+            }
+            ui.end_row();
+        });
+    if let Some(idx) = to_remove {
+        state.attachments.remove(idx);
+        state.dirty = true;
+        state.validation.clear_for_field(&Field::Attachments);
+    }
+    if let Some(errors) = state.validation.get_errors(&Field::Attachments) {
+        errors.iter().for_each(|e| {
+            ui.colored_label(Colors::Error.col(), format!("❎ {}", e));
+        });
+    }
+
+    if let Some(dialog) = &mut state.export_state.attachment_dialog {
+        if dialog.show(ctx).selected() {
+            if let Some(file) = dialog.path() {
+                *file_picker_startpoint = Some(file.to_path_buf());
+                state.attachments.push(file.to_path_buf());
+                state.dirty = true;
+                state.validation.clear_for_field(&Field::Attachments);
+            }
+            state.export_state.attachment_dialog = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::export::invoice::MAX_ITEMS;
+
+    #[test]
+    fn quotes_do_not_need_an_invoice_number() {
+        let mut state = InvoiceState::new();
+        assert!(state.validate().get_errors(&Field::Nr).is_some());
+
+        state.metadata.kind = DocumentKind::Quote;
+        assert!(state.validate().get_errors(&Field::Nr).is_none());
+    }
+
+    #[test]
+    fn invoices_need_at_least_one_item() {
+        let mut state = InvoiceState::new();
+        assert_eq!(
+            state.validate().get_errors(&Field::Items),
+            Some(&vec![Messages::NoInvoiceItems.msg().to_owned()])
+        );
+
+        state.upsert_item(Item::default());
+        assert!(state.validate().get_errors(&Field::Items).is_none());
+    }
+
+    #[test]
+    fn malformed_numbers_are_an_error_instead_of_a_panic() {
+        let mut state = InvoiceState::new();
+        state.upsert_item(Item {
+            nr: String::from("1"),
+            decription: String::from("Work"),
+            amount: String::from("2"),
+            price_per_unit: String::from("100"),
+            ..Item::default()
+        });
+        assert!(Invoice::try_from(&state).is_ok());
+
+        state.items[0].price_per_unit = String::from("a lot");
+        let error = Invoice::try_from(&state).unwrap_err();
+        assert!(matches!(error, GuiError::InvalidInvoice(ref msg) if msg.contains("a lot")));
+
+        state.items[0].price_per_unit = String::from("100");
+        state.items[0].nr = String::from("-1");
+        assert!(matches!(
+            Invoice::try_from(&state),
+            Err(GuiError::InvalidInvoice(_))
+        ));
+
+        state.items[0].nr = String::from("1.2");
+        assert_eq!(Invoice::try_from(&state).unwrap().items[0].nr, "1.2");
+
+        assert!(state.items[0].validate().get_errors(&Field::Nr).is_none());
+        state.items[0].nr = String::from("a");
+        assert!(state.items[0].validate().get_errors(&Field::Nr).is_some());
+        assert!(matches!(
+            Invoice::try_from(&state),
+            Err(GuiError::InvalidInvoice(_))
+        ));
+
+        state.items[0].nr = String::from("1");
+        state.adjustments.push(AdjustmentInput {
+            amount: String::from("ten"),
+            ..AdjustmentInput::default()
+        });
+        assert!(matches!(
+            Invoice::try_from(&state),
+            Err(GuiError::InvalidInvoice(_))
+        ));
+    }
+
+    #[test]
+    fn iban_has_to_be_valid_if_given() {
+        let mut state = InvoiceState::new();
+        assert!(state.validate().get_errors(&Field::Iban).is_none());
+
+        state.metadata.bank_account.iban = String::from("AT62 1904 3002 3457 3201");
+        assert!(state.validate().get_errors(&Field::Iban).is_some());
+
+        state.metadata.bank_account.iban = String::from("at61 1904 3002 3457 3201");
+        assert!(state.validate().get_errors(&Field::Iban).is_none());
+    }
+
+    #[test]
+    fn attachments_have_to_exist_for_export() {
+        let mut state = InvoiceState::new();
+        state.attachments.push(std::env::temp_dir());
+        assert!(state
+            .validate_for_export()
+            .get_errors(&Field::Attachments)
+            .is_none());
+
+        state
+            .attachments
+            .push(std::env::temp_dir().join(format!("{}.pdf", Uuid::now_v7())));
+        assert_eq!(
+            state
+                .validate_for_export()
+                .get_errors(&Field::Attachments)
+                .map(|e| e.len()),
+            Some(1)
+        );
+        // only needed for the PDF
+        assert!(state.validate().get_errors(&Field::Attachments).is_none());
+    }
+
+    #[test]
+    fn invoices_are_recorded_per_item_or_by_vat() {
+        let mut state = InvoiceState::new();
+        state.metadata.invoice_number = String::from("INV-7");
+        state.metadata.to.name = String::from("Client");
+        for (nr, description, price, vat) in [
+            ("1", "Consulting", "100", Vat::Twenty),
+            ("2", "Books", "50", Vat::Ten),
+            ("3", "Support", "20", Vat::Twenty),
+        ] {
+            state.upsert_item(Item {
+                id: Uuid::now_v7(),
+                nr: String::from(nr),
+                decription: String::from(description),
+                amount: String::from("1"),
+                price_per_unit: String::from(price),
+                vat,
+                ..Item::default()
+            });
+        }
+        state.adjustments.push(AdjustmentInput {
+            description: String::from("Discount"),
+            amount: String::from("-10"),
+            vat: Vat::Twenty,
+            ..AdjustmentInput::default()
+        });
+        let invoice = Invoice::try_from(&state).unwrap();
+        let value = |v: i64| CurrencyValue::new_from_decimal(v.into()).to_value_string();
+
+        let per_item =
+            accounting_items_for_invoice(&invoice, false, RecordMode::PerItem, "Revenue");
+        assert_eq!(per_item.len(), 4);
+        assert!(per_item.iter().all(|i| i.invoice_type == InvoiceType::Out
+            && i.company.0 == "Client"
+            && i.category.0 == "Revenue"
+            && i.note == "INV-7"));
+        assert_eq!(per_item[0].name, "Consulting");
+        assert_eq!(per_item[3].net.to_value_string(), value(-10));
+
+        let by_vat =
+            accounting_items_for_invoice(&invoice, false, RecordMode::SummarizedByVat, "Revenue");
+        assert_eq!(by_vat.len(), 2);
+        assert_eq!(by_vat[0].name, "INV-7");
+        assert_eq!(by_vat[0].vat, Vat::Ten);
+        assert_eq!(by_vat[0].net.to_value_string(), value(50));
+        assert_eq!(by_vat[1].vat, Vat::Twenty);
+        assert_eq!(by_vat[1].net.to_value_string(), value(110));
+    }
+
+    #[test]
+    fn upsert_item_adds_or_replaces() {
+        let mut state = InvoiceState::new();
+        let mut item = Item {
+            nr: String::from("1"),
+            ..Item::default()
+        };
+        state.upsert_item(item.clone());
+        state.upsert_item(Item::default());
+        assert_eq!(state.items.len(), 2);
+
+        item.decription = String::from("Consulting");
+        state.upsert_item(item.clone());
+        assert_eq!(state.items.len(), 2);
+        assert_eq!(state.items[0].decription, "Consulting");
+    }
+
+    #[test]
+    fn changes_make_the_invoice_dirty() {
+        let mut state = InvoiceState::new();
+        assert!(!state.has_unsaved_changes());
+        let mut invoice = Invoice::try_from(&state).unwrap();
+
+        state.upsert_item(Item::default());
+        assert!(state.has_unsaved_changes());
+
+        // e.g. after saving a template
+        state.mark_saved();
+        state.fill_to_address(Address::new());
+        assert!(state.has_unsaved_changes());
+
+        // a loaded draft is saved already, unlike a filled in template
+        state.load_draft(&invoice.clone().into_draft());
+        assert!(!state.has_unsaved_changes());
+        invoice.id = Uuid::now_v7();
+        state.fill_from_template(&invoice);
+        assert!(state.has_unsaved_changes());
+    }
+
+    #[test]
+    fn too_many_rows_for_the_pdf() {
+        let config: Config = toml::from_str("language = \"en\"").unwrap();
+        let mut state = InvoiceState::new();
+        state.items = vec![Item::default(); MAX_ITEMS];
+        assert_eq!(state.too_many_rows(&config), None);
+
+        // adjustments take up rows as well
+        state.adjustments = vec![AdjustmentInput::default()];
+        assert_eq!(
+            state.too_many_rows(&config),
+            Some(format!(
+                "{} {}/{}",
+                Messages::TooManyItemsForPDFExport.msg(),
+                MAX_ITEMS + 1,
+                MAX_ITEMS
+            ))
+        );
+    }
+}
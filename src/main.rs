@@ -1,11 +1,18 @@
+use accounting::MonthlyExport;
 use anyhow::{anyhow, Result};
-use config::Config;
-use data::{AccountingItem, Invoice};
-use db::{DateRange, DB};
+use chrono::{Months, NaiveDate};
+use config::{
+    Config, DateFormat, NumberFormat, PageSize, MAX_SUGGESTIONS_RANGE, MIN_WINDOW_SIZE_RANGE,
+};
+use data::{
+    currency::NumberLocale, AccountingItem, Address, CategoryDefaults, Draft, ExportLogEntry,
+    Invoice,
+};
+use db::{get_date_range_for_all_time, DateRange, DB};
 use eframe::{
     egui::{
-        self, Align2, Color32, Grid, RichText, ScrollArea, SelectableLabel, Shadow, TextEdit,
-        Window,
+        self, Align2, Button, Color32, ComboBox, DragValue, Grid, RichText, ScrollArea,
+        SelectableLabel, Shadow, TextEdit, ViewportCommand, Window, WindowLevel,
     },
     App,
 };
@@ -16,14 +23,18 @@ use messages::{Language, Messages};
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::mpsc::{channel, Receiver, Sender},
 };
 use ui::{
+    autosuggest::SuggestionLimits,
     dialog::{self, Dialog, DialogResponse},
-    notification::{self, InnerNotification, Notification},
+    notification::{self, push_notification, InnerNotification, Notification},
+};
+use util::{
+    files::{DEFAULT_ACCOUNTING_EXPORT_TEMPLATE, DEFAULT_INVOICE_EXPORT_TEMPLATE, PATH_FOR_FILES},
+    Colors, Month, MONTHS,
 };
-use util::Colors;
 
 mod accounting;
 mod config;
@@ -46,20 +57,96 @@ fn get_language() -> Language {
     *config
 }
 
-const DATE_FORMAT: &str = "%d.%m.%Y";
+static NUMBER_FORMAT: Lazy<Mutex<NumberFormat>> =
+    Lazy::new(|| Mutex::new(NumberFormat::FollowLanguage));
+
+fn update_number_format(new_val: NumberFormat) {
+    let mut config = NUMBER_FORMAT
+        .lock()
+        .expect("failed to get NUMBER_FORMAT lock");
+    *config = new_val;
+}
+
+fn get_number_locale() -> NumberLocale {
+    let number_format = *NUMBER_FORMAT
+        .lock()
+        .expect("failed to get NUMBER_FORMAT lock");
+    number_format.locale(get_language())
+}
+
+static DATE_FORMAT: Lazy<Mutex<DateFormat>> = Lazy::new(|| Mutex::new(DateFormat::default()));
+
+fn update_date_format(new_val: DateFormat) {
+    let mut config = DATE_FORMAT.lock().expect("failed to get DATE_FORMAT lock");
+    *config = new_val;
+}
+
+// the format for displaying and entering dates - not for DB keys, which have to stay sortable
+fn date_format() -> &'static str {
+    DATE_FORMAT
+        .lock()
+        .expect("failed to get DATE_FORMAT lock")
+        .pattern()
+}
+
+static SUGGESTION_LIMITS: Lazy<Mutex<SuggestionLimits>> =
+    Lazy::new(|| Mutex::new(SuggestionLimits::default()));
+
+fn update_suggestion_limits(new_val: SuggestionLimits) {
+    let mut config = SUGGESTION_LIMITS
+        .lock()
+        .expect("failed to get SUGGESTION_LIMITS lock");
+    *config = new_val;
+}
+
+fn suggestion_limits() -> SuggestionLimits {
+    *SUGGESTION_LIMITS
+        .lock()
+        .expect("failed to get SUGGESTION_LIMITS lock")
+}
+
+const MANY_ITEMS_WARNING_THRESHOLD: usize = 500;
+// has to be typed to delete all data
+const RESET_CONFIRMATION: &str = "RESET";
+
+fn window_level(always_on_top: bool) -> WindowLevel {
+    if always_on_top {
+        WindowLevel::AlwaysOnTop
+    } else {
+        WindowLevel::Normal
+    }
+}
+
+// the preferred size of the config window, shrunk to fit small screens
+const CONFIG_WINDOW_SIZE: [f32; 2] = [400.0, 100.0];
+const OPEN_BUTTON_WIDTH: f32 = 60.0;
+
+fn config_window_size(screen: egui::Vec2) -> [f32; 2] {
+    [
+        CONFIG_WINDOW_SIZE[0].min(screen.x * 0.9),
+        CONFIG_WINDOW_SIZE[1].min(screen.y * 0.9),
+    ]
+}
 
 fn main() -> Result<(), anyhow::Error> {
-    env_logger::init();
+    util::logging::init();
 
     let (background_event_sender, background_event_receiver) = channel::<Event>();
     let (gui_event_sender, gui_event_receiver) = channel::<GuiEvent>();
-    let config = config::load_config()?;
+    let (config, config_warnings) = config::load_config()?;
+    config_warnings.iter().for_each(|warning| {
+        util::send_gui_event(
+            &gui_event_sender,
+            GuiEvent::ShowWarningNotification(warning.msg()),
+        );
+    });
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_app_id(Messages::Title)
-            .with_always_on_top()
-            .with_inner_size([1024.0, 1024.0]),
+            .with_window_level(window_level(config.always_on_top))
+            .with_inner_size([1024.0, 1024.0])
+            .with_min_inner_size([config.min_window_width, config.min_window_height]),
         ..Default::default()
     };
 
@@ -72,26 +159,7 @@ fn main() -> Result<(), anyhow::Error> {
                 if db.is_none() {
                     db = Some(DB::new(data_folder.as_path()));
                     if let Some(ref db) = db {
-                        handle_background_events(
-                            Event::FetchInvoiceTemplates(),
-                            gui_event_sender_clone.clone(),
-                            db,
-                        );
-                        handle_background_events(
-                            Event::FetchNames(),
-                            gui_event_sender_clone.clone(),
-                            db,
-                        );
-                        handle_background_events(
-                            Event::FetchCategories(),
-                            gui_event_sender_clone.clone(),
-                            db,
-                        );
-                        handle_background_events(
-                            Event::FetchCompanies(),
-                            gui_event_sender_clone.clone(),
-                            db,
-                        );
+                        fetch_initial_data(data_folder, &gui_event_sender_clone, db);
                     }
                 }
             }
@@ -127,6 +195,88 @@ fn main() -> Result<(), anyhow::Error> {
     .map_err(|e| anyhow!("eframe error: {}", e))
 }
 
+// everything the GUI needs once the database is opened
+fn fetch_initial_data(data_folder: &Path, sender: &Sender<GuiEvent>, db: &db::DB) {
+    [
+        Event::FetchInvoiceTemplates(),
+        Event::FetchDrafts(),
+        Event::FetchNames(),
+        Event::FetchCategories(),
+        Event::FetchCompanies(),
+        Event::FetchExportLog(),
+        Event::FetchAddressCompanies(),
+        Event::CheckFileIntegrity(data_folder.join(PATH_FOR_FILES)),
+    ]
+    .into_iter()
+    .for_each(|event| handle_background_events(event, sender.clone(), db));
+}
+
+// the merged items might be shown, and the merged suggestion is gone
+fn handle_merged(
+    res: Result<usize, GuiError>,
+    date_range: DateRange,
+    sender: Sender<GuiEvent>,
+    db: &db::DB,
+) {
+    match res {
+        Ok(merged) => {
+            util::send_gui_event(
+                &sender,
+                GuiEvent::ShowInfoNotification(format!(
+                    "{} {}",
+                    merged,
+                    Messages::ItemsMerged.msg()
+                )),
+            );
+            match db.get_accounting_items_for_range(&date_range) {
+                Ok(items) => {
+                    util::send_gui_event(&sender, GuiEvent::SetAccountingItems(date_range, items))
+                }
+                Err(e) => error!("Could not fetch items: {e}"),
+            };
+            handle_background_events(Event::FetchCompanies(), sender.clone(), db);
+            handle_background_events(Event::FetchCategories(), sender, db);
+        }
+        Err(e) => {
+            error!("Could not merge: {e}");
+            util::send_gui_event(
+                &sender,
+                GuiEvent::ShowErrorNotification(String::from(Messages::CouldNotMerge.msg())),
+            );
+        }
+    }
+}
+
+// split items, or the items of a recorded invoice, which are shown and suggested right away
+fn create_items(
+    items: &[AccountingItem],
+    date_range: DateRange,
+    created: Messages,
+    failed: Messages,
+    sender: Sender<GuiEvent>,
+    db: &db::DB,
+) {
+    match db.create_accounting_items_and_refetch(items, &date_range) {
+        Ok(fetched) => {
+            util::send_gui_event(
+                &sender,
+                GuiEvent::ShowInfoNotification(format!("{} {}", items.len(), created.msg())),
+            );
+            util::send_gui_event(&sender, GuiEvent::SetAccountingItems(date_range, fetched));
+            handle_background_events(Event::FetchNames(), sender.clone(), db);
+            handle_background_events(Event::FetchCompanies(), sender.clone(), db);
+            handle_background_events(Event::FetchCategories(), sender, db);
+        }
+        Err(e) => {
+            error!("Could not create items: {e}");
+            util::send_gui_event(
+                &sender,
+                GuiEvent::ShowErrorNotification(failed.msg().to_owned()),
+            );
+        }
+    }
+}
+
 fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB) {
     match event {
         Event::OpenFile(file) => {
@@ -138,17 +288,35 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                 );
             };
         }
+        Event::OpenFolder(folder) => {
+            if let Err(e) = open::that(&folder) {
+                error!("Could not open folder {folder:?}: {e}");
+                util::send_gui_event(
+                    &sender,
+                    GuiEvent::ShowErrorNotification(String::from(
+                        Messages::CouldNotOpenFolder.msg(),
+                    )),
+                );
+            };
+        }
         Event::SaveItem(item, date_range) => {
             match db.create_or_update_accounting_item_and_refetch(&item, &date_range) {
-                Ok(items) => {
+                Ok((items, new_suggestions)) => {
                     util::send_gui_event(
                         &sender,
                         GuiEvent::ShowInfoNotification(String::from(Messages::ItemCreated.msg())),
                     );
-                    util::send_gui_event(&sender, GuiEvent::SetAccountingItems(items));
-                    handle_background_events(Event::FetchNames(), sender.clone(), db);
-                    handle_background_events(Event::FetchCompanies(), sender.clone(), db);
-                    handle_background_events(Event::FetchCategories(), sender.clone(), db);
+                    util::send_gui_event(&sender, GuiEvent::SetAccountingItems(date_range, items));
+                    // only re-fetch suggestions, which got a new entry
+                    if new_suggestions.name {
+                        handle_background_events(Event::FetchNames(), sender.clone(), db);
+                    }
+                    if new_suggestions.company {
+                        handle_background_events(Event::FetchCompanies(), sender.clone(), db);
+                    }
+                    if new_suggestions.category {
+                        handle_background_events(Event::FetchCategories(), sender.clone(), db);
+                    }
                 }
                 Err(e) => {
                     error!(
@@ -164,6 +332,26 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                 }
             };
         }
+        Event::SaveSplitItems(items, date_range) => {
+            create_items(
+                &items,
+                date_range,
+                Messages::ItemsCreated,
+                Messages::CouldNotCreateItem,
+                sender,
+                db,
+            );
+        }
+        Event::RecordInvoice(items, date_range) => {
+            create_items(
+                &items,
+                date_range,
+                Messages::ItemsRecorded,
+                Messages::CouldNotRecordInvoice,
+                sender,
+                db,
+            );
+        }
         Event::RemoveItem(item_id, date_range) => {
             match db.delete_accounting_item_and_refetch(&item_id, &date_range) {
                 Ok(items) => {
@@ -171,7 +359,7 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                         &sender,
                         GuiEvent::ShowInfoNotification(String::from(Messages::ItemDeleted.msg())),
                     );
-                    util::send_gui_event(&sender, GuiEvent::SetAccountingItems(items));
+                    util::send_gui_event(&sender, GuiEvent::SetAccountingItems(date_range, items));
                 }
                 Err(e) => {
                     error!("Could not delete item {item_id} and re-fetch items: {e}");
@@ -184,6 +372,85 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                 }
             };
         }
+        Event::FindDuplicates() => {
+            match db.find_duplicate_items() {
+                Ok(groups) => {
+                    util::send_gui_event(&sender, GuiEvent::SetDuplicateItems(groups));
+                }
+                Err(e) => {
+                    error!("Could not find duplicate items: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotFindDuplicates.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::CountItemsToMerge(companies, from) => {
+            let count = if companies {
+                db.count_company_items(&from)
+            } else {
+                db.count_category_items(&from)
+            };
+            match count {
+                Ok(count) => util::send_gui_event(&sender, GuiEvent::ConfirmMerge(count)),
+                Err(e) => {
+                    error!("Could not count items to merge: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotMerge.msg(),
+                        )),
+                    );
+                }
+            }
+        }
+        Event::MergeCategories(from, to, date_range) => {
+            let res = db.merge_category(&from, &to);
+            handle_merged(res, date_range, sender, db);
+        }
+        Event::MergeCompanies(from, to, date_range) => {
+            let res = db.merge_company(&from, &to);
+            handle_merged(res, date_range, sender, db);
+        }
+        Event::FetchAllItems() => {
+            match db.get_all_accounting_items() {
+                Ok(items) => {
+                    if items.len() > MANY_ITEMS_WARNING_THRESHOLD {
+                        util::send_gui_event(
+                            &sender,
+                            GuiEvent::ShowWarningNotification(format!(
+                                "{} {}",
+                                items.len(),
+                                Messages::ManyItemsFetched.msg()
+                            )),
+                        );
+                    } else {
+                        util::send_gui_event(
+                            &sender,
+                            GuiEvent::ShowInfoNotification(String::from(
+                                Messages::ItemsFetched.msg(),
+                            )),
+                        );
+                    }
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::SetAccountingItems(get_date_range_for_all_time(), items),
+                    );
+                }
+                Err(e) => {
+                    error!("Could not fetch all items: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotFetchData.msg(),
+                        )),
+                    );
+                }
+            };
+        }
         Event::FetchItems(date_range) => {
             match db.get_accounting_items_for_range(&date_range) {
                 Ok(items) => {
@@ -191,7 +458,7 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                         &sender,
                         GuiEvent::ShowInfoNotification(String::from(Messages::ItemsFetched.msg())),
                     );
-                    util::send_gui_event(&sender, GuiEvent::SetAccountingItems(items));
+                    util::send_gui_event(&sender, GuiEvent::SetAccountingItems(date_range, items));
                 }
                 Err(e) => {
                     error!("Could not fetch items: {e}");
@@ -204,7 +471,130 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                 }
             };
         }
+        Event::FindPeriodWithItemsBefore(date_range) => {
+            match db.latest_nonempty_period_before(&date_range) {
+                Ok(Some(date)) => {
+                    util::send_gui_event(&sender, GuiEvent::JumpToPeriodOf(date));
+                }
+                Ok(None) => {
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowInfoNotification(String::from(
+                            Messages::NoEarlierItems.msg(),
+                        )),
+                    );
+                }
+                Err(e) => {
+                    error!("Could not find earlier items: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotFetchData.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::CopyItemsToNextYear(keys, date_range) => {
+            match db.clone_items_to_period(&keys, Months::new(12), &date_range) {
+                Ok(items) => {
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowInfoNotification(format!(
+                            "{} {}",
+                            keys.len(),
+                            Messages::ItemsCopied.msg()
+                        )),
+                    );
+                    util::send_gui_event(&sender, GuiEvent::SetAccountingItems(date_range, items));
+                }
+                Err(e) => {
+                    error!("Could not copy items to next year: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotCopyItems.msg(),
+                        )),
+                    );
+                }
+            };
+        }
         Event::SetDB(_) => (),
+        Event::ResetAllData(data_folder, date_range) => {
+            match db.clear_all() {
+                Ok(()) => {
+                    info!("all data was deleted");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowInfoNotification(String::from(
+                            Messages::AllDataDeleted.msg(),
+                        )),
+                    );
+                    // like after opening the database, so no stale data stays in the GUI
+                    fetch_initial_data(&data_folder, &sender, db);
+                    handle_background_events(Event::FetchItems(date_range), sender, db);
+                }
+                Err(e) => {
+                    error!("Could not delete all data: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotDeleteAllData.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::RelocateFiles(files_root, per_year, date_range) => {
+            match db.relocate_item_files(&files_root, per_year) {
+                Ok(moved) => {
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowInfoNotification(format!(
+                            "{} {}",
+                            moved,
+                            Messages::FilesMoved.msg()
+                        )),
+                    );
+                    handle_background_events(Event::FetchItems(date_range), sender, db);
+                }
+                Err(e) => {
+                    error!("Could not relocate files in {files_root:?}: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotMoveFiles.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::CheckFileIntegrity(files_root) => {
+            match db.check_file_integrity(&files_root) {
+                Ok(0) => {
+                    info!("All item files are present in {files_root:?}");
+                }
+                Ok(missing) => {
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowWarningNotification(format!(
+                            "{} {}",
+                            missing,
+                            Messages::MissingItemFiles.msg()
+                        )),
+                    );
+                }
+                Err(e) => {
+                    error!("Could not check file integrity: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotCheckFileIntegrity.msg(),
+                        )),
+                    );
+                }
+            };
+        }
         Event::RemoveInvoiceTemplate(invoice_id) => {
             match db.delete_invoice_template_and_refetch(&invoice_id) {
                 Ok(items) => {
@@ -237,6 +627,8 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                         )),
                     );
                     util::send_gui_event(&sender, GuiEvent::SetInvoiceTemplates(items));
+                    util::send_gui_event(&sender, GuiEvent::InvoiceSaved);
+                    handle_background_events(Event::FetchAddressCompanies(), sender.clone(), db);
                 }
                 Err(e) => {
                     error!(
@@ -268,6 +660,121 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                 }
             };
         }
+        Event::FetchDrafts() => {
+            match db.get_drafts() {
+                Ok(drafts) => {
+                    util::send_gui_event(&sender, GuiEvent::SetDrafts(drafts));
+                }
+                Err(e) => {
+                    error!("Could not fetch drafts: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotFetchDrafts.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::SaveDraft(draft) => {
+            match db.save_draft(&draft) {
+                Ok(drafts) => {
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowInfoNotification(String::from(Messages::DraftSaved.msg())),
+                    );
+                    util::send_gui_event(&sender, GuiEvent::SetDrafts(drafts));
+                    util::send_gui_event(&sender, GuiEvent::InvoiceSaved);
+                }
+                Err(e) => {
+                    error!("Could not save draft with id {}: {e}", draft.id());
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotSaveDraft.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::RemoveDraft(key) => {
+            match db.delete_draft(&key) {
+                Ok(drafts) => {
+                    util::send_gui_event(&sender, GuiEvent::SetDrafts(drafts));
+                }
+                Err(e) => {
+                    error!("Could not delete draft {key}: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotDeleteDraft.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::RecordExport(entry) => {
+            match db.record_export(entry) {
+                Ok(()) => handle_background_events(Event::FetchExportLog(), sender, db),
+                Err(e) => {
+                    error!("Could not record export: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotRecordExport.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::ExportMonths(export) => {
+            let summary = accounting::export_months(&export, db, &sender);
+            let created = summary.exports.len();
+            for entry in summary.exports {
+                if let Err(e) = db.record_export(entry) {
+                    error!("Could not record export: {e}");
+                }
+            }
+            util::send_gui_event(
+                &sender,
+                GuiEvent::ShowInfoNotification(format!(
+                    "{} {} {} {}",
+                    created,
+                    Messages::MonthlyPDFsCreated.msg(),
+                    summary.skipped,
+                    Messages::EmptyMonthsSkipped.msg()
+                )),
+            );
+            if summary.failed > 0 {
+                util::send_gui_event(
+                    &sender,
+                    GuiEvent::ShowErrorNotification(format!(
+                        "{} {}",
+                        summary.failed,
+                        Messages::MonthlyPDFsFailed.msg()
+                    )),
+                );
+            }
+            if created > 0 {
+                handle_background_events(Event::FetchExportLog(), sender, db);
+            }
+        }
+        Event::FetchExportLog() => {
+            match db.get_export_log() {
+                Ok(entries) => {
+                    util::send_gui_event(&sender, GuiEvent::SetExportLog(entries));
+                }
+                Err(e) => {
+                    error!("Could not fetch export log: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotFetchExportLog.msg(),
+                        )),
+                    );
+                }
+            };
+        }
         Event::FetchNames() => {
             match db.get_all_names() {
                 Ok(items) => {
@@ -316,19 +823,78 @@ fn handle_background_events(event: Event, sender: Sender<GuiEvent>, db: &db::DB)
                 }
             };
         }
-    }
-}
-
-#[derive(Debug)]
-struct Helferlein {
-    state: State,
-    context: AppContext,
-    config: Config,
-}
-
-#[derive(Debug)]
-struct AppContext {
-    background_event_sender: Sender<Event>,
+        Event::SaveCompanyAddress(address) => {
+            match db.save_company_address(&address) {
+                Ok(()) => handle_background_events(Event::FetchAddressCompanies(), sender, db),
+                Err(e) => {
+                    error!("Could not save address of {}: {e}", address.name);
+                }
+            };
+        }
+        Event::FetchAddressCompanies() => {
+            match db.get_address_companies() {
+                Ok(companies) => {
+                    util::send_gui_event(&sender, GuiEvent::SetAddressCompanies(companies));
+                }
+                Err(e) => {
+                    error!("Could not fetch companies with addresses: {e}");
+                }
+            };
+        }
+        Event::FetchCompanyAddress(company) => {
+            match db.get_address_for_company(&company) {
+                Ok(Some(address)) => {
+                    util::send_gui_event(&sender, GuiEvent::SetCompanyAddress(address));
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowInfoNotification(String::from(Messages::AddressFilled.msg())),
+                    );
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    error!("Could not fetch address of {company}: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotFetchCompanyAddress.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+        Event::FetchCategoryDefaults(category) => {
+            match db.get_category_defaults(&category) {
+                Ok(Some(defaults)) => {
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::SetCategoryDefaults(category, defaults),
+                    );
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    error!("Could not fetch category defaults: {e}");
+                    util::send_gui_event(
+                        &sender,
+                        GuiEvent::ShowErrorNotification(String::from(
+                            Messages::CouldNotFetchCategoryDefaults.msg(),
+                        )),
+                    );
+                }
+            };
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Helferlein {
+    state: State,
+    context: AppContext,
+    config: Config,
+}
+
+#[derive(Debug)]
+struct AppContext {
+    background_event_sender: Sender<Event>,
     gui_event_receiver: Receiver<GuiEvent>,
     gui_event_sender: Sender<GuiEvent>,
     db_set: bool,
@@ -342,17 +908,28 @@ struct State {
     notifications: Vec<Notification>,
     config_state: ConfigState,
     file_picker_startpoint: Option<PathBuf>,
+    // folders item files were recently attached from, most recent first
+    recent_folders: Vec<PathBuf>,
+    // asks before closing the app with an unsaved invoice
+    exit_dialog: Option<Dialog>,
+    exit_confirmed: bool,
+    // the PDF exported last in this session
+    last_export: Option<PathBuf>,
 }
 
 impl State {
-    fn new() -> Self {
+    fn new(config: &Config) -> Self {
         Self {
             navigation: NavigationState::new(),
-            accounting: accounting::AccountingState::new(),
-            invoice: invoice::InvoiceState::new(),
+            accounting: accounting::AccountingState::new(config),
+            invoice: invoice::InvoiceState::new().with_bank_account(&config.bank_account),
             notifications: vec![],
             config_state: ConfigState::new(),
             file_picker_startpoint: None,
+            recent_folders: vec![],
+            exit_dialog: None,
+            exit_confirmed: false,
+            last_export: None,
         }
     }
 }
@@ -362,9 +939,13 @@ struct ConfigState {
     open_file_dialog: Option<FileDialog>,
     selected_folder: Option<PathBuf>,
     change_data_folder_dialog: Option<Dialog>,
+    overwrite_db_dialog: Option<Dialog>,
+    // the typed confirmation, while the reset dialog is open
+    reset_confirmation: Option<String>,
     file_open_command: String,
     file_open_command_change: bool,
     language: Language,
+    export_log: Vec<ExportLogEntry>,
 }
 
 impl ConfigState {
@@ -373,13 +954,17 @@ impl ConfigState {
             open_file_dialog: None,
             selected_folder: None,
             change_data_folder_dialog: None,
+            overwrite_db_dialog: None,
+            reset_confirmation: None,
             file_open_command: String::default(),
             file_open_command_change: false,
             language: Language::EN,
+            export_log: vec![],
         }
     }
 }
 
+// switching screens only changes the current screen, the state of each screen is kept
 #[derive(Debug)]
 struct NavigationState {
     current_screen: Screen,
@@ -400,9 +985,10 @@ impl Helferlein {
         gui_event_sender: Sender<GuiEvent>,
         config: Config,
     ) -> Box<Self> {
+        let state = State::new(&config);
         Box::new(Self {
             config,
-            state: State::new(),
+            state,
             context: AppContext {
                 background_event_sender,
                 gui_event_receiver,
@@ -424,7 +1010,7 @@ impl Helferlein {
                     .fade_out(false)
                     .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
                     .drag_to_scroll(false)
-                    .fixed_size([400.0, 100.0])
+                    .fixed_size(config_window_size(ctx.screen_rect().size()))
                     .show(ctx, |ui| {
                         StripBuilder::new(ui)
                             .size(Size::remainder())
@@ -445,7 +1031,10 @@ impl Helferlein {
                                 strip.cell(|ui| {
                                     ui.vertical_centered(|ui| {
                                         ui.horizontal(|ui| {
-                                            ui.text_edit_singleline(
+                                            // leaves room for the open button on narrow windows
+                                            let text_width =
+                                                (ui.available_width() - OPEN_BUTTON_WIDTH).max(0.0);
+                                            TextEdit::singleline(
                                                 &mut self
                                                     .state
                                                     .config_state
@@ -455,7 +1044,9 @@ impl Helferlein {
                                                         || "",
                                                         |path| path.to_str().unwrap_or(""),
                                                     ),
-                                            );
+                                            )
+                                            .desired_width(text_width)
+                                            .show(ui);
                                             if (ui.button(Messages::Open)).clicked() {
                                                 let mut dialog =
                                                     ui::get_localized_select_folder_dialog(
@@ -494,20 +1085,18 @@ impl Helferlein {
                                             {
                                                 let cfg = Config {
                                                     data_folder: Some(data_folder.clone()),
-                                                    file_open_command: self
-                                                        .config
-                                                        .file_open_command
-                                                        .clone(),
                                                     language: self
                                                         .state
                                                         .config_state
                                                         .language
                                                         .name()
                                                         .into(),
+                                                    ..self.config.clone()
                                                 };
-                                                if let Err(e) = config::save_config(&cfg) {
-                                                    error!("Could not save config: {e}");
-                                                } else {
+                                                if config::save_config_or_notify(
+                                                    &cfg,
+                                                    &self.context.gui_event_sender,
+                                                ) {
                                                     self.config = cfg;
                                                 }
                                             }
@@ -526,6 +1115,9 @@ impl Helferlein {
                         &self.context.background_event_sender,
                         Event::SetDB(data_folder.clone()),
                     );
+                    if self.config.accounting_period.is_some() {
+                        accounting::select_date_range(&mut self.state, &self.context, ctx);
+                    }
                 }
             }
         }
@@ -537,20 +1129,52 @@ impl Helferlein {
                 GuiEvent::SetInvoiceTemplates(items) => {
                     self.state.invoice.templates = items;
                 }
-                GuiEvent::ShowInfoNotification(text) => self
-                    .state
-                    .notifications
-                    .push(Notification::Info(InnerNotification::new(text))),
+                GuiEvent::SetDrafts(drafts) => {
+                    self.state.invoice.drafts = drafts;
+                }
+                GuiEvent::InvoiceSaved => {
+                    self.state.invoice.mark_saved();
+                }
+                GuiEvent::SetAddressCompanies(companies) => {
+                    self.state.invoice.address_companies = companies;
+                }
+                GuiEvent::SetCompanyAddress(address) => {
+                    self.state.invoice.fill_to_address(address);
+                }
+                GuiEvent::SetExportLog(entries) => {
+                    self.state.config_state.export_log = entries;
+                }
+                GuiEvent::ShowInfoNotification(text) => push_notification(
+                    &mut self.state.notifications,
+                    Notification::Info(InnerNotification::new(text)),
+                ),
 
+                GuiEvent::ShowWarningNotification(text) => {
+                    push_notification(
+                        &mut self.state.notifications,
+                        Notification::Warning(InnerNotification::new(text)),
+                    );
+                }
                 GuiEvent::ShowErrorNotification(text) => {
-                    self.state
-                        .notifications
-                        .push(Notification::Error(InnerNotification::new(text)));
+                    push_notification(
+                        &mut self.state.notifications,
+                        Notification::Error(InnerNotification::new(text)),
+                    );
                 }
-                GuiEvent::SetAccountingItems(items) => {
-                    if let Some(ref mut sheet) = self.state.accounting.selected_accounting_sheet {
-                        sheet.items = items;
-                    }
+                GuiEvent::ConfirmMerge(count) => {
+                    self.state.accounting.confirm_merge(count);
+                }
+                GuiEvent::SetLastExport(file) => {
+                    self.state.last_export = Some(file);
+                }
+                GuiEvent::SetDuplicateItems(groups) => {
+                    self.state.accounting.duplicates = Some(groups);
+                }
+                GuiEvent::JumpToPeriodOf(date) => {
+                    self.state.accounting.jump_to_date = Some(date);
+                }
+                GuiEvent::SetAccountingItems(date_range, items) => {
+                    self.state.accounting.set_fetched_items(&date_range, items);
                 }
                 GuiEvent::SetNames(items) => {
                     self.state.accounting.names = items;
@@ -561,6 +1185,13 @@ impl Helferlein {
                 GuiEvent::SetCompanies(items) => {
                     self.state.accounting.companies = items;
                 }
+                GuiEvent::SetCategoryDefaults(category, defaults) => {
+                    accounting::apply_category_defaults(
+                        &mut self.state.accounting,
+                        &category,
+                        defaults,
+                    );
+                }
             }
         }
     }
@@ -616,39 +1247,524 @@ impl Helferlein {
             {
                 self.state.navigation.current_screen = Screen::Settings;
             }
+            ui.separator();
+            let last_export = self.state.last_export.as_ref().filter(|file| file.exists());
+            if ui
+                .add_enabled(
+                    last_export.is_some(),
+                    Button::new(Messages::OpenLastExport.msg()),
+                )
+                .on_disabled_hover_text(Messages::NoExportYet)
+                .clicked()
+            {
+                if let Some(file) = last_export {
+                    util::send_event_and_request_repaint(
+                        ui.ctx(),
+                        &self.context.background_event_sender,
+                        Event::OpenFile(file.to_string_lossy().into_owned()),
+                    );
+                }
+            }
         });
     }
 
+    fn handle_close_request(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.viewport().close_requested())
+            && self.config.confirm_exit
+            && !self.state.exit_confirmed
+            && self.state.invoice.has_unsaved_changes()
+        {
+            ctx.send_viewport_cmd(ViewportCommand::CancelClose);
+            self.state.exit_dialog = Some(Dialog::new(
+                Messages::ReallyExitWithUnsavedInvoice.msg().to_string(),
+                Messages::Close.msg(),
+                Messages::Cancel.msg(),
+            ));
+        }
+        if let Some(ref dialog) = self.state.exit_dialog {
+            match dialog::render_dialog(ctx, dialog) {
+                DialogResponse::Ok => {
+                    self.state.exit_dialog = None;
+                    self.state.exit_confirmed = true;
+                    ctx.send_viewport_cmd(ViewportCommand::Close);
+                }
+                DialogResponse::Cancel => {
+                    self.state.exit_dialog = None;
+                }
+                _ => (),
+            }
+        }
+    }
+
     fn build_home(&mut self, ui: &mut egui::Ui) {
         ui.label(RichText::new(Messages::Welcome).strong());
     }
 
+    // snapshots the database before risky operations, if enabled - false, if that failed
+    fn auto_backup(&self) -> bool {
+        util::files::auto_backup_or_notify(&self.config, &self.context.gui_event_sender)
+    }
+
+    fn move_data_folder(&mut self, ctx: &egui::Context) {
+        if !self.auto_backup() {
+            self.state.config_state.selected_folder = None;
+            return;
+        }
+        if let Some(ref source) = self.config.data_folder {
+            if let Some(ref target) = self.state.config_state.selected_folder {
+                match util::files::move_folder_recursively(source.as_path(), target.as_path()) {
+                    Err(e) => {
+                        util::send_gui_event(
+                            &self.context.gui_event_sender,
+                            GuiEvent::ShowErrorNotification(
+                                Messages::ErrorChangingDataFolder.msg().to_owned(),
+                            ),
+                        );
+                        log::error!("error while changing data folder: {e}")
+                    }
+                    Ok(_) => {
+                        self.config.data_folder = Some(target.to_path_buf());
+                        if config::save_config_or_notify(
+                            &self.config,
+                            &self.context.gui_event_sender,
+                        ) {
+                            util::send_gui_event(
+                                &self.context.gui_event_sender,
+                                GuiEvent::ShowInfoNotification(
+                                    Messages::SuccessFullyChangedDataFolder.msg().to_owned(),
+                                ),
+                            );
+                            util::send_event_and_request_repaint(
+                                ctx,
+                                &self.context.background_event_sender,
+                                Event::SetDB(target.to_owned()),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        self.state.config_state.selected_folder = None;
+    }
+
+    // a typed confirmation, since there's no way back
+    fn build_reset_dialog(&mut self, ctx: &egui::Context) {
+        let Some(ref mut confirmation) = self.state.config_state.reset_confirmation else {
+            return;
+        };
+        let mut close = false;
+        let mut reset = false;
+        Window::new(Messages::ResetAllData.msg())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} \"{}\"",
+                    Messages::ReallyResetAllData,
+                    RESET_CONFIRMATION
+                ));
+                ui.text_edit_singleline(confirmation).request_focus();
+                ui.horizontal(|ui| {
+                    if ui.button(Messages::Cancel.msg()).clicked() {
+                        close = true;
+                    }
+                    if ui
+                        .add_enabled(
+                            confirmation.trim() == RESET_CONFIRMATION,
+                            Button::new(Messages::Reset.msg()),
+                        )
+                        .clicked()
+                    {
+                        reset = true;
+                    }
+                });
+            });
+        if reset && self.auto_backup() {
+            if let Some(ref data_folder) = self.config.data_folder {
+                util::send_event_and_request_repaint(
+                    ctx,
+                    &self.context.background_event_sender,
+                    Event::ResetAllData(
+                        data_folder.clone(),
+                        accounting::selected_date_range(&self.state.accounting),
+                    ),
+                );
+            }
+        }
+        if close || reset {
+            self.state.config_state.reset_confirmation = None;
+        }
+    }
+
     fn build_settings(&mut self, ui: &mut egui::Ui) {
         ui.label(RichText::new(Messages::Settings).strong());
         Grid::new("settings_grid").num_columns(3).show(ui, |ui| {
             ui.label(Messages::Language);
             ui.horizontal(|ui| {
                 let current_lang = Language::from(self.config.language.clone());
-                [Language::EN, Language::DE].iter().for_each(|lang| {
+                [Language::EN, Language::DE, Language::ES, Language::IT]
+                    .iter()
+                    .for_each(|lang| {
+                        if ui
+                            .add(SelectableLabel::new(current_lang == *lang, lang.name()))
+                            .clicked()
+                        {
+                            self.state.config_state.language = *lang;
+                            let cfg = Config {
+                                language: self.state.config_state.language.name().into(),
+                                ..self.config.clone()
+                            };
+                            if config::save_config_or_notify(&cfg, &self.context.gui_event_sender) {
+                                self.config = cfg;
+                            }
+                        }
+                    });
+            });
+            ui.end_row();
+            ui.label(Messages::PageSize);
+            ui.horizontal(|ui| {
+                [PageSize::A4, PageSize::Letter]
+                    .iter()
+                    .for_each(|page_size| {
+                        if ui
+                            .add(SelectableLabel::new(
+                                self.config.page_size == *page_size,
+                                page_size.name(),
+                            ))
+                            .clicked()
+                        {
+                            self.config.page_size = *page_size;
+                            config::save_config_or_notify(
+                                &self.config,
+                                &self.context.gui_event_sender,
+                            );
+                        }
+                    });
+            });
+            ui.end_row();
+            ui.label(Messages::NumberFormat);
+            ui.horizontal(|ui| {
+                [
+                    NumberFormat::FollowLanguage,
+                    NumberFormat::DeDe,
+                    NumberFormat::EnUs,
+                ]
+                .iter()
+                .for_each(|number_format| {
+                    if ui
+                        .add(SelectableLabel::new(
+                            self.config.number_format == *number_format,
+                            number_format.name(),
+                        ))
+                        .clicked()
+                    {
+                        self.config.number_format = *number_format;
+                        config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+                    }
+                });
+            });
+            ui.end_row();
+            ui.label(Messages::DateFormat);
+            ui.horizontal(|ui| {
+                [
+                    DateFormat::DayMonthYear,
+                    DateFormat::MonthDayYear,
+                    DateFormat::Iso,
+                ]
+                .iter()
+                .for_each(|date_format| {
                     if ui
-                        .add(SelectableLabel::new(current_lang == *lang, lang.name()))
+                        .add(SelectableLabel::new(
+                            self.config.date_format == *date_format,
+                            date_format.name(),
+                        ))
                         .clicked()
                     {
-                        self.state.config_state.language = *lang;
-                        let cfg = Config {
-                            data_folder: self.config.data_folder.clone(),
-                            file_open_command: self.config.file_open_command.clone(),
-                            language: self.state.config_state.language.name().into(),
-                        };
-                        if let Err(e) = config::save_config(&cfg) {
-                            error!("Could not save config: {e}");
-                        } else {
-                            self.config = cfg;
+                        let previous = self.config.date_format;
+                        self.config.date_format = *date_format;
+                        // open forms keep their dates, in the new format
+                        if config::save_config_or_notify(
+                            &self.config,
+                            &self.context.gui_event_sender,
+                        ) {
+                            let (from, to) = (previous.pattern(), date_format.pattern());
+                            self.state.invoice.reformat_dates(from, to);
+                            self.state.accounting.reformat_dates(from, to);
                         }
                     }
                 });
             });
             ui.end_row();
+            ui.label(Messages::QuantityDecimals);
+            ui.horizontal(|ui| {
+                (0..=3).for_each(|decimals| {
+                    if ui
+                        .add(SelectableLabel::new(
+                            self.config.quantity_decimals == decimals,
+                            decimals.to_string(),
+                        ))
+                        .clicked()
+                    {
+                        self.config.quantity_decimals = decimals;
+                        config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+                    }
+                });
+            });
+            ui.end_row();
+            ui.label(Messages::TopMarginOffset);
+            let response = ui.add(
+                DragValue::new(&mut self.config.top_margin_offset_mm)
+                    .range(0.0..=100.0)
+                    .suffix(" mm"),
+            );
+            // only save once dragging is done, not on every step
+            if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::YearHistorySpan);
+            ui.horizontal(|ui| {
+                [5, 10, 25, 50].iter().for_each(|span| {
+                    if ui
+                        .add(SelectableLabel::new(
+                            self.config.year_history_span == *span,
+                            span.to_string(),
+                        ))
+                        .clicked()
+                    {
+                        self.config.year_history_span = *span;
+                        config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+                    }
+                });
+            });
+            ui.end_row();
+            ui.label(Messages::MaxSuggestions);
+            let response = ui.add(
+                DragValue::new(&mut self.config.max_suggestions).range(MAX_SUGGESTIONS_RANGE),
+            );
+            if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::MinSuggestionInput);
+            let response = ui.add(
+                DragValue::new(&mut self.config.min_suggestion_input)
+                    .range(0..=5)
+                    .suffix(format!(" {}", Messages::Characters)),
+            );
+            if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::FiscalYearStart);
+            let start_month = Month::from(self.config.fiscal_year_start_month);
+            ComboBox::from_id_salt("fiscal_year_start_selector")
+                .selected_text(start_month.name())
+                .show_ui(ui, |ui| {
+                    MONTHS.iter().for_each(|month| {
+                        if ui
+                            .add(SelectableLabel::new(start_month == *month, month.name()))
+                            .clicked()
+                        {
+                            self.config.fiscal_year_start_month = (*month).into();
+                            self.state.accounting.fiscal_year_start_month =
+                                self.config.fiscal_year_start_month;
+                            config::save_config_or_notify(
+                                &self.config,
+                                &self.context.gui_event_sender,
+                            );
+                        }
+                    });
+                });
+            ui.end_row();
+            ui.label(Messages::CategoryDefaults);
+            if ui
+                .checkbox(
+                    &mut self.config.category_defaults,
+                    Messages::PreFillFromCategory,
+                )
+                .changed()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::Window);
+            if ui
+                .checkbox(&mut self.config.always_on_top, Messages::AlwaysOnTop)
+                .changed()
+            {
+                // takes effect right away, without a restart
+                ui.ctx()
+                    .send_viewport_cmd(ViewportCommand::WindowLevel(window_level(
+                        self.config.always_on_top,
+                    )));
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::MinWindowSize);
+            ui.horizontal(|ui| {
+                let width = ui.add(
+                    DragValue::new(&mut self.config.min_window_width)
+                        .range(MIN_WINDOW_SIZE_RANGE)
+                        .suffix(" px"),
+                );
+                ui.label("×");
+                let height = ui.add(
+                    DragValue::new(&mut self.config.min_window_height)
+                        .range(MIN_WINDOW_SIZE_RANGE)
+                        .suffix(" px"),
+                );
+                if [width, height]
+                    .iter()
+                    .any(|r| r.drag_stopped() || (r.changed() && !r.dragged()))
+                {
+                    ui.ctx()
+                        .send_viewport_cmd(ViewportCommand::MinInnerSize(egui::vec2(
+                            self.config.min_window_width,
+                            self.config.min_window_height,
+                        )));
+                    config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+                }
+            });
+            ui.end_row();
+            ui.label(Messages::Tables);
+            if ui
+                .checkbox(
+                    &mut self.config.color_rows,
+                    Messages::ColorRowsByInvoiceType,
+                )
+                .changed()
+            {
+                self.state.accounting.color_rows = self.config.color_rows;
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label("");
+            if ui
+                .checkbox(&mut self.config.show_cents, Messages::ShowCentsInTotals)
+                .changed()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::Export);
+            if ui
+                .checkbox(
+                    &mut self.config.open_pdf_after_export,
+                    Messages::OpenPdfAfterExport,
+                )
+                .changed()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::Files);
+            if ui
+                .checkbox(&mut self.config.files_per_year, Messages::FilesPerYear)
+                .changed()
+            {
+                // the setting only changes together with the files
+                if !self.auto_backup()
+                    || !config::save_config_or_notify(&self.config, &self.context.gui_event_sender)
+                {
+                    self.config.files_per_year = !self.config.files_per_year;
+                } else if let Some(ref data_folder) = self.config.data_folder {
+                    // moves the existing files, so they're all organized the same way
+                    util::send_event_and_request_repaint(
+                        ui.ctx(),
+                        &self.context.background_event_sender,
+                        Event::RelocateFiles(
+                            data_folder.join(PATH_FOR_FILES),
+                            self.config.files_per_year,
+                            accounting::selected_date_range(&self.state.accounting),
+                        ),
+                    );
+                }
+            }
+            ui.end_row();
+            ui.label(Messages::PDFAuthor);
+            // only save once editing is done, not on every key press
+            if ui
+                .add(TextEdit::singleline(&mut self.config.pdf_author).desired_width(250.0))
+                .lost_focus()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::AccountingExportFileName);
+            if ui
+                .add(
+                    TextEdit::singleline(&mut self.config.accounting_export_template)
+                        .hint_text(DEFAULT_ACCOUNTING_EXPORT_TEMPLATE)
+                        .desired_width(250.0),
+                )
+                .on_hover_text(format!(
+                    "{}: {{period}}, {{year}}, {{quarter}}, {{month}}",
+                    Messages::Placeholders
+                ))
+                .lost_focus()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::InvoiceExportFileName);
+            if ui
+                .add(
+                    TextEdit::singleline(&mut self.config.invoice_export_template)
+                        .hint_text(DEFAULT_INVOICE_EXPORT_TEMPLATE)
+                        .desired_width(250.0),
+                )
+                .on_hover_text(format!(
+                    "{}: {{kind}}, {{name}}, {{invoice_number}}, {{to_name}}, {{year}}, {{month}}, {{day}}",
+                    Messages::Placeholders
+                ))
+                .lost_focus()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::PaymentQRCode);
+            if ui
+                .checkbox(&mut self.config.epc_qr_code, Messages::EPCQRCodeOnInvoices)
+                .changed()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::ConfirmExit);
+            if ui
+                .checkbox(
+                    &mut self.config.confirm_exit,
+                    Messages::ConfirmExitWithUnsavedInvoice,
+                )
+                .changed()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::Drafts);
+            if ui
+                .checkbox(
+                    &mut self.config.delete_draft_after_export,
+                    Messages::DeleteDraftAfterExport,
+                )
+                .changed()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            ui.label(Messages::VatColumn);
+            if ui
+                .checkbox(
+                    &mut self.config.invoice_vat_column,
+                    Messages::VatAmountPerLineOnInvoices,
+                )
+                .changed()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
             ui.label(Messages::FileOpenProgram);
             let file_open_command = self.config.file_open_command.clone();
             if ui.button(Messages::Change.msg()).clicked() {
@@ -670,9 +1786,7 @@ impl Helferlein {
                 if ui.button(Messages::Save.msg()).clicked() {
                     self.config.file_open_command =
                         Some(self.state.config_state.file_open_command.clone());
-                    if let Err(e) = config::save_config(&self.config) {
-                        error!("Could not save config: {e}");
-                    } else {
+                    if config::save_config_or_notify(&self.config, &self.context.gui_event_sender) {
                         util::send_gui_event(
                             &self.context.gui_event_sender,
                             GuiEvent::ShowInfoNotification(
@@ -719,45 +1833,17 @@ impl Helferlein {
                 match dialog::render_dialog(ui.ctx(), dialog) {
                     DialogResponse::Ok => {
                         self.state.config_state.change_data_folder_dialog = None;
-                        if let Some(ref source) = self.config.data_folder {
-                            if let Some(ref target) = self.state.config_state.selected_folder {
-                                match util::files::move_folder_recursively(
-                                    source.as_path(),
-                                    target.as_path(),
-                                ) {
-                                    Err(e) => {
-                                        util::send_gui_event(
-                                            &self.context.gui_event_sender,
-                                            GuiEvent::ShowErrorNotification(
-                                                Messages::ErrorChangingDataFolder.msg().to_owned(),
-                                            ),
-                                        );
-                                        log::error!("error while changing data folder: {e}")
-                                    }
-                                    Ok(_) => {
-                                        self.config.data_folder = Some(target.to_path_buf());
-                                        if let Err(e) = config::save_config(&self.config) {
-                                            error!("Could not save config: {e}");
-                                        } else {
-                                            util::send_gui_event(
-                                                &self.context.gui_event_sender,
-                                                GuiEvent::ShowInfoNotification(
-                                                    Messages::SuccessFullyChangedDataFolder
-                                                        .msg()
-                                                        .to_owned(),
-                                                ),
-                                            );
-                                            util::send_event_and_request_repaint(
-                                                ui.ctx(),
-                                                &self.context.background_event_sender,
-                                                Event::SetDB(target.to_owned()),
-                                            );
-                                        }
-                                    }
-                                }
+                        match self.state.config_state.selected_folder {
+                            // ask again, if there is already a database in the target folder
+                            Some(ref target) if util::files::folder_has_existing_db(target) => {
+                                self.state.config_state.overwrite_db_dialog = Some(Dialog::new(
+                                    Messages::DataFolderContainsDatabase.msg().to_string(),
+                                    Messages::Overwrite.msg(),
+                                    Messages::Cancel.msg(),
+                                ));
                             }
+                            _ => self.move_data_folder(ui.ctx()),
                         }
-                        self.state.config_state.selected_folder = None;
                     }
                     DialogResponse::Cancel => {
                         self.state.config_state.change_data_folder_dialog = None;
@@ -767,6 +1853,129 @@ impl Helferlein {
                     _ => (),
                 }
             }
+
+            if let Some(ref dialog) = self.state.config_state.overwrite_db_dialog {
+                match dialog::render_dialog(ui.ctx(), dialog) {
+                    DialogResponse::Ok => {
+                        self.state.config_state.overwrite_db_dialog = None;
+                        self.move_data_folder(ui.ctx());
+                    }
+                    DialogResponse::Cancel => {
+                        self.state.config_state.overwrite_db_dialog = None;
+                        self.state.config_state.selected_folder = None;
+                        info!("canceled overwriting existing database")
+                    }
+                    _ => (),
+                }
+            }
+
+            ui.label(Messages::ResetAllData);
+            if ui
+                .add_enabled(
+                    self.config.data_folder.is_some(),
+                    Button::new(Messages::Reset.msg()),
+                )
+                .clicked()
+            {
+                self.state.config_state.reset_confirmation = Some(String::default());
+            }
+            ui.end_row();
+            ui.label(Messages::Backups);
+            ui.horizontal(|ui| {
+                if ui
+                    .checkbox(&mut self.config.auto_backup, Messages::AutoBackup)
+                    .on_hover_text(Messages::AutoBackupHint)
+                    .changed()
+                {
+                    config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+                }
+                let response = ui.add_enabled(
+                    self.config.auto_backup,
+                    DragValue::new(&mut self.config.auto_backup_count)
+                        .range(1..=100)
+                        .prefix(format!("{} ", Messages::Keep)),
+                );
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+                }
+            });
+            ui.end_row();
+            ui.label(Messages::DeveloperMode);
+            if ui
+                .checkbox(&mut self.config.developer_mode, Messages::ShowActivityLog)
+                .changed()
+            {
+                config::save_config_or_notify(&self.config, &self.context.gui_event_sender);
+            }
+            ui.end_row();
+            self.build_reset_dialog(ui.ctx());
+        });
+        ui.separator();
+        ui.collapsing(Messages::ExportLog.msg(), |ui| {
+            if self.state.config_state.export_log.is_empty() {
+                ui.label(Messages::NoExportsYet);
+                return;
+            }
+            Grid::new("export_log_grid")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(Messages::Date);
+                    ui.strong(Messages::Type);
+                    ui.strong(Messages::ExportedPeriodOrNumber);
+                    ui.strong(Messages::File);
+                    ui.end_row();
+                    // newest first
+                    self.state
+                        .config_state
+                        .export_log
+                        .iter()
+                        .rev()
+                        .for_each(|entry| {
+                            ui.label(
+                                entry
+                                    .timestamp
+                                    .format(&format!("{} %H:%M", date_format()))
+                                    .to_string(),
+                            );
+                            ui.label(entry.export_type.name());
+                            ui.label(&entry.reference);
+                            ui.label(entry.path.to_string_lossy());
+                            ui.end_row();
+                        });
+                });
+        });
+        if self.config.developer_mode {
+            self.build_activity_log(ui);
+        }
+    }
+
+    fn build_activity_log(&self, ui: &mut egui::Ui) {
+        ui.collapsing(Messages::ActivityLog.msg(), |ui| {
+            let lines: Vec<String> = util::logging::recent_records()
+                .iter()
+                .map(|record| record.line())
+                .collect();
+            if lines.is_empty() {
+                ui.label(Messages::NoLogRecordsYet);
+                return;
+            }
+            if ui.button(Messages::CopyLog).clicked() {
+                ui.ctx().copy_text(lines.join("\n"));
+                util::send_gui_event(
+                    &self.context.gui_event_sender,
+                    GuiEvent::ShowInfoNotification(String::from(Messages::LogCopied.msg())),
+                );
+            }
+            ScrollArea::vertical()
+                .id_salt("activity_log")
+                .max_height(300.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    lines.iter().for_each(|line| {
+                        ui.monospace(line);
+                    });
+                });
         });
     }
 }
@@ -775,6 +1984,7 @@ impl App for Helferlein {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.handle_config_init(ctx);
         self.handle_gui_events();
+        self.handle_close_request(ctx);
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ScrollArea::vertical().show(ui, |ui| {
@@ -789,13 +1999,13 @@ impl App for Helferlein {
                             self.build_home(ui);
                         }
                         Screen::Invoice => {
-                            invoice::build(ctx, &mut self.state, &self.context, ui);
+                            invoice::build(ctx, &mut self.state, &self.config, &self.context, ui);
                         }
                         Screen::Accounting => {
                             accounting::build(
                                 ctx,
                                 &mut self.state,
-                                &self.config,
+                                &mut self.config,
                                 &self.context,
                                 ui,
                             );
@@ -813,29 +2023,71 @@ impl App for Helferlein {
 
 #[derive(Debug)]
 enum GuiError {
-    CopyItemFileFailed(String),
     FileAccessError(String),
     ExportFailed(String),
     DatabaseError(String),
+    // the invoice form contains values, which can't be converted
+    InvalidInvoice(String),
+    Io(std::io::Error),
+    // boxed, since redb errors are large
+    Database(Box<redb::Error>),
+}
+
+impl From<std::io::Error> for GuiError {
+    fn from(e: std::io::Error) -> Self {
+        GuiError::Io(e)
+    }
+}
+
+impl From<redb::Error> for GuiError {
+    fn from(e: redb::Error) -> Self {
+        GuiError::Database(Box::new(e))
+    }
+}
+
+// all redb operations have their own error type, which can be converted into a redb::Error
+macro_rules! from_redb_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for GuiError {
+                fn from(e: $error) -> Self {
+                    GuiError::Database(Box::new(e.into()))
+                }
+            }
+        )*
+    };
+}
+
+from_redb_error!(
+    redb::DatabaseError,
+    redb::TransactionError,
+    redb::TableError,
+    redb::StorageError,
+    redb::CommitError
+);
+
+impl std::error::Error for GuiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GuiError::Io(e) => Some(e),
+            GuiError::Database(e) => Some(e.as_ref()),
+            GuiError::FileAccessError(_)
+            | GuiError::ExportFailed(_)
+            | GuiError::DatabaseError(_)
+            | GuiError::InvalidInvoice(_) => None,
+        }
+    }
 }
 
 impl From<&GuiError> for String {
     fn from(val: &GuiError) -> Self {
-        match val {
-            GuiError::CopyItemFileFailed(msg) => msg.to_owned(),
-            GuiError::FileAccessError(msg) => msg.to_owned(),
-            GuiError::ExportFailed(msg) => msg.to_owned(),
-            GuiError::DatabaseError(msg) => msg.to_owned(),
-        }
+        val.to_string()
     }
 }
 
 impl std::fmt::Display for GuiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            GuiError::CopyItemFileFailed(msg) => {
-                write!(f, "{}", msg)
-            }
             GuiError::FileAccessError(msg) => {
                 write!(f, "{}", msg)
             }
@@ -845,6 +2097,15 @@ impl std::fmt::Display for GuiError {
             GuiError::DatabaseError(msg) => {
                 write!(f, "{}", msg)
             }
+            GuiError::InvalidInvoice(msg) => {
+                write!(f, "{}: {}", Messages::InvalidInvoice.msg(), msg)
+            }
+            GuiError::Io(e) => {
+                write!(f, "{}: {}", Messages::FileAccessFailed.msg(), e)
+            }
+            GuiError::Database(e) => {
+                write!(f, "{}: {}", Messages::DatabaseAccessFailed.msg(), e)
+            }
         }
     }
 }
@@ -860,24 +2121,68 @@ enum Screen {
 enum Event {
     RemoveItem(String, DateRange),
     FetchItems(DateRange),
+    FetchAllItems(),
+    // the latest period with items before the given range
+    FindPeriodWithItemsBefore(DateRange),
+    FindDuplicates(),
+    // whether it's a company, and the category or company to be merged
+    CountItemsToMerge(bool, String),
+    // from, to and the currently shown date range, to refetch afterwards
+    MergeCategories(String, String, DateRange),
+    MergeCompanies(String, String, DateRange),
     FetchNames(),
     FetchCompanies(),
     FetchCategories(),
+    FetchCategoryDefaults(String),
     SaveItem(AccountingItem, DateRange),
+    // the items of a receipt split across categories
+    SaveSplitItems(Vec<AccountingItem>, DateRange),
+    RecordInvoice(Vec<AccountingItem>, DateRange),
+    CopyItemsToNextYear(Vec<String>, DateRange),
     SetDB(PathBuf),
+    // data folder and the currently shown date range, to refetch afterwards
+    ResetAllData(PathBuf, DateRange),
+    CheckFileIntegrity(PathBuf),
+    RelocateFiles(PathBuf, bool, DateRange),
     OpenFile(String),
+    OpenFolder(PathBuf),
     FetchInvoiceTemplates(),
     SaveInvoiceTemplate(Box<Invoice>),
     RemoveInvoiceTemplate(String),
+    FetchDrafts(),
+    SaveDraft(Box<Draft>),
+    RemoveDraft(String),
+    RecordExport(ExportLogEntry),
+    FetchExportLog(),
+    ExportMonths(MonthlyExport),
+    SaveCompanyAddress(Address),
+    FetchAddressCompanies(),
+    FetchCompanyAddress(String),
 }
 
 #[derive(Debug)]
 enum GuiEvent {
     ShowInfoNotification(String),
+    ShowWarningNotification(String),
     ShowErrorNotification(String),
-    SetAccountingItems(Vec<AccountingItem>),
+    // the items of the given range, only shown if it is still the selected one
+    SetAccountingItems(DateRange, Vec<AccountingItem>),
+    SetDuplicateItems(Vec<Vec<AccountingItem>>),
+    // a date in the period to jump to
+    JumpToPeriodOf(NaiveDate),
     SetNames(Vec<String>),
     SetCompanies(Vec<String>),
     SetCategories(Vec<String>),
+    SetCategoryDefaults(String, CategoryDefaults),
     SetInvoiceTemplates(Vec<Invoice>),
+    SetDrafts(Vec<Draft>),
+    SetExportLog(Vec<ExportLogEntry>),
+    SetAddressCompanies(Vec<String>),
+    SetCompanyAddress(Address),
+    // the number of items a merge changes, to be confirmed
+    ConfirmMerge(usize),
+    // a PDF was created, to be reopened later
+    SetLastExport(PathBuf),
+    // the invoice form was stored as a template or draft
+    InvoiceSaved,
 }
@@ -6,6 +6,8 @@ use crate::get_language;
 pub(crate) enum Language {
     EN,
     DE,
+    ES,
+    IT,
 }
 
 impl Language {
@@ -13,6 +15,8 @@ impl Language {
         match self {
             Language::EN => "en",
             Language::DE => "de",
+            Language::ES => "es",
+            Language::IT => "it",
         }
     }
 }
@@ -21,6 +25,8 @@ impl From<String> for Language {
     fn from(value: String) -> Self {
         match value.as_str() {
             "de" => Language::DE,
+            "es" => Language::ES,
+            "it" => Language::IT,
             _ => Language::EN,
         }
     }
@@ -30,6 +36,8 @@ impl From<&str> for Language {
     fn from(value: &str) -> Self {
         match value {
             "de" => Language::DE,
+            "es" => Language::ES,
+            "it" => Language::IT,
             _ => Language::EN,
         }
     }
@@ -47,6 +55,53 @@ pub(crate) enum Messages {
     SuccessFullyChangedDataFolder,
     ErrorChangingDataFolder,
     SuccessFullyChangedProgramToOpen,
+    PageSize,
+    CategoryDefaults,
+    PreFillFromCategory,
+    OpenPdfAfterExport,
+    FiscalYearStart,
+    ExportLog,
+    NoExportsYet,
+    ExportedPeriodOrNumber,
+    Type,
+    NumberFormat,
+    FollowLanguage,
+    QuantityDecimals,
+    YearHistorySpan,
+    Tables,
+    ColorRowsByInvoiceType,
+    TopMarginOffset,
+    Window,
+    AlwaysOnTop,
+    PDFAuthor,
+    FilesPerYear,
+    Files,
+    PaymentQRCode,
+    EPCQRCodeOnInvoices,
+    DateFormat,
+    ResetAllData,
+    ReallyResetAllData,
+    VatColumn,
+    VatAmountPerLineOnInvoices,
+    ConfirmExit,
+    ConfirmExitWithUnsavedInvoice,
+    MaxSuggestions,
+    MinSuggestionInput,
+    Characters,
+    ShowCentsInTotals,
+    DeleteDraftAfterExport,
+    DeveloperMode,
+    ShowActivityLog,
+    ActivityLog,
+    NoLogRecordsYet,
+    MinWindowSize,
+    AccountingExportFileName,
+    InvoiceExportFileName,
+    Placeholders,
+    Backups,
+    AutoBackup,
+    AutoBackupHint,
+    Keep,
 
     // Invoice
     General,
@@ -76,6 +131,26 @@ pub(crate) enum Messages {
     PreText,
     PostText,
     BankData,
+    RoundTotal,
+    Rounding,
+    TotalHours,
+    InclVat,
+    PricesIncludeVat,
+    Quote,
+    QuoteShort,
+    DocumentKind,
+    Adjustments,
+    AdjustmentAmount,
+    FillAddress,
+    NoInvoiceItems,
+    Attachments,
+    AccountHolder,
+    Iban,
+    Bic,
+    CopySuffix,
+    SaveDraft,
+    Drafts,
+    DraftLoaded,
 
     // Accounting
     Accounting,
@@ -87,6 +162,23 @@ pub(crate) enum Messages {
     AccountingSummary,
     CategoriesSummary,
     Sum,
+    OtherYear,
+    Search,
+    SearchShortcut,
+    Split,
+    Splits,
+    NotSplitYet,
+    RevenueByCompany,
+    Share,
+    AllAmounts,
+    Private,
+    ExcludeFromSummary,
+    ExcludedFromSummary,
+    NoItemsForThisPeriod,
+    MergeCategoriesOrCompanies,
+    MergeInto,
+    MergeHint,
+    ItemsWillBeMerged,
 
     // Accounting Items
     InvoiceType,
@@ -112,6 +204,8 @@ pub(crate) enum Messages {
     EditItem,
     Edit,
     Delete,
+    Note,
+    RecentFolders,
 
     // Navigation
     Home,
@@ -137,6 +231,52 @@ pub(crate) enum Messages {
     ReallySave,
     ReallyChangeDataFolder,
     Export,
+    ExportSelected,
+    ClearSelection,
+    AllTime,
+    CopyToNextYear,
+    Copy,
+    ItemsWillBeCopiedToNextYear,
+    AttachedFilesAreReferenced,
+    ShowOnlyProblemItems,
+    ReallyDeleteItem,
+    PagesWillBeExported,
+    DataFolderContainsDatabase,
+    Overwrite,
+    Add,
+    CopyPath,
+    CopyAsText,
+    ExportAllMonths,
+    ClearAll,
+    OpenContainingFolder,
+    Combined,
+    ByType,
+    ValidateItems,
+    Close,
+    RecordInAccounting,
+    Record,
+    PerItem,
+    SummarizedByVat,
+    SelectedItems,
+    MinNet,
+    MaxNet,
+    Subtotal,
+    ExportCSV,
+    SubtotalsPer,
+    FindDuplicates,
+    Duplicate,
+    ThisMonth,
+    ThisQuarter,
+    ThisYear,
+    DropFileHint,
+    Load,
+    CopyLog,
+    JumpToPreviousPeriodWithItems,
+    KeepDateCompanyCategory,
+    KeepDateCompanyCategoryHint,
+    Merge,
+    OpenLastExport,
+    NoExportYet,
 
     // Months
     January,
@@ -177,9 +317,42 @@ pub(crate) enum Messages {
     InvoiceTemplateCreated,
     InvoiceTemplateFilled,
     ItemsFetched,
+    ItemsCopied,
+    PathCopied,
+    InvoiceCopied,
+    MonthExported,
+    MonthlyPDFsCreated,
+    EmptyMonthsSkipped,
+    AddressFilled,
+    AllItemsValid,
+    ItemsRecorded,
+    ItemsWillBeRecorded,
+    CSVCreated,
+    NoDuplicatesFound,
+    FilesMoved,
+    AllDataDeleted,
+    ItemsCreated,
+    DraftSaved,
+    LogCopied,
+    NoEarlierItems,
+    ItemsMerged,
 
     // Warnings
     DateNotInSelectedDateRange,
+    MissingItemFiles,
+    ManyItemsFetched,
+    VatIdDoesNotMatchCountry,
+    DateInFuture,
+    DataFolderMissing,
+    UnknownLanguage,
+    ItemsWithProblems,
+    FileDoesNotExist,
+    DuplicateItems,
+    NotAPDFWillBeSkipped,
+    AttachmentsSkipped,
+    QRCodeMissingIBAN,
+    ReallyExitWithUnsavedInvoice,
+    SettingOutOfRange,
 
     // Errors
     PDFFilesCopyFailed,
@@ -200,6 +373,34 @@ pub(crate) enum Messages {
     CouldNotCreateInvoiceTemplate,
     CouldNotOpenFile,
     TooManyItemsForPDFExport,
+    CouldNotCheckFileIntegrity,
+    CouldNotFetchCategoryDefaults,
+    CouldNotCopyItems,
+    CouldNotRecordExport,
+    CouldNotFetchExportLog,
+    MonthlyPDFsFailed,
+    CouldNotSaveConfig,
+    CouldNotFetchCompanyAddress,
+    CouldNotOpenFolder,
+    CouldNotRecordInvoice,
+    DatabaseAccessFailed,
+    FileAccessFailed,
+    CSVNotCreated,
+    CouldNotFindDuplicates,
+    CouldNotMoveFiles,
+    IbanNotValid,
+    InvalidInvoice,
+    CouldNotDeleteAllData,
+    SplitsDoNotAddUpToNet,
+    InvalidFileType,
+    CouldNotSaveDraft,
+    CouldNotFetchDrafts,
+    AutoBackupFailed,
+    NotAPosition,
+    CouldNotMerge,
+    YearOutOfRange,
+    TotalCantBeRounded,
+    CouldNotDeleteDraft,
 }
 
 impl From<Messages> for &str {
@@ -240,7 +441,11 @@ impl std::fmt::Display for Messages {
 
 impl Messages {
     pub(crate) fn months() -> &'static [&'static str] {
-        match get_language() {
+        Self::months_for(get_language())
+    }
+
+    pub(crate) fn months_for(language: Language) -> &'static [&'static str] {
+        match language {
             Language::EN => &[
                 "January",
                 "February",
@@ -269,6 +474,34 @@ impl Messages {
                 "November",
                 "Dezember",
             ],
+            Language::ES => &[
+                "Enero",
+                "Febrero",
+                "Marzo",
+                "Abril",
+                "Mayo",
+                "Junio",
+                "Julio",
+                "Agosto",
+                "Septiembre",
+                "Octubre",
+                "Noviembre",
+                "Diciembre",
+            ],
+            Language::IT => &[
+                "Gennaio",
+                "Febbraio",
+                "Marzo",
+                "Aprile",
+                "Maggio",
+                "Giugno",
+                "Luglio",
+                "Agosto",
+                "Settembre",
+                "Ottobre",
+                "Novembre",
+                "Dicembre",
+            ],
         }
     }
 
@@ -276,11 +509,17 @@ impl Messages {
         match get_language() {
             Language::EN => &["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
             Language::DE => &["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+            Language::ES => &["Lu", "Ma", "Mi", "Ju", "Vi", "Sá", "Do"],
+            Language::IT => &["Lu", "Ma", "Me", "Gi", "Ve", "Sa", "Do"],
         }
     }
 
     pub(crate) fn msg(&self) -> &'static str {
-        match get_language() {
+        self.msg_for(get_language())
+    }
+
+    pub(crate) fn msg_for(&self, language: Language) -> &'static str {
+        match language {
             Language::EN => {
                 match self {
                     // General
@@ -297,6 +536,63 @@ impl Messages {
                     Messages::SuccessFullyChangedProgramToOpen => {
                         "Program to open files changed successfully!"
                     }
+                    Messages::PageSize => "Page size",
+                    Messages::CategoryDefaults => "Category defaults",
+                    Messages::PreFillFromCategory => "Pre-fill VAT and invoice type from category",
+                    Messages::OpenPdfAfterExport => "Open PDF after export",
+                    Messages::FiscalYearStart => "Fiscal year starts in",
+                    Messages::ExportLog => "Export log",
+                    Messages::NoExportsYet => "No exports yet",
+                    Messages::ExportedPeriodOrNumber => "Period / Nr.",
+                    Messages::Type => "Type",
+                    Messages::NumberFormat => "Number format",
+                    Messages::FollowLanguage => "Like language",
+                    Messages::QuantityDecimals => "Decimal Places of Quantities",
+                    Messages::YearHistorySpan => "Years in Year Selection",
+                    Messages::Tables => "Tables",
+                    Messages::ColorRowsByInvoiceType => "Highlight outgoing invoices",
+                    Messages::TopMarginOffset => "Space for Letterhead",
+                    Messages::Window => "Window",
+                    Messages::AlwaysOnTop => "Always on Top",
+                    Messages::PDFAuthor => "PDF Author",
+                    Messages::FilesPerYear => "Files in a folder per year",
+                    Messages::Files => "Files",
+                    Messages::PaymentQRCode => "Payment QR code",
+                    Messages::EPCQRCodeOnInvoices => {
+                        "EPC QR code on invoices, from the IBAN in the bank data"
+                    }
+                    Messages::DateFormat => "Date Format",
+                    Messages::ResetAllData => "Reset all data",
+                    Messages::ReallyResetAllData => {
+                        "All items, templates and suggestions will be deleted - copied item files stay in the data folder. To confirm, type"
+                    }
+                    Messages::VatColumn => "VAT Column",
+                    Messages::VatAmountPerLineOnInvoices => {
+                        "Show the VAT amount of each line on invoices"
+                    }
+                    Messages::ConfirmExit => "Confirm Exit",
+                    Messages::ConfirmExitWithUnsavedInvoice => {
+                        "Ask before closing with an unsaved invoice"
+                    }
+                    Messages::MaxSuggestions => "Max. Suggestions",
+                    Messages::MinSuggestionInput => "Suggest After",
+                    Messages::Characters => "characters",
+                    Messages::ShowCentsInTotals => "Show cents in totals and summaries",
+                    Messages::DeleteDraftAfterExport => "Delete a loaded draft after exporting it",
+                    Messages::DeveloperMode => "Developer Mode",
+                    Messages::ShowActivityLog => "Show the activity log, e.g. for bug reports",
+                    Messages::ActivityLog => "Activity Log",
+                    Messages::NoLogRecordsYet => "Nothing logged yet",
+                    Messages::MinWindowSize => "Minimum window size",
+                    Messages::AccountingExportFileName => "Accounting export file name",
+                    Messages::InvoiceExportFileName => "Invoice export file name",
+                    Messages::Placeholders => "Placeholders",
+                    Messages::Backups => "Backups",
+                    Messages::AutoBackup => "Back up the database automatically",
+                    Messages::AutoBackupHint => {
+                        "Before changing the data folder, resetting all data or moving item files, the database is copied to the backups folder"
+                    }
+                    Messages::Keep => "Keep",
                     // Invoice
                     Messages::Invoice => "Invoice",
                     Messages::InvoiceShort => "inv",
@@ -325,6 +621,26 @@ impl Messages {
                     Messages::PreText => "Pre Text",
                     Messages::PostText => "Post Text",
                     Messages::BankData => "Bank Data",
+                    Messages::RoundTotal => "Round total",
+                    Messages::Rounding => "Rounding",
+                    Messages::TotalHours => "Total hours",
+                    Messages::InclVat => "incl.",
+                    Messages::PricesIncludeVat => "Prices include VAT",
+                    Messages::Quote => "Quote",
+                    Messages::QuoteShort => "quote",
+                    Messages::DocumentKind => "Document",
+                    Messages::Adjustments => "Discounts / Surcharges",
+                    Messages::AdjustmentAmount => "e.g. -50 or 100",
+                    Messages::FillAddress => "Fill Address",
+                    Messages::NoInvoiceItems => "The invoice needs at least one item.",
+                    Messages::Attachments => "Attachments",
+                    Messages::AccountHolder => "Account Holder",
+                    Messages::Iban => "IBAN",
+                    Messages::Bic => "BIC",
+                    Messages::CopySuffix => "(copy)",
+                    Messages::SaveDraft => "Save Draft",
+                    Messages::Drafts => "Drafts",
+                    Messages::DraftLoaded => "Draft loaded",
 
                     // Accounting
                     Messages::Accounting => "Accounting",
@@ -336,6 +652,25 @@ impl Messages {
                     Messages::AccountingSummary => "Accounting Summary",
                     Messages::CategoriesSummary => "Categories Summary",
                     Messages::Sum => "Sum",
+                    Messages::OtherYear => "Other year",
+                    Messages::Search => "Search",
+                    Messages::SearchShortcut => "Search (Ctrl+F)",
+                    Messages::Split => "Split",
+                    Messages::Splits => "Splits",
+                    Messages::NotSplitYet => "Not split yet",
+                    Messages::RevenueByCompany => "Revenue by company",
+                    Messages::Share => "Share",
+                    Messages::AllAmounts => "All amounts",
+                    Messages::Private => "Private",
+                    Messages::ExcludeFromSummary => "Exclude from summary and totals",
+                    Messages::ExcludedFromSummary => "Private - excluded from summary and totals",
+                    Messages::NoItemsForThisPeriod => "No items for this period",
+                    Messages::MergeCategoriesOrCompanies => "Merge categories / companies",
+                    Messages::MergeInto => "into",
+                    Messages::MergeHint => {
+                        "Rewrites all items, e.g. to fix typos. This can't be undone."
+                    }
+                    Messages::ItemsWillBeMerged => "items will be changed. This can't be undone.",
 
                     // Accounting Items
                     Messages::InvoiceType => "Inv. Type",
@@ -361,6 +696,8 @@ impl Messages {
                     Messages::EditItem => "Edit Item",
                     Messages::Edit => "Edit",
                     Messages::Delete => "Delete",
+                    Messages::Note => "Note",
+                    Messages::RecentFolders => "Recent Folders",
 
                     // Navigation
                     Messages::Home => "Home",
@@ -388,6 +725,68 @@ impl Messages {
                         "Do you really want to save? If there are files at the new location, they might be overridden."
                     }
                     Messages::Export => "Export",
+                    Messages::ExportSelected => "Export selected",
+                    Messages::ClearSelection => "Clear selection",
+                    Messages::AllTime => "All time",
+                    Messages::CopyToNextYear => "Copy to next year",
+                    Messages::Copy => "Copy",
+                    Messages::ItemsWillBeCopiedToNextYear => {
+                        "item(s) will be copied to the following year with new dates."
+                    }
+                    Messages::AttachedFilesAreReferenced => {
+                        "Attached files are referenced, not copied."
+                    }
+                    Messages::ShowOnlyProblemItems => {
+                        "Only show items with missing files or empty fields"
+                    }
+                    Messages::ReallyDeleteItem => "Do you really want to delete this item?",
+                    Messages::PagesWillBeExported => {
+                        "pages will be exported. Do you want to continue?"
+                    }
+                    Messages::DataFolderContainsDatabase => {
+                        "The selected folder already contains a helferlein database. Moving your data there will overwrite it and all of its data will be lost!"
+                    }
+                    Messages::Overwrite => "Overwrite",
+                    Messages::Add => "Add",
+                    Messages::CopyPath => "Copy path",
+                    Messages::CopyAsText => "Copy as Text",
+                    Messages::ExportAllMonths => "Export All Months",
+                    Messages::ClearAll => "Clear All",
+                    Messages::OpenContainingFolder => "Open containing folder",
+                    Messages::Combined => "Combined",
+                    Messages::ByType => "By Type",
+                    Messages::ValidateItems => "Validate Items",
+                    Messages::Close => "Close",
+                    Messages::RecordInAccounting => "Record in Accounting",
+                    Messages::Record => "Record",
+                    Messages::PerItem => "Per Item",
+                    Messages::SummarizedByVat => "Summarized by VAT",
+                    Messages::SelectedItems => "Selected",
+                    Messages::MinNet => "Min. Net",
+                    Messages::MaxNet => "Max. Net",
+                    Messages::Subtotal => "Subtotal",
+                    Messages::ExportCSV => "Export CSV",
+                    Messages::SubtotalsPer => "Subtotals per",
+                    Messages::FindDuplicates => "Find Duplicates",
+                    Messages::Duplicate => "Duplicate",
+                    Messages::ThisMonth => "This month",
+                    Messages::ThisQuarter => "This quarter",
+                    Messages::ThisYear => "This year",
+                    Messages::DropFileHint => "or drop a file here",
+                    Messages::Load => "Load",
+                    Messages::CopyLog => "Copy Log",
+                    Messages::JumpToPreviousPeriodWithItems => {
+                        "Go to the previous period with items"
+                    }
+                    Messages::KeepDateCompanyCategory => "Keep date, company and category",
+                    Messages::KeepDateCompanyCategoryHint => {
+                        "For entering several similar items in a row"
+                    }
+                    Messages::Merge => "Merge",
+                    Messages::OpenLastExport => "Open last export",
+                    Messages::NoExportYet => {
+                        "Nothing was exported yet, or the file doesn't exist anymore."
+                    }
 
                     //Months
                     Messages::January => "January",
@@ -430,11 +829,62 @@ impl Messages {
                     Messages::InvoiceTemplateCreated => "Invoice Template successfully created.",
                     Messages::InvoiceTemplateFilled => "Invoice Template filled.",
                     Messages::ItemsFetched => "Items successfully fetched.",
+                    Messages::ItemsCopied => "item(s) copied to the following year.",
+                    Messages::PathCopied => "Path copied to clipboard",
+                    Messages::InvoiceCopied => "Invoice copied to clipboard",
+                    Messages::MonthExported => "was created.",
+                    Messages::MonthlyPDFsCreated => "monthly PDF(s) created,",
+                    Messages::EmptyMonthsSkipped => "empty month(s) skipped.",
+                    Messages::AddressFilled => {
+                        "Address filled from the last invoice to this company."
+                    }
+                    Messages::AllItemsValid => "All items in the selected period are valid.",
+                    Messages::ItemsRecorded => "items were recorded in accounting.",
+                    Messages::ItemsWillBeRecorded => "items will be recorded as revenue.",
+                    Messages::CSVCreated => "CSV successfully created.",
+                    Messages::NoDuplicatesFound => "No duplicate items found.",
+                    Messages::FilesMoved => "files moved",
+                    Messages::AllDataDeleted => "All data was deleted",
+                    Messages::ItemsCreated => "items were created.",
+                    Messages::DraftSaved => "Draft saved",
+                    Messages::LogCopied => "Log copied",
+                    Messages::NoEarlierItems => "There are no earlier items",
+                    Messages::ItemsMerged => "items updated.",
 
                     // Warnings
                     Messages::DateNotInSelectedDateRange => {
                         "The selected date is not within the selected date range."
                     }
+                    Messages::MissingItemFiles => {
+                        "accounting item file(s) are missing. Please restore them from a backup."
+                    }
+                    Messages::ManyItemsFetched => {
+                        "items fetched - the exported PDF will have many pages."
+                    }
+                    Messages::VatIdDoesNotMatchCountry => {
+                        "The VAT ID doesn't match the format of the given country."
+                    }
+                    Messages::DateInFuture => "Date is in the future",
+                    Messages::DataFolderMissing => {
+                        "The configured data folder does not exist, please select a new one"
+                    }
+                    Messages::UnknownLanguage => {
+                        "Unknown language in the config, falling back to English"
+                    }
+                    Messages::ItemsWithProblems => "Items with problems:",
+                    Messages::FileDoesNotExist => "File does not exist",
+                    Messages::DuplicateItems => "Items with the same date, company and net amount:",
+                    Messages::NotAPDFWillBeSkipped => "Not a PDF, will be skipped on export",
+                    Messages::AttachmentsSkipped => {
+                        "Attachments skipped, since they aren't readable PDFs:"
+                    }
+                    Messages::QRCodeMissingIBAN => {
+                        "No payment QR code added - the bank data doesn't contain a valid IBAN"
+                    }
+                    Messages::ReallyExitWithUnsavedInvoice => {
+                        "The invoice hasn't been exported or saved as a template. Close anyway?"
+                    }
+                    Messages::SettingOutOfRange => "Setting in the config out of range, adjusted",
 
                     // Errors
                     Messages::DateNotValid => "Not a valid date.",
@@ -446,6 +896,42 @@ impl Messages {
                     Messages::FilesFolderNotCreated => {
                         "Couldn't create files folder in the data folder"
                     }
+                    Messages::CouldNotCheckFileIntegrity => {
+                        "Could not check the item files in the data folder."
+                    }
+                    Messages::CouldNotFetchCategoryDefaults => "Could not fetch category defaults.",
+                    Messages::CouldNotCopyItems => "Could not copy items.",
+                    Messages::CouldNotRecordExport => {
+                        "Could not record the export in the export log"
+                    }
+                    Messages::CouldNotFetchExportLog => "Could not fetch the export log",
+                    Messages::MonthlyPDFsFailed => "monthly PDF(s) could not be created.",
+                    Messages::CouldNotSaveConfig => "Could not save the settings.",
+                    Messages::CouldNotFetchCompanyAddress => {
+                        "Could not fetch the address of the company."
+                    }
+                    Messages::CouldNotOpenFolder => "Could not open the folder.",
+                    Messages::CouldNotRecordInvoice => "Couldn't record the invoice in accounting",
+                    Messages::DatabaseAccessFailed => "Database access failed",
+                    Messages::FileAccessFailed => "File access failed",
+                    Messages::CSVNotCreated => "Couldn't create CSV",
+                    Messages::CouldNotFindDuplicates => "Could not search for duplicate items",
+                    Messages::CouldNotMoveFiles => "Could not move the files",
+                    Messages::IbanNotValid => "The IBAN is not valid",
+                    Messages::InvalidInvoice => "The invoice contains invalid values",
+                    Messages::CouldNotDeleteAllData => "Could not delete all data",
+                    Messages::SplitsDoNotAddUpToNet => "The splits don't add up to the net",
+                    Messages::InvalidFileType => "File type not supported, use one of",
+                    Messages::CouldNotSaveDraft => "Could not save draft",
+                    Messages::CouldNotFetchDrafts => "Could not fetch drafts",
+                    Messages::AutoBackupFailed => {
+                        "The database could not be backed up, nothing was changed"
+                    }
+                    Messages::NotAPosition => "is not a valid position, e.g. 1 or 1.2.",
+                    Messages::CouldNotMerge => "Could not merge.",
+                    Messages::YearOutOfRange => "The year has to be between 1900 and 2200.",
+                    Messages::TotalCantBeRounded => "The total is too large to be rounded",
+                    Messages::CouldNotDeleteDraft => "Could not delete draft.",
 
                     Messages::FileCouldNotBeDeleted => "Couldn't delete file",
                     Messages::FolderCouldNotBeDeleted => "Couldn't delete folder",
@@ -480,6 +966,67 @@ impl Messages {
                     Messages::SuccessFullyChangedProgramToOpen => {
                         "Programm um Dateien zu öffnen erfolgreich geändert!"
                     }
+                    Messages::PageSize => "Seitenformat",
+                    Messages::CategoryDefaults => "Kategorie-Vorgaben",
+                    Messages::PreFillFromCategory => {
+                        "MwSt. und Rechnungsart aus Kategorie vorbelegen"
+                    }
+                    Messages::OpenPdfAfterExport => "PDF nach dem Export öffnen",
+                    Messages::FiscalYearStart => "Geschäftsjahr beginnt im",
+                    Messages::ExportLog => "Export-Protokoll",
+                    Messages::NoExportsYet => "Noch keine Exporte",
+                    Messages::ExportedPeriodOrNumber => "Zeitraum / Nr.",
+                    Messages::Type => "Art",
+                    Messages::NumberFormat => "Zahlenformat",
+                    Messages::FollowLanguage => "Wie Sprache",
+                    Messages::QuantityDecimals => "Nachkommastellen der Mengen",
+                    Messages::YearHistorySpan => "Jahre in der Jahresauswahl",
+                    Messages::Tables => "Tabellen",
+                    Messages::ColorRowsByInvoiceType => "Ausgangsrechnungen hervorheben",
+                    Messages::TopMarginOffset => "Platz für Briefkopf",
+                    Messages::Window => "Fenster",
+                    Messages::AlwaysOnTop => "Immer im Vordergrund",
+                    Messages::PDFAuthor => "PDF-Autor",
+                    Messages::FilesPerYear => "Dateien in einem Ordner pro Jahr",
+                    Messages::Files => "Dateien",
+                    Messages::PaymentQRCode => "Zahlungs-QR-Code",
+                    Messages::EPCQRCodeOnInvoices => {
+                        "EPC-QR-Code auf Rechnungen, aus der IBAN in den Bankdaten"
+                    }
+                    Messages::DateFormat => "Datumsformat",
+                    Messages::ResetAllData => "Alle Daten zurücksetzen",
+                    Messages::ReallyResetAllData => {
+                        "Alle Posten, Vorlagen und Vorschläge werden gelöscht - kopierte Belegdateien bleiben im Datenordner. Zum Bestätigen eingeben:"
+                    }
+                    Messages::VatColumn => "USt.-Spalte",
+                    Messages::VatAmountPerLineOnInvoices => {
+                        "USt.-Betrag jeder Zeile auf Rechnungen anzeigen"
+                    }
+                    Messages::ConfirmExit => "Beenden bestätigen",
+                    Messages::ConfirmExitWithUnsavedInvoice => {
+                        "Vor dem Schließen mit ungespeicherter Rechnung nachfragen"
+                    }
+                    Messages::MaxSuggestions => "Max. Vorschläge",
+                    Messages::MinSuggestionInput => "Vorschläge ab",
+                    Messages::Characters => "Zeichen",
+                    Messages::ShowCentsInTotals => "Cent in Summen und Zusammenfassungen anzeigen",
+                    Messages::DeleteDraftAfterExport => "Geladenen Entwurf nach dem Export löschen",
+                    Messages::DeveloperMode => "Entwicklermodus",
+                    Messages::ShowActivityLog => {
+                        "Aktivitätsprotokoll anzeigen, z.B. für Fehlerberichte"
+                    }
+                    Messages::ActivityLog => "Aktivitätsprotokoll",
+                    Messages::NoLogRecordsYet => "Noch keine Einträge",
+                    Messages::MinWindowSize => "Minimale Fenstergröße",
+                    Messages::AccountingExportFileName => "Dateiname des Buchhaltungsexports",
+                    Messages::InvoiceExportFileName => "Dateiname des Rechnungsexports",
+                    Messages::Placeholders => "Platzhalter",
+                    Messages::Backups => "Backups",
+                    Messages::AutoBackup => "Datenbank automatisch sichern",
+                    Messages::AutoBackupHint => {
+                        "Vor dem Ändern des Datenordners, dem Zurücksetzen aller Daten oder dem Verschieben von Eintragsdateien wird die Datenbank in den Backup-Ordner kopiert"
+                    }
+                    Messages::Keep => "Behalte",
 
                     // Rechnung
                     Messages::Invoice => "Rechnung",
@@ -509,6 +1056,26 @@ impl Messages {
                     Messages::PreText => "Textzeilen Bevor",
                     Messages::PostText => "Textzeilen Danach",
                     Messages::BankData => "Bankdaten",
+                    Messages::RoundTotal => "Summe runden",
+                    Messages::Rounding => "Rundung",
+                    Messages::TotalHours => "Gesamtstunden",
+                    Messages::InclVat => "inkl.",
+                    Messages::PricesIncludeVat => "Preise inkl. USt",
+                    Messages::Quote => "Angebot",
+                    Messages::QuoteShort => "an",
+                    Messages::DocumentKind => "Dokument",
+                    Messages::Adjustments => "Rabatte / Zuschläge",
+                    Messages::AdjustmentAmount => "z.B. -50 oder 100",
+                    Messages::FillAddress => "Adresse ausfüllen",
+                    Messages::NoInvoiceItems => "Die Rechnung braucht mindestens eine Position.",
+                    Messages::Attachments => "Anhänge",
+                    Messages::AccountHolder => "Kontoinhaber",
+                    Messages::Iban => "IBAN",
+                    Messages::Bic => "BIC",
+                    Messages::CopySuffix => "(Kopie)",
+                    Messages::SaveDraft => "Entwurf speichern",
+                    Messages::Drafts => "Entwürfe",
+                    Messages::DraftLoaded => "Entwurf geladen",
 
                     // Accounting
                     Messages::Accounting => "Buchhaltung",
@@ -520,6 +1087,29 @@ impl Messages {
                     Messages::AccountingSummary => "Buchhaltungsübersicht",
                     Messages::CategoriesSummary => "Kategorienübersicht",
                     Messages::Sum => "Summe",
+                    Messages::OtherYear => "Anderes Jahr",
+                    Messages::Search => "Suchen",
+                    Messages::SearchShortcut => "Suchen (Strg+F)",
+                    Messages::Split => "Aufteilen",
+                    Messages::Splits => "Aufteilung",
+                    Messages::NotSplitYet => "Noch nicht aufgeteilt",
+                    Messages::RevenueByCompany => "Umsatz nach Firma",
+                    Messages::Share => "Anteil",
+                    Messages::AllAmounts => "Alle Beträge",
+                    Messages::Private => "Privat",
+                    Messages::ExcludeFromSummary => "Nicht in Zusammenfassung und Summen aufnehmen",
+                    Messages::ExcludedFromSummary => {
+                        "Privat - nicht in Zusammenfassung und Summen enthalten"
+                    }
+                    Messages::NoItemsForThisPeriod => "Keine Einträge in diesem Zeitraum",
+                    Messages::MergeCategoriesOrCompanies => "Kategorien / Firmen zusammenführen",
+                    Messages::MergeInto => "in",
+                    Messages::MergeHint => {
+                        "Ändert alle Einträge, z.B. um Tippfehler zu beheben. Das kann nicht rückgängig gemacht werden."
+                    }
+                    Messages::ItemsWillBeMerged => {
+                        "Einträge werden geändert. Das kann nicht rückgängig gemacht werden."
+                    }
 
                     // Accounting Items
                     Messages::InvoiceType => "Typ",
@@ -545,6 +1135,8 @@ impl Messages {
                     Messages::EditItem => "Eintrag ändern",
                     Messages::Edit => "Ändern",
                     Messages::Delete => "Löschen",
+                    Messages::Note => "Notiz",
+                    Messages::RecentFolders => "Zuletzt verwendete Ordner",
 
                     // Navigation
                     Messages::Home => "Übersicht",
@@ -572,6 +1164,68 @@ impl Messages {
                         "Willst du wirklich speichern? Wenn es Dateien am ausgewählten Ort gibt, werden diese überschrieben."
                     }
                     Messages::Export => "Exportieren",
+                    Messages::ExportSelected => "Auswahl exportieren",
+                    Messages::ClearSelection => "Auswahl aufheben",
+                    Messages::AllTime => "Gesamter Zeitraum",
+                    Messages::CopyToNextYear => "Ins nächste Jahr kopieren",
+                    Messages::Copy => "Kopieren",
+                    Messages::ItemsWillBeCopiedToNextYear => {
+                        "Eintrag/Einträge werden mit neuem Datum ins Folgejahr kopiert."
+                    }
+                    Messages::AttachedFilesAreReferenced => {
+                        "Angehängte Dateien werden referenziert, nicht kopiert."
+                    }
+                    Messages::ShowOnlyProblemItems => {
+                        "Nur Einträge mit fehlenden Dateien oder leeren Feldern anzeigen"
+                    }
+                    Messages::ReallyDeleteItem => "Willst du diesen Eintrag wirklich löschen?",
+                    Messages::PagesWillBeExported => {
+                        "Seiten werden exportiert. Willst du fortfahren?"
+                    }
+                    Messages::DataFolderContainsDatabase => {
+                        "Der ausgewählte Ordner enthält bereits eine helferlein-Datenbank. Wenn deine Daten dorthin verschoben werden, wird sie überschrieben und alle ihre Daten gehen verloren!"
+                    }
+                    Messages::Overwrite => "Überschreiben",
+                    Messages::Add => "Hinzufügen",
+                    Messages::CopyPath => "Pfad kopieren",
+                    Messages::CopyAsText => "Als Text kopieren",
+                    Messages::ExportAllMonths => "Alle Monate exportieren",
+                    Messages::ClearAll => "Alle schließen",
+                    Messages::OpenContainingFolder => "Übergeordneten Ordner öffnen",
+                    Messages::Combined => "Gesamt",
+                    Messages::ByType => "Nach Typ",
+                    Messages::ValidateItems => "Einträge prüfen",
+                    Messages::Close => "Schließen",
+                    Messages::RecordInAccounting => "In Buchhaltung erfassen",
+                    Messages::Record => "Erfassen",
+                    Messages::PerItem => "Pro Position",
+                    Messages::SummarizedByVat => "Nach USt. zusammengefasst",
+                    Messages::SelectedItems => "Ausgewählt",
+                    Messages::MinNet => "Min. Netto",
+                    Messages::MaxNet => "Max. Netto",
+                    Messages::Subtotal => "Zwischensumme",
+                    Messages::ExportCSV => "CSV exportieren",
+                    Messages::SubtotalsPer => "Zwischensummen pro",
+                    Messages::FindDuplicates => "Duplikate suchen",
+                    Messages::Duplicate => "Duplizieren",
+                    Messages::ThisMonth => "Dieser Monat",
+                    Messages::ThisQuarter => "Dieses Quartal",
+                    Messages::ThisYear => "Dieses Jahr",
+                    Messages::DropFileHint => "oder Datei hierher ziehen",
+                    Messages::Load => "Laden",
+                    Messages::CopyLog => "Protokoll kopieren",
+                    Messages::JumpToPreviousPeriodWithItems => {
+                        "Zum vorherigen Zeitraum mit Einträgen"
+                    }
+                    Messages::KeepDateCompanyCategory => "Datum, Firma und Kategorie behalten",
+                    Messages::KeepDateCompanyCategoryHint => {
+                        "Für die Eingabe mehrerer ähnlicher Einträge hintereinander"
+                    }
+                    Messages::Merge => "Zusammenführen",
+                    Messages::OpenLastExport => "Letzten Export öffnen",
+                    Messages::NoExportYet => {
+                        "Noch nichts exportiert, oder die Datei existiert nicht mehr."
+                    }
 
                     //Months
                     Messages::January => "Jänner",
@@ -616,11 +1270,66 @@ impl Messages {
                     Messages::InvoiceTemplateCreated => "Rechnungsvorlage erfolgreich erstellt.",
                     Messages::InvoiceTemplateFilled => "Rechnungsvorlage eingefüllt",
                     Messages::ItemsFetched => "Einträge gefunden.",
+                    Messages::ItemsCopied => "Eintrag/Einträge ins Folgejahr kopiert.",
+                    Messages::PathCopied => "Pfad in die Zwischenablage kopiert",
+                    Messages::InvoiceCopied => "Rechnung in die Zwischenablage kopiert",
+                    Messages::MonthExported => "wurde erstellt.",
+                    Messages::MonthlyPDFsCreated => "Monats-PDF(s) erstellt,",
+                    Messages::EmptyMonthsSkipped => "leere(r) Monat(e) übersprungen.",
+                    Messages::AddressFilled => {
+                        "Adresse aus der letzten Rechnung an diese Firma übernommen."
+                    }
+                    Messages::AllItemsValid => "Alle Einträge im gewählten Zeitraum sind gültig.",
+                    Messages::ItemsRecorded => "Einträge wurden in der Buchhaltung erfasst.",
+                    Messages::ItemsWillBeRecorded => "Einträge werden als Einnahmen erfasst.",
+                    Messages::CSVCreated => "CSV erfolgreich erstellt.",
+                    Messages::NoDuplicatesFound => "Keine doppelten Einträge gefunden.",
+                    Messages::FilesMoved => "Dateien verschoben",
+                    Messages::AllDataDeleted => "Alle Daten wurden gelöscht",
+                    Messages::ItemsCreated => "Einträge wurden erstellt.",
+                    Messages::DraftSaved => "Entwurf gespeichert",
+                    Messages::LogCopied => "Protokoll kopiert",
+                    Messages::NoEarlierItems => "Es gibt keine früheren Einträge",
+                    Messages::ItemsMerged => "Einträge geändert.",
 
                     // Warnings
                     Messages::DateNotInSelectedDateRange => {
                         "Das augewählte Datum ist nicht innerhalb des ausgewählten Bereichs."
                     }
+                    Messages::MissingItemFiles => {
+                        "Eintragsdatei(en) fehlen. Bitte stelle sie aus einem Backup wieder her."
+                    }
+                    Messages::ManyItemsFetched => {
+                        "Einträge geladen - das exportierte PDF wird viele Seiten haben."
+                    }
+                    Messages::VatIdDoesNotMatchCountry => {
+                        "Die UID-Nummer entspricht nicht dem Format des angegebenen Landes."
+                    }
+                    Messages::DateInFuture => "Datum liegt in der Zukunft",
+                    Messages::DataFolderMissing => {
+                        "Der konfigurierte Datenordner existiert nicht, bitte wähle einen neuen aus"
+                    }
+                    Messages::UnknownLanguage => {
+                        "Unbekannte Sprache in der Konfiguration, Englisch wird verwendet"
+                    }
+                    Messages::ItemsWithProblems => "Einträge mit Problemen:",
+                    Messages::FileDoesNotExist => "Datei existiert nicht",
+                    Messages::DuplicateItems => {
+                        "Einträge mit gleichem Datum, gleicher Firma und gleichem Nettobetrag:"
+                    }
+                    Messages::NotAPDFWillBeSkipped => "Kein PDF, wird beim Export übersprungen",
+                    Messages::AttachmentsSkipped => {
+                        "Anhänge übersprungen, da sie keine lesbaren PDFs sind:"
+                    }
+                    Messages::QRCodeMissingIBAN => {
+                        "Kein Zahlungs-QR-Code hinzugefügt - die Bankdaten enthalten keine gültige IBAN"
+                    }
+                    Messages::ReallyExitWithUnsavedInvoice => {
+                        "Die Rechnung wurde nicht exportiert oder als Vorlage gespeichert. Trotzdem schließen?"
+                    }
+                    Messages::SettingOutOfRange => {
+                        "Einstellung in der Konfiguration außerhalb des gültigen Bereichs, angepasst"
+                    }
 
                     // Errors
                     Messages::DateNotValid => "Kein gültiges Datum.",
@@ -632,6 +1341,56 @@ impl Messages {
                     Messages::FilesFolderNotCreated => {
                         "Dateien im Datenverzeichnis konnten nicht angelegt werden."
                     }
+                    Messages::CouldNotCheckFileIntegrity => {
+                        "Die Eintragsdateien im Datenverzeichnis konnten nicht überprüft werden."
+                    }
+                    Messages::CouldNotFetchCategoryDefaults => {
+                        "Kategorie-Vorgaben konnten nicht geladen werden."
+                    }
+                    Messages::CouldNotCopyItems => "Einträge konnten nicht kopiert werden.",
+                    Messages::CouldNotRecordExport => {
+                        "Export konnte nicht im Export-Protokoll vermerkt werden"
+                    }
+                    Messages::CouldNotFetchExportLog => {
+                        "Export-Protokoll konnte nicht geladen werden"
+                    }
+                    Messages::MonthlyPDFsFailed => "Monats-PDF(s) konnten nicht erstellt werden.",
+                    Messages::CouldNotSaveConfig => {
+                        "Die Einstellungen konnten nicht gespeichert werden."
+                    }
+                    Messages::CouldNotFetchCompanyAddress => {
+                        "Die Adresse der Firma konnte nicht geladen werden."
+                    }
+                    Messages::CouldNotOpenFolder => "Der Ordner konnte nicht geöffnet werden.",
+                    Messages::CouldNotRecordInvoice => {
+                        "Rechnung konnte nicht in der Buchhaltung erfasst werden"
+                    }
+                    Messages::DatabaseAccessFailed => "Datenbankzugriff fehlgeschlagen",
+                    Messages::FileAccessFailed => "Dateizugriff fehlgeschlagen",
+                    Messages::CSVNotCreated => "CSV konnte nicht erstellt werden",
+                    Messages::CouldNotFindDuplicates => {
+                        "Doppelte Einträge konnten nicht gesucht werden"
+                    }
+                    Messages::CouldNotMoveFiles => "Dateien konnten nicht verschoben werden",
+                    Messages::IbanNotValid => "Die IBAN ist nicht gültig",
+                    Messages::InvalidInvoice => "Die Rechnung enthält ungültige Werte",
+                    Messages::CouldNotDeleteAllData => "Konnte nicht alle Daten löschen",
+                    Messages::SplitsDoNotAddUpToNet => {
+                        "Die Aufteilung ergibt nicht den Nettobetrag"
+                    }
+                    Messages::InvalidFileType => "Dateityp nicht unterstützt, erlaubt sind",
+                    Messages::CouldNotSaveDraft => "Entwurf konnte nicht gespeichert werden",
+                    Messages::CouldNotFetchDrafts => "Entwürfe konnten nicht geladen werden",
+                    Messages::AutoBackupFailed => {
+                        "Die Datenbank konnte nicht gesichert werden, es wurde nichts geändert"
+                    }
+                    Messages::NotAPosition => "ist keine gültige Position, z.B. 1 oder 1.2.",
+                    Messages::CouldNotMerge => "Konnte nicht zusammenführen.",
+                    Messages::YearOutOfRange => "Das Jahr muss zwischen 1900 und 2200 liegen.",
+                    Messages::TotalCantBeRounded => {
+                        "Die Gesamtsumme ist zu groß, um gerundet zu werden"
+                    }
+                    Messages::CouldNotDeleteDraft => "Entwurf konnte nicht gelöscht werden.",
 
                     Messages::FileCouldNotBeDeleted => "Datei konnte nicht gelöscht werden.",
                     Messages::FolderCouldNotBeDeleted => "Ordner konnte nicht gelöscht werden.",
@@ -654,6 +1413,1277 @@ impl Messages {
                     Messages::TooManyItemsForPDFExport => "Zu viele Posten für PDF Export.",
                 }
             }
+            Language::ES => {
+                match self {
+                    // General
+                    Messages::Title => "Helferlein",
+
+                    // Settings
+                    Messages::DataFolder => "Carpeta de datos",
+                    Messages::Language => "Idioma",
+                    Messages::FileOpenProgram => "Programa para abrir archivos",
+                    Messages::SuccessFullyChangedDataFolder => {
+                        "¡Carpeta de datos cambiada correctamente!"
+                    }
+                    Messages::ErrorChangingDataFolder => {
+                        "Se produjo un error al cambiar la carpeta de datos."
+                    }
+                    Messages::SuccessFullyChangedProgramToOpen => {
+                        "¡Programa para abrir archivos cambiado correctamente!"
+                    }
+                    Messages::PageSize => "Tamaño de página",
+                    Messages::CategoryDefaults => "Valores por categoría",
+                    Messages::PreFillFromCategory => {
+                        "Rellenar IVA y tipo de factura según la categoría"
+                    }
+                    Messages::OpenPdfAfterExport => "Abrir PDF después de exportar",
+                    Messages::FiscalYearStart => "El año fiscal empieza en",
+                    Messages::ExportLog => "Registro de exportaciones",
+                    Messages::NoExportsYet => "Todavía no hay exportaciones",
+                    Messages::ExportedPeriodOrNumber => "Periodo / N.º",
+                    Messages::Type => "Tipo",
+                    Messages::NumberFormat => "Formato de números",
+                    Messages::FollowLanguage => "Según el idioma",
+                    Messages::QuantityDecimals => "Decimales de las cantidades",
+                    Messages::YearHistorySpan => "Años en la selección de año",
+                    Messages::Tables => "Tablas",
+                    Messages::ColorRowsByInvoiceType => "Resaltar facturas emitidas",
+                    Messages::TopMarginOffset => "Espacio para membrete",
+                    Messages::Window => "Ventana",
+                    Messages::AlwaysOnTop => "Siempre visible",
+                    Messages::PDFAuthor => "Autor del PDF",
+                    Messages::FilesPerYear => "Archivos en una carpeta por año",
+                    Messages::Files => "Archivos",
+                    Messages::PaymentQRCode => "Código QR de pago",
+                    Messages::EPCQRCodeOnInvoices => {
+                        "Código QR EPC en las facturas, con el IBAN de los datos bancarios"
+                    }
+                    Messages::DateFormat => "Formato de fecha",
+                    Messages::ResetAllData => "Restablecer todos los datos",
+                    Messages::ReallyResetAllData => {
+                        "Se eliminarán todos los elementos, plantillas y sugerencias - los archivos copiados permanecen en la carpeta de datos. Para confirmar, escriba"
+                    }
+                    Messages::VatColumn => "Columna de IVA",
+                    Messages::VatAmountPerLineOnInvoices => {
+                        "Mostrar el importe del IVA de cada línea en las facturas"
+                    }
+                    Messages::ConfirmExit => "Confirmar salida",
+                    Messages::ConfirmExitWithUnsavedInvoice => {
+                        "Preguntar antes de cerrar con una factura sin guardar"
+                    }
+                    Messages::MaxSuggestions => "Máx. sugerencias",
+                    Messages::MinSuggestionInput => "Sugerir a partir de",
+                    Messages::Characters => "caracteres",
+                    Messages::ShowCentsInTotals => "Mostrar céntimos en totales y resúmenes",
+                    Messages::DeleteDraftAfterExport => {
+                        "Eliminar el borrador cargado tras exportarlo"
+                    }
+                    Messages::DeveloperMode => "Modo desarrollador",
+                    Messages::ShowActivityLog => {
+                        "Mostrar el registro de actividad, p. ej. para informes de errores"
+                    }
+                    Messages::ActivityLog => "Registro de actividad",
+                    Messages::NoLogRecordsYet => "Aún no hay registros",
+                    Messages::MinWindowSize => "Tamaño mínimo de ventana",
+                    Messages::AccountingExportFileName => {
+                        "Nombre del archivo de exportación contable"
+                    }
+                    Messages::InvoiceExportFileName => {
+                        "Nombre del archivo de exportación de facturas"
+                    }
+                    Messages::Placeholders => "Marcadores",
+                    Messages::Backups => "Copias de seguridad",
+                    Messages::AutoBackup => {
+                        "Hacer copia de seguridad de la base de datos automáticamente"
+                    }
+                    Messages::AutoBackupHint => {
+                        "Antes de cambiar la carpeta de datos, restablecer todos los datos o mover archivos de entradas, la base de datos se copia en la carpeta de copias de seguridad"
+                    }
+                    Messages::Keep => "Conservar",
+                    // Invoice
+                    Messages::Invoice => "Factura",
+                    Messages::InvoiceShort => "fac",
+                    Messages::General => "General",
+                    Messages::ServicePeriod => "Período de servicio",
+                    Messages::CreateNewInvoice => "Crear nueva factura",
+                    Messages::From => "De",
+                    Messages::To => "Para",
+                    Messages::Items => "Posiciones",
+                    Messages::PostalAddress => "Dirección",
+                    Messages::Zip => "C.P.",
+                    Messages::City => "Ciudad",
+                    Messages::Country => "País",
+                    Messages::VatNr => "NIF-IVA",
+                    Messages::Misc => "Otros",
+                    Messages::Nr => "N.º",
+                    Messages::Pos => "Pos",
+                    Messages::Description => "Descripción",
+                    Messages::Unit => "Unidad",
+                    Messages::UnitShort => "Ud.",
+                    Messages::Qty => "Cant.",
+                    Messages::Amount => "Cantidad",
+                    Messages::PricePerUnit => "Precio por unidad",
+                    Messages::SaveAsTemplate => "Guardar como plantilla",
+                    Messages::Templates => "Plantillas",
+                    Messages::PreText => "Texto previo",
+                    Messages::PostText => "Texto final",
+                    Messages::BankData => "Datos bancarios",
+                    Messages::RoundTotal => "Redondear total",
+                    Messages::Rounding => "Redondeo",
+                    Messages::TotalHours => "Horas totales",
+                    Messages::InclVat => "incl.",
+                    Messages::PricesIncludeVat => "Precios con IVA incluido",
+                    Messages::Quote => "Presupuesto",
+                    Messages::QuoteShort => "pre",
+                    Messages::DocumentKind => "Documento",
+                    Messages::Adjustments => "Descuentos / Recargos",
+                    Messages::AdjustmentAmount => "p.ej. -50 o 100",
+                    Messages::FillAddress => "Rellenar dirección",
+                    Messages::NoInvoiceItems => "La factura necesita al menos una posición.",
+                    Messages::Attachments => "Adjuntos",
+                    Messages::AccountHolder => "Titular de la cuenta",
+                    Messages::Iban => "IBAN",
+                    Messages::Bic => "BIC",
+                    Messages::CopySuffix => "(copia)",
+                    Messages::SaveDraft => "Guardar borrador",
+                    Messages::Drafts => "Borradores",
+                    Messages::DraftLoaded => "Borrador cargado",
+
+                    // Accounting
+                    Messages::Accounting => "Contabilidad",
+                    Messages::Year => "Año",
+                    Messages::Quarter => "Trimestre",
+                    Messages::Month => "Mes",
+                    Messages::Ingoing => "Entrante",
+                    Messages::Outgoing => "Saliente",
+                    Messages::AccountingSummary => "Resumen contable",
+                    Messages::CategoriesSummary => "Resumen por categorías",
+                    Messages::Sum => "Suma",
+                    Messages::OtherYear => "Otro año",
+                    Messages::Search => "Buscar",
+                    Messages::SearchShortcut => "Buscar (Ctrl+F)",
+                    Messages::Split => "Dividir",
+                    Messages::Splits => "División",
+                    Messages::NotSplitYet => "Aún sin dividir",
+                    Messages::RevenueByCompany => "Ingresos por empresa",
+                    Messages::Share => "Proporción",
+                    Messages::AllAmounts => "Todos los importes",
+                    Messages::Private => "Privado",
+                    Messages::ExcludeFromSummary => "Excluir del resumen y los totales",
+                    Messages::ExcludedFromSummary => "Privado - excluido del resumen y los totales",
+                    Messages::NoItemsForThisPeriod => "No hay entradas para este período",
+                    Messages::MergeCategoriesOrCompanies => "Fusionar categorías / empresas",
+                    Messages::MergeInto => "en",
+                    Messages::MergeHint => {
+                        "Modifica todas las entradas, p. ej. para corregir errores. No se puede deshacer."
+                    }
+                    Messages::ItemsWillBeMerged => {
+                        "entradas serán modificadas. No se puede deshacer."
+                    }
+
+                    // Accounting Items
+                    Messages::InvoiceType => "Tipo fact.",
+                    Messages::InvoiceNumber => "#",
+                    Messages::InvoiceNumberText => "Número de factura",
+                    Messages::Date => "Fecha",
+                    Messages::Name => "Nombre",
+                    Messages::Company => "Empresa",
+                    Messages::Category => "Categoría",
+                    Messages::Net => "Neto",
+                    Messages::Vat => "IVA",
+                    Messages::Tax => "Impuesto",
+                    Messages::Gross => "Bruto",
+                    Messages::Total => "Total",
+                    Messages::File => "Archivo",
+                    Messages::ChooseFile => "Elegir archivo",
+                    Messages::SaveFile => "Guardar archivo",
+                    Messages::SelectFolder => "Seleccionar carpeta",
+                    Messages::FileTitle => "Archivo:",
+                    Messages::Link => "Enlace",
+                    Messages::AddItem => "Añadir nueva entrada",
+                    Messages::NewItem => "Nueva entrada",
+                    Messages::EditItem => "Editar entrada",
+                    Messages::Edit => "Editar",
+                    Messages::Delete => "Eliminar",
+                    Messages::Note => "Nota",
+                    Messages::RecentFolders => "Carpetas recientes",
+
+                    // Navigation
+                    Messages::Home => "Inicio",
+                    Messages::Welcome => "Bienvenido",
+                    Messages::Settings => "Ajustes",
+
+                    // Buttons / Ui
+                    Messages::Select => "Seleccionar",
+                    Messages::Fill => "Rellenar",
+                    Messages::Done => "Listo",
+                    Messages::SaveItem => "Guardar entrada",
+                    Messages::Save => "Guardar",
+                    Messages::Rename => "Renombrar",
+                    Messages::Refresh => "Actualizar",
+                    Messages::NewFolder => "Nueva carpeta",
+                    Messages::ParentFolder => "Carpeta superior",
+                    Messages::ShowHidden => "Mostrar ocultos",
+                    Messages::Change => "Cambiar",
+                    Messages::Cancel => "Cancelar",
+                    Messages::Reset => "Restablecer",
+                    Messages::Open => "Abrir",
+                    Messages::ThereAreWarnings => "⚠ ¡Hay advertencias!",
+                    Messages::ReallySave => "¿Realmente desea guardar?",
+                    Messages::ReallyChangeDataFolder => {
+                        "¿Realmente desea guardar? Si hay archivos en la nueva ubicación, podrían sobrescribirse."
+                    }
+                    Messages::Export => "Exportar",
+                    Messages::ExportSelected => "Exportar selección",
+                    Messages::ClearSelection => "Borrar selección",
+                    Messages::AllTime => "Todo",
+                    Messages::CopyToNextYear => "Copiar al año siguiente",
+                    Messages::Copy => "Copiar",
+                    Messages::ItemsWillBeCopiedToNextYear => {
+                        "entrada(s) se copiarán al año siguiente con nuevas fechas."
+                    }
+                    Messages::AttachedFilesAreReferenced => {
+                        "Los archivos adjuntos se referencian, no se copian."
+                    }
+                    Messages::ShowOnlyProblemItems => {
+                        "Mostrar solo entradas con archivos faltantes o campos vacíos"
+                    }
+                    Messages::ReallyDeleteItem => "¿Realmente desea eliminar esta entrada?",
+                    Messages::PagesWillBeExported => "páginas se exportarán. ¿Desea continuar?",
+                    Messages::DataFolderContainsDatabase => {
+                        "La carpeta seleccionada ya contiene una base de datos de helferlein. ¡Si mueves tus datos allí, se sobrescribirá y se perderán todos sus datos!"
+                    }
+                    Messages::Overwrite => "Sobrescribir",
+                    Messages::Add => "Añadir",
+                    Messages::CopyPath => "Copiar ruta",
+                    Messages::CopyAsText => "Copiar como texto",
+                    Messages::ExportAllMonths => "Exportar todos los meses",
+                    Messages::ClearAll => "Borrar todo",
+                    Messages::OpenContainingFolder => "Abrir carpeta contenedora",
+                    Messages::Combined => "Combinado",
+                    Messages::ByType => "Por tipo",
+                    Messages::ValidateItems => "Validar entradas",
+                    Messages::Close => "Cerrar",
+                    Messages::RecordInAccounting => "Registrar en contabilidad",
+                    Messages::Record => "Registrar",
+                    Messages::PerItem => "Por posición",
+                    Messages::SummarizedByVat => "Resumido por IVA",
+                    Messages::SelectedItems => "Seleccionadas",
+                    Messages::MinNet => "Neto mín.",
+                    Messages::MaxNet => "Neto máx.",
+                    Messages::Subtotal => "Subtotal",
+                    Messages::ExportCSV => "Exportar CSV",
+                    Messages::SubtotalsPer => "Subtotales por",
+                    Messages::FindDuplicates => "Buscar duplicados",
+                    Messages::Duplicate => "Duplicar",
+                    Messages::ThisMonth => "Este mes",
+                    Messages::ThisQuarter => "Este trimestre",
+                    Messages::ThisYear => "Este año",
+                    Messages::DropFileHint => "o suelte un archivo aquí",
+                    Messages::Load => "Cargar",
+                    Messages::CopyLog => "Copiar registro",
+                    Messages::JumpToPreviousPeriodWithItems => {
+                        "Ir al período anterior con entradas"
+                    }
+                    Messages::KeepDateCompanyCategory => "Mantener fecha, empresa y categoría",
+                    Messages::KeepDateCompanyCategoryHint => {
+                        "Para introducir varias entradas similares seguidas"
+                    }
+                    Messages::Merge => "Fusionar",
+                    Messages::OpenLastExport => "Abrir última exportación",
+                    Messages::NoExportYet => "Aún no se exportó nada, o el archivo ya no existe.",
+
+                    //Months
+                    Messages::January => "Enero",
+                    Messages::February => "Febrero",
+                    Messages::March => "Marzo",
+                    Messages::April => "Abril",
+                    Messages::May => "Mayo",
+                    Messages::June => "Junio",
+                    Messages::July => "Julio",
+                    Messages::August => "Agosto",
+                    Messages::September => "Septiembre",
+                    Messages::October => "Octubre",
+                    Messages::November => "Noviembre",
+                    Messages::December => "Diciembre",
+
+                    //Months short
+                    Messages::Jan => "Ene",
+                    Messages::Feb => "Feb",
+                    Messages::Mar => "Mar",
+                    Messages::Apr => "Abr",
+                    Messages::Jun => "Jun",
+                    Messages::Jul => "Jul",
+                    Messages::Aug => "Ago",
+                    Messages::Sep => "Sep",
+                    Messages::Oct => "Oct",
+                    Messages::Nov => "Nov",
+                    Messages::Dec => "Dic",
+
+                    // Suggestions
+                    Messages::NoDataFolder => {
+                        "Por favor, elija una carpeta para guardar sus datos contables. Asegúrese de que los datos estén seguros allí y se respalden regularmente."
+                    }
+                    // Infos
+                    Messages::FileCopied => {
+                        "El archivo de la entrada se copió a la carpeta de datos."
+                    }
+                    Messages::PDFCreated => {
+                        "Se creó el informe PDF y todos los archivos de facturas se guardaron junto a él en una carpeta \"_files\"."
+                    }
+                    Messages::ItemDeleted => "Entrada eliminada correctamente.",
+                    Messages::ItemCreated => "Entrada creada correctamente.",
+                    Messages::InvoiceTemplateCreated => {
+                        "Plantilla de factura creada correctamente."
+                    }
+                    Messages::InvoiceTemplateFilled => "Plantilla de factura aplicada.",
+                    Messages::ItemsFetched => "Entradas cargadas correctamente.",
+                    Messages::ItemsCopied => "entrada(s) copiadas al año siguiente.",
+                    Messages::PathCopied => "Ruta copiada al portapapeles",
+                    Messages::InvoiceCopied => "Factura copiada al portapapeles",
+                    Messages::MonthExported => "fue creado.",
+                    Messages::MonthlyPDFsCreated => "PDF(s) mensuales creados,",
+                    Messages::EmptyMonthsSkipped => "mes(es) vacío(s) omitido(s).",
+                    Messages::AddressFilled => {
+                        "Dirección rellenada desde la última factura a esta empresa."
+                    }
+                    Messages::AllItemsValid => {
+                        "Todas las entradas del periodo seleccionado son válidas."
+                    }
+                    Messages::ItemsRecorded => "entradas se registraron en la contabilidad.",
+                    Messages::ItemsWillBeRecorded => "entradas se registrarán como ingresos.",
+                    Messages::CSVCreated => "CSV creado correctamente.",
+                    Messages::NoDuplicatesFound => "No se encontraron entradas duplicadas.",
+                    Messages::FilesMoved => "archivos movidos",
+                    Messages::AllDataDeleted => "Se eliminaron todos los datos",
+                    Messages::ItemsCreated => "entradas fueron creadas.",
+                    Messages::DraftSaved => "Borrador guardado",
+                    Messages::LogCopied => "Registro copiado",
+                    Messages::NoEarlierItems => "No hay entradas anteriores",
+                    Messages::ItemsMerged => "entradas actualizadas.",
+
+                    // Warnings
+                    Messages::DateNotInSelectedDateRange => {
+                        "La fecha elegida no está dentro del período seleccionado."
+                    }
+                    Messages::MissingItemFiles => {
+                        "archivo(s) de entradas contables faltan. Por favor, restáurelos desde una copia de seguridad."
+                    }
+                    Messages::ManyItemsFetched => {
+                        "entradas cargadas - el PDF exportado tendrá muchas páginas."
+                    }
+                    Messages::VatIdDoesNotMatchCountry => {
+                        "El NIF-IVA no coincide con el formato del país indicado."
+                    }
+                    Messages::DateInFuture => "La fecha está en el futuro",
+                    Messages::DataFolderMissing => {
+                        "La carpeta de datos configurada no existe, por favor selecciona una nueva"
+                    }
+                    Messages::UnknownLanguage => {
+                        "Idioma desconocido en la configuración, se usa inglés"
+                    }
+                    Messages::ItemsWithProblems => "Entradas con problemas:",
+                    Messages::FileDoesNotExist => "El archivo no existe",
+                    Messages::DuplicateItems => {
+                        "Entradas con la misma fecha, empresa e importe neto:"
+                    }
+                    Messages::NotAPDFWillBeSkipped => "No es un PDF, se omitirá al exportar",
+                    Messages::AttachmentsSkipped => {
+                        "Adjuntos omitidos, porque no son PDFs legibles:"
+                    }
+                    Messages::QRCodeMissingIBAN => {
+                        "No se añadió el código QR de pago - los datos bancarios no contienen un IBAN válido"
+                    }
+                    Messages::ReallyExitWithUnsavedInvoice => {
+                        "La factura no se ha exportado ni guardado como plantilla. ¿Cerrar de todos modos?"
+                    }
+                    Messages::SettingOutOfRange => {
+                        "Ajuste de la configuración fuera de rango, corregido"
+                    }
+
+                    // Errors
+                    Messages::DateNotValid => "No es una fecha válida.",
+                    Messages::PDFFilesCopyFailed => {
+                        "archivos no se pudieron copiar. No se creó el informe PDF. Por favor, revise los archivos de la hoja."
+                    }
+                    Messages::CanNotBeEmpty => "no puede estar vacío.",
+                    Messages::NotANumber => "no es un número.",
+                    Messages::FilesFolderNotCreated => {
+                        "No se pudo crear la carpeta de archivos en la carpeta de datos"
+                    }
+                    Messages::CouldNotCheckFileIntegrity => {
+                        "No se pudieron comprobar los archivos de las entradas en la carpeta de datos."
+                    }
+                    Messages::CouldNotFetchCategoryDefaults => {
+                        "No se pudieron cargar los valores de la categoría."
+                    }
+                    Messages::CouldNotCopyItems => "No se pudieron copiar las entradas.",
+                    Messages::CouldNotRecordExport => "No se pudo registrar la exportación",
+                    Messages::CouldNotFetchExportLog => {
+                        "No se pudo cargar el registro de exportaciones"
+                    }
+                    Messages::MonthlyPDFsFailed => "PDF(s) mensuales no se pudieron crear.",
+                    Messages::CouldNotSaveConfig => "No se pudo guardar la configuración.",
+                    Messages::CouldNotFetchCompanyAddress => {
+                        "No se pudo cargar la dirección de la empresa."
+                    }
+                    Messages::CouldNotOpenFolder => "No se pudo abrir la carpeta.",
+                    Messages::CouldNotRecordInvoice => {
+                        "No se pudo registrar la factura en la contabilidad"
+                    }
+                    Messages::DatabaseAccessFailed => "Error al acceder a la base de datos",
+                    Messages::FileAccessFailed => "Error al acceder al archivo",
+                    Messages::CSVNotCreated => "No se pudo crear el CSV",
+                    Messages::CouldNotFindDuplicates => "No se pudieron buscar entradas duplicadas",
+                    Messages::CouldNotMoveFiles => "No se pudieron mover los archivos",
+                    Messages::IbanNotValid => "El IBAN no es válido",
+                    Messages::InvalidInvoice => "La factura contiene valores no válidos",
+                    Messages::CouldNotDeleteAllData => "No se pudieron eliminar todos los datos",
+                    Messages::SplitsDoNotAddUpToNet => "La división no suma el importe neto",
+                    Messages::InvalidFileType => "Tipo de archivo no admitido, use uno de",
+                    Messages::CouldNotSaveDraft => "No se pudo guardar el borrador",
+                    Messages::CouldNotFetchDrafts => "No se pudieron cargar los borradores",
+                    Messages::AutoBackupFailed => {
+                        "No se pudo hacer la copia de seguridad de la base de datos, no se cambió nada"
+                    }
+                    Messages::NotAPosition => "no es una posición válida, p. ej. 1 o 1.2.",
+                    Messages::CouldNotMerge => "No se pudo fusionar.",
+                    Messages::YearOutOfRange => "El año debe estar entre 1900 y 2200.",
+                    Messages::TotalCantBeRounded => "El total es demasiado grande para redondearlo",
+                    Messages::CouldNotDeleteDraft => "No se pudo eliminar el borrador.",
+
+                    Messages::FileCouldNotBeDeleted => "No se pudo eliminar el archivo",
+                    Messages::FolderCouldNotBeDeleted => "No se pudo eliminar la carpeta",
+                    Messages::ItemCopyFailed => {
+                        "No se pudo copiar el archivo a la carpeta de datos"
+                    }
+                    Messages::PDFNotCreated => "No se pudo crear el informe PDF.",
+                    Messages::CouldNotFetchData => "No se pudieron cargar los datos.",
+                    Messages::CouldNotDeleteItem => "No se pudo eliminar la entrada.",
+                    Messages::CouldNotFetchNames => "No se pudieron cargar los nombres.",
+                    Messages::CouldNotFetchCategories => "No se pudieron cargar las categorías.",
+                    Messages::CouldNotFetchCompanies => "No se pudieron cargar las empresas.",
+                    Messages::CouldNotCreateItem => "No se pudo crear la entrada.",
+                    Messages::CouldNotOpenFile => "No se pudo abrir el archivo.",
+                    Messages::CouldNotCreateInvoiceTemplate => {
+                        "No se pudo crear la plantilla de factura."
+                    }
+                    Messages::TooManyItemsForPDFExport => {
+                        "Demasiadas entradas para exportar a PDF."
+                    }
+                }
+            }
+            Language::IT => {
+                match self {
+                    // General
+                    Messages::Title => "Helferlein",
+
+                    // Settings
+                    Messages::DataFolder => "Cartella dati",
+                    Messages::Language => "Lingua",
+                    Messages::FileOpenProgram => "Programma per aprire i file",
+                    Messages::SuccessFullyChangedDataFolder => {
+                        "Cartella dati cambiata con successo!"
+                    }
+                    Messages::ErrorChangingDataFolder => {
+                        "Si è verificato un errore durante il cambio della cartella dati."
+                    }
+                    Messages::SuccessFullyChangedProgramToOpen => {
+                        "Programma per aprire i file cambiato con successo!"
+                    }
+                    Messages::PageSize => "Formato pagina",
+                    Messages::CategoryDefaults => "Valori predefiniti per categoria",
+                    Messages::PreFillFromCategory => {
+                        "Precompilare IVA e tipo di fattura dalla categoria"
+                    }
+                    Messages::OpenPdfAfterExport => "Apri PDF dopo l'esportazione",
+                    Messages::FiscalYearStart => "L'anno fiscale inizia a",
+                    Messages::ExportLog => "Registro delle esportazioni",
+                    Messages::NoExportsYet => "Ancora nessuna esportazione",
+                    Messages::ExportedPeriodOrNumber => "Periodo / N.",
+                    Messages::Type => "Tipo",
+                    Messages::NumberFormat => "Formato dei numeri",
+                    Messages::FollowLanguage => "Come la lingua",
+                    Messages::QuantityDecimals => "Decimali delle quantità",
+                    Messages::YearHistorySpan => "Anni nella selezione dell'anno",
+                    Messages::Tables => "Tabelle",
+                    Messages::ColorRowsByInvoiceType => "Evidenzia fatture emesse",
+                    Messages::TopMarginOffset => "Spazio per carta intestata",
+                    Messages::Window => "Finestra",
+                    Messages::AlwaysOnTop => "Sempre in primo piano",
+                    Messages::PDFAuthor => "Autore del PDF",
+                    Messages::FilesPerYear => "File in una cartella per anno",
+                    Messages::Files => "File",
+                    Messages::PaymentQRCode => "Codice QR di pagamento",
+                    Messages::EPCQRCodeOnInvoices => {
+                        "Codice QR EPC sulle fatture, dall'IBAN dei dati bancari"
+                    }
+                    Messages::DateFormat => "Formato data",
+                    Messages::ResetAllData => "Ripristina tutti i dati",
+                    Messages::ReallyResetAllData => {
+                        "Tutte le voci, i modelli e i suggerimenti verranno eliminati - i file copiati restano nella cartella dei dati. Per confermare, digita"
+                    }
+                    Messages::VatColumn => "Colonna IVA",
+                    Messages::VatAmountPerLineOnInvoices => {
+                        "Mostra l'importo IVA di ogni riga nelle fatture"
+                    }
+                    Messages::ConfirmExit => "Conferma uscita",
+                    Messages::ConfirmExitWithUnsavedInvoice => {
+                        "Chiedi prima di chiudere con una fattura non salvata"
+                    }
+                    Messages::MaxSuggestions => "Max. suggerimenti",
+                    Messages::MinSuggestionInput => "Suggerisci dopo",
+                    Messages::Characters => "caratteri",
+                    Messages::ShowCentsInTotals => "Mostra i centesimi in totali e riepiloghi",
+                    Messages::DeleteDraftAfterExport => {
+                        "Elimina la bozza caricata dopo l'esportazione"
+                    }
+                    Messages::DeveloperMode => "Modalità sviluppatore",
+                    Messages::ShowActivityLog => {
+                        "Mostra il registro attività, ad es. per segnalazioni di bug"
+                    }
+                    Messages::ActivityLog => "Registro attività",
+                    Messages::NoLogRecordsYet => "Ancora nessuna voce",
+                    Messages::MinWindowSize => "Dimensione minima della finestra",
+                    Messages::AccountingExportFileName => "Nome del file di esportazione contabile",
+                    Messages::InvoiceExportFileName => "Nome del file di esportazione fatture",
+                    Messages::Placeholders => "Segnaposto",
+                    Messages::Backups => "Backup",
+                    Messages::AutoBackup => "Esegui automaticamente il backup del database",
+                    Messages::AutoBackupHint => {
+                        "Prima di cambiare la cartella dei dati, ripristinare tutti i dati o spostare i file delle voci, il database viene copiato nella cartella dei backup"
+                    }
+                    Messages::Keep => "Conserva",
+                    // Invoice
+                    Messages::Invoice => "Fattura",
+                    Messages::InvoiceShort => "fatt",
+                    Messages::General => "Generale",
+                    Messages::ServicePeriod => "Periodo di prestazione",
+                    Messages::CreateNewInvoice => "Crea nuova fattura",
+                    Messages::From => "Da",
+                    Messages::To => "A",
+                    Messages::Items => "Voci",
+                    Messages::PostalAddress => "Indirizzo",
+                    Messages::Zip => "CAP",
+                    Messages::City => "Città",
+                    Messages::Country => "Paese",
+                    Messages::VatNr => "P. IVA",
+                    Messages::Misc => "Varie",
+                    Messages::Nr => "N.",
+                    Messages::Pos => "Pos",
+                    Messages::Description => "Descrizione",
+                    Messages::Unit => "Unità",
+                    Messages::UnitShort => "Unità",
+                    Messages::Qty => "Qtà",
+                    Messages::Amount => "Quantità",
+                    Messages::PricePerUnit => "Prezzo unitario",
+                    Messages::SaveAsTemplate => "Salva come modello",
+                    Messages::Templates => "Modelli",
+                    Messages::PreText => "Testo iniziale",
+                    Messages::PostText => "Testo finale",
+                    Messages::BankData => "Dati bancari",
+                    Messages::RoundTotal => "Arrotonda totale",
+                    Messages::Rounding => "Arrotondamento",
+                    Messages::TotalHours => "Ore totali",
+                    Messages::InclVat => "incl.",
+                    Messages::PricesIncludeVat => "Prezzi IVA inclusa",
+                    Messages::Quote => "Preventivo",
+                    Messages::QuoteShort => "prev",
+                    Messages::DocumentKind => "Documento",
+                    Messages::Adjustments => "Sconti / Supplementi",
+                    Messages::AdjustmentAmount => "ad es. -50 o 100",
+                    Messages::FillAddress => "Compila indirizzo",
+                    Messages::NoInvoiceItems => "La fattura richiede almeno una voce.",
+                    Messages::Attachments => "Allegati",
+                    Messages::AccountHolder => "Intestatario del conto",
+                    Messages::Iban => "IBAN",
+                    Messages::Bic => "BIC",
+                    Messages::CopySuffix => "(copia)",
+                    Messages::SaveDraft => "Salva bozza",
+                    Messages::Drafts => "Bozze",
+                    Messages::DraftLoaded => "Bozza caricata",
+
+                    // Accounting
+                    Messages::Accounting => "Contabilità",
+                    Messages::Year => "Anno",
+                    Messages::Quarter => "Trimestre",
+                    Messages::Month => "Mese",
+                    Messages::Ingoing => "In entrata",
+                    Messages::Outgoing => "In uscita",
+                    Messages::AccountingSummary => "Riepilogo contabile",
+                    Messages::CategoriesSummary => "Riepilogo per categorie",
+                    Messages::Sum => "Somma",
+                    Messages::OtherYear => "Altro anno",
+                    Messages::Search => "Cerca",
+                    Messages::SearchShortcut => "Cerca (Ctrl+F)",
+                    Messages::Split => "Dividi",
+                    Messages::Splits => "Suddivisione",
+                    Messages::NotSplitYet => "Non ancora suddiviso",
+                    Messages::RevenueByCompany => "Ricavi per azienda",
+                    Messages::Share => "Quota",
+                    Messages::AllAmounts => "Tutti gli importi",
+                    Messages::Private => "Privato",
+                    Messages::ExcludeFromSummary => "Escludi dal riepilogo e dai totali",
+                    Messages::ExcludedFromSummary => "Privato - escluso dal riepilogo e dai totali",
+                    Messages::NoItemsForThisPeriod => "Nessuna voce per questo periodo",
+                    Messages::MergeCategoriesOrCompanies => "Unisci categorie / aziende",
+                    Messages::MergeInto => "in",
+                    Messages::MergeHint => {
+                        "Modifica tutte le voci, ad es. per correggere errori. Non può essere annullato."
+                    }
+                    Messages::ItemsWillBeMerged => {
+                        "voci verranno modificate. Non può essere annullato."
+                    }
+
+                    // Accounting Items
+                    Messages::InvoiceType => "Tipo fatt.",
+                    Messages::InvoiceNumber => "#",
+                    Messages::InvoiceNumberText => "Numero fattura",
+                    Messages::Date => "Data",
+                    Messages::Name => "Nome",
+                    Messages::Company => "Azienda",
+                    Messages::Category => "Categoria",
+                    Messages::Net => "Netto",
+                    Messages::Vat => "IVA",
+                    Messages::Tax => "Imposta",
+                    Messages::Gross => "Lordo",
+                    Messages::Total => "Totale",
+                    Messages::File => "File",
+                    Messages::ChooseFile => "Scegli file",
+                    Messages::SaveFile => "Salva file",
+                    Messages::SelectFolder => "Seleziona cartella",
+                    Messages::FileTitle => "File:",
+                    Messages::Link => "Link",
+                    Messages::AddItem => "Aggiungi nuova voce",
+                    Messages::NewItem => "Nuova voce",
+                    Messages::EditItem => "Modifica voce",
+                    Messages::Edit => "Modifica",
+                    Messages::Delete => "Elimina",
+                    Messages::Note => "Nota",
+                    Messages::RecentFolders => "Cartelle recenti",
+
+                    // Navigation
+                    Messages::Home => "Home",
+                    Messages::Welcome => "Benvenuto",
+                    Messages::Settings => "Impostazioni",
+
+                    // Buttons / Ui
+                    Messages::Select => "Seleziona",
+                    Messages::Fill => "Compila",
+                    Messages::Done => "Fatto",
+                    Messages::SaveItem => "Salva voce",
+                    Messages::Save => "Salva",
+                    Messages::Rename => "Rinomina",
+                    Messages::Refresh => "Aggiorna",
+                    Messages::NewFolder => "Nuova cartella",
+                    Messages::ParentFolder => "Cartella superiore",
+                    Messages::ShowHidden => "Mostra nascosti",
+                    Messages::Change => "Cambia",
+                    Messages::Cancel => "Annulla",
+                    Messages::Reset => "Reimposta",
+                    Messages::Open => "Apri",
+                    Messages::ThereAreWarnings => "⚠ Ci sono avvisi!",
+                    Messages::ReallySave => "Vuoi davvero salvare?",
+                    Messages::ReallyChangeDataFolder => {
+                        "Vuoi davvero salvare? Se ci sono file nella nuova posizione, potrebbero essere sovrascritti."
+                    }
+                    Messages::Export => "Esporta",
+                    Messages::ExportSelected => "Esporta selezione",
+                    Messages::ClearSelection => "Annulla selezione",
+                    Messages::AllTime => "Tutto",
+                    Messages::CopyToNextYear => "Copia nell'anno successivo",
+                    Messages::Copy => "Copia",
+                    Messages::ItemsWillBeCopiedToNextYear => {
+                        "voce/i verranno copiate nell'anno successivo con nuove date."
+                    }
+                    Messages::AttachedFilesAreReferenced => {
+                        "I file allegati vengono referenziati, non copiati."
+                    }
+                    Messages::ShowOnlyProblemItems => {
+                        "Mostra solo voci con file mancanti o campi vuoti"
+                    }
+                    Messages::ReallyDeleteItem => "Vuoi davvero eliminare questa voce?",
+                    Messages::PagesWillBeExported => "pagine verranno esportate. Vuoi continuare?",
+                    Messages::DataFolderContainsDatabase => {
+                        "La cartella selezionata contiene già un database di helferlein. Spostando i tuoi dati lì verrà sovrascritto e tutti i suoi dati andranno persi!"
+                    }
+                    Messages::Overwrite => "Sovrascrivere",
+                    Messages::Add => "Aggiungi",
+                    Messages::CopyPath => "Copia percorso",
+                    Messages::CopyAsText => "Copia come testo",
+                    Messages::ExportAllMonths => "Esporta tutti i mesi",
+                    Messages::ClearAll => "Cancella tutto",
+                    Messages::OpenContainingFolder => "Apri cartella contenitore",
+                    Messages::Combined => "Combinato",
+                    Messages::ByType => "Per tipo",
+                    Messages::ValidateItems => "Verifica voci",
+                    Messages::Close => "Chiudi",
+                    Messages::RecordInAccounting => "Registra in contabilità",
+                    Messages::Record => "Registra",
+                    Messages::PerItem => "Per voce",
+                    Messages::SummarizedByVat => "Riepilogato per IVA",
+                    Messages::SelectedItems => "Selezionate",
+                    Messages::MinNet => "Netto min.",
+                    Messages::MaxNet => "Netto max.",
+                    Messages::Subtotal => "Subtotale",
+                    Messages::ExportCSV => "Esporta CSV",
+                    Messages::SubtotalsPer => "Subtotali per",
+                    Messages::FindDuplicates => "Trova duplicati",
+                    Messages::Duplicate => "Duplica",
+                    Messages::ThisMonth => "Questo mese",
+                    Messages::ThisQuarter => "Questo trimestre",
+                    Messages::ThisYear => "Quest'anno",
+                    Messages::DropFileHint => "o trascina qui un file",
+                    Messages::Load => "Carica",
+                    Messages::CopyLog => "Copia registro",
+                    Messages::JumpToPreviousPeriodWithItems => "Vai al periodo precedente con voci",
+                    Messages::KeepDateCompanyCategory => "Mantieni data, azienda e categoria",
+                    Messages::KeepDateCompanyCategoryHint => {
+                        "Per inserire più voci simili di seguito"
+                    }
+                    Messages::Merge => "Unisci",
+                    Messages::OpenLastExport => "Apri ultima esportazione",
+                    Messages::NoExportYet => {
+                        "Non è stato ancora esportato nulla, o il file non esiste più."
+                    }
+
+                    //Months
+                    Messages::January => "Gennaio",
+                    Messages::February => "Febbraio",
+                    Messages::March => "Marzo",
+                    Messages::April => "Aprile",
+                    Messages::May => "Maggio",
+                    Messages::June => "Giugno",
+                    Messages::July => "Luglio",
+                    Messages::August => "Agosto",
+                    Messages::September => "Settembre",
+                    Messages::October => "Ottobre",
+                    Messages::November => "Novembre",
+                    Messages::December => "Dicembre",
+
+                    //Months short
+                    Messages::Jan => "Gen",
+                    Messages::Feb => "Feb",
+                    Messages::Mar => "Mar",
+                    Messages::Apr => "Apr",
+                    Messages::Jun => "Giu",
+                    Messages::Jul => "Lug",
+                    Messages::Aug => "Ago",
+                    Messages::Sep => "Set",
+                    Messages::Oct => "Ott",
+                    Messages::Nov => "Nov",
+                    Messages::Dec => "Dic",
+
+                    // Suggestions
+                    Messages::NoDataFolder => {
+                        "Imposta una cartella in cui salvare i dati contabili. Assicurati che i dati siano al sicuro e che ne venga fatto regolarmente un backup."
+                    }
+                    // Infos
+                    Messages::FileCopied => {
+                        "Il file della voce è stato copiato nella cartella dati."
+                    }
+                    Messages::PDFCreated => {
+                        "Il report PDF è stato creato e tutti i file delle fatture sono stati messi in una cartella \"_files\" accanto ad esso."
+                    }
+                    Messages::ItemDeleted => "Voce eliminata con successo.",
+                    Messages::ItemCreated => "Voce creata con successo.",
+                    Messages::InvoiceTemplateCreated => "Modello di fattura creato con successo.",
+                    Messages::InvoiceTemplateFilled => "Modello di fattura applicato.",
+                    Messages::ItemsFetched => "Voci caricate con successo.",
+                    Messages::ItemsCopied => "voce/i copiate nell'anno successivo.",
+                    Messages::PathCopied => "Percorso copiato negli appunti",
+                    Messages::InvoiceCopied => "Fattura copiata negli appunti",
+                    Messages::MonthExported => "è stato creato.",
+                    Messages::MonthlyPDFsCreated => "PDF mensili creati,",
+                    Messages::EmptyMonthsSkipped => "mesi vuoti saltati.",
+                    Messages::AddressFilled => {
+                        "Indirizzo compilato dall'ultima fattura a questa azienda."
+                    }
+                    Messages::AllItemsValid => "Tutte le voci del periodo selezionato sono valide.",
+                    Messages::ItemsRecorded => "voci sono state registrate in contabilità.",
+                    Messages::ItemsWillBeRecorded => "voci verranno registrate come entrate.",
+                    Messages::CSVCreated => "CSV creato con successo.",
+                    Messages::NoDuplicatesFound => "Nessuna voce duplicata trovata.",
+                    Messages::FilesMoved => "file spostati",
+                    Messages::AllDataDeleted => "Tutti i dati sono stati eliminati",
+                    Messages::ItemsCreated => "voci sono state create.",
+                    Messages::DraftSaved => "Bozza salvata",
+                    Messages::LogCopied => "Registro copiato",
+                    Messages::NoEarlierItems => "Non ci sono voci precedenti",
+                    Messages::ItemsMerged => "voci aggiornate.",
+
+                    // Warnings
+                    Messages::DateNotInSelectedDateRange => {
+                        "La data scelta non rientra nel periodo selezionato."
+                    }
+                    Messages::MissingItemFiles => {
+                        "file delle voci contabili mancanti. Ripristinali da un backup."
+                    }
+                    Messages::ManyItemsFetched => {
+                        "voci caricate - il PDF esportato avrà molte pagine."
+                    }
+                    Messages::VatIdDoesNotMatchCountry => {
+                        "La partita IVA non corrisponde al formato del paese indicato."
+                    }
+                    Messages::DateInFuture => "La data è nel futuro",
+                    Messages::DataFolderMissing => {
+                        "La cartella dati configurata non esiste, selezionane una nuova"
+                    }
+                    Messages::UnknownLanguage => {
+                        "Lingua sconosciuta nella configurazione, viene usato l'inglese"
+                    }
+                    Messages::ItemsWithProblems => "Voci con problemi:",
+                    Messages::FileDoesNotExist => "Il file non esiste",
+                    Messages::DuplicateItems => "Voci con la stessa data, azienda e importo netto:",
+                    Messages::NotAPDFWillBeSkipped => {
+                        "Non è un PDF, verrà saltato nell'esportazione"
+                    }
+                    Messages::AttachmentsSkipped => {
+                        "Allegati saltati, perché non sono PDF leggibili:"
+                    }
+                    Messages::QRCodeMissingIBAN => {
+                        "Nessun codice QR di pagamento aggiunto - i dati bancari non contengono un IBAN valido"
+                    }
+                    Messages::ReallyExitWithUnsavedInvoice => {
+                        "La fattura non è stata esportata né salvata come modello. Chiudere comunque?"
+                    }
+                    Messages::SettingOutOfRange => {
+                        "Impostazione della configurazione fuori intervallo, corretta"
+                    }
+
+                    // Errors
+                    Messages::DateNotValid => "Data non valida.",
+                    Messages::PDFFilesCopyFailed => {
+                        "file non sono stati copiati. Il report PDF non è stato creato. Controlla i file nel foglio."
+                    }
+                    Messages::CanNotBeEmpty => "non può essere vuoto.",
+                    Messages::NotANumber => "non è un numero.",
+                    Messages::FilesFolderNotCreated => {
+                        "Impossibile creare la cartella dei file nella cartella dati"
+                    }
+                    Messages::CouldNotCheckFileIntegrity => {
+                        "Impossibile controllare i file delle voci nella cartella dati."
+                    }
+                    Messages::CouldNotFetchCategoryDefaults => {
+                        "Impossibile caricare i valori predefiniti della categoria."
+                    }
+                    Messages::CouldNotCopyItems => "Impossibile copiare le voci.",
+                    Messages::CouldNotRecordExport => "Impossibile registrare l'esportazione",
+                    Messages::CouldNotFetchExportLog => {
+                        "Impossibile caricare il registro delle esportazioni"
+                    }
+                    Messages::MonthlyPDFsFailed => "PDF mensili non sono stati creati.",
+                    Messages::CouldNotSaveConfig => "Impossibile salvare le impostazioni.",
+                    Messages::CouldNotFetchCompanyAddress => {
+                        "Impossibile caricare l'indirizzo dell'azienda."
+                    }
+                    Messages::CouldNotOpenFolder => "Impossibile aprire la cartella.",
+                    Messages::CouldNotRecordInvoice => {
+                        "Impossibile registrare la fattura in contabilità"
+                    }
+                    Messages::DatabaseAccessFailed => "Accesso al database non riuscito",
+                    Messages::FileAccessFailed => "Accesso al file non riuscito",
+                    Messages::CSVNotCreated => "Impossibile creare il CSV",
+                    Messages::CouldNotFindDuplicates => "Impossibile cercare voci duplicate",
+                    Messages::CouldNotMoveFiles => "Impossibile spostare i file",
+                    Messages::IbanNotValid => "L'IBAN non è valido",
+                    Messages::InvalidInvoice => "La fattura contiene valori non validi",
+                    Messages::CouldNotDeleteAllData => "Impossibile eliminare tutti i dati",
+                    Messages::SplitsDoNotAddUpToNet => {
+                        "La suddivisione non corrisponde all'importo netto"
+                    }
+                    Messages::InvalidFileType => "Tipo di file non supportato, usa uno tra",
+                    Messages::CouldNotSaveDraft => "Impossibile salvare la bozza",
+                    Messages::CouldNotFetchDrafts => "Impossibile caricare le bozze",
+                    Messages::AutoBackupFailed => {
+                        "Impossibile eseguire il backup del database, nulla è stato modificato"
+                    }
+                    Messages::NotAPosition => "non è una posizione valida, ad es. 1 o 1.2.",
+                    Messages::CouldNotMerge => "Impossibile unire.",
+                    Messages::YearOutOfRange => "L'anno deve essere compreso tra 1900 e 2200.",
+                    Messages::TotalCantBeRounded => {
+                        "Il totale è troppo grande per essere arrotondato"
+                    }
+                    Messages::CouldNotDeleteDraft => "Impossibile eliminare la bozza.",
+
+                    Messages::FileCouldNotBeDeleted => "Impossibile eliminare il file",
+                    Messages::FolderCouldNotBeDeleted => "Impossibile eliminare la cartella",
+                    Messages::ItemCopyFailed => "Impossibile copiare il file nella cartella dati",
+                    Messages::PDFNotCreated => "Impossibile creare il report PDF.",
+                    Messages::CouldNotFetchData => "Impossibile caricare i dati.",
+                    Messages::CouldNotDeleteItem => "Impossibile eliminare la voce.",
+                    Messages::CouldNotFetchNames => "Impossibile caricare i nomi.",
+                    Messages::CouldNotFetchCategories => "Impossibile caricare le categorie.",
+                    Messages::CouldNotFetchCompanies => "Impossibile caricare le aziende.",
+                    Messages::CouldNotCreateItem => "Impossibile creare la voce.",
+                    Messages::CouldNotOpenFile => "Impossibile aprire il file.",
+                    Messages::CouldNotCreateInvoiceTemplate => {
+                        "Impossibile creare il modello di fattura."
+                    }
+                    Messages::TooManyItemsForPDFExport => "Troppe voci per l'esportazione PDF.",
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LANGUAGES: &[Language] = &[Language::EN, Language::DE, Language::ES, Language::IT];
+
+    // keep in sync with the Messages enum
+    const ALL_MESSAGES: &[Messages] = &[
+        Messages::Title,
+        Messages::DataFolder,
+        Messages::Language,
+        Messages::FileOpenProgram,
+        Messages::SuccessFullyChangedDataFolder,
+        Messages::ErrorChangingDataFolder,
+        Messages::SuccessFullyChangedProgramToOpen,
+        Messages::PageSize,
+        Messages::CategoryDefaults,
+        Messages::PreFillFromCategory,
+        Messages::General,
+        Messages::Invoice,
+        Messages::InvoiceShort,
+        Messages::ServicePeriod,
+        Messages::CreateNewInvoice,
+        Messages::From,
+        Messages::To,
+        Messages::Items,
+        Messages::PostalAddress,
+        Messages::Zip,
+        Messages::City,
+        Messages::Country,
+        Messages::VatNr,
+        Messages::Misc,
+        Messages::Nr,
+        Messages::Pos,
+        Messages::Description,
+        Messages::Unit,
+        Messages::UnitShort,
+        Messages::Amount,
+        Messages::Qty,
+        Messages::PricePerUnit,
+        Messages::SaveAsTemplate,
+        Messages::Templates,
+        Messages::PreText,
+        Messages::PostText,
+        Messages::BankData,
+        Messages::RoundTotal,
+        Messages::Rounding,
+        Messages::Accounting,
+        Messages::Year,
+        Messages::Quarter,
+        Messages::Month,
+        Messages::Ingoing,
+        Messages::Outgoing,
+        Messages::AccountingSummary,
+        Messages::CategoriesSummary,
+        Messages::Sum,
+        Messages::InvoiceType,
+        Messages::InvoiceNumber,
+        Messages::InvoiceNumberText,
+        Messages::Date,
+        Messages::Name,
+        Messages::Company,
+        Messages::Category,
+        Messages::Net,
+        Messages::Vat,
+        Messages::Tax,
+        Messages::Gross,
+        Messages::Total,
+        Messages::File,
+        Messages::ChooseFile,
+        Messages::SaveFile,
+        Messages::SelectFolder,
+        Messages::FileTitle,
+        Messages::Link,
+        Messages::AddItem,
+        Messages::NewItem,
+        Messages::EditItem,
+        Messages::Edit,
+        Messages::Delete,
+        Messages::Home,
+        Messages::Settings,
+        Messages::Welcome,
+        Messages::Select,
+        Messages::Fill,
+        Messages::SaveItem,
+        Messages::Save,
+        Messages::Rename,
+        Messages::Refresh,
+        Messages::NewFolder,
+        Messages::ParentFolder,
+        Messages::ShowHidden,
+        Messages::Change,
+        Messages::Cancel,
+        Messages::Done,
+        Messages::Reset,
+        Messages::Open,
+        Messages::ThereAreWarnings,
+        Messages::ReallySave,
+        Messages::ReallyChangeDataFolder,
+        Messages::Export,
+        Messages::ExportSelected,
+        Messages::ClearSelection,
+        Messages::AllTime,
+        Messages::CopyToNextYear,
+        Messages::Copy,
+        Messages::ItemsWillBeCopiedToNextYear,
+        Messages::AttachedFilesAreReferenced,
+        Messages::January,
+        Messages::February,
+        Messages::March,
+        Messages::April,
+        Messages::May,
+        Messages::June,
+        Messages::July,
+        Messages::August,
+        Messages::September,
+        Messages::October,
+        Messages::November,
+        Messages::December,
+        Messages::Jan,
+        Messages::Feb,
+        Messages::Mar,
+        Messages::Apr,
+        Messages::Jun,
+        Messages::Jul,
+        Messages::Aug,
+        Messages::Sep,
+        Messages::Oct,
+        Messages::Nov,
+        Messages::Dec,
+        Messages::NoDataFolder,
+        Messages::FileCopied,
+        Messages::PDFCreated,
+        Messages::ItemDeleted,
+        Messages::ItemCreated,
+        Messages::InvoiceTemplateCreated,
+        Messages::InvoiceTemplateFilled,
+        Messages::ItemsFetched,
+        Messages::ItemsCopied,
+        Messages::DateNotInSelectedDateRange,
+        Messages::MissingItemFiles,
+        Messages::ManyItemsFetched,
+        Messages::VatIdDoesNotMatchCountry,
+        Messages::PDFFilesCopyFailed,
+        Messages::DateNotValid,
+        Messages::CanNotBeEmpty,
+        Messages::NotANumber,
+        Messages::FilesFolderNotCreated,
+        Messages::FileCouldNotBeDeleted,
+        Messages::FolderCouldNotBeDeleted,
+        Messages::ItemCopyFailed,
+        Messages::PDFNotCreated,
+        Messages::CouldNotFetchData,
+        Messages::CouldNotDeleteItem,
+        Messages::CouldNotFetchNames,
+        Messages::CouldNotFetchCategories,
+        Messages::CouldNotFetchCompanies,
+        Messages::CouldNotCreateItem,
+        Messages::CouldNotCreateInvoiceTemplate,
+        Messages::CouldNotOpenFile,
+        Messages::TooManyItemsForPDFExport,
+        Messages::CouldNotCheckFileIntegrity,
+        Messages::CouldNotFetchCategoryDefaults,
+        Messages::CouldNotCopyItems,
+        Messages::ShowOnlyProblemItems,
+        Messages::ReallyDeleteItem,
+        Messages::OpenPdfAfterExport,
+        Messages::PagesWillBeExported,
+        Messages::FiscalYearStart,
+        Messages::DateInFuture,
+        Messages::DataFolderMissing,
+        Messages::UnknownLanguage,
+        Messages::ExportLog,
+        Messages::NoExportsYet,
+        Messages::ExportedPeriodOrNumber,
+        Messages::CouldNotRecordExport,
+        Messages::CouldNotFetchExportLog,
+        Messages::Type,
+        Messages::Note,
+        Messages::NumberFormat,
+        Messages::FollowLanguage,
+        Messages::DataFolderContainsDatabase,
+        Messages::Overwrite,
+        Messages::TotalHours,
+        Messages::Add,
+        Messages::InclVat,
+        Messages::PricesIncludeVat,
+        Messages::CopyPath,
+        Messages::PathCopied,
+        Messages::CopyAsText,
+        Messages::InvoiceCopied,
+        Messages::QuantityDecimals,
+        Messages::RecentFolders,
+        Messages::Quote,
+        Messages::QuoteShort,
+        Messages::DocumentKind,
+        Messages::YearHistorySpan,
+        Messages::OtherYear,
+        Messages::ExportAllMonths,
+        Messages::MonthExported,
+        Messages::MonthlyPDFsCreated,
+        Messages::EmptyMonthsSkipped,
+        Messages::MonthlyPDFsFailed,
+        Messages::ClearAll,
+        Messages::Adjustments,
+        Messages::AdjustmentAmount,
+        Messages::CouldNotSaveConfig,
+        Messages::CouldNotFetchCompanyAddress,
+        Messages::FillAddress,
+        Messages::AddressFilled,
+        Messages::OpenContainingFolder,
+        Messages::CouldNotOpenFolder,
+        Messages::NoInvoiceItems,
+        Messages::Combined,
+        Messages::ByType,
+        Messages::ValidateItems,
+        Messages::Close,
+        Messages::AllItemsValid,
+        Messages::ItemsWithProblems,
+        Messages::FileDoesNotExist,
+        Messages::RecordInAccounting,
+        Messages::Record,
+        Messages::PerItem,
+        Messages::SummarizedByVat,
+        Messages::ItemsRecorded,
+        Messages::ItemsWillBeRecorded,
+        Messages::CouldNotRecordInvoice,
+        Messages::Tables,
+        Messages::ColorRowsByInvoiceType,
+        Messages::SelectedItems,
+        Messages::TopMarginOffset,
+        Messages::MinNet,
+        Messages::MaxNet,
+        Messages::DatabaseAccessFailed,
+        Messages::FileAccessFailed,
+        Messages::Window,
+        Messages::AlwaysOnTop,
+        Messages::Subtotal,
+        Messages::ExportCSV,
+        Messages::SubtotalsPer,
+        Messages::CSVCreated,
+        Messages::CSVNotCreated,
+        Messages::FindDuplicates,
+        Messages::NoDuplicatesFound,
+        Messages::DuplicateItems,
+        Messages::CouldNotFindDuplicates,
+        Messages::PDFAuthor,
+        Messages::Attachments,
+        Messages::NotAPDFWillBeSkipped,
+        Messages::AttachmentsSkipped,
+        Messages::Search,
+        Messages::SearchShortcut,
+        Messages::FilesPerYear,
+        Messages::FilesMoved,
+        Messages::CouldNotMoveFiles,
+        Messages::Files,
+        Messages::PaymentQRCode,
+        Messages::EPCQRCodeOnInvoices,
+        Messages::QRCodeMissingIBAN,
+        Messages::AccountHolder,
+        Messages::Iban,
+        Messages::Bic,
+        Messages::IbanNotValid,
+        Messages::DateFormat,
+        Messages::InvalidInvoice,
+        Messages::ResetAllData,
+        Messages::ReallyResetAllData,
+        Messages::AllDataDeleted,
+        Messages::CouldNotDeleteAllData,
+        Messages::VatColumn,
+        Messages::VatAmountPerLineOnInvoices,
+        Messages::ConfirmExit,
+        Messages::ConfirmExitWithUnsavedInvoice,
+        Messages::ReallyExitWithUnsavedInvoice,
+        Messages::Split,
+        Messages::Splits,
+        Messages::NotSplitYet,
+        Messages::ItemsCreated,
+        Messages::SplitsDoNotAddUpToNet,
+        Messages::MaxSuggestions,
+        Messages::MinSuggestionInput,
+        Messages::Characters,
+        Messages::Duplicate,
+        Messages::CopySuffix,
+        Messages::ThisMonth,
+        Messages::ThisQuarter,
+        Messages::ThisYear,
+        Messages::InvalidFileType,
+        Messages::DropFileHint,
+        Messages::RevenueByCompany,
+        Messages::Share,
+        Messages::ShowCentsInTotals,
+        Messages::DraftSaved,
+        Messages::CouldNotSaveDraft,
+        Messages::CouldNotFetchDrafts,
+        Messages::SaveDraft,
+        Messages::Drafts,
+        Messages::DraftLoaded,
+        Messages::DeleteDraftAfterExport,
+        Messages::Load,
+        Messages::AllAmounts,
+        Messages::DeveloperMode,
+        Messages::ShowActivityLog,
+        Messages::ActivityLog,
+        Messages::NoLogRecordsYet,
+        Messages::CopyLog,
+        Messages::LogCopied,
+        Messages::MinWindowSize,
+        Messages::Private,
+        Messages::ExcludeFromSummary,
+        Messages::ExcludedFromSummary,
+        Messages::AccountingExportFileName,
+        Messages::InvoiceExportFileName,
+        Messages::Placeholders,
+        Messages::NoItemsForThisPeriod,
+        Messages::JumpToPreviousPeriodWithItems,
+        Messages::NoEarlierItems,
+        Messages::KeepDateCompanyCategory,
+        Messages::KeepDateCompanyCategoryHint,
+        Messages::Backups,
+        Messages::AutoBackup,
+        Messages::AutoBackupHint,
+        Messages::Keep,
+        Messages::AutoBackupFailed,
+        Messages::NotAPosition,
+        Messages::MergeCategoriesOrCompanies,
+        Messages::MergeInto,
+        Messages::MergeHint,
+        Messages::Merge,
+        Messages::ItemsMerged,
+        Messages::CouldNotMerge,
+        Messages::OpenLastExport,
+        Messages::NoExportYet,
+        Messages::YearOutOfRange,
+        Messages::ItemsWillBeMerged,
+        Messages::TotalCantBeRounded,
+        Messages::SettingOutOfRange,
+        Messages::CouldNotDeleteDraft,
+    ];
+
+    #[test]
+    fn no_empty_messages() {
+        for language in LANGUAGES {
+            for message in ALL_MESSAGES {
+                assert!(
+                    !message.msg_for(*language).trim().is_empty(),
+                    "{message:?} is empty for {language:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn language_names_round_trip() {
+        for language in LANGUAGES {
+            assert_eq!(Language::from(language.name()), *language);
         }
+        assert_eq!(Language::from("unknown"), Language::EN);
     }
 }
@@ -1,3 +1,4 @@
+use crate::suggestion_limits;
 use eframe::egui::{
     Key, Modifiers, PopupCloseBehavior, Response, ScrollArea, TextBuffer, Ui,
     popup::popup_below_widget,
@@ -6,6 +7,25 @@ use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use std::cmp::Reverse;
 
+pub(crate) const DEFAULT_MAX_SUGGESTIONS: usize = 20;
+
+// keeps the popup fast and readable with thousands of values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SuggestionLimits {
+    pub(crate) max_results: usize,
+    // no suggestions for shorter input
+    pub(crate) min_input_length: usize,
+}
+
+impl Default for SuggestionLimits {
+    fn default() -> Self {
+        Self {
+            max_results: DEFAULT_MAX_SUGGESTIONS,
+            min_input_length: 0,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct AutoSuggest {
     selected_index: Option<usize>,
@@ -27,7 +47,8 @@ impl AutoSuggest {
     }
 
     pub(crate) fn ui(&mut self, ui: &mut Ui, input: &mut String, values: &[String]) -> Response {
-        let data = filter(values, input.as_str());
+        let limits = suggestion_limits();
+        let data = filter(values, input.as_str(), &limits);
 
         let mut tab_pressed = false;
         let mut enter_pressed = false;
@@ -52,7 +73,7 @@ impl AutoSuggest {
             });
         }
 
-        let text_field = ui.text_edit_singleline(input);
+        let mut text_field = ui.text_edit_singleline(input);
         let field_id = ui.make_persistent_id(text_field.id);
 
         let popup_id = field_id.with("popup");
@@ -61,6 +82,7 @@ impl AutoSuggest {
             if let Some(idx) = self.selected_index {
                 let text = data[idx];
                 input.replace_with(text);
+                text_field.mark_changed();
                 self.selected_index = None;
             }
             ui.memory_mut(|m| {
@@ -112,6 +134,7 @@ impl AutoSuggest {
         ) {
             let text = data[idx];
             input.replace_with(text);
+            text_field.mark_changed();
             self.selected_index = None;
             ui.memory_mut(|m| {
                 if m.is_popup_open(popup_id) {
@@ -123,7 +146,7 @@ impl AutoSuggest {
             });
         }
 
-        if text_field.has_focus() {
+        if text_field.has_focus() && input.chars().count() >= limits.min_input_length {
             ui.memory_mut(|m| m.open_popup(popup_id));
         } else {
             ui.memory_mut(|m| {
@@ -171,7 +194,10 @@ impl AutoSuggest {
     }
 }
 
-fn filter<'a>(data: &'a [String], input: &str) -> Vec<&'a String> {
+fn filter<'a>(data: &'a [String], input: &str, limits: &SuggestionLimits) -> Vec<&'a String> {
+    if input.chars().count() < limits.min_input_length {
+        return vec![];
+    }
     let matcher = SkimMatcherV2::default();
     let mut res = data
         .iter()
@@ -180,6 +206,53 @@ fn filter<'a>(data: &'a [String], input: &str) -> Vec<&'a String> {
             score.map(|score| (s, score))
         })
         .collect::<Vec<(&String, i64)>>();
+    // stable, so equally good matches keep their order
     res.sort_by_key(|k| Reverse(k.1));
+    res.truncate(limits.max_results);
     res.into_iter().map(|(s, _)| s).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn results_are_capped() {
+        let data: Vec<String> = (0..100).map(|i| format!("Name {i}")).collect();
+        let limits = SuggestionLimits {
+            max_results: 20,
+            min_input_length: 0,
+        };
+        assert_eq!(filter(&data, "", &limits).len(), 20);
+        assert_eq!(filter(&data, "Name", &limits).len(), 20);
+        // fewer matches than the cap
+        assert_eq!(filter(&data, "Name 99", &limits), vec!["Name 99"]);
+    }
+
+    #[test]
+    fn cap_keeps_the_best_matches_in_order() {
+        let data = values(&["Office", "Coffee", "Officer", "Off"]);
+        let all = filter(&data, "off", &SuggestionLimits::default());
+        let limits = SuggestionLimits {
+            max_results: 2,
+            min_input_length: 0,
+        };
+        assert_eq!(filter(&data, "off", &limits), all[..2].to_vec());
+    }
+
+    #[test]
+    fn short_input_has_no_suggestions() {
+        let data = values(&["Office", "Rent"]);
+        let limits = SuggestionLimits {
+            max_results: 20,
+            min_input_length: 2,
+        };
+        assert!(filter(&data, "", &limits).is_empty());
+        assert!(filter(&data, "o", &limits).is_empty());
+        assert_eq!(filter(&data, "of", &limits), vec!["Office"]);
+    }
+}
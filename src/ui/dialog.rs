@@ -25,9 +25,9 @@ pub(crate) enum DialogResponse {
     None,
 }
 
-pub(crate) fn render_dialog(ctx: &Context, dialog: &Dialog) -> DialogResponse {
-    let mut result = DialogResponse::None;
-    Window::new("dialog")
+// a fixed, centered window without a title bar
+pub(crate) fn modal_window(id: &'static str, size: [f32; 2]) -> Window<'static> {
+    Window::new(id)
         .movable(false)
         .resizable(false)
         .collapsible(false)
@@ -35,8 +35,13 @@ pub(crate) fn render_dialog(ctx: &Context, dialog: &Dialog) -> DialogResponse {
         .fade_in(false)
         .fade_out(false)
         .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .fixed_size(size)
+}
+
+pub(crate) fn render_dialog(ctx: &Context, dialog: &Dialog) -> DialogResponse {
+    let mut result = DialogResponse::None;
+    modal_window("dialog", [400.0, 100.0])
         .drag_to_scroll(false)
-        .fixed_size([400.0, 100.0])
         .show(ctx, |ui| {
             StripBuilder::new(ui)
                 .size(Size::remainder())
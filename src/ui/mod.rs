@@ -1,4 +1,6 @@
+use crate::data::{VATS, Vat};
 use crate::messages::Messages;
+use eframe::egui::{ComboBox, Key, Modifiers, Ui};
 use egui_file::FileDialog;
 use std::path::PathBuf;
 
@@ -31,6 +33,37 @@ pub(crate) fn get_localized_save_file_dialog(path: Option<PathBuf>, title: &str)
     get_localized_file_dialog(dialog, title)
 }
 
+// a compact VAT selector, which is in the tab order and can be changed with the arrow keys while
+// focused - returns, whether the VAT was changed
+pub(crate) fn vat_combo_box(ui: &mut Ui, id_salt: &str, vat: &mut Vat) -> bool {
+    let before = *vat;
+    let response = ComboBox::from_id_salt(id_salt)
+        .width(60.0)
+        .selected_text(vat.name())
+        .show_ui(ui, |ui| {
+            VATS.iter().for_each(|v| {
+                ui.selectable_value(vat, *v, v.name());
+            });
+        })
+        .response;
+    // an open popup handles the keys itself
+    if response.has_focus() && !ComboBox::is_open(ui.ctx(), response.id) {
+        let (up, down) = ui.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+            )
+        });
+        if up {
+            *vat = vat.previous();
+        }
+        if down {
+            *vat = vat.next();
+        }
+    }
+    *vat != before
+}
+
 pub(crate) fn get_localized_select_folder_dialog(path: Option<PathBuf>, title: &str) -> FileDialog {
     let dialog = FileDialog::select_folder(path);
     get_localized_file_dialog(dialog, title)
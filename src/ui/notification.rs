@@ -1,12 +1,27 @@
-use crate::{State, util::Colors};
+use crate::{State, messages::Messages, util::Colors};
 use chrono::{DateTime, Duration, Local};
 use eframe::egui::{
-    Align, Align2, Color32, Context, CursorIcon, Id, Label, Layout, RichText, Sense, Window,
+    Align, Align2, Button, Color32, Context, CursorIcon, Id, Label, Layout, RichText, Sense, Window,
 };
 use egui_extras::{Size, StripBuilder};
 
 const TIMEOUT_MS: i64 = 5000;
 const MAX_SHOW_TEXT_LEN: usize = 100;
+const MAX_NOTIFICATIONS: usize = 5;
+const WINDOW_HEIGHT: f32 = 50.0;
+
+// adds the notification, dropping the oldest of the least important ones beyond the cap
+pub(crate) fn push_notification(notifications: &mut Vec<Notification>, notification: Notification) {
+    notifications.push(notification);
+    while notifications.len() > MAX_NOTIFICATIONS {
+        let Some(lowest) = notifications.iter().map(Notification::priority).min() else {
+            return;
+        };
+        if let Some(idx) = notifications.iter().position(|n| n.priority() == lowest) {
+            notifications.remove(idx);
+        }
+    }
+}
 
 pub(crate) fn render_notifications(ctx: &Context, state: &mut State) {
     state
@@ -27,6 +42,17 @@ pub(crate) fn render_notifications(ctx: &Context, state: &mut State) {
                         inner.hidden = true
                     }
                 }
+                Notification::Warning(inner) => {
+                    if is_within_timeout(&inner.ts, &now) {
+                        if render_notification(ctx, i, &inner.text, "⚠", Colors::Warning.col())
+                            == HiddenState::Hide
+                        {
+                            inner.hidden = true;
+                        };
+                    } else {
+                        inner.hidden = true
+                    }
+                }
                 Notification::Info(inner) => {
                     if is_within_timeout(&inner.ts, &now) {
                         if render_notification(ctx, i, &inner.text, "ℹ", Colors::Info.col())
@@ -46,9 +72,40 @@ pub(crate) fn render_notifications(ctx: &Context, state: &mut State) {
         .clone()
         .into_iter()
         .filter(|n| match n {
-            Notification::Info(inner) | Notification::Error(inner) => !inner.hidden,
+            Notification::Info(inner)
+            | Notification::Warning(inner)
+            | Notification::Error(inner) => !inner.hidden,
         })
         .collect();
+
+    if state.notifications.len() > 1
+        && render_clear_all(ctx, state.notifications.len()) == HiddenState::Hide
+    {
+        state.notifications.clear();
+    }
+}
+
+fn offset_top(idx: usize) -> f32 {
+    idx as f32 * WINDOW_HEIGHT + (10.0 + idx as f32 * 20.0)
+}
+
+// rendered below the notifications, hides all of them at once
+fn render_clear_all(ctx: &Context, count: usize) -> HiddenState {
+    let mut hidden = HiddenState::Show;
+    Window::new("clear_notifications")
+        .movable(false)
+        .resizable(false)
+        .collapsible(false)
+        .title_bar(false)
+        .fade_in(false)
+        .fade_out(false)
+        .anchor(Align2::RIGHT_TOP, [-10.0, offset_top(count)])
+        .show(ctx, |ui| {
+            if ui.add(Button::new(Messages::ClearAll.msg())).clicked() {
+                hidden = HiddenState::Hide;
+            }
+        });
+    hidden
 }
 
 fn is_within_timeout(ts: &DateTime<Local>, now: &DateTime<Local>) -> bool {
@@ -70,8 +127,6 @@ fn render_notification(
     color: Color32,
 ) -> HiddenState {
     let mut hidden = HiddenState::Show;
-    let window_height = 50.0;
-    let offset_top: f32 = idx as f32 * window_height + (10.0 + idx as f32 * 20.0);
     Window::new(idx.to_string())
         .movable(false)
         .resizable(false)
@@ -79,9 +134,9 @@ fn render_notification(
         .title_bar(false)
         .fade_in(false)
         .fade_out(false)
-        .anchor(Align2::RIGHT_TOP, [-10.0, offset_top])
+        .anchor(Align2::RIGHT_TOP, [-10.0, offset_top(idx)])
         .drag_to_scroll(false)
-        .fixed_size([200.0, window_height])
+        .fixed_size([200.0, WINDOW_HEIGHT])
         .show(ctx, |ui| {
             if ui
                 .interact(
@@ -146,9 +201,20 @@ fn render_notification(
 #[derive(Debug, Clone)]
 pub(crate) enum Notification {
     Error(InnerNotification),
+    Warning(InnerNotification),
     Info(InnerNotification),
 }
 
+impl Notification {
+    fn priority(&self) -> u8 {
+        match self {
+            Notification::Info(_) => 0,
+            Notification::Warning(_) => 1,
+            Notification::Error(_) => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct InnerNotification {
     ts: DateTime<Local>,
@@ -165,3 +231,53 @@ impl InnerNotification {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(notifications: &[Notification]) -> Vec<&str> {
+        notifications
+            .iter()
+            .map(|n| match n {
+                Notification::Info(inner)
+                | Notification::Warning(inner)
+                | Notification::Error(inner) => inner.text.as_str(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cap_drops_oldest_infos_first() {
+        let mut notifications = vec![];
+        push_notification(
+            &mut notifications,
+            Notification::Error(InnerNotification::new(String::from("error 1"))),
+        );
+        for i in 1..=4 {
+            push_notification(
+                &mut notifications,
+                Notification::Info(InnerNotification::new(format!("info {i}"))),
+            );
+        }
+        push_notification(
+            &mut notifications,
+            Notification::Warning(InnerNotification::new(String::from("warning 1"))),
+        );
+        assert_eq!(
+            texts(&notifications),
+            vec!["error 1", "info 2", "info 3", "info 4", "warning 1"]
+        );
+
+        for i in 2..=6 {
+            push_notification(
+                &mut notifications,
+                Notification::Error(InnerNotification::new(format!("error {i}"))),
+            );
+        }
+        assert_eq!(
+            texts(&notifications),
+            vec!["error 2", "error 3", "error 4", "error 5", "error 6"]
+        );
+    }
+}
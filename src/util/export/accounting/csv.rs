@@ -0,0 +1,300 @@
+use crate::{
+    GuiError, Messages,
+    data::{AccountingItem, AccountingSheet, currency::CurrencyValue},
+    date_format,
+    util::{Month, Quarter, fiscal_year_of},
+};
+use chrono::Datelike;
+use std::path::Path;
+
+// semicolons, so the comma can be the decimal separator
+const SEPARATOR: &str = ";";
+
+// the periods the rows are grouped into, each followed by a subtotal row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SubtotalPeriod {
+    Month,
+    Quarter,
+}
+
+pub(crate) fn export_accounting_csv_with_subtotals(
+    file_name: &Path,
+    sheet: &AccountingSheet,
+    period: SubtotalPeriod,
+    fiscal_year_start_month: u32,
+) -> Result<(), GuiError> {
+    std::fs::write(
+        file_name,
+        render_csv_with_subtotals(sheet, period, fiscal_year_start_month),
+    )?;
+    Ok(())
+}
+
+pub(crate) fn render_csv_with_subtotals(
+    sheet: &AccountingSheet,
+    period: SubtotalPeriod,
+    fiscal_year_start_month: u32,
+) -> String {
+    let mut lines = vec![header()];
+
+    // numbered like in the table and the PDF, but bucketed by date
+    let mut items: Vec<(usize, &AccountingItem)> = sheet
+        .items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| (idx + 1, item))
+        .collect();
+    items.sort_by_key(|(_, item)| item.date);
+
+    let mut buckets: Vec<(String, Vec<(usize, &AccountingItem)>)> = vec![];
+    for (nr, item) in items {
+        let label = period_label(item, period, fiscal_year_start_month);
+        match buckets.last_mut() {
+            Some((last, bucket)) if *last == label => bucket.push((nr, item)),
+            _ => buckets.push((label, vec![(nr, item)])),
+        }
+    }
+
+    for (label, bucket) in buckets.iter() {
+        bucket
+            .iter()
+            .for_each(|(nr, item)| lines.push(item_row(*nr, item)));
+        lines.push(total_row(
+            &format!("{} {}", Messages::Subtotal.msg(), label),
+            bucket.iter().map(|(_, item)| *item),
+        ));
+    }
+    lines.push(total_row(Messages::Total.msg(), sheet.items.iter()));
+
+    let mut csv = lines.join("\n");
+    csv.push('\n');
+    csv
+}
+
+fn period_label(
+    item: &AccountingItem,
+    period: SubtotalPeriod,
+    fiscal_year_start_month: u32,
+) -> String {
+    match period {
+        SubtotalPeriod::Month => {
+            format!(
+                "{} {}",
+                Month::from(item.date.month()).name(),
+                item.date.year()
+            )
+        }
+        SubtotalPeriod::Quarter => format!(
+            "{} {}",
+            Quarter::from_month(item.date.month(), fiscal_year_start_month).name(),
+            fiscal_year_of(item.date, fiscal_year_start_month)
+        ),
+    }
+}
+
+fn header() -> String {
+    [
+        Messages::InvoiceNumber,
+        Messages::InvoiceType,
+        Messages::Date,
+        Messages::Name,
+        Messages::Company,
+        Messages::Category,
+        Messages::Net,
+        Messages::Vat,
+        Messages::Tax,
+        Messages::Gross,
+        Messages::Note,
+        Messages::Private,
+    ]
+    .iter()
+    .map(|m| escape(m.msg()))
+    .collect::<Vec<String>>()
+    .join(SEPARATOR)
+}
+
+fn item_row(nr: usize, item: &AccountingItem) -> String {
+    let vat_result = item.net.calculate_vat(item.vat);
+    [
+        nr.to_string(),
+        item.invoice_type.name().to_owned(),
+        item.date.format(date_format()).to_string(),
+        item.name.to_owned(),
+        item.company.0.to_owned(),
+        item.category.0.to_owned(),
+        item.net.to_value_string(),
+        item.vat.name().to_owned(),
+        vat_result.tax.to_value_string(),
+        vat_result.gross.to_value_string(),
+        item.note.to_owned(),
+        if item.excluded {
+            String::from("x")
+        } else {
+            String::new()
+        },
+    ]
+    .iter()
+    .map(|field| escape(field))
+    .collect::<Vec<String>>()
+    .join(SEPARATOR)
+}
+
+// the label goes into the name column, the sums into their columns - excluded items aren't summed
+fn total_row<'a>(label: &str, items: impl Iterator<Item = &'a AccountingItem>) -> String {
+    let (net, tax, gross) = items.filter(|item| !item.excluded).fold(
+        (
+            CurrencyValue::zero(),
+            CurrencyValue::zero(),
+            CurrencyValue::zero(),
+        ),
+        |(net, tax, gross), item| {
+            let vat_result = item.net.calculate_vat(item.vat);
+            (
+                net + item.net.clone(),
+                tax + vat_result.tax,
+                gross + vat_result.gross,
+            )
+        },
+    );
+    [
+        String::new(),
+        String::new(),
+        String::new(),
+        label.to_owned(),
+        String::new(),
+        String::new(),
+        net.to_value_string(),
+        String::new(),
+        tax.to_value_string(),
+        gross.to_value_string(),
+        String::new(),
+        String::new(),
+    ]
+    .iter()
+    .map(|field| escape(field))
+    .collect::<Vec<String>>()
+    .join(SEPARATOR)
+}
+
+// quotes fields containing the separator, quotes or line breaks
+fn escape(field: &str) -> String {
+    if field.contains(SEPARATOR) || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn item(date: (i32, u32, u32), net: i64) -> AccountingItem {
+        AccountingItem {
+            date: NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap(),
+            net: CurrencyValue::new(net),
+            ..AccountingItem::test_item()
+        }
+    }
+
+    fn sheet(items: Vec<AccountingItem>) -> AccountingSheet {
+        AccountingSheet {
+            year: 2024,
+            quarter: None,
+            month: None,
+            all_time: false,
+            items,
+        }
+    }
+
+    fn columns(line: &str) -> Vec<&str> {
+        line.split(SEPARATOR).collect()
+    }
+
+    fn value(cents: i64) -> String {
+        CurrencyValue::new(cents).to_value_string()
+    }
+
+    #[test]
+    fn monthly_subtotals_follow_their_items() {
+        let sheet = sheet(vec![
+            item((2024, 1, 5), 10000),
+            item((2024, 1, 20), 5000),
+            item((2024, 3, 1), 2000),
+        ]);
+        let csv = render_csv_with_subtotals(&sheet, SubtotalPeriod::Month, 1);
+        let lines: Vec<&str> = csv.lines().collect();
+        // header, 2 items + subtotal, 1 item + subtotal, grand total
+        assert_eq!(lines.len(), 7);
+
+        let january = columns(lines[3]);
+        assert_eq!(
+            january[3],
+            format!("{} {} 2024", Messages::Subtotal, Month::January.name())
+        );
+        assert_eq!(january[6], value(15000));
+        assert_eq!(january[8], value(3000));
+        assert_eq!(january[9], value(18000));
+
+        assert_eq!(columns(lines[4])[0], "3");
+        let march = columns(lines[5]);
+        assert_eq!(march[6], value(2000));
+
+        let total = columns(lines[6]);
+        assert_eq!(total[3], Messages::Total.msg());
+        assert_eq!(total[6], value(17000));
+        assert_eq!(total[9], value(20400));
+    }
+
+    #[test]
+    fn excluded_items_are_listed_but_not_summed() {
+        let private = AccountingItem {
+            excluded: true,
+            ..item((2024, 1, 20), 5000)
+        };
+        let sheet = sheet(vec![item((2024, 1, 5), 10000), private]);
+        let csv = render_csv_with_subtotals(&sheet, SubtotalPeriod::Month, 1);
+        let lines: Vec<&str> = csv.lines().collect();
+        // header, 2 items, subtotal, grand total
+        assert_eq!(lines.len(), 5);
+        assert_eq!(columns(lines[1])[11], "");
+        assert_eq!(columns(lines[2])[11], "x");
+        assert_eq!(columns(lines[2])[6], value(5000));
+        assert_eq!(columns(lines[3])[6], value(10000));
+        assert_eq!(columns(lines[4])[6], value(10000));
+        assert_eq!(columns(lines[4])[9], value(12000));
+    }
+
+    #[test]
+    fn quarterly_subtotals_use_the_fiscal_year() {
+        // fiscal year starting in July - August and September are both in Q1 2024
+        let sheet = sheet(vec![
+            item((2024, 9, 1), 1000),
+            item((2024, 8, 1), 3000),
+            item((2025, 1, 10), 500),
+        ]);
+        let csv = render_csv_with_subtotals(&sheet, SubtotalPeriod::Quarter, 7);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 7);
+
+        // sorted by date, but keeping their numbers
+        assert_eq!(columns(lines[1])[0], "2");
+        assert_eq!(columns(lines[2])[0], "1");
+        let q1 = columns(lines[3]);
+        assert_eq!(q1[3], format!("{} Q1 2024", Messages::Subtotal));
+        assert_eq!(q1[6], value(4000));
+
+        let q3 = columns(lines[5]);
+        assert_eq!(q3[3], format!("{} Q3 2024", Messages::Subtotal));
+        assert_eq!(q3[6], value(500));
+        assert_eq!(columns(lines[6])[6], value(4500));
+    }
+
+    #[test]
+    fn fields_with_separators_are_quoted() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a;b"), "\"a;b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
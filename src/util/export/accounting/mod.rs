@@ -1,14 +1,13 @@
 use crate::{
-    DATE_FORMAT, GuiError,
+    GuiError,
+    config::PageSize,
     data::{
-        AccountingItem, AccountingSheet, Category, InvoiceType,
-        currency::{CurrencyValue, VatCalculationResult, default_currency_value},
+        AccountingItem, AccountingSheet, Category, Company, InvoiceType,
+        currency::{CurrencyValue, VatCalculationResult},
     },
+    date_format,
     messages::Messages,
-    util::{
-        export::{FONT_SIZE, LINE_WIDTH, PADDING, ROW_HEIGHT},
-        files::SUFFIX_FOR_FILES,
-    },
+    util::export::{FONT_SIZE, LINE_WIDTH, PADDING, PT_TO_MM, ROW_HEIGHT, get_text_width},
 };
 use log::info;
 use printpdf::{Color, IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point, Rgb};
@@ -20,50 +19,117 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use super::{MARGIN, MAX_CHARS_CURRENCY, MAX_CHARS_VAT, TABLE_LINE_HEIGHT};
+use super::{MAX_CHARS_VAT, Page, TABLE_LINE_HEIGHT, right_aligned_x, use_text};
+
+pub(crate) mod csv;
 
-const ITEMS_PER_PAGE: usize = 22;
+// space on a page, which is not available for item rows (title, header, page number)
+const ITEMS_RESERVED_HEIGHT: f32 = 20.0;
 const SUMMARY_CUTOFF: usize = 8;
-const MAX_DIGITS_NR: i32 = 3;
 const CATEGORIES_SUMMARY_COLS: usize = 4;
 const CATEGORIES_SUMMARY_ITEMS_PER_COL: usize = 6;
 
-const WIDTH: Mm = Mm(297.0);
-const HEIGHT: Mm = Mm(210.0);
-const LEFT: Mm = Mm(MARGIN);
-const RIGHT: Mm = Mm(WIDTH.0 - MARGIN);
-const TOP: Mm = Mm(HEIGHT.0 - MARGIN);
-const BOTTOM: Mm = Mm(MARGIN);
-
 // COL WIDTHS
 const INVOICE_TYPE_WIDTH: Mm = Mm(18.0);
 const NR_WIDTH: Mm = Mm(10.0);
 const DATE_WIDTH: Mm = Mm(22.0);
-const COMPANY_NAME_WIDTH: Mm = Mm(80.0);
-const COMPANY_NAME_CUTOFF_CHARS: usize = 40;
 const CATEGORY_WIDTH: Mm = Mm(36.0);
 const CATEGORY_CUTOFF_CHARS: usize = 18;
 const NET_WIDTH: Mm = Mm(26.0);
 const VAT_WIDTH: Mm = Mm(12.0);
 const TAX_WIDTH: Mm = Mm(26.0);
+const GROSS_WIDTH: Mm = Mm(27.0);
 
 // SUMMARY WIDTHS
 const SUMMARY_INGOING_OUTGOING_WIDTH: Mm = Mm(20.0);
 const SUMMARY_NET_WIDTH: Mm = Mm(30.0);
 const SUMMARY_TAX_WIDTH: Mm = Mm(30.0);
-const SUMMARY_CATEGORY_WIDTH: Mm = Mm(34.0);
+const SUMMARY_GROSS_WIDTH: Mm = Mm(30.0);
+
+fn items_per_page(page: &Page) -> usize {
+    ((page.top.0 - page.bottom.0 - ITEMS_RESERVED_HEIGHT) / ROW_HEIGHT) as usize
+}
+
+// the summary gets its own page, if it doesn't fit below the items on the last page
+fn summary_needs_new_page(num_items: usize, items_per_page: usize) -> bool {
+    num_items % items_per_page > SUMMARY_CUTOFF
+}
+
+// the number of pages the exported PDF will have, including the summary
+pub(crate) fn estimate_accounting_pages(sheet: &AccountingSheet, page_size: PageSize) -> usize {
+    let items_per_page = items_per_page(&Page::landscape(page_size));
+    let num_items = sheet.items.len();
+    let item_pages = (num_items / items_per_page) + 1;
+    if summary_needs_new_page(num_items, items_per_page) {
+        item_pages + 1
+    } else {
+        item_pages
+    }
+}
+
+// the number of digits of the highest item number, which all numbers are right-aligned to
+fn nr_digits(num_items: usize) -> usize {
+    num_items.max(1).to_string().len()
+}
+
+// all digits have the same width
+fn digit_width() -> f32 {
+    get_text_width("0") * PT_TO_MM
+}
+
+// the company column takes up the space not used by the other columns
+fn company_name_width(page: &Page) -> Mm {
+    Mm(page.right.0
+        - page.left.0
+        - INVOICE_TYPE_WIDTH.0
+        - NR_WIDTH.0
+        - DATE_WIDTH.0
+        - CATEGORY_WIDTH.0
+        - NET_WIDTH.0
+        - VAT_WIDTH.0
+        - TAX_WIDTH.0
+        - GROSS_WIDTH.0)
+}
+
+fn company_name_cutoff_chars(page: &Page) -> usize {
+    (company_name_width(page).0 / 2.0) as usize
+}
+
+fn summary_category_width(page: &Page) -> Mm {
+    Mm((page.right.0 - page.left.0) / CATEGORIES_SUMMARY_COLS as f32 - SUMMARY_NET_WIDTH.0)
+}
 
 #[derive(Debug, Clone)]
-struct Summary {
+pub(crate) struct Summary {
     categories: HashMap<Category, CurrencyValue>,
-    accounting: HashMap<InvoiceType, AccountingSummary>,
+    pub(crate) accounting: HashMap<InvoiceType, AccountingSummary>,
+    // the net of outgoing items per company, biggest first
+    pub(crate) revenue_by_company: Vec<(Company, CurrencyValue)>,
+}
+
+impl Summary {
+    // net, tax and gross of all items, regardless of their invoice type
+    pub(crate) fn combined(&self) -> AccountingSummary {
+        let sums = || self.accounting.values();
+        AccountingSummary {
+            net: sums().map(|s| s.net.clone()).sum(),
+            tax: sums().map(|s| s.tax.clone()).sum(),
+            gross: sums().map(|s| s.gross.clone()).sum(),
+        }
+    }
+
+    // the percentage of the outgoing net, if there is any revenue
+    pub(crate) fn revenue_share(&self, net: &CurrencyValue) -> Option<Decimal> {
+        let revenue = &self.accounting.get(&InvoiceType::Out)?.net.value;
+        (!revenue.is_zero()).then(|| net.value * Decimal::ONE_HUNDRED / revenue)
+    }
 }
 
 #[derive(Debug, Clone)]
-struct AccountingSummary {
-    net: CurrencyValue,
-    tax: CurrencyValue,
-    gross: CurrencyValue,
+pub(crate) struct AccountingSummary {
+    pub(crate) net: CurrencyValue,
+    pub(crate) tax: CurrencyValue,
+    pub(crate) gross: CurrencyValue,
 }
 
 #[derive(Debug, Clone)]
@@ -76,14 +142,20 @@ pub(crate) struct CreatePDFResult {
 pub(crate) fn create_accounting_pdf(
     file_name: &Path,
     sheet: &AccountingSheet,
+    page_size: PageSize,
+    files_suffix: &str,
+    author: &str,
 ) -> Result<CreatePDFResult, GuiError> {
     // SETUP
+    let page = &Page::landscape(page_size);
+    let items_per_page = items_per_page(page);
     let title = create_title(sheet);
     let num_items = sheet.items.len();
-    let pages = (num_items / ITEMS_PER_PAGE) + 1;
+    let pages = (num_items / items_per_page) + 1;
     info!("items: {num_items}, pages: {pages}");
 
-    let (doc, page1, layer) = PdfDocument::new(&title, WIDTH, HEIGHT, "layer");
+    let (doc, page1, layer) = PdfDocument::new(&title, page.width, page.height, "layer");
+    let doc = doc.with_author(author).with_subject(create_subject(sheet));
     let font = doc
         .add_builtin_font(printpdf::BuiltinFont::Helvetica)
         .expect("font is available");
@@ -96,24 +168,32 @@ pub(crate) fn create_accounting_pdf(
     current_layer.set_outline_thickness(LINE_WIDTH);
 
     // TITLE
-    current_layer.use_text(&title, FONT_SIZE.0, LEFT, TOP, &bold_font);
+    use_text(
+        &current_layer,
+        &title,
+        FONT_SIZE.0,
+        page.left,
+        page.top,
+        &bold_font,
+    );
     let line = Line {
         points: vec![
-            (Point::new(LEFT, Mm(TOP.0 - PADDING)), false),
-            (Point::new(RIGHT, Mm(TOP.0 - PADDING)), false),
+            (Point::new(page.left, Mm(page.top.0 - PADDING)), false),
+            (Point::new(page.right, Mm(page.top.0 - PADDING)), false),
         ],
         is_closed: true,
     };
     current_layer.add_line(line);
 
     // Page 1
-    build_items_table(sheet, &current_layer, &font, &bold_font, 0);
+    build_items_table(page, sheet, &current_layer, &font, &bold_font, 0);
 
-    current_layer.use_text(
+    use_text(
+        &current_layer,
         "1",
         FONT_SIZE.0,
-        Mm(LEFT.0 + (RIGHT.0 - LEFT.0) / 2.0),
-        BOTTOM,
+        Mm(page.left.0 + (page.right.0 - page.left.0) / 2.0),
+        page.bottom,
         &font,
     );
 
@@ -122,18 +202,19 @@ pub(crate) fn create_accounting_pdf(
 
     // Pages 2 - N
     for i in 1..pages {
-        let (page_idx, layer_idx) = doc.add_page(WIDTH, HEIGHT, format!("layer{i}"));
+        let (page_idx, layer_idx) = doc.add_page(page.width, page.height, format!("layer{i}"));
         let layer = doc.get_page(page_idx).get_layer(layer_idx);
         layer.set_outline_color(Color::Rgb(Rgb::new(0.7, 0.7, 0.7, None)));
         layer.set_outline_thickness(LINE_WIDTH);
 
-        build_items_table(sheet, &layer, &font, &bold_font, i * ITEMS_PER_PAGE);
+        build_items_table(page, sheet, &layer, &font, &bold_font, i * items_per_page);
 
-        layer.use_text(
+        use_text(
+            &layer,
             format!("{}", i + 1),
             FONT_SIZE.0,
-            Mm(LEFT.0 + (RIGHT.0 - LEFT.0) / 2.0),
-            BOTTOM,
+            Mm(page.left.0 + (page.right.0 - page.left.0) / 2.0),
+            page.bottom,
             &font,
         );
 
@@ -142,126 +223,117 @@ pub(crate) fn create_accounting_pdf(
     }
 
     // SUMMARY
-    let rest = num_items % ITEMS_PER_PAGE;
-    let summary_needs_new_page = rest > SUMMARY_CUTOFF;
+    let rest = num_items % items_per_page;
+    let summary_needs_new_page = summary_needs_new_page(num_items, items_per_page);
     info!("new page: {summary_needs_new_page}, {rest}");
     let (layer, top) = if summary_needs_new_page {
-        let (page_idx, layer_idx) = doc.add_page(WIDTH, HEIGHT, format!("layer{}", pages));
-        (doc.get_page(page_idx).get_layer(layer_idx), TOP)
+        let (page_idx, layer_idx) =
+            doc.add_page(page.width, page.height, format!("layer{}", pages));
+        (doc.get_page(page_idx).get_layer(layer_idx), page.top)
     } else {
         // use last page, right after items + 1 ROW HEIGHT
         (
             doc.get_page(last_page_idx).get_layer(last_layer_idx),
-            Mm(TOP.0 - ((rest + 3) as f32 * ROW_HEIGHT)),
+            Mm(page.top.0 - ((rest + 3) as f32 * ROW_HEIGHT)),
         )
     };
     layer.set_outline_color(Color::Rgb(Rgb::new(0.7, 0.7, 0.7, None)));
     layer.set_outline_thickness(LINE_WIDTH);
     let summary = calculate_summary(sheet);
-    build_summary(&summary, top, &layer, &font, &bold_font);
+    build_summary(page, &summary, top, &layer, &font, &bold_font);
 
     // SAVE (overwrites the file)
-    doc.save(&mut BufWriter::new(
-        File::create(file_name).map_err(|e| GuiError::ExportFailed(e.to_string()))?,
-    ))
-    .map_err(|e| GuiError::ExportFailed(e.to_string()))?;
+    doc.save(&mut BufWriter::new(File::create(file_name)?))
+        .map_err(|e| GuiError::ExportFailed(e.to_string()))?;
 
     // Create files folder, if it exists, remove the old one first
     let folder_name = file_name.with_extension("");
     let files_folder = PathBuf::from(format!(
         "{}{}",
         folder_name.to_str().expect("path is valid utf-8"),
-        SUFFIX_FOR_FILES
+        files_suffix
     ));
 
     if files_folder.exists() {
-        remove_dir_all(&files_folder).map_err(|e| GuiError::ExportFailed(e.to_string()))?;
+        remove_dir_all(&files_folder)?;
     }
 
-    create_dir_all(&files_folder).map_err(|e| GuiError::ExportFailed(e.to_string()))?;
+    create_dir_all(&files_folder)?;
     Ok(CreatePDFResult {
         file: file_name.to_path_buf(),
         files_folder,
     })
 }
 
-fn calculate_summary(sheet: &AccountingSheet) -> Summary {
-    let mut categories: HashMap<Category, Decimal> = HashMap::new();
-    let mut accounting = HashMap::new();
-    let mut out_net_sum = default_currency_value();
-    let mut out_tax_sum = default_currency_value();
-    let mut out_gross_sum = default_currency_value();
-    let mut in_net_sum = default_currency_value();
-    let mut in_tax_sum = default_currency_value();
-    let mut in_gross_sum = default_currency_value();
-
-    sheet.items.iter().for_each(|item| match item.invoice_type {
-        InvoiceType::Out => {
-            let net = &item.net;
-            out_net_sum = out_net_sum
-                .checked_add(net.value)
-                .unwrap_or_else(default_currency_value);
-            let VatCalculationResult { tax, gross } = net.calculate_vat(item.vat);
-            out_tax_sum = out_tax_sum
-                .checked_add(tax.value)
-                .unwrap_or_else(default_currency_value);
-            out_gross_sum = out_gross_sum
-                .checked_add(gross.value)
-                .unwrap_or_else(default_currency_value);
-        }
-        InvoiceType::In => {
-            let net = &item.net;
-            in_net_sum = in_net_sum
-                .checked_add(net.value)
-                .unwrap_or_else(default_currency_value);
-            let VatCalculationResult { tax, gross } = net.calculate_vat(item.vat);
-            in_tax_sum = in_tax_sum
-                .checked_add(tax.value)
-                .unwrap_or_else(default_currency_value);
-            in_gross_sum = in_gross_sum
-                .checked_add(gross.value)
-                .unwrap_or_else(default_currency_value);
-
-            let category = &item.category;
-            categories
-                .entry(category.to_owned())
-                .and_modify(|v| {
-                    *v = v
-                        .checked_add(net.value)
-                        .unwrap_or_else(default_currency_value)
-                })
-                .or_insert(net.value);
-        }
+// excluded items are listed, but not summed up
+fn summed_items(sheet: &AccountingSheet) -> impl Iterator<Item = &AccountingItem> {
+    sheet.items.iter().filter(|item| !item.excluded)
+}
+
+pub(crate) fn calculate_summary(sheet: &AccountingSheet) -> Summary {
+    let mut categories: HashMap<Category, CurrencyValue> = HashMap::new();
+    summed_items(sheet)
+        .filter(|item| item.invoice_type == InvoiceType::In)
+        .for_each(|item| {
+            let sum = categories
+                .entry(item.category.to_owned())
+                .or_insert_with(CurrencyValue::zero);
+            *sum = sum.clone() + item.net.clone();
+        });
+
+    let mut companies: HashMap<Company, CurrencyValue> = HashMap::new();
+    summed_items(sheet)
+        .filter(|item| item.invoice_type == InvoiceType::Out)
+        .for_each(|item| {
+            let sum = companies
+                .entry(item.company.to_owned())
+                .or_insert_with(CurrencyValue::zero);
+            *sum = sum.clone() + item.net.clone();
+        });
+    let mut revenue_by_company: Vec<(Company, CurrencyValue)> = companies.into_iter().collect();
+    revenue_by_company.sort_by(|(company_a, net_a), (company_b, net_b)| {
+        net_b.cmp(net_a).then_with(|| company_a.0.cmp(&company_b.0))
     });
 
-    accounting.insert(
-        InvoiceType::In,
-        AccountingSummary {
-            net: CurrencyValue::new_from_decimal(in_net_sum),
-            tax: CurrencyValue::new_from_decimal(in_tax_sum),
-            gross: CurrencyValue::new_from_decimal(in_gross_sum),
-        },
-    );
-    accounting.insert(
-        InvoiceType::Out,
-        AccountingSummary {
-            net: CurrencyValue::new_from_decimal(out_net_sum),
-            tax: CurrencyValue::new_from_decimal(out_tax_sum),
-            gross: CurrencyValue::new_from_decimal(out_gross_sum),
-        },
-    );
+    let mut accounting = HashMap::new();
+    accounting.insert(InvoiceType::In, summarize(sheet, InvoiceType::In));
+    accounting.insert(InvoiceType::Out, summarize(sheet, InvoiceType::Out));
 
     Summary {
-        categories: categories
-            .into_iter()
-            .map(|(k, v)| (k, CurrencyValue::new_from_decimal(v)))
-            .collect(),
+        categories,
         accounting,
+        revenue_by_company,
+    }
+}
+
+fn summarize(sheet: &AccountingSheet, invoice_type: InvoiceType) -> AccountingSummary {
+    let items = || summed_items(sheet).filter(move |item| item.invoice_type == invoice_type);
+    AccountingSummary {
+        net: items().map(|item| item.net.clone()).sum(),
+        tax: items()
+            .map(|item| item.net.calculate_vat(item.vat).tax)
+            .sum(),
+        gross: items()
+            .map(|item| item.net.calculate_vat(item.vat).gross)
+            .sum(),
     }
 }
 
 fn create_title(sheet: &AccountingSheet) -> String {
-    let mut title = format!("{} - {} ", Messages::Accounting.msg(), sheet.year);
+    format!("{} - {}", Messages::Accounting.msg(), period_name(sheet))
+}
+
+// e.g. "Accounting 2024 Q1", the subject of the PDF
+fn create_subject(sheet: &AccountingSheet) -> String {
+    format!("{} {}", Messages::Accounting.msg(), period_name(sheet))
+}
+
+// e.g. "2024 Q1", used in the title and the export log
+pub(crate) fn period_name(sheet: &AccountingSheet) -> String {
+    if sheet.all_time {
+        return Messages::AllTime.msg().to_owned();
+    }
+    let mut name = format!("{} ", sheet.year);
     match sheet.quarter {
         None => {
             match sheet.month {
@@ -269,15 +341,15 @@ fn create_title(sheet: &AccountingSheet) -> String {
                     // do nothing
                 }
                 Some(month) => {
-                    title.push_str(month.name());
+                    name.push_str(month.name());
                 }
             }
         }
         Some(quarter) => {
-            title.push_str(quarter.name());
+            name.push_str(quarter.name());
         }
     };
-    title
+    name.trim_end().to_owned()
 }
 
 // TABLE
@@ -294,6 +366,7 @@ fn create_title(sheet: &AccountingSheet) -> String {
 // |      |    |                |       |       |     |     |       |       |     |     |          |
 // -------------------------------------------------------------------------------------------------
 fn build_items_table(
+    page: &Page,
     sheet: &AccountingSheet,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
@@ -301,19 +374,22 @@ fn build_items_table(
     from_item: usize,
 ) {
     let top = match from_item {
-        0 => Mm(TOP.0 - 5.0 * PADDING),
-        _ => Mm(TOP.0 - PADDING),
+        0 => Mm(page.top.0 - 5.0 * PADDING),
+        _ => Mm(page.top.0 - PADDING),
     };
-    render_table_header(top, layer, bold_font);
+    render_table_header(page, top, layer, bold_font);
+    let max_digits = nr_digits(sheet.items.len());
     for (idx, item) in sheet
         .items
         .iter()
         .skip(from_item)
-        .take(ITEMS_PER_PAGE)
+        .take(items_per_page(page))
         .enumerate()
     {
         render_row(
+            page,
             from_item + idx + 1,
+            max_digits,
             item,
             Mm(top.0 - ROW_HEIGHT - (idx as f32 * ROW_HEIGHT)),
             layer,
@@ -322,94 +398,94 @@ fn build_items_table(
     }
 }
 
-fn render_table_header(top: Mm, layer: &PdfLayerReference, font: &IndirectFontRef) {
+fn render_table_header(page: &Page, top: Mm, layer: &PdfLayerReference, font: &IndirectFontRef) {
     let mut col_line_x = 0.0;
     // START OF ROW
-    render_row_line(top, layer);
-    render_col_line(LEFT, top, layer);
+    render_row_line(page, top, layer);
+    render_col_line(page.left, top, layer);
     // Invoice Type
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::InvoiceType.msg(),
         layer,
         font,
     );
     col_line_x += INVOICE_TYPE_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Number
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::InvoiceNumber.msg(),
         layer,
         font,
     );
     col_line_x += NR_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Date
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Date.msg(),
         layer,
         font,
     );
     col_line_x += DATE_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // COMPANY + NAME
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         &format!("{} - {}", Messages::Company.msg(), Messages::Name.msg()),
         layer,
         font,
     );
-    col_line_x += COMPANY_NAME_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    col_line_x += company_name_width(page).0;
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // CATEGORY
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Category.msg(),
         layer,
         font,
     );
     col_line_x += CATEGORY_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // NET
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Net.msg(),
         layer,
         font,
     );
     col_line_x += NET_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // VAT
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Vat.msg(),
         layer,
         font,
     );
     col_line_x += VAT_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Tax
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Tax.msg(),
         layer,
         font,
     );
     col_line_x += TAX_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Gross
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Gross.msg(),
         layer,
@@ -418,12 +494,32 @@ fn render_table_header(top: Mm, layer: &PdfLayerReference, font: &IndirectFontRe
     // Omit last col_line, since it's the row's col line
 
     // END OF ROW
-    render_col_line(RIGHT, top, layer);
-    render_row_line(Mm(top.0 - ROW_HEIGHT), layer);
+    render_col_line(page.right, top, layer);
+    render_row_line(page, Mm(top.0 - ROW_HEIGHT), layer);
+}
+
+// excluded items are marked, since they're listed, but not part of the totals - the marker is
+// kept when the text is cut off
+fn company_name_text(item: &AccountingItem, cutoff_chars: usize) -> String {
+    let mut text: String = format!("{} - {}", &item.company.0, &item.name);
+    let marker = if item.excluded {
+        format!(" ({})", Messages::Private.msg())
+    } else {
+        String::new()
+    };
+    let cutoff = cutoff_chars.saturating_sub(marker.chars().count());
+    if text.chars().count() > cutoff {
+        text = text.chars().take(cutoff).collect();
+        text.push_str("...");
+    }
+    text.push_str(&marker);
+    text
 }
 
 fn render_row(
+    page: &Page,
     idx: usize,
+    max_digits: usize,
     item: &AccountingItem,
     top: Mm,
     layer: &PdfLayerReference,
@@ -431,61 +527,54 @@ fn render_row(
 ) {
     let mut col_line_x = 0.0;
     // START OF ROW
-    render_row_line(top, layer);
-    render_col_line(LEFT, top, layer);
+    render_row_line(page, top, layer);
+    render_col_line(page.left, top, layer);
     // Invoice Type
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         item.invoice_type.name(),
         layer,
         font,
     );
     col_line_x += INVOICE_TYPE_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Number
     let nr_str = idx.to_string();
     render_col_text(
-        Mm(LEFT.0
+        // right-align to the highest number
+        Mm(page.left.0
             + col_line_x
             + PADDING
-            + ((MAX_DIGITS_NR - nr_str.chars().count() as i32) as f32 * PADDING)), // right-align for max. 3
-        // numbers
+            + (max_digits.saturating_sub(nr_str.len()) as f32 * digit_width())),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         &nr_str,
         layer,
         font,
     );
     col_line_x += NR_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Date
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
-        &item.date.format(DATE_FORMAT).to_string(),
+        &item.date.format(date_format()).to_string(),
         layer,
         font,
     );
     col_line_x += DATE_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // COMPANY + NAME
-    let mut company_name_str: String = format!("{} - {}", &item.company.0, &item.name);
-    if company_name_str.chars().count() > COMPANY_NAME_CUTOFF_CHARS {
-        company_name_str = company_name_str
-            .chars()
-            .take(COMPANY_NAME_CUTOFF_CHARS)
-            .collect();
-        company_name_str.push_str("...");
-    }
+    let company_name_str = company_name_text(item, company_name_cutoff_chars(page));
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         &company_name_str,
         layer,
         font,
     );
-    col_line_x += COMPANY_NAME_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    col_line_x += company_name_width(page).0;
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // CATEGORY
     let mut category_str = item.category.0.clone();
     if category_str.chars().count() > CATEGORY_CUTOFF_CHARS {
@@ -493,34 +582,30 @@ fn render_row(
         category_str.push_str("...");
     }
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         &category_str,
         layer,
         font,
     );
     col_line_x += CATEGORY_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // NET
-    let net_str = item.net.to_str();
+    let net_str = &item.net.to_display_string();
     render_col_text(
-        // right-align for max. 11 characters
-        Mm(LEFT.0
-            + col_line_x
-            + PADDING
-            + ((MAX_CHARS_CURRENCY - net_str.chars().count() as i32) as f32 * PADDING)),
+        right_aligned_x(net_str, page.left.0 + col_line_x + NET_WIDTH.0),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         net_str,
         layer,
         font,
     );
     col_line_x += NET_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // VAT
     let vat_str = item.vat.name();
     render_col_text(
         // right-align for max. 4 characters
-        Mm(LEFT.0
+        Mm(page.left.0
             + col_line_x
             + PADDING
             + ((MAX_CHARS_VAT - vat_str.chars().count() as i32) as f32 * PADDING)),
@@ -530,31 +615,23 @@ fn render_row(
         font,
     );
     col_line_x += VAT_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     let VatCalculationResult { tax, gross } = item.net.calculate_vat(item.vat);
     // Tax
-    let tax_str = tax.to_str();
+    let tax_str = &tax.to_display_string();
     render_col_text(
-        // right-align for max. 10 characters
-        Mm(LEFT.0
-            + col_line_x
-            + PADDING
-            + ((MAX_CHARS_CURRENCY - tax_str.chars().count() as i32) as f32 * PADDING)),
+        right_aligned_x(tax_str, page.left.0 + col_line_x + TAX_WIDTH.0),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         tax_str,
         layer,
         font,
     );
     col_line_x += TAX_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Gross
-    let gross_str = gross.to_str();
+    let gross_str = &gross.to_display_string();
     render_col_text(
-        // right-align for max. 11 characters
-        Mm(LEFT.0
-            + col_line_x
-            + PADDING
-            + ((MAX_CHARS_CURRENCY - gross_str.chars().count() as i32) as f32 * PADDING)),
+        right_aligned_x(gross_str, page.right.0),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         gross_str,
         layer,
@@ -563,13 +640,16 @@ fn render_row(
     // Omit last col_line, since it's the row's col line
 
     // END OF ROW
-    render_col_line(RIGHT, top, layer);
-    render_row_line(Mm(top.0 - ROW_HEIGHT), layer);
+    render_col_line(page.right, top, layer);
+    render_row_line(page, Mm(top.0 - ROW_HEIGHT), layer);
 }
 
-fn render_row_line(y: Mm, layer: &PdfLayerReference) {
+fn render_row_line(page: &Page, y: Mm, layer: &PdfLayerReference) {
     let line = Line {
-        points: vec![(Point::new(LEFT, y), false), (Point::new(RIGHT, y), false)],
+        points: vec![
+            (Point::new(page.left, y), false),
+            (Point::new(page.right, y), false),
+        ],
         is_closed: true,
     };
 
@@ -578,7 +658,7 @@ fn render_row_line(y: Mm, layer: &PdfLayerReference) {
 
 fn render_col_text(x: Mm, y: Mm, text: &str, layer: &PdfLayerReference, font: &IndirectFontRef) {
     layer.set_line_height(TABLE_LINE_HEIGHT.0);
-    layer.use_text(text, FONT_SIZE.0, x, y, font);
+    use_text(layer, text, FONT_SIZE.0, x, y, font);
 }
 
 fn render_col_line(x: Mm, y: Mm, layer: &PdfLayerReference) {
@@ -596,6 +676,7 @@ fn render_col_line(x: Mm, y: Mm, layer: &PdfLayerReference) {
 // SUMMARY
 
 fn build_summary(
+    page: &Page,
     summary: &Summary,
     top: Mm,
     layer: &PdfLayerReference,
@@ -603,40 +684,48 @@ fn build_summary(
     bold_font: &IndirectFontRef,
 ) {
     // TITLE
-    layer.use_text(
+    use_text(
+        layer,
         Messages::AccountingSummary.msg(),
         FONT_SIZE.0,
-        Mm(LEFT.0 + (RIGHT.0 - LEFT.0) / 2.0),
+        Mm(page.left.0 + (page.right.0 - page.left.0) / 2.0),
         Mm(top.0 - 1.0 * ROW_HEIGHT),
         bold_font,
     );
 
     // Accounting headers
-    layer.use_text(
+    use_text(
+        layer,
         Messages::InvoiceType.msg(),
         FONT_SIZE.0,
-        Mm(LEFT.0),
+        Mm(page.left.0),
         Mm(top.0 - 2.0 * ROW_HEIGHT),
         bold_font,
     );
-    layer.use_text(
+    use_text(
+        layer,
         Messages::Net.msg(),
         FONT_SIZE.0,
-        Mm(LEFT.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0),
+        Mm(page.left.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0),
         Mm(top.0 - 2.0 * ROW_HEIGHT),
         bold_font,
     );
-    layer.use_text(
+    use_text(
+        layer,
         Messages::Tax.msg(),
         FONT_SIZE.0,
-        Mm(LEFT.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0 + SUMMARY_NET_WIDTH.0),
+        Mm(page.left.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0 + SUMMARY_NET_WIDTH.0),
         Mm(top.0 - 2.0 * ROW_HEIGHT),
         bold_font,
     );
-    layer.use_text(
+    use_text(
+        layer,
         Messages::Gross.msg(),
         FONT_SIZE.0,
-        Mm(LEFT.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0 + SUMMARY_NET_WIDTH.0 + SUMMARY_TAX_WIDTH.0),
+        Mm(page.left.0
+            + SUMMARY_INGOING_OUTGOING_WIDTH.0
+            + SUMMARY_NET_WIDTH.0
+            + SUMMARY_TAX_WIDTH.0),
         Mm(top.0 - 2.0 * ROW_HEIGHT),
         bold_font,
     );
@@ -644,12 +733,12 @@ fn build_summary(
     let line = Line {
         points: vec![
             (
-                Point::new(LEFT, Mm(top.0 - 2.0 * ROW_HEIGHT - PADDING)),
+                Point::new(page.left, Mm(top.0 - 2.0 * ROW_HEIGHT - PADDING)),
                 false,
             ),
             (
                 Point::new(
-                    Mm(LEFT.0
+                    Mm(page.left.0
                         + SUMMARY_INGOING_OUTGOING_WIDTH.0
                         + SUMMARY_NET_WIDTH.0
                         + SUMMARY_NET_WIDTH.0
@@ -667,14 +756,14 @@ fn build_summary(
         points: vec![
             (
                 Point::new(
-                    Mm(LEFT.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0 - PADDING),
+                    Mm(page.left.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0 - PADDING),
                     Mm(top.0 - 1.0 * ROW_HEIGHT - PADDING),
                 ),
                 false,
             ),
             (
                 Point::new(
-                    Mm(LEFT.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0 - PADDING),
+                    Mm(page.left.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0 - PADDING),
                     Mm(top.0 - 4.0 * ROW_HEIGHT - PADDING),
                 ),
                 false,
@@ -684,14 +773,16 @@ fn build_summary(
     };
     layer.add_line(line);
     // INGOING
-    layer.use_text(
+    use_text(
+        layer,
         Messages::Ingoing.msg(),
         FONT_SIZE.0,
-        LEFT,
+        page.left,
         Mm(top.0 - 3.0 * ROW_HEIGHT),
         bold_font,
     );
     render_accounting_summary(
+        page,
         summary.accounting.get(&InvoiceType::In),
         layer,
         font,
@@ -699,14 +790,16 @@ fn build_summary(
     );
 
     // OUTGOING
-    layer.use_text(
+    use_text(
+        layer,
         Messages::Outgoing.msg(),
         FONT_SIZE.0,
-        LEFT,
+        page.left,
         Mm(top.0 - 4.0 * ROW_HEIGHT),
         bold_font,
     );
     render_accounting_summary(
+        page,
         summary.accounting.get(&InvoiceType::Out),
         layer,
         font,
@@ -714,10 +807,11 @@ fn build_summary(
     );
 
     // CATEGORIES
-    layer.use_text(
+    use_text(
+        layer,
         Messages::CategoriesSummary.msg(),
         FONT_SIZE.0,
-        Mm(LEFT.0 + (RIGHT.0 - LEFT.0) / 2.0),
+        Mm(page.left.0 + (page.right.0 - page.left.0) / 2.0),
         Mm(top.0 - 6.0 * ROW_HEIGHT),
         bold_font,
     );
@@ -725,11 +819,11 @@ fn build_summary(
     let line = Line {
         points: vec![
             (
-                Point::new(LEFT, Mm(top.0 - 8.0 * ROW_HEIGHT - PADDING)),
+                Point::new(page.left, Mm(top.0 - 8.0 * ROW_HEIGHT - PADDING)),
                 false,
             ),
             (
-                Point::new(RIGHT, Mm(top.0 - 8.0 * ROW_HEIGHT - PADDING)),
+                Point::new(page.right, Mm(top.0 - 8.0 * ROW_HEIGHT - PADDING)),
                 false,
             ),
         ],
@@ -739,19 +833,22 @@ fn build_summary(
 
     let line_padding = 4.0;
     for i in 0..CATEGORIES_SUMMARY_COLS {
-        let left = Mm(LEFT.0 + (i as f32 * (SUMMARY_CATEGORY_WIDTH.0 + SUMMARY_NET_WIDTH.0)));
+        let left =
+            Mm(page.left.0 + (i as f32 * (summary_category_width(page).0 + SUMMARY_NET_WIDTH.0)));
         // Category headers
-        layer.use_text(
+        use_text(
+            layer,
             Messages::Category.msg(),
             FONT_SIZE.0,
             left,
             Mm(top.0 - 8.0 * ROW_HEIGHT),
             bold_font,
         );
-        layer.use_text(
+        use_text(
+            layer,
             format!("{} ({})", Messages::Sum.msg(), Messages::Net.msg()),
             FONT_SIZE.0,
-            Mm(left.0 + SUMMARY_CATEGORY_WIDTH.0),
+            Mm(left.0 + summary_category_width(page).0),
             Mm(top.0 - 8.0 * ROW_HEIGHT),
             bold_font,
         );
@@ -763,7 +860,7 @@ fn build_summary(
                         false,
                     ),
                     (
-                        Point::new(Mm(left.0 - line_padding), Mm(BOTTOM.0 + ROW_HEIGHT)),
+                        Point::new(Mm(left.0 - line_padding), Mm(page.bottom.0 + ROW_HEIGHT)),
                         false,
                     ),
                 ],
@@ -784,20 +881,24 @@ fn build_summary(
                     category_str = category_str.chars().take(CATEGORY_CUTOFF_CHARS).collect();
                     category_str.push_str("...");
                 }
-                layer.use_text(
+                use_text(
+                    layer,
                     &category_str,
                     FONT_SIZE.0,
                     left,
                     Mm(top.0 - (9.0 + idx as f32) * ROW_HEIGHT),
                     font,
                 );
-                let net_str = v.to_str();
-                layer.use_text(
+                let net_str = &v.to_display_string();
+                use_text(
+                    layer,
                     net_str,
                     FONT_SIZE.0,
-                    Mm(left.0
-                        + SUMMARY_CATEGORY_WIDTH.0
-                        + ((MAX_CHARS_CURRENCY - net_str.chars().count() as i32) as f32 * PADDING)),
+                    right_aligned_x(
+                        net_str,
+                        left.0 + summary_category_width(page).0 + SUMMARY_NET_WIDTH.0
+                            - line_padding,
+                    ),
                     Mm(top.0 - (9.0 + idx as f32) * ROW_HEIGHT),
                     font,
                 );
@@ -806,42 +907,53 @@ fn build_summary(
 }
 
 fn render_accounting_summary(
+    page: &Page,
     accounting_summary: Option<&AccountingSummary>,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
     top: Mm,
 ) {
     if let Some(acc_sum) = accounting_summary {
-        let net_str = acc_sum.net.to_str();
-        layer.use_text(
+        let net_str = &acc_sum.net.to_display_string();
+        use_text(
+            layer,
             net_str,
             FONT_SIZE.0,
-            Mm(LEFT.0
-                + SUMMARY_INGOING_OUTGOING_WIDTH.0
-                + ((MAX_CHARS_CURRENCY - net_str.chars().count() as i32) as f32 * PADDING)),
+            right_aligned_x(
+                net_str,
+                page.left.0 + SUMMARY_INGOING_OUTGOING_WIDTH.0 + SUMMARY_NET_WIDTH.0,
+            ),
             top,
             font,
         );
-        let tax_str = acc_sum.tax.to_str();
-        layer.use_text(
+        let tax_str = &acc_sum.tax.to_display_string();
+        use_text(
+            layer,
             tax_str,
             FONT_SIZE.0,
-            Mm(LEFT.0
-                + SUMMARY_INGOING_OUTGOING_WIDTH.0
-                + SUMMARY_NET_WIDTH.0
-                + ((MAX_CHARS_CURRENCY - tax_str.chars().count() as i32) as f32 * PADDING)),
+            right_aligned_x(
+                tax_str,
+                page.left.0
+                    + SUMMARY_INGOING_OUTGOING_WIDTH.0
+                    + SUMMARY_NET_WIDTH.0
+                    + SUMMARY_TAX_WIDTH.0,
+            ),
             top,
             font,
         );
-        let gross_str = acc_sum.gross.to_str();
-        layer.use_text(
+        let gross_str = &acc_sum.gross.to_display_string();
+        use_text(
+            layer,
             gross_str,
             FONT_SIZE.0,
-            Mm(LEFT.0
-                + SUMMARY_INGOING_OUTGOING_WIDTH.0
-                + SUMMARY_NET_WIDTH.0
-                + SUMMARY_TAX_WIDTH.0
-                + ((MAX_CHARS_CURRENCY - gross_str.chars().count() as i32) as f32 * PADDING)),
+            right_aligned_x(
+                gross_str,
+                page.left.0
+                    + SUMMARY_INGOING_OUTGOING_WIDTH.0
+                    + SUMMARY_NET_WIDTH.0
+                    + SUMMARY_TAX_WIDTH.0
+                    + SUMMARY_GROSS_WIDTH.0,
+            ),
             top,
             font,
         );
@@ -852,10 +964,10 @@ fn render_accounting_summary(
 mod tests {
     use super::*;
     use crate::{
-        data::{Company, Vat},
+        data::{Company, Vat, currency::default_currency_value},
         util::Quarter,
     };
-    use uuid::Uuid;
+    use rust_decimal::Decimal;
     fn accounting_item(
         it: InvoiceType,
         net: CurrencyValue,
@@ -864,14 +976,10 @@ mod tests {
     ) -> AccountingItem {
         AccountingItem {
             invoice_type: it,
-            id: Uuid::now_v7(),
-            date: chrono::Local::now().date_naive(),
-            name: String::from("some name"),
-            company: Company(String::from("some company")),
             net,
             vat,
             category,
-            file: PathBuf::from("/some/file"),
+            ..AccountingItem::test_item()
         }
     }
 
@@ -882,6 +990,7 @@ mod tests {
             year: 2024,
             month: None,
             quarter: Some(Quarter::Q1),
+            all_time: false,
         };
         let result = calculate_summary(&sheet);
         assert!(result.categories.is_empty());
@@ -910,6 +1019,7 @@ mod tests {
             year: 2024,
             month: None,
             quarter: Some(Quarter::Q1),
+            all_time: false,
         };
 
         let result = calculate_summary(&sheet);
@@ -934,6 +1044,59 @@ mod tests {
         assert!(outgoing.gross.value.eq(&default_currency_value()));
     }
 
+    #[test]
+    fn calculate_summary_revenue_by_company() {
+        let out = |company: &str, net: i64| AccountingItem {
+            company: Company(String::from(company)),
+            ..accounting_item(
+                InvoiceType::Out,
+                CurrencyValue::new(net),
+                Vat::Twenty,
+                Category(String::from("revenue")),
+            )
+        };
+        let sheet = AccountingSheet {
+            items: vec![
+                out("b", 10000),
+                out("a", 5000),
+                out("b", 20000),
+                out("c", 15000),
+                accounting_item(
+                    InvoiceType::In,
+                    CurrencyValue::new(99999),
+                    Vat::Twenty,
+                    Category(String::from("rent")),
+                ),
+            ],
+            year: 2024,
+            month: None,
+            quarter: None,
+            all_time: false,
+        };
+
+        let result = calculate_summary(&sheet);
+        assert_eq!(
+            result.revenue_by_company,
+            vec![
+                (Company(String::from("b")), CurrencyValue::new(30000)),
+                (Company(String::from("c")), CurrencyValue::new(15000)),
+                (Company(String::from("a")), CurrencyValue::new(5000)),
+            ]
+        );
+        assert_eq!(
+            result.revenue_share(&CurrencyValue::new(30000)),
+            Some(Decimal::new(60, 0))
+        );
+        assert_eq!(
+            calculate_summary(&AccountingSheet {
+                items: vec![],
+                ..sheet
+            })
+            .revenue_share(&CurrencyValue::new(100)),
+            None
+        );
+    }
+
     #[test]
     fn calculate_summary_in_out() {
         let net = CurrencyValue::new(225000);
@@ -956,6 +1119,7 @@ mod tests {
             year: 2024,
             month: None,
             quarter: Some(Quarter::Q1),
+            all_time: false,
         };
 
         let result = calculate_summary(&sheet);
@@ -978,6 +1142,69 @@ mod tests {
         assert!(outgoing.net.value.eq(&net.value));
         assert!(outgoing.tax.value.eq(&tax.value));
         assert!(outgoing.gross.value.eq(&gross.value));
+
+        let combined = result.combined();
+        assert_eq!(combined.net.value, net.value * Decimal::TWO);
+        assert_eq!(combined.tax.value, tax.value * Decimal::TWO);
+        assert_eq!(combined.gross.value, gross.value * Decimal::TWO);
+    }
+
+    #[test]
+    fn calculate_summary_skips_excluded_items() {
+        let net = CurrencyValue::new(225000);
+        let vat = Vat::Twenty;
+        let sheet = AccountingSheet {
+            items: vec![
+                accounting_item(
+                    InvoiceType::In,
+                    net.clone(),
+                    vat,
+                    Category(String::from("a")),
+                ),
+                AccountingItem {
+                    excluded: true,
+                    ..accounting_item(
+                        InvoiceType::In,
+                        CurrencyValue::new(10000),
+                        vat,
+                        Category(String::from("private")),
+                    )
+                },
+                AccountingItem {
+                    excluded: true,
+                    ..accounting_item(
+                        InvoiceType::Out,
+                        CurrencyValue::new(5000),
+                        vat,
+                        Category(String::from("a")),
+                    )
+                },
+            ],
+            year: 2024,
+            month: None,
+            quarter: Some(Quarter::Q1),
+            all_time: false,
+        };
+
+        let result = calculate_summary(&sheet);
+        assert_eq!(result.categories.len(), 1);
+        assert!(
+            !result
+                .categories
+                .contains_key(&Category(String::from("private")))
+        );
+        assert!(result.revenue_by_company.is_empty());
+
+        let ingoing = result.accounting.get(&InvoiceType::In).unwrap();
+        let outgoing = result.accounting.get(&InvoiceType::Out).unwrap();
+        let VatCalculationResult { tax, gross } = CurrencyValue::calculate_vat(&net, vat);
+        assert_eq!(ingoing.net.value, net.value);
+        assert_eq!(ingoing.tax.value, tax.value);
+        assert_eq!(ingoing.gross.value, gross.value);
+        assert!(outgoing.net.value.is_zero());
+        assert!(outgoing.gross.value.is_zero());
+        // still listed on the sheet
+        assert_eq!(sheet.items.len(), 3);
     }
 
     #[test]
@@ -1003,6 +1230,7 @@ mod tests {
             year: 2024,
             month: None,
             quarter: Some(Quarter::Q1),
+            all_time: false,
         };
 
         let result = calculate_summary(&sheet);
@@ -1055,6 +1283,7 @@ mod tests {
             year: 2024,
             month: None,
             quarter: Some(Quarter::Q1),
+            all_time: false,
         };
 
         let result = calculate_summary(&sheet);
@@ -1078,4 +1307,121 @@ mod tests {
         assert!(outgoing.tax.value.eq(&default_currency_value()));
         assert!(outgoing.gross.value.eq(&default_currency_value()));
     }
+
+    #[test]
+    fn excluded_items_are_marked() {
+        let mut item = accounting_item(
+            InvoiceType::In,
+            CurrencyValue::new(1000),
+            Vat::Twenty,
+            Category(String::from("Office")),
+        );
+        assert_eq!(company_name_text(&item, 40), "Landlord - Rent");
+        item.excluded = true;
+        let marker = format!(" ({})", Messages::Private.msg());
+        assert_eq!(
+            company_name_text(&item, 40),
+            format!("Landlord - Rent{marker}")
+        );
+        assert_eq!(
+            company_name_text(&item, 10 + marker.chars().count()),
+            format!("Landlord -...{marker}")
+        );
+    }
+
+    #[test]
+    fn numbers_of_large_sheets_fit_the_column() {
+        assert_eq!(nr_digits(0), 1);
+        assert_eq!(nr_digits(999), 3);
+        let max_digits = nr_digits(1200);
+        assert_eq!(max_digits, 4);
+        assert!(PADDING + max_digits as f32 * digit_width() <= NR_WIDTH.0);
+        // "1200" and "7" end at the same position
+        let end = |nr: &str| {
+            PADDING
+                + max_digits.saturating_sub(nr.len()) as f32 * digit_width()
+                + get_text_width(nr) * PT_TO_MM
+        };
+        assert!((end("1200") - end("7")).abs() < 0.001);
+    }
+
+    #[test]
+    fn layout_fits_within_page() {
+        [PageSize::A4, PageSize::Letter]
+            .iter()
+            .for_each(|page_size| {
+                let page = &Page::landscape(*page_size);
+                let table_right = page.left.0
+                    + INVOICE_TYPE_WIDTH.0
+                    + NR_WIDTH.0
+                    + DATE_WIDTH.0
+                    + company_name_width(page).0
+                    + CATEGORY_WIDTH.0
+                    + NET_WIDTH.0
+                    + VAT_WIDTH.0
+                    + TAX_WIDTH.0
+                    + GROSS_WIDTH.0;
+                assert!(company_name_width(page).0 > 0.0);
+                assert!(table_right <= page.right.0 + f32::EPSILON);
+                assert!(page.right.0 < page.width.0);
+
+                let summary_right = page.left.0
+                    + CATEGORIES_SUMMARY_COLS as f32
+                        * (summary_category_width(page).0 + SUMMARY_NET_WIDTH.0);
+                assert!(summary_right <= page.right.0 + f32::EPSILON);
+                assert!(items_per_page(page) > SUMMARY_CUTOFF);
+            });
+    }
+
+    #[test]
+    fn items_per_page_a4() {
+        assert_eq!(items_per_page(&Page::landscape(PageSize::A4)), 22);
+    }
+
+    #[test]
+    fn estimate_pages() {
+        let sheet_with = |num_items: usize| AccountingSheet {
+            items: (0..num_items)
+                .map(|_| {
+                    accounting_item(
+                        InvoiceType::In,
+                        CurrencyValue::new(100),
+                        Vat::Twenty,
+                        Category(String::from("a")),
+                    )
+                })
+                .collect(),
+            year: 2024,
+            month: None,
+            quarter: Some(Quarter::Q1),
+            all_time: false,
+        };
+        // 22 items per page on A4, the summary fits below up to 8 items
+        assert_eq!(estimate_accounting_pages(&sheet_with(0), PageSize::A4), 1);
+        assert_eq!(estimate_accounting_pages(&sheet_with(8), PageSize::A4), 1);
+        assert_eq!(estimate_accounting_pages(&sheet_with(9), PageSize::A4), 2);
+        assert_eq!(estimate_accounting_pages(&sheet_with(22), PageSize::A4), 2);
+        assert_eq!(estimate_accounting_pages(&sheet_with(30), PageSize::A4), 2);
+        assert_eq!(estimate_accounting_pages(&sheet_with(31), PageSize::A4), 3);
+    }
+
+    #[test]
+    fn document_metadata_names_the_period() {
+        let mut sheet = AccountingSheet {
+            items: vec![],
+            year: 2024,
+            month: None,
+            quarter: Some(Quarter::Q1),
+            all_time: false,
+        };
+        let accounting = Messages::Accounting.msg();
+        assert_eq!(create_title(&sheet), format!("{accounting} - 2024 Q1"));
+        assert_eq!(create_subject(&sheet), format!("{accounting} 2024 Q1"));
+
+        sheet.all_time = true;
+        assert_eq!(
+            create_subject(&sheet),
+            format!("{accounting} {}", Messages::AllTime)
+        );
+    }
 }
@@ -0,0 +1,119 @@
+use crate::GuiError;
+use log::warn;
+use printpdf::lopdf::{self, Document, Object, ObjectId};
+use std::path::{Path, PathBuf};
+
+// page attributes, which pages can inherit from their parents in the page tree
+const INHERITABLE_ATTRIBUTES: &[&[u8]] = &[b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+pub(crate) fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+}
+
+// appends the pages of the attachments to the document - returns the skipped attachments, which
+// aren't PDFs, or can't be read
+pub(crate) fn append_attachments(
+    doc: &mut Document,
+    attachments: &[PathBuf],
+) -> Result<Vec<PathBuf>, GuiError> {
+    let mut skipped = vec![];
+    for attachment in attachments {
+        if !is_pdf(attachment) {
+            warn!("skipping attachment {attachment:?}, since it's not a PDF");
+            skipped.push(attachment.to_owned());
+            continue;
+        }
+        match Document::load(attachment) {
+            Ok(other) => append_pages(doc, other)
+                .map_err(|e| GuiError::ExportFailed(format!("{attachment:?}: {e}")))?,
+            Err(e) => {
+                warn!("skipping attachment {attachment:?}, since it can't be read: {e}");
+                skipped.push(attachment.to_owned());
+            }
+        }
+    }
+    doc.prune_objects();
+    Ok(skipped)
+}
+
+fn append_pages(doc: &mut Document, mut other: Document) -> Result<(), lopdf::Error> {
+    other.renumber_objects_with(doc.max_id + 1);
+    let pages: Vec<ObjectId> = other.get_pages().into_values().collect();
+    // the pages are moved to the root of the page tree, so they lose their parents
+    for page_id in pages.iter() {
+        inherit_attributes(&mut other, *page_id)?;
+    }
+    doc.max_id = other.max_id;
+    doc.objects.extend(other.objects);
+
+    let pages_id = doc.catalog()?.get(b"Pages")?.as_reference()?;
+    for page_id in pages.iter() {
+        doc.get_dictionary_mut(*page_id)?.set("Parent", pages_id);
+    }
+    let tree = doc.get_dictionary_mut(pages_id)?;
+    let count = tree.get(b"Count")?.as_i64()? + pages.len() as i64;
+    tree.get_mut(b"Kids")?
+        .as_array_mut()?
+        .extend(pages.into_iter().map(Object::Reference));
+    tree.set("Count", count);
+    Ok(())
+}
+
+fn inherit_attributes(doc: &mut Document, page_id: ObjectId) -> Result<(), lopdf::Error> {
+    for key in INHERITABLE_ATTRIBUTES {
+        let page = doc.get_dictionary(page_id)?;
+        if page.has(key) {
+            continue;
+        }
+        let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+        while let Some(parent_id) = parent {
+            let node = doc.get_dictionary(parent_id)?;
+            if let Ok(value) = node.get(key) {
+                let value = value.to_owned();
+                doc.get_dictionary_mut(page_id)?.set(key.to_vec(), value);
+                break;
+            }
+            parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::{Mm, PdfDocument};
+    use uuid::Uuid;
+
+    fn pdf_with_pages(pages: usize) -> Vec<u8> {
+        let (doc, _, _) = PdfDocument::new("test", Mm(210.0), Mm(297.0), "layer");
+        (1..pages).for_each(|_| {
+            doc.add_page(Mm(210.0), Mm(297.0), "layer");
+        });
+        doc.save_to_bytes().unwrap()
+    }
+
+    #[test]
+    fn pdf_attachments_are_appended_and_others_skipped() {
+        let folder = std::env::temp_dir().join(format!("helferlein_test_{}", Uuid::now_v7()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let timesheet = folder.join("timesheet.PDF");
+        std::fs::write(&timesheet, pdf_with_pages(2)).unwrap();
+        let notes = folder.join("notes.txt");
+        std::fs::write(&notes, "some notes").unwrap();
+        let broken = folder.join("broken.pdf");
+        std::fs::write(&broken, "not really a pdf").unwrap();
+
+        let mut doc = Document::load_mem(&pdf_with_pages(1)).unwrap();
+        let skipped = append_attachments(&mut doc, &[notes.clone(), timesheet, broken.clone()]);
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert_eq!(skipped.unwrap(), vec![notes, broken]);
+
+        // the merged document can be read again
+        let mut bytes = vec![];
+        doc.save_to(&mut bytes).unwrap();
+        let merged = Document::load_mem(&bytes).unwrap();
+        assert_eq!(merged.get_pages().len(), 3);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{
+    data::{BankAccount, currency::CurrencyValue},
+    util::validation::{normalize_iban, strip_label, validate_iban},
+};
+
+// the limits of the EPC069-12 standard
+const MAX_NAME_CHARS: usize = 70;
+const MAX_REFERENCE_CHARS: usize = 140;
+
+// the payload of an EPC (SEPA credit transfer) QR code, version 002, in UTF-8
+pub(crate) fn build_epc_qr_payload(
+    iban: &str,
+    bic: &str,
+    name: &str,
+    amount: &CurrencyValue,
+    reference: &str,
+) -> String {
+    [
+        String::from("BCD"),
+        String::from("002"),
+        String::from("1"),
+        String::from("SCT"),
+        bic.trim().to_uppercase(),
+        truncate(name.trim(), MAX_NAME_CHARS),
+        normalize_iban(iban),
+        format!("EUR{:.2}", amount.value.round_dp(2)),
+        // purpose and structured reference stay empty, the reference is free text
+        String::new(),
+        String::new(),
+        truncate(reference.trim(), MAX_REFERENCE_CHARS),
+    ]
+    .join("\n")
+}
+
+// the structured bank account, falling back to the free text bank data
+pub(crate) fn iban_and_bic(
+    bank_account: &BankAccount,
+    bank_data: &str,
+) -> Option<(String, String)> {
+    if bank_account.is_empty() {
+        return parse_bank_data(bank_data);
+    }
+    let iban = normalize_iban(&bank_account.iban);
+    validate_iban(&iban).then(|| (iban, bank_account.bic.trim().to_uppercase()))
+}
+
+// the IBAN and BIC from free text bank data, e.g. "IBAN: AT61 1904 3002 3457 3201"
+pub(crate) fn parse_bank_data(bank_data: &str) -> Option<(String, String)> {
+    let iban = bank_data.lines().find_map(parse_iban)?;
+    let bic = bank_data.lines().find_map(parse_bic).unwrap_or_default();
+    Some((iban, bic))
+}
+
+fn parse_iban(line: &str) -> Option<String> {
+    let value = line.trim();
+    let value = strip_label(value, "IBAN").unwrap_or(value);
+    let iban = normalize_iban(value);
+    validate_iban(&iban).then_some(iban)
+}
+
+fn parse_bic(line: &str) -> Option<String> {
+    let value = line.trim();
+    let value = strip_label(value, "BIC").or_else(|| strip_label(value, "SWIFT"))?;
+    let bic = value.split_whitespace().next()?.to_uppercase();
+    ((bic.len() == 8 || bic.len() == 11) && bic.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then_some(bic)
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    value.chars().take(max_chars).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_format() {
+        let payload = build_epc_qr_payload(
+            "at61 1904 3002 3457 3201",
+            "bkaucwwxxx",
+            "Mario Zupan",
+            &CurrencyValue::new(123456),
+            "Invoice 2024-001",
+        );
+        assert_eq!(
+            payload,
+            "BCD\n002\n1\nSCT\nBKAUCWWXXX\nMario Zupan\nAT611904300234573201\nEUR1234.56\n\n\nInvoice 2024-001"
+        );
+    }
+
+    #[test]
+    fn payload_fields_are_truncated() {
+        let payload = build_epc_qr_payload(
+            "AT611904300234573201",
+            "",
+            &"n".repeat(80),
+            &CurrencyValue::new(100),
+            &"r".repeat(150),
+        );
+        let lines: Vec<&str> = payload.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[4], "");
+        assert_eq!(lines[5].len(), 70);
+        assert_eq!(lines[7], "EUR1.00");
+        assert_eq!(lines[10].len(), 140);
+    }
+
+    #[test]
+    fn iban_and_bic_are_parsed_from_bank_data() {
+        assert_eq!(
+            parse_bank_data("My Bank\nIBAN: AT61 1904 3002 3457 3201\nBIC: BKAUATWW"),
+            Some((
+                String::from("AT611904300234573201"),
+                String::from("BKAUATWW")
+            ))
+        );
+        assert_eq!(
+            parse_bank_data("de89370400440532013000"),
+            Some((String::from("DE89370400440532013000"), String::default()))
+        );
+        // wrong checksum
+        assert_eq!(parse_bank_data("IBAN: AT62 1904 3002 3457 3201"), None);
+        assert_eq!(parse_bank_data("BIC: BKAUATWW"), None);
+    }
+
+    #[test]
+    fn structured_bank_account_comes_first() {
+        let account = BankAccount {
+            account_holder: String::from("Jane Doe"),
+            iban: String::from("at61 1904 3002 3457 3201"),
+            bic: String::from("bkauatww"),
+        };
+        let free_text = "IBAN: DE89370400440532013000";
+        assert_eq!(
+            iban_and_bic(&account, free_text),
+            Some((
+                String::from("AT611904300234573201"),
+                String::from("BKAUATWW")
+            ))
+        );
+        assert_eq!(
+            iban_and_bic(&BankAccount::default(), free_text),
+            Some((String::from("DE89370400440532013000"), String::default()))
+        );
+        let without_iban = BankAccount {
+            iban: String::default(),
+            ..account
+        };
+        assert_eq!(iban_and_bic(&without_iban, free_text), None);
+    }
+}
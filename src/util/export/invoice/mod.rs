@@ -1,62 +1,175 @@
-use std::{fs::File, io::BufWriter, path::Path};
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
 
-use chrono::NaiveDate;
-use printpdf::{Color, IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point, Rgb};
+use printpdf::{
+    lopdf, path::PaintMode, Color, IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference,
+    Point, Rect, Rgb,
+};
+use rust_decimal::Decimal;
 
 use crate::{
+    config::PageSize,
     data::{
-        currency::{default_currency_value, CurrencyValue, VatCalculationResult},
-        Address, Invoice, InvoiceItem, ServicePeriod, Vat,
+        calculate_sum,
+        currency::{format_number, format_quantity, round_to_step, CurrencyValue},
+        Address, Adjustment, DocumentKind, Invoice, InvoiceItem, RoundingStep, SumData, Unit, Vat,
     },
-    util::export::PT_TO_MM,
-    GuiError, Messages, DATE_FORMAT,
+    date_format,
+    util::export::{qr, PT_TO_MM},
+    GuiError, Messages,
 };
 
+use attachments::append_attachments;
+use epc::{build_epc_qr_payload, iban_and_bic};
+use log::warn;
+
 use super::{
-    get_text_width, FONT, FONT_SIZE, LINE_WIDTH, MARGIN, MAX_CHARS_CURRENCY, PADDING, ROW_HEIGHT,
-    TABLE_LINE_HEIGHT,
+    get_text_width, right_aligned_x, use_text, Page, FONT, FONT_SIZE, LINE_WIDTH, PADDING,
+    ROW_HEIGHT, TABLE_LINE_HEIGHT,
 };
 
-pub const MAX_ITEMS: usize = 10;
+pub(crate) mod attachments;
+pub(crate) mod epc;
+pub(crate) mod text;
 
-const HEIGHT: Mm = Mm(297.0);
-const WIDTH: Mm = Mm(210.0);
-const LEFT: Mm = Mm(MARGIN);
-const RIGHT: Mm = Mm(WIDTH.0 - MARGIN);
-const TOP: Mm = Mm(HEIGHT.0 - MARGIN);
-const BOTTOM: Mm = Mm(MARGIN);
+pub const MAX_ITEMS: usize = 10;
 
-const MAX_DIGITS_POS: i32 = 2;
-const MAX_DIGITS_QTY: i32 = 3;
 const MAX_CHARS_UNIT: i32 = 2;
 
 // COL WIDTHS
-const POS_WIDTH: Mm = Mm(10.0);
-const DESC_WIDTH: Mm = Mm(61.0);
-const QTY_WIDTH: Mm = Mm(12.0);
+const POS_WIDTH: Mm = Mm(14.0);
+const DESC_WIDTH: Mm = Mm(53.0);
+const QTY_WIDTH: Mm = Mm(16.0);
 const UNIT_WIDTH: Mm = Mm(12.0);
 const UNIT_PRICE_WIDTH: Mm = Mm(27.0);
 const GAP_WIDTH: Mm = Mm(20.0);
+// the description gives up space for the VAT column, which replaces the gap
+const DESC_WIDTH_WITH_VAT: Mm = Mm(46.0);
+const VAT_WIDTH: Mm = Mm(27.0);
+// the payment QR code in the footer, including its quiet zone
+const QR_CODE_SIZE: Mm = Mm(28.0);
+const QR_CODE_QUIET_ZONE: usize = 4;
 
 #[derive(Debug, Clone)]
-pub(crate) struct CreatePDFResult;
+pub(crate) struct CreatePDFResult {
+    // attachments, which weren't appended, since they aren't readable PDFs
+    pub(crate) skipped_attachments: Vec<PathBuf>,
+    // the payment QR code was enabled, but there was no valid IBAN in the bank data
+    pub(crate) missing_qr_code: bool,
+}
 
-#[derive(Debug, Clone)]
-pub(crate) struct SumData {
-    pub(crate) net: CurrencyValue,
-    pub(crate) tax: CurrencyValue,
-    pub(crate) total: CurrencyValue,
+// how the sums and quantities of an invoice are calculated and formatted
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct InvoiceOptions {
+    pub(crate) rounding_step: RoundingStep,
+    pub(crate) prices_include_vat: bool,
+    pub(crate) quantity_decimals: u32,
+    // space kept free at the top, e.g. for a pre-printed letterhead
+    pub(crate) top_margin_offset_mm: f32,
+    // an EPC QR code in the footer, so the client can pay by scanning it
+    pub(crate) epc_qr_code: bool,
+    // the VAT amount of each line, in its own column
+    pub(crate) vat_column: bool,
+}
+
+// the widths of the item table's columns, except for the sum, which takes the rest
+#[derive(Debug, Clone, Copy)]
+struct Columns {
+    description: f32,
+    // empty in the item rows, or their VAT amount - the labels of the sum block go here
+    gap: f32,
+    vat_column: bool,
+}
+
+impl Columns {
+    fn new(vat_column: bool) -> Self {
+        if vat_column {
+            Self {
+                description: DESC_WIDTH_WITH_VAT.0,
+                gap: VAT_WIDTH.0,
+                vat_column,
+            }
+        } else {
+            Self {
+                description: DESC_WIDTH.0,
+                gap: GAP_WIDTH.0,
+                vat_column,
+            }
+        }
+    }
+
+    fn widths(&self) -> [f32; 6] {
+        [
+            POS_WIDTH.0,
+            self.description,
+            QTY_WIDTH.0,
+            UNIT_WIDTH.0,
+            UNIT_PRICE_WIDTH.0,
+            self.gap,
+        ]
+    }
+
+    // where the gap, and the labels of the sum block, start
+    fn gap_x(&self) -> f32 {
+        self.widths()[..5].iter().sum()
+    }
+
+    // where the sum column starts
+    fn sum_x(&self) -> f32 {
+        self.gap_x() + self.gap
+    }
+}
+
+impl InvoiceOptions {
+    fn columns(&self) -> Columns {
+        Columns::new(self.vat_column)
+    }
+
+    fn sum_data(&self, invoice: &Invoice) -> Result<SumData, GuiError> {
+        apply_rounding(
+            calculate_sum(
+                &invoice.items,
+                &invoice.adjustments,
+                self.prices_include_vat,
+            ),
+            self.rounding_step,
+        )
+        .ok_or_else(|| GuiError::ExportFailed(Messages::TotalCantBeRounded.msg().to_owned()))
+    }
+
+    // where the content starts below the offset
+    fn content_top(&self, page: &Page) -> Mm {
+        Mm(page.top.0 - self.top_margin_offset_mm.max(0.0))
+    }
+
+    // the offset takes away space from the items table, since the footer stays at the bottom
+    pub(crate) fn max_items(&self) -> usize {
+        let rows_taken = (self.top_margin_offset_mm.max(0.0) / ROW_HEIGHT).ceil() as usize;
+        MAX_ITEMS.saturating_sub(rows_taken)
+    }
 }
 
 pub(crate) fn create_invoice_pdf(
     file_name: &Path,
     invoice: &Invoice,
+    page_size: PageSize,
+    options: &InvoiceOptions,
 ) -> Result<CreatePDFResult, GuiError> {
-    if invoice.items.len() > MAX_ITEMS {
-        return Err(GuiError::ExportFailed("Too many items - max 15".into()));
+    let page = &Page::portrait(page_size);
+    let max_items = options.max_items();
+    if invoice.items.len() + invoice.adjustments.len() > max_items {
+        return Err(GuiError::ExportFailed(format!(
+            "Too many items - max {max_items}"
+        )));
     }
-    let title = "Invoice".to_string();
-    let (doc, page1, layer) = PdfDocument::new(&title, WIDTH, HEIGHT, "layer");
+    let (doc, page1, layer) =
+        PdfDocument::new(document_title(invoice), page.width, page.height, "layer");
+    let doc = doc
+        .with_author(&invoice.from.name)
+        .with_subject(document_subject(invoice));
     let mut font_reader = std::io::Cursor::new(FONT);
     let font = doc
         .add_external_font(&mut font_reader)
@@ -72,45 +185,159 @@ pub(crate) fn create_invoice_pdf(
     current_layer.set_line_height(TABLE_LINE_HEIGHT.0);
     current_layer.set_font(&font, FONT_SIZE.0);
 
-    let from_top = render_from(&invoice.from, &current_layer, &font, TOP);
-    let to_top = render_to(&invoice.to, &current_layer, &font, from_top);
-    let mt_top = render_metadata(
-        &invoice.city,
-        &invoice.date,
-        &invoice.invoice_number,
-        &invoice.service_period,
+    let from_top = render_from(
+        page,
+        &invoice.from,
+        &current_layer,
+        &font,
+        options.content_top(page),
+    );
+    let to_top = render_to(page, &invoice.to, &current_layer, &font, from_top);
+    let mt_top = render_metadata(page, invoice, &current_layer, &font, to_top);
+    let pre_top = render_pre(
+        page,
+        invoice.kind,
+        &invoice.pre_text,
         &current_layer,
         &font,
-        to_top,
+        &bold_font,
+        mt_top,
     );
-    let pre_top = render_pre(&invoice.pre_text, &current_layer, &font, &bold_font, mt_top);
-    let items_top = render_items(&invoice.items, &current_layer, &font, &bold_font, pre_top);
-    render_post(&invoice.post_text, &current_layer, &font, items_top);
+    let items_top = render_items(
+        page,
+        invoice,
+        options,
+        &current_layer,
+        &font,
+        &bold_font,
+        pre_top,
+    )?;
+    render_post(page, &invoice.post_text, &current_layer, &font, items_top);
     render_footer(
+        page,
         &invoice.from,
-        &invoice.bank_data,
+        &invoice.bank_data_lines(),
         &current_layer,
         &font,
-        Mm(BOTTOM.0 + 5.0 * ROW_HEIGHT + PADDING),
+        Mm(page.bottom.0 + 5.0 * ROW_HEIGHT + PADDING),
     );
+    let mut missing_qr_code = false;
+    if options.epc_qr_code && invoice.kind == DocumentKind::Invoice {
+        let total = options.sum_data(invoice)?.total;
+        match iban_and_bic(&invoice.bank_account, &invoice.bank_data) {
+            Some((iban, bic)) if total.value > Decimal::ZERO => render_qr_code(
+                page,
+                &build_epc_qr_payload(
+                    &iban,
+                    &bic,
+                    &invoice.from.name,
+                    &total,
+                    &document_subject(invoice),
+                ),
+                &current_layer,
+                Mm(page.bottom.0 + 5.0 * ROW_HEIGHT + PADDING),
+            ),
+            // there's nothing to pay
+            Some(_) => (),
+            None => {
+                warn!("no QR code added, since there is no valid IBAN");
+                missing_qr_code = true;
+            }
+        }
+    }
 
+    if invoice.attachments.is_empty() {
+        // SAVE (overwrites the file)
+        doc.save(&mut BufWriter::new(File::create(file_name)?))
+            .map_err(|e| GuiError::ExportFailed(e.to_string()))?;
+        return Ok(CreatePDFResult {
+            skipped_attachments: vec![],
+            missing_qr_code,
+        });
+    }
+
+    // the attachments are merged in after rendering, so the client gets a single file
+    let bytes = doc
+        .save_to_bytes()
+        .map_err(|e| GuiError::ExportFailed(e.to_string()))?;
+    let mut merged =
+        lopdf::Document::load_mem(&bytes).map_err(|e| GuiError::ExportFailed(e.to_string()))?;
+    let skipped_attachments = append_attachments(&mut merged, &invoice.attachments)?;
     // SAVE (overwrites the file)
-    doc.save(&mut BufWriter::new(
-        File::create(file_name).map_err(|e| GuiError::ExportFailed(e.to_string()))?,
-    ))
-    .map_err(|e| GuiError::ExportFailed(e.to_string()))?;
-    Ok(CreatePDFResult {})
+    merged
+        .save_to(&mut BufWriter::new(File::create(file_name)?))
+        .map_err(|e| GuiError::ExportFailed(e.to_string()))?;
+    Ok(CreatePDFResult {
+        skipped_attachments,
+        missing_qr_code,
+    })
+}
+
+// centered below the footer line, between the address and the bank data
+fn render_qr_code(page: &Page, payload: &str, layer: &PdfLayerReference, top: Mm) {
+    let Some(code) = qr::encode(payload.as_bytes()) else {
+        warn!("no QR code added, since the payment data is too long");
+        return;
+    };
+    let module = QR_CODE_SIZE.0 / (code.size() + 2 * QR_CODE_QUIET_ZONE) as f32;
+    let left =
+        (page.left.0 + page.right.0 - QR_CODE_SIZE.0) / 2.0 + QR_CODE_QUIET_ZONE as f32 * module;
+    let top = top.0 - QR_CODE_QUIET_ZONE as f32 * module;
+    for y in 0..code.size() {
+        for x in 0..code.size() {
+            if !code.is_dark(x, y) {
+                continue;
+            }
+            let module_left = left + x as f32 * module;
+            let module_top = top - y as f32 * module;
+            layer.add_rect(
+                Rect::new(
+                    Mm(module_left),
+                    Mm(module_top - module),
+                    Mm(module_left + module),
+                    Mm(module_top),
+                )
+                .with_mode(PaintMode::Fill),
+            );
+        }
+    }
+}
+
+// e.g. "Invoice 2024-001" - drafts don't have a number yet
+fn document_subject(invoice: &Invoice) -> String {
+    format!("{} {}", invoice.kind.name(), invoice.invoice_number)
+        .trim_end()
+        .to_owned()
 }
 
-fn calc_left(txt_width: f32) -> Mm {
-    Mm(RIGHT.0 - PADDING - (txt_width * PT_TO_MM))
+// the subject and who it's for, e.g. "Invoice 2024-001 - ACME"
+fn document_title(invoice: &Invoice) -> String {
+    let subject = document_subject(invoice);
+    if invoice.to.name.is_empty() {
+        return subject;
+    }
+    format!("{subject} - {}", invoice.to.name)
+}
+
+fn calc_left(page: &Page, txt_width: f32) -> Mm {
+    Mm(page.right.0 - PADDING - (txt_width * PT_TO_MM))
 }
 
 fn calc_top(top: Mm, from_top: f32) -> Mm {
     Mm(top.0 - from_top * ROW_HEIGHT + PADDING * from_top)
 }
 
+// the postal address can span multiple lines, e.g. street / building / floor
+fn address_lines(address: &Address) -> impl Iterator<Item = &str> {
+    address
+        .postal_address
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+}
+
 pub(crate) fn render_to(
+    page: &Page,
     address: &Address,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
@@ -118,7 +345,6 @@ pub(crate) fn render_to(
 ) -> Mm {
     let mut from_top: f32 = 1.0;
     let name = &address.name.trim().to_owned();
-    let addr = &address.postal_address.trim().to_owned();
     let zip_city = &format!(
         "{} {}",
         &address.zip.trim().to_owned(),
@@ -128,28 +354,72 @@ pub(crate) fn render_to(
     let vat = &address.vat.trim().to_owned();
     let misc = &address.misc.trim().to_owned();
 
-    layer.use_text(name, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+    use_text(
+        layer,
+        name,
+        FONT_SIZE.0,
+        page.left,
+        calc_top(top, from_top),
+        font,
+    );
 
-    from_top += 1.0;
-    layer.use_text(addr, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+    address_lines(address).for_each(|l| {
+        from_top += 1.0;
+        use_text(
+            layer,
+            l,
+            FONT_SIZE.0,
+            page.left,
+            calc_top(top, from_top),
+            font,
+        );
+    });
 
     from_top += 1.0;
-    layer.use_text(zip_city, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+    use_text(
+        layer,
+        zip_city,
+        FONT_SIZE.0,
+        page.left,
+        calc_top(top, from_top),
+        font,
+    );
 
     if !country.is_empty() {
         from_top += 1.0;
-        layer.use_text(country, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+        use_text(
+            layer,
+            country,
+            FONT_SIZE.0,
+            page.left,
+            calc_top(top, from_top),
+            font,
+        );
     }
 
     if !vat.is_empty() {
         from_top += 1.0;
-        layer.use_text(vat, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+        use_text(
+            layer,
+            vat,
+            FONT_SIZE.0,
+            page.left,
+            calc_top(top, from_top),
+            font,
+        );
     }
 
     if !misc.is_empty() {
         misc.lines().enumerate().for_each(|l| {
             from_top += 1.0;
-            layer.use_text(l.1, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+            use_text(
+                layer,
+                l.1,
+                FONT_SIZE.0,
+                page.left,
+                calc_top(top, from_top),
+                font,
+            );
         });
     }
     // return bottom of text for next alignment
@@ -158,13 +428,13 @@ pub(crate) fn render_to(
 }
 
 pub(crate) fn render_from(
+    page: &Page,
     address: &Address,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
     top: Mm,
 ) -> Mm {
     let name = &address.name.trim().to_owned();
-    let addr = &address.postal_address.trim().to_owned();
     let zip_city = &format!(
         "{} {}",
         &address.zip.trim().to_owned(),
@@ -172,38 +442,45 @@ pub(crate) fn render_from(
     );
     let country = &address.country.trim().to_owned();
 
-    layer.use_text(
+    use_text(
+        layer,
         name,
         FONT_SIZE.0,
-        calc_left(get_text_width(name)),
+        calc_left(page, get_text_width(name)),
         top,
         font,
     );
 
-    let mut from_top: f32 = 1.0;
-    layer.use_text(
-        addr,
-        FONT_SIZE.0,
-        calc_left(get_text_width(addr)),
-        calc_top(top, from_top),
-        font,
-    );
+    let mut from_top: f32 = 0.0;
+    address_lines(address).for_each(|l| {
+        from_top += 1.0;
+        use_text(
+            layer,
+            l,
+            FONT_SIZE.0,
+            calc_left(page, get_text_width(l)),
+            calc_top(top, from_top),
+            font,
+        );
+    });
 
     from_top += 1.0;
-    layer.use_text(
+    use_text(
+        layer,
         zip_city,
         FONT_SIZE.0,
-        calc_left(get_text_width(zip_city)),
+        calc_left(page, get_text_width(zip_city)),
         calc_top(top, from_top),
         font,
     );
 
     if !country.is_empty() {
         from_top += 1.0;
-        layer.use_text(
+        use_text(
+            layer,
             country,
             FONT_SIZE.0,
-            calc_left(get_text_width(country)),
+            calc_left(page, get_text_width(country)),
             calc_top(top, from_top),
             font,
         );
@@ -215,49 +492,57 @@ pub(crate) fn render_from(
 }
 
 pub(crate) fn render_metadata(
-    city: &str,
-    date: &NaiveDate,
-    invoice_number: &str,
-    service_period: &ServicePeriod,
+    page: &Page,
+    invoice: &Invoice,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
     top: Mm,
 ) -> Mm {
     let mut from_top: f32 = 2.0;
-    let city_date = format!("{}, {}", city.trim(), date.format(DATE_FORMAT));
-    layer.use_text(
+    let city_date = format!(
+        "{}, {}",
+        invoice.city.trim(),
+        invoice.date.format(date_format())
+    );
+    use_text(
+        layer,
         &city_date,
         FONT_SIZE.0,
-        calc_left(get_text_width(&city_date)),
+        calc_left(page, get_text_width(&city_date)),
         calc_top(top, from_top),
         font,
     );
-    let inv_nr = format!(
-        "{}: {}",
-        Messages::InvoiceNumberText.msg(),
-        invoice_number.trim()
-    );
+    // quotes don't need an invoice number
+    if !invoice.invoice_number.trim().is_empty() {
+        let inv_nr = format!(
+            "{}: {}",
+            Messages::InvoiceNumberText.msg(),
+            invoice.invoice_number.trim()
+        );
 
-    from_top += 1.0;
-    layer.use_text(
-        &inv_nr,
-        FONT_SIZE.0,
-        calc_left(get_text_width(&inv_nr)),
-        calc_top(top, from_top),
-        font,
-    );
+        from_top += 1.0;
+        use_text(
+            layer,
+            &inv_nr,
+            FONT_SIZE.0,
+            calc_left(page, get_text_width(&inv_nr)),
+            calc_top(top, from_top),
+            font,
+        );
+    }
 
     let serv_period = format!(
         "{}: {} - {}",
         Messages::ServicePeriod.msg(),
-        service_period.from.format(DATE_FORMAT),
-        service_period.to.format(DATE_FORMAT)
+        invoice.service_period.from.format(date_format()),
+        invoice.service_period.to.format(date_format())
     );
     from_top += 1.0;
-    layer.use_text(
+    use_text(
+        layer,
         &serv_period,
         FONT_SIZE.0,
-        calc_left(get_text_width(&serv_period)),
+        calc_left(page, get_text_width(&serv_period)),
         calc_top(top, from_top),
         font,
     );
@@ -268,6 +553,8 @@ pub(crate) fn render_metadata(
 }
 
 pub(crate) fn render_pre(
+    page: &Page,
+    kind: DocumentKind,
     pre_text: &str,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
@@ -275,10 +562,11 @@ pub(crate) fn render_pre(
     top: Mm,
 ) -> Mm {
     let mut from_top: f32 = 1.0;
-    layer.use_text(
-        Messages::Invoice.msg(),
+    use_text(
+        layer,
+        kind.name(),
         FONT_SIZE.0 * 1.2,
-        LEFT,
+        page.left,
         calc_top(top, from_top),
         bold_font,
     );
@@ -286,7 +574,14 @@ pub(crate) fn render_pre(
     if !pre_text.is_empty() {
         pre_text.lines().enumerate().for_each(|l| {
             from_top += 1.0;
-            layer.use_text(l.1, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+            use_text(
+                layer,
+                l.1,
+                FONT_SIZE.0,
+                page.left,
+                calc_top(top, from_top),
+                font,
+            );
         });
     }
 
@@ -305,6 +600,8 @@ pub(crate) fn render_pre(
 // ------------------------------------------------------------
 // |     |             |     |            |          |        |
 // ------------------------------------------------------------
+// |     | Discount    |     |            |          |        |
+// ------------------------------------------------------------
 //                                        |      Net |        |
 //                                        ---------------------
 //                                        | 20 % VAT |        |
@@ -313,105 +610,146 @@ pub(crate) fn render_pre(
 //                                        ---------------------
 //                                        ---------------------
 pub(crate) fn render_items(
-    items: &[InvoiceItem],
+    page: &Page,
+    invoice: &Invoice,
+    options: &InvoiceOptions,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
     bold_font: &IndirectFontRef,
     top: Mm,
-) -> Mm {
+) -> Result<Mm, GuiError> {
     let mut from_top: f32 = 1.0;
-    render_table_header(top, layer, bold_font);
+    let columns = options.columns();
+    render_table_header(page, &columns, top, layer, bold_font);
     let mut to_add_for_lines = 0;
     let mut item_lines = 0;
-    for (idx, item) in items.iter().enumerate() {
+    for (idx, item) in invoice.items.iter().enumerate() {
         to_add_for_lines = render_row(
+            page,
             item,
+            options,
             Mm(top.0 - ROW_HEIGHT - ((idx + to_add_for_lines) as f32 * ROW_HEIGHT)),
             layer,
             font,
         ) - 1;
         item_lines += to_add_for_lines + 1;
     }
+    // adjustments are rendered below the items
+    for adjustment in invoice.adjustments.iter() {
+        item_lines += render_adjustment_row(
+            page,
+            adjustment,
+            options,
+            Mm(top.0 - ROW_HEIGHT - (item_lines as f32 * ROW_HEIGHT)),
+            layer,
+            font,
+        );
+    }
     // start at item lines + 1
     let top_after_items = Mm(top.0 - ROW_HEIGHT * (item_lines + 1) as f32);
     from_top += 1.0;
     // render sum
-    let sum_data = calculate_sum(items);
-    render_sum(top_after_items, sum_data, layer, font);
+    let sum_data = options.sum_data(invoice)?;
+    if sum_data.rounding.is_some() {
+        from_top += 1.0;
+    }
+    let hours = total_hours(&invoice.items);
+    if hours.is_some() {
+        from_top += 1.0;
+    }
+    render_sum(
+        page,
+        &columns,
+        top_after_items,
+        sum_data,
+        hours,
+        layer,
+        font,
+    );
 
     // return bottom of text for next alignment
     from_top += 1.0;
-    calc_top(top_after_items, from_top)
+    Ok(calc_top(top_after_items, from_top))
 }
 
-fn render_table_header(top: Mm, layer: &PdfLayerReference, font: &IndirectFontRef) {
+fn render_table_header(
+    page: &Page,
+    columns: &Columns,
+    top: Mm,
+    layer: &PdfLayerReference,
+    font: &IndirectFontRef,
+) {
     let mut col_line_x = 0.0;
     // START OF ROW
-    render_row_line(top, layer);
-    render_col_line(LEFT, top, layer);
+    render_row_line(page, top, layer);
+    render_col_line(page.left, top, layer);
     // Pos
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Pos.msg(),
         layer,
         font,
     );
     col_line_x += POS_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Description
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Description.msg(),
         layer,
         font,
     );
-    col_line_x += DESC_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    col_line_x += columns.description;
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Qty
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Qty.msg(),
         layer,
         font,
     );
     col_line_x += QTY_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Unit
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::UnitShort.msg(),
         layer,
         font,
     );
     col_line_x += UNIT_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Unit Price
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::PricePerUnit.msg(),
         layer,
         font,
     );
     col_line_x += UNIT_PRICE_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
-    // Gap
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
+    // Gap or VAT
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
-        "",
+        if columns.vat_column {
+            Messages::Vat.msg()
+        } else {
+            ""
+        },
         layer,
         font,
     );
-    col_line_x += GAP_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
+    col_line_x += columns.gap;
+    render_col_line(Mm(page.left.0 + col_line_x), top, layer);
     // Sum
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        Mm(page.left.0 + col_line_x + PADDING),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         Messages::Sum.msg(),
         layer,
@@ -421,68 +759,63 @@ fn render_table_header(top: Mm, layer: &PdfLayerReference, font: &IndirectFontRe
     // Omit last col_line, since it's the row's col line
 
     // END OF ROW
-    render_col_line(RIGHT, top, layer);
-    render_row_line(Mm(top.0 - ROW_HEIGHT), layer);
+    render_col_line(page.right, top, layer);
+    render_row_line(page, Mm(top.0 - ROW_HEIGHT), layer);
 }
 
 fn render_row(
+    page: &Page,
     item: &InvoiceItem,
+    options: &InvoiceOptions,
     top: Mm,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
 ) -> usize {
+    let columns = options.columns();
     let mut col_line_x = 0.0;
     let lines = item.description.lines().count();
     // START OF ROW
-    render_row_line(top, layer);
-    render_col_line_with_multiplier(LEFT, top, lines, layer);
+    render_row_line(page, top, layer);
+    render_col_line_with_multiplier(page.left, top, lines, layer);
     // Pos
-    let pos_str = item.nr.to_string();
+    let pos_str = item.nr.trim();
     render_col_text(
-        // right-align
-        Mm(LEFT.0
-            + col_line_x
-            + PADDING
-            + ((MAX_DIGITS_POS - pos_str.chars().count() as i32) as f32 * PADDING)),
+        right_aligned_x(pos_str, page.left.0 + col_line_x + POS_WIDTH.0),
         Mm(top.0 - ROW_HEIGHT + PADDING),
-        &pos_str,
+        pos_str,
         layer,
         font,
     );
     col_line_x += POS_WIDTH.0;
-    render_col_line_with_multiplier(Mm(LEFT.0 + col_line_x), top, lines, layer);
+    render_col_line_with_multiplier(Mm(page.left.0 + col_line_x), top, lines, layer);
     // Description
     item.description.lines().enumerate().for_each(|(i, line)| {
         render_col_text(
-            Mm(LEFT.0 + col_line_x + PADDING),
+            Mm(page.left.0 + col_line_x + PADDING),
             Mm(top.0 - (ROW_HEIGHT * (i + 1) as f32) + PADDING),
             line,
             layer,
             font,
         );
     });
-    col_line_x += DESC_WIDTH.0;
-    render_col_line_with_multiplier(Mm(LEFT.0 + col_line_x), top, lines, layer);
+    col_line_x += columns.description;
+    render_col_line_with_multiplier(Mm(page.left.0 + col_line_x), top, lines, layer);
     // Qty
-    let qty_str = item.amount.to_string();
+    let qty_str = format_quantity(&item.amount, options.quantity_decimals);
     render_col_text(
-        // right-align
-        Mm(LEFT.0
-            + col_line_x
-            + PADDING
-            + ((MAX_DIGITS_QTY - qty_str.chars().count() as i32) as f32 * PADDING)),
+        right_aligned_x(&qty_str, page.left.0 + col_line_x + QTY_WIDTH.0),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         &qty_str,
         layer,
         font,
     );
     col_line_x += QTY_WIDTH.0;
-    render_col_line_with_multiplier(Mm(LEFT.0 + col_line_x), top, lines, layer);
+    render_col_line_with_multiplier(Mm(page.left.0 + col_line_x), top, lines, layer);
     // Unit
     let unit_str = item.unit.name();
     render_col_text(
         // right-align
-        Mm(LEFT.0
+        Mm(page.left.0
             + col_line_x
             + (PADDING * 2.0)
             + ((MAX_CHARS_UNIT - unit_str.chars().count() as i32) as f32 * PADDING)),
@@ -492,54 +825,36 @@ fn render_row(
         font,
     );
     col_line_x += UNIT_WIDTH.0;
-    render_col_line_with_multiplier(Mm(LEFT.0 + col_line_x), top, lines, layer);
+    render_col_line_with_multiplier(Mm(page.left.0 + col_line_x), top, lines, layer);
     // Price per Unit
-    let ppu_str = item.price_per_unit.to_euro_str();
-    let pad_no_dot = if ppu_str.contains('.') { 0.0 } else { 1.0 }; // if val is < 1000
+    let ppu_str = item.price_per_unit.to_display_string();
     render_col_text(
-        // right-align
-        Mm(LEFT.0
-            + col_line_x
-            + PADDING
-            + ((MAX_CHARS_CURRENCY - ppu_str.chars().count() as i32) as f32 * PADDING)
-            + pad_no_dot),
+        right_aligned_x(&ppu_str, page.left.0 + col_line_x + UNIT_PRICE_WIDTH.0),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         &ppu_str,
         layer,
         font,
     );
     col_line_x += UNIT_PRICE_WIDTH.0;
-    render_col_line_with_multiplier(Mm(LEFT.0 + col_line_x), top, lines, layer);
-    // Gap
-    render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
-        Mm(top.0 - ROW_HEIGHT + PADDING),
-        "",
-        layer,
-        font,
-    );
-    col_line_x += GAP_WIDTH.0;
-    render_col_line_with_multiplier(Mm(LEFT.0 + col_line_x), top, lines, layer);
-    // Sum
-    let sum_str = CurrencyValue::new_from_decimal(
-        item.price_per_unit
-            .value
-            .checked_mul(item.amount)
-            .expect("mul works"),
-    )
-    .to_euro_str();
-    let mut pad_no_dot = if sum_str.contains('.') { 0.0 } else { -1.0 }; // if val is < 1000
-    if item.price_per_unit.value < default_currency_value() {
-        // for negative numbers, pad
-        pad_no_dot = 1.0;
+    render_col_line_with_multiplier(Mm(page.left.0 + col_line_x), top, lines, layer);
+    // Gap or VAT
+    if columns.vat_column {
+        let vat_str =
+            line_vat(&item_sum(item), item.vat, options.prices_include_vat).to_display_string();
+        render_col_text(
+            right_aligned_x(&vat_str, page.left.0 + col_line_x + columns.gap),
+            Mm(top.0 - ROW_HEIGHT + PADDING),
+            &vat_str,
+            layer,
+            font,
+        );
     }
+    col_line_x += columns.gap;
+    render_col_line_with_multiplier(Mm(page.left.0 + col_line_x), top, lines, layer);
+    // Sum
+    let sum_str = item_sum(item).to_display_string();
     render_col_text(
-        // right-align
-        Mm(LEFT.0
-            + col_line_x
-            + (PADDING * 2.0)
-            + ((MAX_CHARS_CURRENCY - sum_str.chars().count() as i32) as f32 * PADDING)
-            + pad_no_dot),
+        right_aligned_x(&sum_str, page.right.0),
         Mm(top.0 - ROW_HEIGHT + PADDING),
         &sum_str,
         layer,
@@ -548,148 +863,256 @@ fn render_row(
     // Omit last col_line, since it's the row's col line
 
     // END OF ROW
-    render_col_line_with_multiplier(RIGHT, top, lines, layer);
-    render_row_line(Mm(top.0 - ROW_HEIGHT * lines as f32), layer);
+    render_col_line_with_multiplier(page.right, top, lines, layer);
+    render_row_line(page, Mm(top.0 - ROW_HEIGHT * lines as f32), layer);
     lines
 }
 
-fn render_sum(top: Mm, sum_data: SumData, layer: &PdfLayerReference, font: &IndirectFontRef) -> Mm {
+// a row with only the description and the signed amount of the adjustment
+fn render_adjustment_row(
+    page: &Page,
+    adjustment: &Adjustment,
+    options: &InvoiceOptions,
+    top: Mm,
+    layer: &PdfLayerReference,
+    font: &IndirectFontRef,
+) -> usize {
+    let lines = adjustment.description.lines().count().max(1);
+    render_row_line(page, top, layer);
+    render_col_line_with_multiplier(page.left, top, lines, layer);
+    // Description
+    adjustment
+        .description
+        .lines()
+        .enumerate()
+        .for_each(|(i, line)| {
+            render_col_text(
+                Mm(page.left.0 + POS_WIDTH.0 + PADDING),
+                Mm(top.0 - (ROW_HEIGHT * (i + 1) as f32) + PADDING),
+                line,
+                layer,
+                font,
+            );
+        });
+    // the remaining columns stay empty, except for the VAT
+    let columns = options.columns();
     let mut col_line_x = 0.0;
-    col_line_x += POS_WIDTH.0;
-    col_line_x += DESC_WIDTH.0;
-    col_line_x += QTY_WIDTH.0;
-    col_line_x += UNIT_WIDTH.0;
-    col_line_x += UNIT_PRICE_WIDTH.0;
-    let line_from = col_line_x;
-
-    // Net
-    render_sum_line(Mm(LEFT.0 + line_from), top, layer);
-    render_sum_line(Mm(LEFT.0 + line_from), Mm(top.0 + 0.1), layer);
-    let col_line_x_left_line = col_line_x;
-    render_col_line(Mm(LEFT.0 + col_line_x_left_line), top, layer);
+    for width in columns.widths() {
+        col_line_x += width;
+        render_col_line_with_multiplier(Mm(page.left.0 + col_line_x), top, lines, layer);
+    }
+    if columns.vat_column {
+        let vat_str = line_vat(
+            &adjustment.amount,
+            adjustment.vat,
+            options.prices_include_vat,
+        )
+        .to_display_string();
+        render_col_text(
+            right_aligned_x(&vat_str, page.left.0 + columns.sum_x()),
+            Mm(top.0 - ROW_HEIGHT + PADDING),
+            &vat_str,
+            layer,
+            font,
+        );
+    }
+    // Sum
+    let sum_str = adjustment.amount.to_display_string();
     render_col_text(
-        Mm(LEFT.0 + col_line_x + PADDING),
+        right_aligned_x(&sum_str, page.right.0),
         Mm(top.0 - ROW_HEIGHT + PADDING),
-        Messages::Net.msg(),
+        &sum_str,
         layer,
         font,
     );
-    col_line_x += GAP_WIDTH.0;
-    render_col_line(Mm(LEFT.0 + col_line_x), top, layer);
-    let net_str = sum_data.net.to_euro_str();
-    let pad_no_dot = if net_str.contains('.') { 0.0 } else { 1.0 }; // if val is < 1000
-    render_col_text(
-        // right-align
-        Mm(LEFT.0
-            + col_line_x
-            + (PADDING * 2.0)
-            + ((MAX_CHARS_CURRENCY - net_str.chars().count() as i32) as f32 * PADDING)
-            + pad_no_dot),
-        Mm(top.0 - ROW_HEIGHT + PADDING),
-        &net_str,
+    render_col_line_with_multiplier(page.right, top, lines, layer);
+    render_row_line(page, Mm(top.0 - ROW_HEIGHT * lines as f32), layer);
+    lines
+}
+
+fn render_sum(
+    page: &Page,
+    columns: &Columns,
+    top: Mm,
+    sum_data: SumData,
+    hours: Option<Decimal>,
+    layer: &PdfLayerReference,
+    font: &IndirectFontRef,
+) -> Mm {
+    let line_from = columns.gap_x();
+
+    // Net
+    render_sum_line(page, Mm(page.left.0 + line_from), top, layer);
+    render_sum_line(page, Mm(page.left.0 + line_from), Mm(top.0 + 0.1), layer);
+    render_sum_row(
+        page,
+        columns,
+        sum_row_top(top, 1.0),
+        Messages::Net.msg(),
+        &sum_data.net.to_display_string(),
         layer,
         font,
     );
-    render_col_line(RIGHT, top, layer);
-    render_sum_line(Mm(LEFT.0 + line_from), Mm(top.0 - ROW_HEIGHT), layer);
     // Tax
-    render_col_line(
-        Mm(LEFT.0 + col_line_x_left_line),
-        Mm(top.0 - ROW_HEIGHT),
-        layer,
-    );
-    render_col_text(
-        Mm(LEFT.0 + col_line_x_left_line + PADDING),
-        Mm(top.0 - (ROW_HEIGHT * 2.0) + PADDING),
-        &format!("{} {}", Vat::Twenty.name(), Messages::Vat.msg()),
-        layer,
-        font,
-    );
-    render_col_line(Mm(LEFT.0 + col_line_x), Mm(top.0 - ROW_HEIGHT), layer);
-    let tax_str = sum_data.tax.to_euro_str();
-    let pad_no_dot = if tax_str.contains('.') { 0.0 } else { -1.0 }; // if val is < 1000
-    render_col_text(
-        // right-align
-        Mm(LEFT.0
-            + col_line_x
-            + (PADDING * 2.0)
-            + ((MAX_CHARS_CURRENCY - tax_str.chars().count() as i32) as f32 * PADDING)
-            + pad_no_dot),
-        Mm(top.0 - (ROW_HEIGHT * 2.0) + PADDING),
-        &tax_str,
+    render_sum_row(
+        page,
+        columns,
+        sum_row_top(top, 2.0),
+        &tax_label(sum_data.prices_include_vat),
+        &sum_data.tax.to_display_string(),
         layer,
         font,
     );
-    render_sum_line(
-        Mm(LEFT.0 + line_from),
-        Mm(top.0 - (ROW_HEIGHT * 2.0)),
-        layer,
-    );
-    render_sum_line(
-        Mm(LEFT.0 + line_from),
-        Mm(top.0 - (ROW_HEIGHT * 2.0) - 0.1),
-        layer,
-    );
-    render_col_line(RIGHT, Mm(top.0 - ROW_HEIGHT), layer);
+    // Rounding
+    let mut total_row = 3.0;
+    if let Some(rounding) = sum_data.rounding {
+        render_sum_row(
+            page,
+            columns,
+            sum_row_top(top, total_row),
+            Messages::Rounding.msg(),
+            &rounding.to_display_string(),
+            layer,
+            font,
+        );
+        total_row += 1.0;
+    }
     // total
-    render_col_line(
-        Mm(LEFT.0 + col_line_x_left_line),
-        Mm(top.0 - (ROW_HEIGHT * 2.0)),
+    render_sum_line(
+        page,
+        Mm(page.left.0 + line_from),
+        Mm(top.0 - (ROW_HEIGHT * (total_row - 1.0)) - 0.1),
         layer,
     );
-    render_col_text(
-        Mm(LEFT.0 + col_line_x_left_line + PADDING),
-        Mm(top.0 - (ROW_HEIGHT * 3.0) + PADDING),
+    render_sum_row(
+        page,
+        columns,
+        sum_row_top(top, total_row),
         Messages::Total.msg(),
+        &sum_data.total.to_display_string(),
         layer,
         font,
     );
-    render_col_line(
-        Mm(LEFT.0 + col_line_x),
-        Mm(top.0 - (ROW_HEIGHT * 2.0)),
+    render_sum_line(
+        page,
+        Mm(page.left.0 + line_from),
+        Mm(top.0 - (ROW_HEIGHT * total_row) + 0.5),
         layer,
     );
-    let total_string = sum_data.total.to_euro_str();
-    let pad_no_dot = if total_string.contains('.') { 0.0 } else { 1.0 }; // if val is < 1000
+    // billed hours
+    if let Some(hours) = hours {
+        render_sum_row(
+            page,
+            columns,
+            sum_row_top(top, total_row + 1.0),
+            Messages::TotalHours.msg(),
+            &format!("{} {}", format_number(&hours), Unit::Hour.name()),
+            layer,
+            font,
+        );
+    }
+
+    top
+}
+
+fn tax_label(prices_include_vat: bool) -> String {
+    let label = format!("{} {}", Vat::Twenty.name(), Messages::Vat.msg());
+    if prices_include_vat {
+        format!("{} {}", Messages::InclVat.msg(), label)
+    } else {
+        label
+    }
+}
+
+// the price of all units of the given item
+fn item_sum(item: &InvoiceItem) -> CurrencyValue {
+    CurrencyValue::new_from_decimal(
+        item.price_per_unit
+            .value
+            .checked_mul(item.amount)
+            .expect("mul works"),
+    )
+}
+
+// the VAT amount of an item or adjustment line
+fn line_vat(price: &CurrencyValue, vat: Vat, prices_include_vat: bool) -> CurrencyValue {
+    if prices_include_vat {
+        price.calculate_included_vat(vat).tax
+    } else {
+        price.calculate_vat(vat).tax
+    }
+}
+
+// the sum of the amounts of all items billed by the hour, if there are any
+fn total_hours(items: &[InvoiceItem]) -> Option<Decimal> {
+    items
+        .iter()
+        .filter(|item| item.unit == Unit::Hour)
+        .map(|item| item.amount)
+        .reduce(|acc, amount| acc.saturating_add(amount))
+}
+
+// the top of the given row (starting at 1) of the sum block
+fn sum_row_top(top: Mm, row: f32) -> Mm {
+    Mm(top.0 - ROW_HEIGHT * (row - 1.0))
+}
+
+// renders the label and right-aligned value of a row of the sum block
+fn render_sum_row(
+    page: &Page,
+    columns: &Columns,
+    row_top: Mm,
+    label: &str,
+    value: &str,
+    layer: &PdfLayerReference,
+    font: &IndirectFontRef,
+) {
+    let col_line_x_left_line = columns.gap_x();
+    let col_line_x = columns.sum_x();
+
+    render_col_line(Mm(page.left.0 + col_line_x_left_line), row_top, layer);
     render_col_text(
-        // right-align
-        Mm(LEFT.0
-            + col_line_x
-            + (PADDING * 2.0)
-            + ((MAX_CHARS_CURRENCY - total_string.chars().count() as i32) as f32 * PADDING)
-            + pad_no_dot),
-        Mm(top.0 - (ROW_HEIGHT * 3.0) + PADDING),
-        &total_string,
+        Mm(page.left.0 + col_line_x_left_line + PADDING),
+        Mm(row_top.0 - ROW_HEIGHT + PADDING),
+        label,
         layer,
         font,
     );
-    render_sum_line(
-        Mm(LEFT.0 + line_from),
-        Mm(top.0 - (ROW_HEIGHT * 3.0)),
+    render_col_line(Mm(page.left.0 + col_line_x), row_top, layer);
+    render_col_text(
+        right_aligned_x(value, page.right.0),
+        Mm(row_top.0 - ROW_HEIGHT + PADDING),
+        value,
         layer,
+        font,
     );
+    render_col_line(page.right, row_top, layer);
     render_sum_line(
-        Mm(LEFT.0 + line_from),
-        Mm(top.0 - (ROW_HEIGHT * 3.0) + 0.5),
+        page,
+        Mm(page.left.0 + col_line_x_left_line),
+        Mm(row_top.0 - ROW_HEIGHT),
         layer,
     );
-    render_col_line(RIGHT, Mm(top.0 - (ROW_HEIGHT * 2.0)), layer);
-
-    top
 }
 
-fn render_row_line(y: Mm, layer: &PdfLayerReference) {
+fn render_row_line(page: &Page, y: Mm, layer: &PdfLayerReference) {
     let line = Line {
-        points: vec![(Point::new(LEFT, y), false), (Point::new(RIGHT, y), false)],
+        points: vec![
+            (Point::new(page.left, y), false),
+            (Point::new(page.right, y), false),
+        ],
         is_closed: true,
     };
 
     layer.add_line(line);
 }
 
-fn render_sum_line(x: Mm, y: Mm, layer: &PdfLayerReference) {
+fn render_sum_line(page: &Page, x: Mm, y: Mm, layer: &PdfLayerReference) {
     let line = Line {
-        points: vec![(Point::new(x, y), false), (Point::new(RIGHT, y), false)],
+        points: vec![
+            (Point::new(x, y), false),
+            (Point::new(page.right, y), false),
+        ],
         is_closed: true,
     };
 
@@ -698,7 +1121,7 @@ fn render_sum_line(x: Mm, y: Mm, layer: &PdfLayerReference) {
 
 fn render_col_text(x: Mm, y: Mm, text: &str, layer: &PdfLayerReference, font: &IndirectFontRef) {
     layer.set_line_height(TABLE_LINE_HEIGHT.0);
-    layer.use_text(text, FONT_SIZE.0, x, y, font);
+    use_text(layer, text, FONT_SIZE.0, x, y, font);
 }
 
 fn render_col_line(x: Mm, y: Mm, layer: &PdfLayerReference) {
@@ -729,6 +1152,7 @@ fn render_col_line_with_multiplier(x: Mm, y: Mm, multiplier: usize, layer: &PdfL
 }
 
 pub(crate) fn render_post(
+    page: &Page,
     post_text: &str,
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
@@ -738,22 +1162,30 @@ pub(crate) fn render_post(
     if !post_text.is_empty() {
         post_text.lines().enumerate().for_each(|l| {
             from_top += 1.0;
-            layer.use_text(l.1, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+            use_text(
+                layer,
+                l.1,
+                FONT_SIZE.0,
+                page.left,
+                calc_top(top, from_top),
+                font,
+            );
         });
     }
 }
 
 pub(crate) fn render_footer(
+    page: &Page,
     address: &Address,
-    bank_data: &str,
+    bank_data: &[String],
     layer: &PdfLayerReference,
     font: &IndirectFontRef,
     top: Mm,
 ) {
     let line = Line {
         points: vec![
-            (Point::new(LEFT, top), false),
-            (Point::new(RIGHT, top), false),
+            (Point::new(page.left, top), false),
+            (Point::new(page.right, top), false),
         ],
         is_closed: true,
     };
@@ -763,17 +1195,25 @@ pub(crate) fn render_footer(
     let name = &address.name.trim().to_owned();
     let addr = &format!(
         "{}, {} {}",
-        &address.postal_address.trim().to_owned(),
+        address_lines(address).collect::<Vec<&str>>().join(", "),
         &address.zip.trim().to_owned(),
         &address.city.trim().to_owned()
     );
-    layer.use_text(name, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+    use_text(
+        layer,
+        name,
+        FONT_SIZE.0,
+        page.left,
+        calc_top(top, from_top),
+        font,
+    );
 
     from_top += 1.0;
-    layer.use_text(
+    use_text(
+        layer,
         addr,
         FONT_SIZE.0,
-        LEFT,
+        page.left,
         Mm(top.0 - from_top * ROW_HEIGHT + PADDING * from_top),
         font,
     );
@@ -783,24 +1223,39 @@ pub(crate) fn render_footer(
 
     if !vat.is_empty() {
         from_top += 1.0;
-        layer.use_text(vat, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+        use_text(
+            layer,
+            vat,
+            FONT_SIZE.0,
+            page.left,
+            calc_top(top, from_top),
+            font,
+        );
     }
 
     if !misc.is_empty() {
         misc.lines().enumerate().for_each(|l| {
             from_top += 1.0;
-            layer.use_text(l.1, FONT_SIZE.0, LEFT, calc_top(top, from_top), font);
+            use_text(
+                layer,
+                l.1,
+                FONT_SIZE.0,
+                page.left,
+                calc_top(top, from_top),
+                font,
+            );
         });
     }
 
     if !bank_data.is_empty() {
         let mut from_top = 1.0;
-        bank_data.lines().enumerate().for_each(|l| {
+        bank_data.iter().for_each(|l| {
             from_top += 1.0;
-            layer.use_text(
-                l.1,
+            use_text(
+                layer,
+                l,
                 FONT_SIZE.0,
-                calc_left(get_text_width(l.1)),
+                calc_left(page, get_text_width(l)),
                 calc_top(top, from_top),
                 font,
             );
@@ -808,31 +1263,171 @@ pub(crate) fn render_footer(
     }
 }
 
-fn calculate_sum(items: &[InvoiceItem]) -> SumData {
-    let mut net_sum = default_currency_value();
-    let mut tax_sum = default_currency_value();
-    let mut total_sum = default_currency_value();
+// rounds the total to the given step and keeps the difference as an explicit rounding amount
+// None, if the total is too large to be rounded
+fn apply_rounding(sum_data: SumData, rounding_step: RoundingStep) -> Option<SumData> {
+    match rounding_step.step() {
+        None => Some(sum_data),
+        Some(step) => {
+            let rounded =
+                CurrencyValue::new_from_decimal(round_to_step(sum_data.total.value, step)?);
+            let difference = rounded.checked_sub(&sum_data.total)?;
+            Some(SumData {
+                total: rounded,
+                rounding: Some(difference),
+                ..sum_data
+            })
+        }
+    }
+}
 
-    items.iter().for_each(|item| {
-        let net = item
-            .price_per_unit
-            .value
-            .checked_mul(item.amount)
-            .unwrap_or_else(default_currency_value);
-        let VatCalculationResult { tax, gross } =
-            CurrencyValue::new_from_decimal(net).calculate_vat(item.vat);
-        net_sum = net_sum.checked_add(net).unwrap_or(default_currency_value());
-        tax_sum = tax_sum
-            .checked_add(tax.value)
-            .unwrap_or(default_currency_value());
-        total_sum = total_sum
-            .checked_add(gross.value)
-            .unwrap_or(default_currency_value());
-    });
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{BankAccount, ServicePeriod};
+    use crate::util::validation::{is_valid_position, MAX_POSITION_LENGTH};
+    use chrono::NaiveDate;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn item(unit: Unit, amount: &str) -> InvoiceItem {
+        InvoiceItem {
+            unit,
+            amount: Decimal::from_str(amount).unwrap(),
+            ..InvoiceItem::test_item()
+        }
+    }
+
+    #[test]
+    fn total_hours_of_mixed_units() {
+        let items = vec![
+            item(Unit::Hour, "2.5"),
+            item(Unit::Day, "3"),
+            item(Unit::Hour, "4"),
+            item(Unit::None, "1"),
+        ];
+        assert_eq!(total_hours(&items), Decimal::from_str("6.5").ok());
+    }
+
+    #[test]
+    fn quantities_fit_the_column() {
+        for (amount, formatted) in [("0.25", "0.25"), ("10", "10.00"), ("100.5", "100.50")] {
+            let qty_str = format_quantity(&Decimal::from_str(amount).unwrap(), 2);
+            assert_eq!(qty_str, formatted);
+            // right-aligned within the column, leaving the padding on the left
+            let x = right_aligned_x(&qty_str, QTY_WIDTH.0);
+            assert!(x.0 >= PADDING, "{qty_str} overflows the quantity column");
+            let end = x.0 + get_text_width(&qty_str) * PT_TO_MM;
+            assert!((end - (QTY_WIDTH.0 - PADDING)).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn positions_fit_the_column() {
+        let longest = "9".repeat(MAX_POSITION_LENGTH);
+        for pos_str in ["1", "1.2", "10.11", longest.as_str()] {
+            assert!(is_valid_position(pos_str));
+            let x = right_aligned_x(pos_str, POS_WIDTH.0);
+            assert!(x.0 >= PADDING, "{pos_str} overflows the position column");
+        }
+    }
+
+    #[test]
+    fn document_metadata_names_the_invoice() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let mut invoice = Invoice {
+            id: Uuid::now_v7(),
+            date,
+            city: String::from("Vienna"),
+            name: String::from("Template"),
+            from: Address::new(),
+            to: Address::new(),
+            service_period: ServicePeriod {
+                from: date,
+                from_field: String::default(),
+                to: date,
+                to_field: String::default(),
+            },
+            invoice_number: String::from("2024-001"),
+            pre_text: String::default(),
+            post_text: String::default(),
+            bank_data: String::default(),
+            items: vec![],
+            kind: DocumentKind::Invoice,
+            adjustments: vec![],
+            attachments: vec![],
+            bank_account: BankAccount::default(),
+            prices_include_vat: false,
+            rounding_step: RoundingStep::None,
+        };
+        let subject = format!("{} 2024-001", Messages::Invoice);
+        assert_eq!(document_subject(&invoice), subject);
+        assert_eq!(document_title(&invoice), subject);
+
+        invoice.to.name = String::from("ACME");
+        assert_eq!(document_title(&invoice), format!("{subject} - ACME"));
+
+        invoice.kind = DocumentKind::Quote;
+        invoice.invoice_number = String::default();
+        assert_eq!(document_subject(&invoice), Messages::Quote.msg());
+    }
+
+    #[test]
+    fn content_starts_below_the_top_margin_offset() {
+        let page = Page::portrait(PageSize::A4);
+        let mut options = InvoiceOptions {
+            rounding_step: RoundingStep::None,
+            prices_include_vat: false,
+            quantity_decimals: 2,
+            top_margin_offset_mm: 0.0,
+            epc_qr_code: false,
+            vat_column: false,
+        };
+        assert_eq!(options.content_top(&page).0, page.top.0);
+        assert_eq!(options.max_items(), MAX_ITEMS);
+
+        options.top_margin_offset_mm = 40.0;
+        assert_eq!(options.content_top(&page).0, page.top.0 - 40.0);
+        assert!(options.max_items() < MAX_ITEMS);
+        // the rows given up cover the offset
+        assert!((MAX_ITEMS - options.max_items()) as f32 * ROW_HEIGHT >= 40.0);
+
+        options.top_margin_offset_mm = -10.0;
+        assert_eq!(options.content_top(&page).0, page.top.0);
+    }
+
+    // the sum column takes the rest of the page, but needs at least this
+    const MIN_SUM_WIDTH: Mm = Mm(25.0);
+
+    #[test]
+    fn table_fits_the_usable_width() {
+        for page_size in [PageSize::A4, PageSize::Letter] {
+            let page = Page::portrait(page_size);
+            for vat_column in [false, true] {
+                let columns = Columns::new(vat_column);
+                let fixed: f32 = columns.widths().iter().sum();
+                assert_eq!(columns.sum_x(), fixed);
+                assert!(fixed + MIN_SUM_WIDTH.0 <= page.right.0 - page.left.0);
+            }
+        }
+    }
+
+    #[test]
+    fn line_vat_depends_on_included_prices() {
+        let price = CurrencyValue::new(12000);
+        assert_eq!(
+            line_vat(&price, Vat::Twenty, false).to_value_string(),
+            "24.00"
+        );
+        assert_eq!(
+            line_vat(&price, Vat::Twenty, true).to_value_string(),
+            "20.00"
+        );
+    }
 
-    SumData {
-        net: CurrencyValue::new_from_decimal(net_sum),
-        tax: CurrencyValue::new_from_decimal(tax_sum),
-        total: CurrencyValue::new_from_decimal(total_sum),
+    #[test]
+    fn no_total_hours_without_hour_items() {
+        assert_eq!(total_hours(&[item(Unit::Day, "2")]), None);
+        assert_eq!(total_hours(&[]), None);
     }
 }
@@ -0,0 +1,261 @@
+use super::{InvoiceOptions, address_lines, item_sum, tax_label, total_hours};
+use crate::{
+    GuiError, Messages,
+    data::{
+        Address, Invoice, Unit,
+        currency::{format_number, format_quantity},
+    },
+    date_format,
+};
+
+// a Markdown version of the invoice, e.g. to paste it into an email
+pub(crate) fn render_invoice_text(
+    invoice: &Invoice,
+    options: &InvoiceOptions,
+) -> Result<String, GuiError> {
+    let mut lines: Vec<String> = vec![format!("# {}", invoice.kind.name()), String::new()];
+
+    lines.extend(address(&invoice.from));
+    lines.push(String::new());
+    lines.extend(address(&invoice.to));
+    if !invoice.to.vat.trim().is_empty() {
+        lines.push(invoice.to.vat.trim().to_owned());
+    }
+    lines.extend(non_empty_lines(&invoice.to.misc));
+    lines.push(String::new());
+
+    lines.push(format!(
+        "{}, {}",
+        invoice.city.trim(),
+        invoice.date.format(date_format())
+    ));
+    if !invoice.invoice_number.trim().is_empty() {
+        lines.push(format!(
+            "{}: {}",
+            Messages::InvoiceNumberText.msg(),
+            invoice.invoice_number.trim()
+        ));
+    }
+    lines.push(format!(
+        "{}: {} - {}",
+        Messages::ServicePeriod.msg(),
+        invoice.service_period.from.format(date_format()),
+        invoice.service_period.to.format(date_format())
+    ));
+
+    push_paragraph(&mut lines, &invoice.pre_text);
+
+    lines.push(String::new());
+    lines.push(format!(
+        "| {} | {} | {} | {} | {} | {} |",
+        Messages::Pos.msg(),
+        Messages::Description.msg(),
+        Messages::Qty.msg(),
+        Messages::UnitShort.msg(),
+        Messages::PricePerUnit.msg(),
+        Messages::Sum.msg(),
+    ));
+    lines.push(String::from("| ---: | --- | ---: | --- | ---: | ---: |"));
+    invoice.items.iter().for_each(|item| {
+        lines.push(format!(
+            "| {} | {} | {} | {} | {} | {} |",
+            item.nr,
+            non_empty_lines(&item.description).join(", "),
+            format_quantity(&item.amount, options.quantity_decimals),
+            item.unit.name(),
+            item.price_per_unit.to_display_string(),
+            item_sum(item).to_display_string(),
+        ));
+    });
+    invoice.adjustments.iter().for_each(|adjustment| {
+        lines.push(format!(
+            "|  | {} |  |  |  | {} |",
+            non_empty_lines(&adjustment.description).join(", "),
+            adjustment.amount.to_display_string(),
+        ));
+    });
+
+    let sum_data = options.sum_data(invoice)?;
+    lines.push(String::new());
+    lines.push(format!(
+        "{}: {}",
+        Messages::Net.msg(),
+        sum_data.net.to_display_string()
+    ));
+    lines.push(format!(
+        "{}: {}",
+        tax_label(options.prices_include_vat),
+        sum_data.tax.to_display_string()
+    ));
+    if let Some(ref rounding) = sum_data.rounding {
+        lines.push(format!(
+            "{}: {}",
+            Messages::Rounding.msg(),
+            rounding.to_display_string()
+        ));
+    }
+    lines.push(format!(
+        "**{}: {}**",
+        Messages::Total.msg(),
+        sum_data.total.to_display_string()
+    ));
+    if let Some(hours) = total_hours(&invoice.items) {
+        lines.push(format!(
+            "{}: {} {}",
+            Messages::TotalHours.msg(),
+            format_number(&hours),
+            Unit::Hour.name()
+        ));
+    }
+
+    push_paragraph(&mut lines, &invoice.post_text);
+    push_paragraph(&mut lines, &invoice.bank_data_lines().join("\n"));
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn non_empty_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// adds the given text as a separate paragraph, if there is any
+fn push_paragraph(lines: &mut Vec<String>, text: &str) {
+    let paragraph = non_empty_lines(text);
+    if !paragraph.is_empty() {
+        lines.push(String::new());
+        lines.extend(paragraph);
+    }
+}
+
+fn address(address: &Address) -> Vec<String> {
+    let mut lines = vec![address.name.trim().to_owned()];
+    lines.extend(address_lines(address).map(String::from));
+    lines.push(format!("{} {}", address.zip.trim(), address.city.trim()));
+    if !address.country.trim().is_empty() {
+        lines.push(address.country.trim().to_owned());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{
+        BankAccount, DocumentKind, InvoiceItem, RoundingStep, ServicePeriod,
+        currency::CurrencyValue,
+    };
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    fn address(name: &str, postal_address: &str, city: &str) -> Address {
+        Address {
+            name: name.to_owned(),
+            postal_address: postal_address.to_owned(),
+            zip: String::from("1010"),
+            city: city.to_owned(),
+            country: String::from("Austria"),
+            vat: String::default(),
+            misc: String::default(),
+        }
+    }
+
+    #[test]
+    fn two_item_invoice() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let invoice = Invoice {
+            id: Uuid::now_v7(),
+            date,
+            city: String::from("Vienna"),
+            name: String::from("March"),
+            from: address("Jane Doe", "Mainstreet 1", "Vienna"),
+            to: Address {
+                vat: String::from("ATU12345678"),
+                ..address("ACME Inc.", "Sidestreet 2\nFloor 3", "Vienna")
+            },
+            service_period: ServicePeriod {
+                from: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                from_field: String::default(),
+                to: date,
+                to_field: String::default(),
+            },
+            invoice_number: String::from("RE-42"),
+            pre_text: String::from("Thank you for your order."),
+            post_text: String::default(),
+            bank_data: String::from("IBAN AT00 0000 0000 0000"),
+            kind: DocumentKind::Invoice,
+            adjustments: vec![],
+            attachments: vec![],
+            bank_account: BankAccount::default(),
+            prices_include_vat: false,
+            rounding_step: RoundingStep::None,
+            items: vec![
+                InvoiceItem {
+                    description: String::from("Development\nBackend"),
+                    amount: Decimal::new(15, 1),
+                    ..InvoiceItem::test_item()
+                },
+                InvoiceItem {
+                    nr: String::from("2"),
+                    description: String::from("Hosting"),
+                    unit: Unit::None,
+                    price_per_unit: CurrencyValue::new(5000),
+                    ..InvoiceItem::test_item()
+                },
+            ],
+        };
+
+        let expected = "\
+# Invoice
+
+Jane Doe
+Mainstreet 1
+1010 Vienna
+Austria
+
+ACME Inc.
+Sidestreet 2
+Floor 3
+1010 Vienna
+Austria
+ATU12345678
+
+Vienna, 31.03.2024
+Invoice Number: RE-42
+Service Period: 01.03.2024 - 31.03.2024
+
+Thank you for your order.
+
+| Pos | Description | Qty | Unit | Price per unit | Sum |
+| ---: | --- | ---: | --- | ---: | ---: |
+| 1 | Development, Backend | 1.50 | h | 100.00 € | 150.00 € |
+| 2 | Hosting | 1.00 | - | 50.00 € | 50.00 € |
+
+Net: 200.00 €
+20 % VAT: 40.00 €
+**Total: 240.00 €**
+Total hours: 1.5 h
+
+IBAN AT00 0000 0000 0000
+";
+        assert_eq!(
+            render_invoice_text(
+                &invoice,
+                &InvoiceOptions {
+                    rounding_step: RoundingStep::None,
+                    prices_include_vat: false,
+                    quantity_decimals: 2,
+                    top_margin_offset_mm: 0.0,
+                    epc_qr_code: false,
+                    vat_column: false,
+                }
+            )
+            .unwrap(),
+            expected
+        );
+    }
+}
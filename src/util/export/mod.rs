@@ -1,11 +1,16 @@
+use crate::config::PageSize;
 use azul_text_layout::{
     text_layout::{split_text_into_words, words_to_scaled_words},
     text_shaping::get_font_metrics_freetype,
 };
-use printpdf::Pt;
+use log::warn;
+use once_cell::sync::Lazy;
+use printpdf::{IndirectFontRef, Mm, PdfLayerReference, Pt};
+use std::borrow::Cow;
 
 pub(crate) mod accounting;
 pub(crate) mod invoice;
+pub(crate) mod qr;
 
 const FONT: &[u8] = include_bytes!("../../Helvetica.ttf");
 const PT_TO_MM: f32 = 0.352_778_f32;
@@ -16,12 +21,90 @@ const PADDING: f32 = 2.0; // Mm
 const LINE_WIDTH: f32 = 0.0; // 1 px everywhere
 const ROW_HEIGHT: f32 = (TABLE_LINE_HEIGHT.0 * PT_TO_MM) + 2.0 * PADDING; // Mm
 const MAX_CHARS_VAT: i32 = 4;
-const MAX_CHARS_CURRENCY: i32 = 12;
+const MISSING_GLYPH_REPLACEMENT: char = '?';
+
+static FONT_FACE: Lazy<Option<ttf_parser::Face<'static>>> =
+    Lazy::new(|| ttf_parser::Face::parse(FONT, 0).ok());
+
+// the dimensions of a page and the area within its margins
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Page {
+    width: Mm,
+    height: Mm,
+    left: Mm,
+    right: Mm,
+    top: Mm,
+    bottom: Mm,
+}
+
+impl Page {
+    fn new(width: f32, height: f32) -> Self {
+        Self {
+            width: Mm(width),
+            height: Mm(height),
+            left: Mm(MARGIN),
+            right: Mm(width - MARGIN),
+            top: Mm(height - MARGIN),
+            bottom: Mm(MARGIN),
+        }
+    }
+
+    fn portrait(page_size: PageSize) -> Self {
+        let (width, height) = page_size.dimensions();
+        Self::new(width, height)
+    }
+
+    fn landscape(page_size: PageSize) -> Self {
+        let (width, height) = page_size.dimensions();
+        Self::new(height, width)
+    }
+}
+
+fn has_glyph(c: char) -> bool {
+    FONT_FACE
+        .as_ref()
+        .is_none_or(|face| face.glyph_index(c).is_some())
+}
+
+// replaces characters the embedded font has no glyph for, so they don't end up as garbage in the PDF
+fn sanitize_text(text: &str) -> Cow<'_, str> {
+    if text.chars().all(has_glyph) {
+        return Cow::Borrowed(text);
+    }
+    let missing: String = text.chars().filter(|&c| !has_glyph(c)).collect();
+    warn!(
+        "The PDF font has no glyph for {missing:?}, replacing with '{MISSING_GLYPH_REPLACEMENT}'"
+    );
+    Cow::Owned(
+        text.chars()
+            .map(|c| {
+                if has_glyph(c) {
+                    c
+                } else {
+                    MISSING_GLYPH_REPLACEMENT
+                }
+            })
+            .collect(),
+    )
+}
+
+fn use_text(
+    layer: &PdfLayerReference,
+    text: impl AsRef<str>,
+    font_size: f32,
+    x: Mm,
+    y: Mm,
+    font: &IndirectFontRef,
+) {
+    layer.use_text(sanitize_text(text.as_ref()), font_size, x, y, font);
+}
 
 fn get_text_width(text: &str) -> f32 {
     if text.is_empty() {
         return 0.0;
     }
+    let text = sanitize_text(text);
+    let text = text.as_ref();
     let space_count = text.chars().filter(|&c| c == ' ').count();
     let font_index: i32 = 0;
     let font_metrics = get_font_metrics_freetype(FONT, font_index);
@@ -34,3 +117,25 @@ fn get_text_width(text: &str) -> f32 {
     let space_width: f32 = space_count as f32 * 2.78;
     total_width + space_width
 }
+
+// x position, at which the given text starts, so it ends at right_edge (minus padding)
+fn right_aligned_x(text: &str, right_edge: f32) -> Mm {
+    Mm(right_edge - PADDING - (get_text_width(text) * PT_TO_MM))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_supported_text() {
+        let text = "Rechnung für Müller – 1.234,56 €";
+        assert!(matches!(sanitize_text(text), Cow::Borrowed(t) if t == text));
+    }
+
+    #[test]
+    fn replaces_missing_glyphs() {
+        assert_eq!(sanitize_text("Tea 茶 🍵"), "Tea ? ?");
+        assert_eq!(get_text_width("Tea 茶 🍵"), get_text_width("Tea ? ?"));
+    }
+}
@@ -0,0 +1,516 @@
+// a small QR code encoder - only byte mode and error correction level M, which is all EPC
+// payment codes need (they are limited to version 13 at level M)
+
+const MAX_VERSION: usize = 13;
+// level M, per version: error correction codewords per block, then the number of blocks and
+// their data codewords for both block groups
+const EC_BLOCKS: [(usize, usize, usize, usize, usize); MAX_VERSION] = [
+    (10, 1, 16, 0, 0),
+    (16, 1, 28, 0, 0),
+    (26, 1, 44, 0, 0),
+    (18, 2, 32, 0, 0),
+    (24, 2, 43, 0, 0),
+    (16, 4, 27, 0, 0),
+    (18, 4, 31, 0, 0),
+    (22, 2, 38, 2, 39),
+    (22, 3, 36, 2, 37),
+    (26, 4, 43, 1, 44),
+    (30, 1, 50, 4, 51),
+    (22, 6, 36, 2, 37),
+    (22, 8, 37, 1, 38),
+];
+const ALIGNMENT_POSITIONS: [&[usize]; MAX_VERSION] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+    &[6, 30, 54],
+    &[6, 32, 58],
+    &[6, 34, 62],
+];
+const MODE_BYTE: u32 = 0b0100;
+// the two bits of level M in the format information
+const EC_LEVEL_M: u32 = 0b00;
+const PAD_BYTES: [u8; 2] = [0xEC, 0x11];
+
+#[derive(Debug)]
+pub(crate) struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    // modules per side, without the quiet zone
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    pub(crate) fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+}
+
+// None, if the data doesn't fit into the supported versions
+pub(crate) fn encode(data: &[u8]) -> Option<QrCode> {
+    let version =
+        (1..=MAX_VERSION).find(|v| data_bits(data.len(), *v) <= data_codewords(*v) * 8)?;
+    let codewords = add_error_correction(&data_codewords_for(data, version), version);
+
+    let mut matrix = Matrix::new(version);
+    matrix.draw_function_patterns();
+    matrix.draw_codewords(&codewords);
+    let mask = (0..8)
+        .min_by_key(|mask| {
+            let mut candidate = matrix.clone();
+            candidate.apply_mask(*mask);
+            candidate.draw_format_bits(*mask);
+            candidate.penalty()
+        })
+        .expect("there are masks");
+    matrix.apply_mask(mask);
+    matrix.draw_format_bits(mask);
+    Some(QrCode {
+        size: matrix.size,
+        modules: matrix.modules,
+    })
+}
+
+fn data_codewords(version: usize) -> usize {
+    let (_, blocks1, data1, blocks2, data2) = EC_BLOCKS[version - 1];
+    blocks1 * data1 + blocks2 * data2
+}
+
+fn count_bits(version: usize) -> usize {
+    if version < 10 { 8 } else { 16 }
+}
+
+fn data_bits(len: usize, version: usize) -> usize {
+    4 + count_bits(version) + len * 8
+}
+
+// mode, length and data, filled up with the terminator and pad bytes
+fn data_codewords_for(data: &[u8], version: usize) -> Vec<u8> {
+    let mut bits = BitBuffer::default();
+    bits.append(MODE_BYTE, 4);
+    bits.append(data.len() as u32, count_bits(version));
+    data.iter().for_each(|b| bits.append(*b as u32, 8));
+
+    let capacity = data_codewords(version) * 8;
+    bits.append(0, (capacity - bits.len()).min(4));
+    bits.append(0, (8 - bits.len() % 8) % 8);
+    let mut codewords = bits.into_bytes();
+    let mut pad = PAD_BYTES.iter().cycle();
+    while codewords.len() < capacity / 8 {
+        codewords.push(*pad.next().expect("cycles"));
+    }
+    codewords
+}
+
+// splits the data into blocks, adds their error correction and interleaves them
+fn add_error_correction(data: &[u8], version: usize) -> Vec<u8> {
+    let (ec_len, blocks1, data1, blocks2, data2) = EC_BLOCKS[version - 1];
+    let divisor = reed_solomon_divisor(ec_len);
+    let mut rest = data;
+    let blocks: Vec<(&[u8], Vec<u8>)> = std::iter::repeat_n(data1, blocks1)
+        .chain(std::iter::repeat_n(data2, blocks2))
+        .map(|len| {
+            let (block, remaining) = rest.split_at(len);
+            rest = remaining;
+            (block, reed_solomon_remainder(block, &divisor))
+        })
+        .collect();
+
+    let mut result = Vec::with_capacity(data.len() + ec_len * (blocks1 + blocks2));
+    for i in 0..data1.max(data2) {
+        blocks
+            .iter()
+            .filter_map(|(block, _)| block.get(i))
+            .for_each(|b| result.push(*b));
+    }
+    for i in 0..ec_len {
+        blocks.iter().for_each(|(_, ec)| result.push(ec[i]));
+    }
+    result
+}
+
+// multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u16 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u16 >> i) & 1) * x as u16;
+    }
+    z as u8
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0; degree];
+    result[degree - 1] = 1;
+    let mut root: u8 = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0; divisor.len()];
+    for b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        result
+            .iter_mut()
+            .zip(divisor)
+            .for_each(|(r, d)| *r ^= gf_multiply(*d, factor));
+    }
+    result
+}
+
+// the 15 bits of error correction level and mask, with their BCH code
+fn format_bits(mask: u32) -> u32 {
+    let data = (EC_LEVEL_M << 3) | mask;
+    let mut rem = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    ((data << 10) | rem) ^ 0x5412
+}
+
+// the 18 bits of the version with their BCH code, only used from version 7
+fn version_bits(version: usize) -> u32 {
+    let mut rem = version as u32;
+    for _ in 0..12 {
+        rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+    }
+    ((version as u32) << 12) | rem
+}
+
+fn bit(value: u32, i: usize) -> bool {
+    (value >> i) & 1 != 0
+}
+
+#[derive(Debug, Default)]
+struct BitBuffer {
+    bits: Vec<bool>,
+}
+
+impl BitBuffer {
+    fn append(&mut self, value: u32, len: usize) {
+        (0..len).rev().for_each(|i| self.bits.push(bit(value, i)));
+    }
+
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.bits
+            .chunks(8)
+            .map(|chunk| chunk.iter().fold(0, |acc, b| (acc << 1) | *b as u8))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Matrix {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+    // finder, timing and alignment patterns, format and version information aren't masked
+    is_function: Vec<bool>,
+}
+
+impl Matrix {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            version,
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.is_function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        for i in 0..self.size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+        self.draw_finder_pattern(3, 3);
+        self.draw_finder_pattern(self.size - 4, 3);
+        self.draw_finder_pattern(3, self.size - 4);
+
+        let positions = ALIGNMENT_POSITIONS[self.version - 1];
+        let last = positions.len().saturating_sub(1);
+        for (i, x) in positions.iter().enumerate() {
+            for (j, y) in positions.iter().enumerate() {
+                // these overlap the finder patterns
+                let corner = (i == 0 || i == last) && (j == 0 || j == last);
+                if corner && !(i == last && j == last) {
+                    continue;
+                }
+                self.draw_alignment_pattern(*x, *y);
+            }
+        }
+
+        // reserved for now, drawn once the mask is known
+        self.draw_format_bits(0);
+        self.draw_version_bits();
+    }
+
+    // including the light separator around it
+    fn draw_finder_pattern(&mut self, x: usize, y: usize) {
+        for dy in -4_isize..=4 {
+            for dx in -4_isize..=4 {
+                let (xx, yy) = (x as isize + dx, y as isize + dy);
+                if xx < 0 || yy < 0 || xx >= self.size as isize || yy >= self.size as isize {
+                    continue;
+                }
+                let distance = dx.abs().max(dy.abs());
+                self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+            }
+        }
+    }
+
+    fn draw_alignment_pattern(&mut self, x: usize, y: usize) {
+        for dy in -2_isize..=2 {
+            for dx in -2_isize..=2 {
+                let distance = dx.abs().max(dy.abs());
+                self.set_function(
+                    (x as isize + dx) as usize,
+                    (y as isize + dy) as usize,
+                    distance != 1,
+                );
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let bits = format_bits(mask);
+        let size = self.size;
+        // around the top left finder pattern
+        for i in 0..=5 {
+            self.set_function(8, i, bit(bits, i));
+        }
+        self.set_function(8, 7, bit(bits, 6));
+        self.set_function(8, 8, bit(bits, 7));
+        self.set_function(7, 8, bit(bits, 8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(bits, i));
+        }
+        // split between the other two finder patterns
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(bits, i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(bits, i));
+        }
+        // always dark
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version_bits(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let bits = version_bits(self.version);
+        for i in 0..18 {
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, bit(bits, i));
+            self.set_function(b, a, bit(bits, i));
+        }
+    }
+
+    // in the zigzag order of two module wide columns, from the bottom right
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let total_bits = codewords.len() * 8;
+        let mut i = 0;
+        let mut right = self.size as isize - 1;
+        while right >= 1 {
+            // skips the vertical timing pattern
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..self.size {
+                let y = if upward {
+                    self.size - 1 - vertical
+                } else {
+                    vertical
+                };
+                for j in 0..2 {
+                    let x = right as usize - j;
+                    if !self.is_function[y * self.size + x] && i < total_bits {
+                        self.modules[y * self.size + x] =
+                            (codewords[i / 8] >> (7 - i % 8)) & 1 != 0;
+                        i += 1;
+                    }
+                    // remaining modules stay light
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.is_function[y * self.size + x] {
+                    self.modules[y * self.size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    // how hard the symbol is to scan, as defined by the standard - lower is better
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let rows: Vec<Vec<bool>> = (0..size)
+            .map(|y| (0..size).map(|x| self.get(x, y)).collect())
+            .collect();
+        let columns: Vec<Vec<bool>> = (0..size)
+            .map(|x| (0..size).map(|y| self.get(x, y)).collect())
+            .collect();
+        let mut penalty = 0;
+
+        // runs of five or more modules of the same color
+        for line in rows.iter().chain(columns.iter()) {
+            let mut run = 1;
+            for i in 1..=size {
+                if i < size && line[i] == line[i - 1] {
+                    run += 1;
+                    continue;
+                }
+                if run >= 5 {
+                    penalty += run - 2;
+                }
+                run = 1;
+            }
+        }
+
+        // 2x2 blocks of the same color
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.get(x, y);
+                if color == self.get(x + 1, y)
+                    && color == self.get(x, y + 1)
+                    && color == self.get(x + 1, y + 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // patterns looking like finder patterns
+        const FINDER_LIKE: [bool; 11] = [
+            true, false, true, true, true, false, true, false, false, false, false,
+        ];
+        for line in rows.iter().chain(columns.iter()) {
+            for window in line.windows(FINDER_LIKE.len()) {
+                if window == FINDER_LIKE || window.iter().rev().eq(FINDER_LIKE.iter()) {
+                    penalty += 40;
+                }
+            }
+        }
+
+        // the balance of dark and light modules
+        let dark = self.modules.iter().filter(|m| **m).count();
+        let percent = dark * 100 / self.modules.len();
+        penalty + percent.abs_diff(50) / 5 * 10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_correction_codewords() {
+        // the data codewords of "HELLO WORLD" as 1-M
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            reed_solomon_remainder(&data, &reed_solomon_divisor(10)),
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+    }
+
+    #[test]
+    fn format_and_version_information() {
+        assert_eq!(format_bits(0), 0b101010000010010);
+        assert_eq!(format_bits(5), 0b100000011001110);
+        assert_eq!(version_bits(7), 0x07C94);
+        assert_eq!(version_bits(13), 0x0D847);
+    }
+
+    #[test]
+    fn data_is_padded_to_the_capacity() {
+        let codewords = data_codewords_for(b"ab", 1);
+        assert_eq!(codewords.len(), 16);
+        // mode, length 2, 'a' and 'b', terminator and then the pad bytes
+        assert_eq!(&codewords[..5], &[0x40, 0x26, 0x16, 0x20, 0xEC]);
+        assert_eq!(codewords[15], 0x11);
+    }
+
+    #[test]
+    fn smallest_version_fitting_the_data() {
+        assert_eq!(encode(&[b'a'; 14]).unwrap().size(), 21);
+        assert_eq!(encode(&[b'a'; 15]).unwrap().size(), 25);
+        assert_eq!(encode(&[b'a'; 331]).unwrap().size(), 69);
+        assert!(encode(&[b'a'; 332]).is_none());
+    }
+
+    #[test]
+    fn both_copies_of_the_format_information_match() {
+        let code = encode(b"https://example.com").unwrap();
+        let size = code.size();
+        let first: Vec<bool> = (0..=5)
+            .map(|i| code.is_dark(8, i))
+            .chain([code.is_dark(8, 7), code.is_dark(8, 8), code.is_dark(7, 8)])
+            .chain((9..15).map(|i| code.is_dark(14 - i, 8)))
+            .collect();
+        let second: Vec<bool> = (0..8)
+            .map(|i| code.is_dark(size - 1 - i, 8))
+            .chain((8..15).map(|i| code.is_dark(8, size - 15 + i)))
+            .collect();
+        assert_eq!(first, second);
+        let bits = first
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, b)| acc | ((*b as u32) << i));
+        assert!((0..8).any(|mask| format_bits(mask) == bits));
+    }
+}
@@ -1,15 +1,31 @@
 use crate::accounting::AccountingState;
+use crate::config::Config;
+use crate::data::{AccountingItem, DocumentKind};
+use crate::db::{DB_FILE, KEY_DATE_FORMAT};
 use crate::invoice::InvoiceState;
 use crate::messages::Messages;
-use crate::GuiError;
-use chrono::Datelike;
+use crate::util::{self, Month, Quarter};
+use crate::{GuiError, GuiEvent};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use log::{error, info};
-use std::fs::{copy, create_dir_all, read_dir, remove_dir_all, remove_file};
+use std::fs::{copy, create_dir_all, read_dir, remove_dir_all, remove_file, rename};
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
 
 pub(crate) const PATH_FOR_FILES: &str = "files";
 pub(crate) const SUFFIX_FOR_FILES: &str = "_files";
+pub(crate) const PATH_FOR_BACKUPS: &str = "backups";
+pub(crate) const DEFAULT_AUTO_BACKUP_COUNT: usize = 5;
+// sorts chronologically by name, e.g. "helferlein_2024-03-15_12-30-00-123.redb"
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S-%3f";
+pub(crate) const DEFAULT_FILE_NAME_TEMPLATE: &str = "{nr}";
+// the suggested names of exported accounting sheets and invoices, without the extension
+pub(crate) const DEFAULT_ACCOUNTING_EXPORT_TEMPLATE: &str = "{period}";
+pub(crate) const DEFAULT_INVOICE_EXPORT_TEMPLATE: &str = "{kind}-{year}_{month}_{day}_{name}";
+const MAX_RECENT_FOLDERS: usize = 5;
+// characters, which aren't allowed in file names on at least one platform
+const ILLEGAL_FILE_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
 
 // returns the path of the copied file at it's new destination
 pub(crate) fn copy_file_and_rename(
@@ -30,7 +46,7 @@ pub(crate) fn copy_file_and_rename(
         })?;
     }
 
-    files_path.push(new_name);
+    files_path.push(sanitize_file_name(new_name));
     if let Some(ext) = file_path.extension() {
         files_path.set_extension(ext);
     }
@@ -38,13 +54,212 @@ pub(crate) fn copy_file_and_rename(
     if file_path != &files_path {
         copy(file_path, &files_path).map_err(|e| {
             error!("Copy, from {file_path:?} to {files_path:?} failed: {e}");
-            GuiError::CopyItemFileFailed(format!("{}, {}", Messages::ItemCopyFailed.msg(), e,))
+            // keep the kind, but tell the user what couldn't be done
+            GuiError::Io(io::Error::new(
+                e.kind(),
+                format!("{}, {}", Messages::ItemCopyFailed.msg(), e),
+            ))
         })?;
     }
 
     Ok(files_path)
 }
 
+// the name with a -2, -3, ... suffix, if a file with it, and the extension of `file_path`, already
+// exists in the folder - e.g. for file name templates without {nr}
+pub(crate) fn unique_file_name(
+    destination_folder: &Path,
+    new_name: &str,
+    file_path: &Path,
+) -> String {
+    let exists = |name: &str| {
+        let mut path = destination_folder.join(sanitize_file_name(name));
+        if let Some(ext) = file_path.extension() {
+            path.set_extension(ext);
+        }
+        path.exists()
+    };
+    if !exists(new_name) {
+        return new_name.to_owned();
+    }
+    (2..)
+        .map(|i| format!("{new_name}-{i}"))
+        .find(|name| !exists(name))
+        .expect("there is a free name")
+}
+
+// the folder item files are copied to, optionally with a subfolder per year of the item's date
+pub(crate) fn files_folder_for(files_root: &Path, date: NaiveDate, per_year: bool) -> PathBuf {
+    if per_year {
+        files_root.join(date.year().to_string())
+    } else {
+        files_root.to_path_buf()
+    }
+}
+
+// where a stored item file belongs after switching between a flat and a per-year files folder -
+// None, if it's there already, or if it's not in the files folder at all
+pub(crate) fn relocated_file_path(
+    files_root: &Path,
+    file: &Path,
+    date: NaiveDate,
+    per_year: bool,
+) -> Option<PathBuf> {
+    let parent = file.parent()?;
+    let in_year_folder = parent.parent() == Some(files_root)
+        && parent
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.parse::<i32>().is_ok());
+    if parent != files_root && !in_year_folder {
+        return None;
+    }
+    let target = files_folder_for(files_root, date, per_year).join(file.file_name()?);
+    (target != file).then_some(target)
+}
+
+// creates the target folder, if necessary
+pub(crate) fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(folder) = to.parent() {
+        create_dir_all(folder)?;
+    }
+    rename(from, to)
+}
+
+// replaces {nr}, {date}, {name}, {company} and {category} with the item's values
+pub(crate) fn apply_file_name_template(template: &str, nr: usize, item: &AccountingItem) -> String {
+    template
+        .replace("{nr}", &nr.to_string())
+        .replace("{date}", &item.date.format(KEY_DATE_FORMAT).to_string())
+        .replace("{name}", item.name.trim())
+        .replace("{company}", item.company.trim())
+        .replace("{category}", item.category.trim())
+}
+
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if ILLEGAL_FILE_NAME_CHARS.contains(&c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let trimmed = sanitized.trim().trim_matches('.');
+    if trimmed.is_empty() {
+        String::from("_")
+    } else {
+        trimmed.to_owned()
+    }
+}
+
+// copies the database into the backups folder of the data folder and removes all but the newest
+// `keep` backups - None, if there's no database yet
+pub(crate) fn auto_backup_db(data_folder: &Path, keep: usize) -> Result<Option<PathBuf>, GuiError> {
+    let db_file = data_folder.join(DB_FILE);
+    if !db_file.is_file() {
+        return Ok(None);
+    }
+    let backups_folder = data_folder.join(PATH_FOR_BACKUPS);
+    create_dir_all(&backups_folder)?;
+    let backup = backups_folder.join(backup_file_name(chrono::Local::now().naive_local()));
+    copy(&db_file, &backup)?;
+    info!("backed up the database to {backup:?}");
+    prune_backups(&backups_folder, keep)?;
+    Ok(Some(backup))
+}
+
+// backs up the database before risky operations, if enabled - false, if the backup failed and the
+// operation should be skipped
+pub(crate) fn auto_backup_or_notify(config: &Config, sender: &Sender<GuiEvent>) -> bool {
+    let Some(ref data_folder) = config.data_folder else {
+        return true;
+    };
+    if !config.auto_backup {
+        return true;
+    }
+    match auto_backup_db(data_folder, config.auto_backup_count) {
+        Ok(_) => true,
+        Err(e) => {
+            error!("error while backing up the database: {e}");
+            util::send_gui_event(
+                sender,
+                GuiEvent::ShowErrorNotification(Messages::AutoBackupFailed.msg().to_owned()),
+            );
+            false
+        }
+    }
+}
+
+fn backup_file_name(timestamp: NaiveDateTime) -> String {
+    let db_file = Path::new(DB_FILE);
+    format!(
+        "{}_{}.{}",
+        db_file
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default(),
+        timestamp.format(BACKUP_TIMESTAMP_FORMAT),
+        db_file
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default(),
+    )
+}
+
+fn is_backup_file_name(name: &str) -> bool {
+    let db_file = Path::new(DB_FILE);
+    let (Some(stem), Some(ext)) = (
+        db_file.file_stem().and_then(|s| s.to_str()),
+        db_file.extension().and_then(|e| e.to_str()),
+    ) else {
+        return false;
+    };
+    name.strip_prefix(stem)
+        .and_then(|rest| rest.strip_prefix('_'))
+        .and_then(|rest| rest.strip_suffix(ext))
+        .and_then(|rest| rest.strip_suffix('.'))
+        .is_some_and(|timestamp| {
+            NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).is_ok()
+        })
+}
+
+// other files in the backups folder, e.g. manual copies, are left alone
+fn prune_backups(backups_folder: &Path, keep: usize) -> io::Result<()> {
+    let mut backups: Vec<PathBuf> = read_dir(backups_folder)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(is_backup_file_name)
+        })
+        .collect();
+    backups.sort();
+    let outdated = backups.len().saturating_sub(keep);
+    for backup in backups.into_iter().take(outdated) {
+        remove_file(&backup)?;
+        info!("removed outdated backup {backup:?}");
+    }
+    Ok(())
+}
+
+// a data folder with a database, which would be overwritten when moving data there
+pub(crate) fn folder_has_existing_db(path: &Path) -> bool {
+    path.join(DB_FILE).is_file()
+}
+
+// moves the folder to the front of the most recently used folders, without duplicates
+pub(crate) fn remember_recent_folder(recent_folders: &mut Vec<PathBuf>, folder: &Path) {
+    recent_folders.retain(|f| f != folder);
+    recent_folders.insert(0, folder.to_path_buf());
+    recent_folders.truncate(MAX_RECENT_FOLDERS);
+}
+
 pub(crate) fn move_folder_recursively(source: &Path, target: &Path) -> Result<(), GuiError> {
     if target.starts_with(source) {
         return Err(GuiError::FileAccessError(String::from(
@@ -64,7 +279,7 @@ pub(crate) fn move_folder_recursively(source: &Path, target: &Path) -> Result<()
         )));
     }
 
-    copy_dir_all(source, target).map_err(|e| GuiError::FileAccessError(e.to_string()))?;
+    copy_dir_all(source, target)?;
 
     if let Err(e) = remove_dir_all(source) {
         log::error!("error while removing source data folder: {e}");
@@ -90,50 +305,382 @@ fn copy_dir_all(source: impl AsRef<Path>, target: impl AsRef<Path>) -> io::Resul
 
 // logs errors
 pub(crate) fn delete_file_and_folder(file: &Path, folder: &Path) {
-    let _ = remove_file(file).map_err(|e| {
+    delete_file(file);
+    let _ = remove_dir_all(folder).map_err(|e| {
         log::error!(
             "{}: {:?}, {}",
-            Messages::FileCouldNotBeDeleted.msg(),
-            file,
+            Messages::FolderCouldNotBeDeleted.msg(),
+            folder,
             e,
         )
     });
-    let _ = remove_dir_all(folder).map_err(|e| {
+}
+
+pub(crate) fn delete_file(file: &Path) {
+    let _ = remove_file(file).map_err(|e| {
         log::error!(
             "{}: {:?}, {}",
-            Messages::FolderCouldNotBeDeleted.msg(),
-            folder,
+            Messages::FileCouldNotBeDeleted.msg(),
+            file,
             e,
         )
     });
 }
 
-// creates a file name suggestion based on the data folder and "year-month/quarter"
-pub(crate) fn build_file_name_suggestion(accounting_state: &AccountingState) -> Option<String> {
-    let mut file_name = String::default();
-    let year = accounting_state.selected_year;
-    file_name.push_str(&year.to_string());
-    if let Some(quarter) = accounting_state.selected_quarter {
-        file_name.push('-');
-        file_name.push_str(quarter.name());
-    } else if let Some(month) = accounting_state.selected_month {
-        file_name.push('-');
-        file_name.push_str(month.name());
-    }
-    file_name.push_str(".pdf");
-    Some(file_name)
-}
-
-pub(crate) fn build_invoice_file_name(invoice_state: &InvoiceState) -> String {
-    let now = chrono::Local::now().date_naive();
-    let mut file_name = format!(
-        "{}-{}_{}_{}_{}",
-        Messages::InvoiceShort.msg(),
-        now.year(),
-        now.month(),
-        now.day(),
-        invoice_state.metadata.name
+// creates a file name suggestion for the selected period from the template
+pub(crate) fn build_file_name_suggestion(
+    accounting_state: &AccountingState,
+    template: &str,
+) -> Option<String> {
+    let file_name = apply_accounting_export_template(
+        template,
+        accounting_state.selected_all_time,
+        accounting_state.selected_year,
+        accounting_state.selected_quarter,
+        accounting_state.selected_month,
     );
-    file_name.push_str(".pdf");
-    file_name
+    Some(format!("{}.pdf", sanitize_file_name(&file_name)))
+}
+
+// replaces {period} (e.g. "2024-Q1", "2024-January" or "All-Time"), {year}, {quarter} and {month},
+// where the latter are empty, if they aren't selected
+pub(crate) fn apply_accounting_export_template(
+    template: &str,
+    all_time: bool,
+    year: i32,
+    quarter: Option<Quarter>,
+    month: Option<Month>,
+) -> String {
+    if all_time {
+        return template
+            .replace("{period}", &Messages::AllTime.msg().replace(' ', "-"))
+            .replace("{year}", "")
+            .replace("{quarter}", "")
+            .replace("{month}", "");
+    }
+    let quarter = quarter.map(|q| q.name()).unwrap_or_default();
+    // a selected quarter takes precedence over the month
+    let month = month
+        .filter(|_| quarter.is_empty())
+        .map(|m| m.name())
+        .unwrap_or_default();
+    let period = [year.to_string().as_str(), quarter, month]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+    template
+        .replace("{period}", &period)
+        .replace("{year}", &year.to_string())
+        .replace("{quarter}", quarter)
+        .replace("{month}", month)
+}
+
+pub(crate) fn build_invoice_file_name(invoice_state: &InvoiceState, template: &str) -> String {
+    let metadata = &invoice_state.metadata;
+    let file_name = apply_invoice_export_template(
+        template,
+        metadata.kind,
+        &metadata.name,
+        &metadata.invoice_number,
+        &metadata.to.name,
+        chrono::Local::now().date_naive(),
+    );
+    format!("{}.pdf", sanitize_file_name(&file_name))
+}
+
+// replaces {kind} (e.g. "RE"), {name} (of the template), {invoice_number}, {to_name} and
+// {year}, {month} and {day} of the export
+pub(crate) fn apply_invoice_export_template(
+    template: &str,
+    kind: DocumentKind,
+    name: &str,
+    invoice_number: &str,
+    to_name: &str,
+    today: NaiveDate,
+) -> String {
+    template
+        .replace("{kind}", kind.short())
+        .replace("{name}", name.trim())
+        .replace("{invoice_number}", invoice_number.trim())
+        .replace("{to_name}", to_name.trim())
+        .replace("{year}", &today.year().to_string())
+        .replace("{month}", &today.month().to_string())
+        .replace("{day}", &today.day().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AccountingItem;
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    #[test]
+    fn files_folder_per_year() {
+        let root = Path::new("/data/files");
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        assert_eq!(files_folder_for(root, date, false), root);
+        assert_eq!(
+            files_folder_for(root, date, true),
+            Path::new("/data/files/2024")
+        );
+    }
+
+    #[test]
+    fn files_are_relocated_within_the_files_folder() {
+        let root = Path::new("/data/files");
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let flat = Path::new("/data/files/abc.pdf");
+        let per_year = Path::new("/data/files/2024/abc.pdf");
+        assert_eq!(
+            relocated_file_path(root, flat, date, true),
+            Some(per_year.to_path_buf())
+        );
+        assert_eq!(
+            relocated_file_path(root, per_year, date, false),
+            Some(flat.to_path_buf())
+        );
+        // in the wrong year's folder, e.g. after changing the item's date
+        assert_eq!(
+            relocated_file_path(root, Path::new("/data/files/2023/abc.pdf"), date, true),
+            Some(per_year.to_path_buf())
+        );
+        // already in place
+        assert_eq!(relocated_file_path(root, flat, date, false), None);
+        assert_eq!(relocated_file_path(root, per_year, date, true), None);
+        // outside of the files folder, or in a folder, which isn't a year
+        assert_eq!(
+            relocated_file_path(root, Path::new("/somewhere/abc.pdf"), date, true),
+            None
+        );
+        assert_eq!(
+            relocated_file_path(root, Path::new("/data/files/misc/abc.pdf"), date, true),
+            None
+        );
+    }
+
+    #[test]
+    fn default_template_is_the_number() {
+        assert_eq!(
+            apply_file_name_template(DEFAULT_FILE_NAME_TEMPLATE, 3, &AccountingItem::test_item()),
+            "3"
+        );
+    }
+
+    #[test]
+    fn template_substitution() {
+        assert_eq!(
+            apply_file_name_template("{nr}_{company}", 12, &AccountingItem::test_item()),
+            "12_Landlord"
+        );
+        assert_eq!(
+            apply_file_name_template(
+                "{date}-{category}-{name}-{unknown}",
+                1,
+                &AccountingItem::test_item()
+            ),
+            "2024-03-15-Office-Rent-{unknown}"
+        );
+    }
+
+    #[test]
+    fn files_with_the_same_name_get_a_suffix() {
+        let folder =
+            std::env::temp_dir().join(format!("helferlein-unique-{}", uuid::Uuid::now_v7()));
+        create_dir_all(&folder).unwrap();
+        let source = Path::new("/somewhere/receipt.pdf");
+        assert_eq!(
+            unique_file_name(&folder, "2024-03-15", source),
+            "2024-03-15"
+        );
+        std::fs::write(folder.join("2024-03-15.pdf"), "pdf").unwrap();
+        assert_eq!(
+            unique_file_name(&folder, "2024-03-15", source),
+            "2024-03-15-2"
+        );
+        std::fs::write(folder.join("2024-03-15-2.pdf"), "pdf").unwrap();
+        assert_eq!(
+            unique_file_name(&folder, "2024-03-15", source),
+            "2024-03-15-3"
+        );
+        // other extensions don't collide
+        assert_eq!(
+            unique_file_name(&folder, "2024-03-15", Path::new("receipt.png")),
+            "2024-03-15"
+        );
+        remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn accounting_export_template() {
+        let default = |quarter, month| {
+            apply_accounting_export_template(
+                DEFAULT_ACCOUNTING_EXPORT_TEMPLATE,
+                false,
+                2024,
+                quarter,
+                month,
+            )
+        };
+        assert_eq!(default(None, None), "2024");
+        assert_eq!(default(Some(Quarter::Q1), None), "2024-Q1");
+        assert_eq!(
+            default(None, Some(Month::March)),
+            format!("2024-{}", Month::March.name())
+        );
+        assert_eq!(
+            apply_accounting_export_template(
+                "{year}-{quarter}-accounting",
+                false,
+                2024,
+                Some(Quarter::Q2),
+                Some(Month::May)
+            ),
+            "2024-Q2-accounting"
+        );
+        assert_eq!(
+            apply_accounting_export_template("{period}_{year}", true, 2024, None, None),
+            format!("{}_", Messages::AllTime.msg().replace(' ', "-"))
+        );
+    }
+
+    #[test]
+    fn invoice_export_template() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        assert_eq!(
+            apply_invoice_export_template(
+                DEFAULT_INVOICE_EXPORT_TEMPLATE,
+                DocumentKind::Invoice,
+                "Monthly",
+                "RE-42",
+                "ACME",
+                today
+            ),
+            format!("{}-2024_3_5_Monthly", DocumentKind::Invoice.short())
+        );
+        assert_eq!(
+            apply_invoice_export_template(
+                "{invoice_number}-{to_name}",
+                DocumentKind::Quote,
+                "Monthly",
+                " RE-42 ",
+                "ACME Inc.",
+                today
+            ),
+            "RE-42-ACME Inc."
+        );
+    }
+
+    #[test]
+    fn export_templates_are_sanitized() {
+        let name = apply_invoice_export_template(
+            "{invoice_number}_{to_name}",
+            DocumentKind::Invoice,
+            "",
+            "2024/03",
+            "A: B <C>",
+            NaiveDate::from_ymd_opt(2024, 3, 5).unwrap(),
+        );
+        assert_eq!(sanitize_file_name(&name), "2024_03_A_ B _C_");
+    }
+
+    #[test]
+    fn sanitize_slashes_and_colons() {
+        assert_eq!(sanitize_file_name("a/b\\c"), "a_b_c");
+        assert_eq!(sanitize_file_name("12:30 *draft?"), "12_30 _draft_");
+        assert_eq!(sanitize_file_name("<a|b>\"c\""), "_a_b__c_");
+        assert_eq!(sanitize_file_name("tab\there"), "tab_here");
+    }
+
+    #[test]
+    fn sanitize_keeps_unicode() {
+        assert_eq!(sanitize_file_name("Müller & Söhne"), "Müller & Söhne");
+        assert_eq!(sanitize_file_name("café_日本_🚀"), "café_日本_🚀");
+    }
+
+    #[test]
+    fn sanitize_empty_and_dots() {
+        assert_eq!(sanitize_file_name(""), "_");
+        assert_eq!(sanitize_file_name("  ..  "), "_");
+        assert_eq!(sanitize_file_name(".hidden."), "hidden");
+    }
+
+    #[test]
+    fn backup_file_names() {
+        let timestamp = NaiveDate::from_ymd_opt(2024, 3, 15)
+            .unwrap()
+            .and_hms_milli_opt(12, 30, 5, 42)
+            .unwrap();
+        let name = backup_file_name(timestamp);
+        assert_eq!(name, "helferlein_2024-03-15_12-30-05-042.redb");
+        assert!(is_backup_file_name(&name));
+        assert!(!is_backup_file_name(DB_FILE));
+        assert!(!is_backup_file_name("helferlein_manual.redb"));
+        assert!(!is_backup_file_name(
+            "helferlein_2024-03-15_12-30-05-042.txt"
+        ));
+    }
+
+    #[test]
+    fn auto_backups_are_rotated() {
+        let folder = std::env::temp_dir().join(format!("helferlein_test_{}", Uuid::now_v7()));
+        create_dir_all(&folder).unwrap();
+        // nothing to back up yet
+        assert!(auto_backup_db(&folder, 2).unwrap().is_none());
+
+        std::fs::write(folder.join(DB_FILE), b"data").unwrap();
+        let backups_folder = folder.join(PATH_FOR_BACKUPS);
+        create_dir_all(&backups_folder).unwrap();
+        let old: Vec<PathBuf> = (1..=3)
+            .map(|day| {
+                let timestamp = NaiveDate::from_ymd_opt(2024, 1, day)
+                    .unwrap()
+                    .and_hms_opt(8, 0, 0)
+                    .unwrap();
+                backups_folder.join(backup_file_name(timestamp))
+            })
+            .collect();
+        old.iter()
+            .for_each(|backup| std::fs::write(backup, b"old").unwrap());
+        std::fs::write(backups_folder.join("manual.redb"), b"keep me").unwrap();
+
+        let backup = auto_backup_db(&folder, 2).unwrap().unwrap();
+        assert_eq!(std::fs::read(&backup).unwrap(), b"data");
+        assert!(!old[0].exists());
+        assert!(!old[1].exists());
+        assert!(old[2].exists());
+        assert!(backups_folder.join("manual.redb").exists());
+        assert_eq!(read_dir(&backups_folder).unwrap().count(), 3);
+
+        remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn detects_existing_db() {
+        let folder = std::env::temp_dir().join(format!("helferlein_test_{}", Uuid::now_v7()));
+        create_dir_all(&folder).unwrap();
+        assert!(!folder_has_existing_db(&folder));
+
+        std::fs::write(folder.join(DB_FILE), b"").unwrap();
+        assert!(folder_has_existing_db(&folder));
+        assert!(!folder_has_existing_db(&folder.join("missing")));
+
+        remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn recent_folders_are_deduped_and_capped() {
+        let mut recent = vec![];
+        for folder in ["a", "b", "c", "b", "d", "e", "f"] {
+            remember_recent_folder(&mut recent, Path::new(folder));
+        }
+        assert_eq!(
+            recent,
+            ["f", "e", "d", "b", "c"]
+                .iter()
+                .map(PathBuf::from)
+                .collect::<Vec<PathBuf>>()
+        );
+    }
 }
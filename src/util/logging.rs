@@ -0,0 +1,140 @@
+use chrono::NaiveDateTime;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+// the number of records kept for the activity log, older ones are dropped
+const MAX_RECORDS: usize = 500;
+
+static ACTIVITY_LOG: Lazy<Mutex<VecDeque<LogRecord>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_RECORDS)));
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LogRecord {
+    timestamp: NaiveDateTime,
+    level: Level,
+    target: String,
+    message: String,
+}
+
+impl LogRecord {
+    // e.g. "2024-03-01 12:00:00 INFO helferlein::db: opened database"
+    pub(crate) fn line(&self) -> String {
+        format!(
+            "{} {} {}: {}",
+            self.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+// logs to the console like env_logger (RUST_LOG), and keeps the recent records for the GUI
+struct ActivityLogger {
+    console: env_logger::Logger,
+}
+
+impl ActivityLogger {
+    // info from the app itself, only problems from its dependencies
+    fn captures(metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+            || (metadata.level() <= Level::Info
+                && metadata.target().starts_with(env!("CARGO_CRATE_NAME")))
+    }
+}
+
+impl Log for ActivityLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console.enabled(metadata) || Self::captures(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.console.matches(record) {
+            self.console.log(record);
+        }
+        if Self::captures(record.metadata()) {
+            let mut records = ACTIVITY_LOG
+                .lock()
+                .expect("failed to get ACTIVITY_LOG lock");
+            push_record(
+                &mut records,
+                LogRecord {
+                    timestamp: chrono::Local::now().naive_local(),
+                    level: record.level(),
+                    target: record.target().to_owned(),
+                    message: record.args().to_string(),
+                },
+            );
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+    }
+}
+
+// replaces env_logger::init
+pub(crate) fn init() {
+    let console = env_logger::Builder::from_default_env().build();
+    let max_level = console.filter().max(LevelFilter::Info);
+    if log::set_logger(Box::leak(Box::new(ActivityLogger { console }))).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+fn push_record(records: &mut VecDeque<LogRecord>, record: LogRecord) {
+    if records.len() >= MAX_RECORDS {
+        records.pop_front();
+    }
+    records.push_back(record);
+}
+
+// oldest first
+pub(crate) fn recent_records() -> Vec<LogRecord> {
+    ACTIVITY_LOG
+        .lock()
+        .expect("failed to get ACTIVITY_LOG lock")
+        .iter()
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn record(message: &str) -> LogRecord {
+        LogRecord {
+            timestamp: NaiveDate::from_ymd_opt(2024, 3, 1)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap(),
+            level: Level::Info,
+            target: String::from("helferlein::db"),
+            message: message.to_owned(),
+        }
+    }
+
+    #[test]
+    fn only_the_recent_records_are_kept() {
+        let mut records = VecDeque::new();
+        (0..MAX_RECORDS + 2).for_each(|i| push_record(&mut records, record(&i.to_string())));
+        assert_eq!(records.len(), MAX_RECORDS);
+        assert_eq!(records.front(), Some(&record("2")));
+        assert_eq!(
+            records.back(),
+            Some(&record(&(MAX_RECORDS + 1).to_string()))
+        );
+    }
+
+    #[test]
+    fn records_are_formatted_as_lines() {
+        assert_eq!(
+            record("opened database").line(),
+            "2024-03-01 12:00:00 INFO helferlein::db: opened database"
+        );
+    }
+}
@@ -1,13 +1,15 @@
 use crate::messages::Messages;
-use crate::{Event, GuiEvent};
-use chrono::{Duration, NaiveDate};
+use crate::{Event, GuiEvent, date_format};
+use chrono::{Datelike, Duration, Months, NaiveDate};
 use eframe::egui::Color32;
 use eframe::egui::Context;
 use log::error;
+use serde::{Deserialize, Serialize};
 use std::sync::mpsc::Sender;
 
 pub(crate) mod export;
 pub(crate) mod files;
+pub(crate) mod logging;
 pub(crate) mod validation;
 
 #[derive(Debug)]
@@ -17,6 +19,8 @@ pub(crate) enum Colors {
     Info,
     ButtonDefault,
     ButtonActive,
+    OutgoingRow,
+    WarningField,
 }
 
 impl Colors {
@@ -27,13 +31,16 @@ impl Colors {
             Colors::Info => Color32::LIGHT_GREEN,
             Colors::ButtonDefault => Color32::LIGHT_GRAY,
             Colors::ButtonActive => Color32::LIGHT_BLUE,
+            // translucent, so text stays readable in light and dark mode
+            Colors::OutgoingRow => Color32::from_rgba_unmultiplied(0, 160, 0, 28),
+            Colors::WarningField => Color32::from_rgba_unmultiplied(255, 200, 0, 60),
         }
     }
 }
 
 pub(crate) const VALID_FILETYPES: &[&str] = &["pdf", "png", "jpg", "jpeg", "gif"];
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub(crate) enum Quarter {
     Q1,
     Q2,
@@ -51,27 +58,28 @@ impl Quarter {
         }
     }
 
-    pub(crate) fn start_and_end_months(&self) -> (u32, u32) {
+    fn index(&self) -> u32 {
         match self {
-            Quarter::Q1 => (1, 3),
-            Quarter::Q2 => (4, 6),
-            Quarter::Q3 => (7, 9),
-            Quarter::Q4 => (10, 12),
+            Quarter::Q1 => 0,
+            Quarter::Q2 => 1,
+            Quarter::Q3 => 2,
+            Quarter::Q4 => 3,
         }
     }
 
-    pub(crate) fn from_month(month: u32) -> Self {
-        match month {
-            1..=3 => Quarter::Q1,
-            4..=6 => Quarter::Q2,
-            7..=9 => Quarter::Q3,
-            10..=12 => Quarter::Q4,
-            _ => Quarter::Q1,
+    // the quarter of the fiscal year the given calendar month belongs to
+    pub(crate) fn from_month(month: u32, fiscal_year_start_month: u32) -> Self {
+        let start = normalize_month(fiscal_year_start_month);
+        match ((normalize_month(month) + 12 - start) % 12) / 3 {
+            0 => Quarter::Q1,
+            1 => Quarter::Q2,
+            2 => Quarter::Q3,
+            _ => Quarter::Q4,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub(crate) enum Month {
     January = 1,
     February,
@@ -195,15 +203,181 @@ pub(crate) fn send_gui_event(sender: &Sender<GuiEvent>, event: GuiEvent) {
     }
 }
 
-pub(crate) fn last_day_of_month(year: i32, month: u32) -> NaiveDate {
-    let (next_year, next_month) = if month == 12 {
-        (year + 1, 1)
+// a date in the configured format, or with a month name, e.g. "5 May 2024" or "5. Mai 2024"
+pub(crate) fn parse_flexible_date(input: &str) -> Option<NaiveDate> {
+    parse_date(input, date_format(), Messages::months())
+}
+
+// a date typed in the previous format, in the new one - other input is kept, e.g. while typing
+pub(crate) fn reformat_date_field(field: &mut String, from: &str, to: &str) {
+    if let Some(reformatted) = reformat_date(field, from, to, Messages::months()) {
+        *field = reformatted;
+    }
+}
+
+fn reformat_date(input: &str, from: &str, to: &str, months: &[&str]) -> Option<String> {
+    parse_date(input, from, months).map(|date| date.format(to).to_string())
+}
+
+fn parse_date(input: &str, format: &str, months: &[&str]) -> Option<NaiveDate> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, format) {
+        return Some(date);
+    }
+    let parts: Vec<&str> = input
+        .split_whitespace()
+        .map(|part| part.trim_end_matches(['.', ',']))
+        .collect();
+    let [day, month, year] = parts.as_slice() else {
+        return None;
+    };
+    let day = day.parse::<u32>().ok()?;
+    let year = year.parse::<i32>().ok()?;
+    NaiveDate::from_ymd_opt(year, parse_month_name(month, months)?, day)
+}
+
+// the full month name, or an unambiguous abbreviation of at least three letters, e.g. "Sep"
+fn parse_month_name(input: &str, months: &[&str]) -> Option<u32> {
+    let input = input.to_lowercase();
+    if input.chars().count() < 3 {
+        return None;
+    }
+    let mut matches = months
+        .iter()
+        .zip(1..)
+        .filter(|(name, _)| name.to_lowercase().starts_with(&input));
+    match (matches.next(), matches.next()) {
+        (Some((_, month)), None) => Some(month),
+        _ => None,
+    }
+}
+
+fn normalize_month(month: u32) -> u32 {
+    Month::from(month).into()
+}
+
+// the fiscal year a date belongs to, named after the calendar year it starts in
+pub(crate) fn fiscal_year_of(date: NaiveDate, fiscal_year_start_month: u32) -> i32 {
+    if date.month() < normalize_month(fiscal_year_start_month) {
+        date.year() - 1
     } else {
-        (year, month + 1)
+        date.year()
+    }
+}
+
+// first and last day of the selected fiscal year, quarter, or month
+pub(crate) fn time_span(
+    fiscal_year: i32,
+    quarter: Option<Quarter>,
+    month: Option<Month>,
+    fiscal_year_start_month: u32,
+) -> (NaiveDate, NaiveDate) {
+    let start = normalize_month(fiscal_year_start_month);
+    let fiscal_year_start =
+        NaiveDate::from_ymd_opt(fiscal_year, start, 1).expect("is a valid date");
+    let (from, months) = match (quarter, month) {
+        (Some(q), _) => (fiscal_year_start + Months::new(q.index() * 3), 3),
+        (None, Some(m)) => {
+            let month: u32 = m.into();
+            (
+                fiscal_year_start + Months::new((month + 12 - start) % 12),
+                1,
+            )
+        }
+        (None, None) => (fiscal_year_start, 12),
     };
+    (from, from + Months::new(months) - Duration::days(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Language;
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
 
-    let first_day_next_month =
-        NaiveDate::from_ymd_opt(next_year, next_month, 1).expect("is a valid date");
+    #[test]
+    fn configured_format_still_works() {
+        let months = Messages::months_for(Language::EN);
+        assert_eq!(
+            parse_date("05.05.2024", "%d.%m.%Y", months),
+            date(2024, 5, 5)
+        );
+        assert_eq!(
+            parse_date(" 2024-05-05 ", "%Y-%m-%d", months),
+            date(2024, 5, 5)
+        );
+    }
+
+    #[test]
+    fn dates_are_reformatted() {
+        let months = Messages::months_for(Language::EN);
+        assert_eq!(
+            reformat_date("31.12.2024", "%d.%m.%Y", "%m/%d/%Y", months).as_deref(),
+            Some("12/31/2024")
+        );
+        assert_eq!(
+            reformat_date("12/31/2024", "%m/%d/%Y", "%Y-%m-%d", months).as_deref(),
+            Some("2024-12-31")
+        );
+        assert_eq!(
+            reformat_date("31.12.", "%d.%m.%Y", "%Y-%m-%d", months),
+            None
+        );
+    }
+
+    #[test]
+    fn english_month_names() {
+        let months = Messages::months_for(Language::EN);
+        assert_eq!(
+            parse_date("5 May 2024", "%d.%m.%Y", months),
+            date(2024, 5, 5)
+        );
+        assert_eq!(
+            parse_date("31 december 2023", "%d.%m.%Y", months),
+            date(2023, 12, 31)
+        );
+        assert_eq!(
+            parse_date("1 Sep, 2024", "%d.%m.%Y", months),
+            date(2024, 9, 1)
+        );
+    }
+
+    #[test]
+    fn german_month_names() {
+        let months = Messages::months_for(Language::DE);
+        assert_eq!(
+            parse_date("5 Mai 2024", "%d.%m.%Y", months),
+            date(2024, 5, 5)
+        );
+        assert_eq!(
+            parse_date("5. Mai 2024", "%d.%m.%Y", months),
+            date(2024, 5, 5)
+        );
+        assert_eq!(
+            parse_date("1. März 2024", "%d.%m.%Y", months),
+            date(2024, 3, 1)
+        );
+        assert_eq!(
+            parse_date("15 Jänner 2025", "%d.%m.%Y", months),
+            date(2025, 1, 15)
+        );
+        // the English name isn't known in German
+        assert_eq!(parse_date("5 May 2024", "%d.%m.%Y", months), None);
+    }
 
-    first_day_next_month - Duration::days(1)
+    #[test]
+    fn ambiguous_and_invalid_dates() {
+        let months = Messages::months_for(Language::EN);
+        // June or July
+        assert_eq!(parse_date("5 Ju 2024", "%d.%m.%Y", months), None);
+        assert_eq!(parse_date("5 M 2024", "%d.%m.%Y", months), None);
+        assert_eq!(parse_date("31 February 2024", "%d.%m.%Y", months), None);
+        assert_eq!(parse_date("5 Foo 2024", "%d.%m.%Y", months), None);
+        assert_eq!(parse_date("May 2024", "%d.%m.%Y", months), None);
+        assert_eq!(parse_date("5 May 2024 12", "%d.%m.%Y", months), None);
+        assert_eq!(parse_date("", "%d.%m.%Y", months), None);
+    }
 }
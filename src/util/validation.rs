@@ -1,5 +1,5 @@
-use crate::util::{Month, Quarter, last_day_of_month};
-use chrono::{Datelike, NaiveDate};
+use crate::util::{Month, Quarter, time_span};
+use chrono::NaiveDate;
 use std::collections::HashMap;
 
 #[derive(Debug, Eq, Hash, PartialEq)]
@@ -31,6 +31,10 @@ pub(crate) enum Field {
     File,
     Amount,
     PricePerUnit,
+    Items,
+    Attachments,
+    Iban,
+    Splits,
 }
 
 #[derive(Debug)]
@@ -83,6 +87,21 @@ impl ValidationResult {
         };
     }
 
+    // all errors, then all warnings, in a stable order
+    pub(crate) fn messages(&self) -> Vec<&str> {
+        let mut errors: Vec<&str> = self.errors.values().flatten().map(|m| m.as_str()).collect();
+        let mut warnings: Vec<&str> = self
+            .warnings
+            .values()
+            .flatten()
+            .map(|m| m.as_str())
+            .collect();
+        errors.sort();
+        warnings.sort();
+        errors.extend(warnings);
+        errors
+    }
+
     pub(crate) fn add_error(&mut self, field: Field, msg: String) {
         match self.errors.get_mut(&field) {
             None => {
@@ -100,27 +119,215 @@ pub(crate) fn is_date_in_selected_time_span(
     year: i32,
     selected_quarter: Option<Quarter>,
     selected_month: Option<Month>,
+    fiscal_year_start_month: u32,
 ) -> bool {
-    if let Some(quarter) = selected_quarter {
-        let (start, end) = quarter.start_and_end_months();
-        let start_of_quarter = NaiveDate::from_ymd_opt(year, start, 1).expect("is a valid date");
-        let end_of_quarter = last_day_of_month(year, end);
-        return selected_date.ge(&start_of_quarter) && selected_date.le(&end_of_quarter);
+    let (start, end) = time_span(
+        year,
+        selected_quarter,
+        selected_month,
+        fiscal_year_start_month,
+    );
+    selected_date.ge(&start) && selected_date.le(&end)
+}
+
+// soft format check for VAT IDs of known countries - unknown countries and empty IDs pass
+pub(crate) fn validate_vat_id(country: &str, vat: &str) -> bool {
+    let vat: String = vat
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    if vat.is_empty() {
+        return true;
+    }
+    let (prefix, digits) = match country.trim().to_lowercase().as_str() {
+        "at" | "austria" | "österreich" => ("ATU", 8),
+        "de" | "germany" | "deutschland" => ("DE", 9),
+        "it" | "italy" | "italien" => ("IT", 11),
+        _ => return true,
+    };
+    match vat.strip_prefix(prefix) {
+        Some(rest) => rest.len() == digits && rest.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+// longer positions don't fit the position column of the PDF
+pub(crate) const MAX_POSITION_LENGTH: usize = 5;
+
+// an invoice position like "1", or "1.2" for a sub-item
+pub(crate) fn is_valid_position(nr: &str) -> bool {
+    let is_number = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if nr.trim().chars().count() > MAX_POSITION_LENGTH {
+        return false;
     }
+    match nr.trim().split_once('.') {
+        None => is_number(nr.trim()),
+        Some((whole, sub)) => is_number(whole) && is_number(sub),
+    }
+}
 
-    if let Some(month) = selected_month {
-        let start_of_month =
-            NaiveDate::from_ymd_opt(year, month.into(), 1).expect("is a valid date");
-        let end_of_month = last_day_of_month(year, month.into());
-        return selected_date.ge(&start_of_month) && selected_date.le(&end_of_month);
+// without whitespace, in upper case, e.g. "AT611904300234573201"
+pub(crate) fn normalize_iban(iban: &str) -> String {
+    iban.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+// country code, check digits and the mod 97 checksum, regardless of spaces and case
+pub(crate) fn validate_iban(iban: &str) -> bool {
+    let chars: Vec<char> = normalize_iban(iban).chars().collect();
+    if !(15..=34).contains(&chars.len())
+        || !chars[..2].iter().all(|c| c.is_ascii_uppercase())
+        || !chars[2..4].iter().all(|c| c.is_ascii_digit())
+        || !chars.iter().all(|c| c.is_ascii_alphanumeric())
+    {
+        return false;
     }
-    selected_date.year() == year
+    chars[4..]
+        .iter()
+        .chain(chars[..4].iter())
+        .filter_map(|c| c.to_digit(36))
+        .fold(0, |rem, digit| {
+            if digit < 10 {
+                (rem * 10 + digit) % 97
+            } else {
+                (rem * 100 + digit) % 97
+            }
+        })
+        == 1
+}
+
+// in groups of four, e.g. "AT61 1904 3002 3457 3201"
+pub(crate) fn format_iban(iban: &str) -> String {
+    normalize_iban(iban)
+        .chars()
+        .collect::<Vec<char>>()
+        .chunks(4)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// the value of a line like "IBAN: ..." or "iban ..."
+pub(crate) fn strip_label<'a>(value: &'a str, label: &str) -> Option<&'a str> {
+    let prefix = value.get(..label.len())?;
+    if !prefix.eq_ignore_ascii_case(label) {
+        return None;
+    }
+    let rest = &value[label.len()..];
+    if !rest.starts_with([':', ' ']) {
+        return None;
+    }
+    Some(rest.trim_start_matches([':', ' ']))
+}
+
+// the IBANs of the lines labelled "IBAN" in free text, e.g. the bank data
+pub(crate) fn labelled_ibans(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .filter_map(|line| strip_label(line.trim(), "IBAN"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn positions() {
+        assert!(is_valid_position("1"));
+        assert!(is_valid_position(" 12 "));
+        assert!(is_valid_position("1.2"));
+        assert!(is_valid_position("10.11"));
+        assert!(is_valid_position("99999"));
+        assert!(!is_valid_position("999999"));
+        assert!(!is_valid_position("99999999999999999999"));
+        assert!(!is_valid_position("100.11"));
+        assert!(!is_valid_position("a"));
+        assert!(!is_valid_position(""));
+        assert!(!is_valid_position("-1"));
+        assert!(!is_valid_position("1."));
+        assert!(!is_valid_position(".2"));
+        assert!(!is_valid_position("1.2.3"));
+        assert!(!is_valid_position("1,2"));
+    }
+
+    #[test]
+    fn valid_ibans() {
+        assert!(validate_iban("AT611904300234573201"));
+        assert!(validate_iban("at61 1904 3002 3457 3201"));
+        assert!(validate_iban("DE89 3704 0044 0532 0130 00"));
+        assert!(validate_iban("GB82WEST12345698765432"));
+        assert!(validate_iban("NO9386011117947"));
+    }
+
+    #[test]
+    fn invalid_ibans() {
+        // wrong checksum
+        assert!(!validate_iban("AT621904300234573201"));
+        assert!(!validate_iban("DE89370400440532013001"));
+        // too short, too long, wrong characters or no country code
+        assert!(!validate_iban("AT61"));
+        assert!(!validate_iban(""));
+        assert!(!validate_iban(&format!("DE89{}", "1".repeat(31))));
+        assert!(!validate_iban("AT61-1904-3002-3457-3201"));
+        assert!(!validate_iban("1261190430023457320100"));
+    }
+
+    #[test]
+    fn ibans_are_formatted_in_groups_of_four() {
+        assert_eq!(
+            format_iban("at611904300234573201"),
+            "AT61 1904 3002 3457 3201"
+        );
+        assert_eq!(
+            format_iban(" DE89 3704004405320130 00"),
+            "DE89 3704 0044 0532 0130 00"
+        );
+        assert_eq!(format_iban(""), "");
+    }
+
+    #[test]
+    fn ibans_of_labelled_lines() {
+        let text = "My Bank\nIBAN: AT61 1904 3002 3457 3201\n iban de89370400440532013000\nBIC: X";
+        assert_eq!(
+            labelled_ibans(text).collect::<Vec<&str>>(),
+            vec!["AT61 1904 3002 3457 3201", "de89370400440532013000"]
+        );
+    }
+
+    #[test]
+    fn vat_id_at() {
+        assert!(validate_vat_id("AT", "ATU12345678"));
+        assert!(validate_vat_id("Österreich", "ATU 1234 5678"));
+        assert!(validate_vat_id("austria", "atu12345678"));
+        assert!(!validate_vat_id("AT", "AT12345678"));
+        assert!(!validate_vat_id("AT", "ATU1234567"));
+        assert!(!validate_vat_id("AT", "DE123456789"));
+    }
+
+    #[test]
+    fn vat_id_de() {
+        assert!(validate_vat_id("DE", "DE123456789"));
+        assert!(validate_vat_id("Deutschland", "DE 123 456 789"));
+        assert!(!validate_vat_id("Germany", "DE12345678"));
+        assert!(!validate_vat_id("DE", "DE12345678A"));
+        assert!(!validate_vat_id("DE", "ATU12345678"));
+    }
+
+    #[test]
+    fn vat_id_it() {
+        assert!(validate_vat_id("IT", "IT12345678901"));
+        assert!(!validate_vat_id("Italy", "IT1234567890"));
+    }
+
+    #[test]
+    fn vat_id_unknown_country_or_empty() {
+        assert!(validate_vat_id("Narnia", "whatever"));
+        assert!(validate_vat_id("", "ATU1"));
+        assert!(validate_vat_id("AT", ""));
+    }
+
     #[test]
     fn no_year() {
         assert!(!is_date_in_selected_time_span(
@@ -128,24 +335,28 @@ mod tests {
             2022,
             None,
             None,
+            1,
         ));
         assert!(!is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
             2022,
             Some(Quarter::Q2),
             None,
+            1,
         ));
         assert!(!is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
             2022,
             None,
             Some(Month::March),
+            1,
         ));
         assert!(!is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
             2022,
             Some(Quarter::Q2),
             Some(Month::March),
+            1,
         ));
     }
     #[test]
@@ -155,12 +366,14 @@ mod tests {
             2015,
             None,
             None,
+            1,
         ));
         assert!(!is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
             2016,
             None,
             None,
+            1,
         ));
     }
 
@@ -171,36 +384,42 @@ mod tests {
             2015,
             Some(Quarter::Q1),
             None,
+            1,
         ));
         assert!(is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
             2015,
             Some(Quarter::Q1),
             None,
+            1,
         ));
         assert!(is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 5, 1).unwrap(),
             2015,
             Some(Quarter::Q2),
             None,
+            1,
         ));
         assert!(is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 9, 1).unwrap(),
             2015,
             Some(Quarter::Q3),
             None,
+            1,
         ));
         assert!(is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 11, 1).unwrap(),
             2015,
             Some(Quarter::Q4),
             None,
+            1,
         ));
         assert!(!is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
             2015,
             Some(Quarter::Q2),
             None,
+            1,
         ));
     }
 
@@ -211,24 +430,28 @@ mod tests {
             2015,
             None,
             Some(Month::March),
+            1,
         ));
         assert!(is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 12, 31).unwrap(),
             2015,
             None,
             Some(Month::December),
+            1,
         ));
         assert!(!is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
             2015,
             None,
             Some(Month::May),
+            1,
         ));
         assert!(is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
             2024,
             None,
             Some(Month::February),
+            1,
         ));
     }
 
@@ -239,12 +462,118 @@ mod tests {
             2015,
             Some(Quarter::Q1),
             Some(Month::May),
+            1,
         ));
         assert!(!is_date_in_selected_time_span(
             NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
             2015,
             Some(Quarter::Q2),
             Some(Month::May),
+            1,
+        ));
+    }
+
+    #[test]
+    fn fiscal_year_starting_in_april() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        // fiscal year 2024 runs from 2024-04-01 to 2025-03-31
+        assert!(is_date_in_selected_time_span(
+            date(2024, 4, 1),
+            2024,
+            None,
+            None,
+            4
+        ));
+        assert!(is_date_in_selected_time_span(
+            date(2024, 12, 31),
+            2024,
+            None,
+            None,
+            4
+        ));
+        assert!(is_date_in_selected_time_span(
+            date(2025, 1, 1),
+            2024,
+            None,
+            None,
+            4
+        ));
+        assert!(is_date_in_selected_time_span(
+            date(2025, 3, 31),
+            2024,
+            None,
+            None,
+            4
+        ));
+        assert!(!is_date_in_selected_time_span(
+            date(2024, 3, 31),
+            2024,
+            None,
+            None,
+            4
+        ));
+        assert!(!is_date_in_selected_time_span(
+            date(2025, 4, 1),
+            2024,
+            None,
+            None,
+            4
+        ));
+    }
+
+    #[test]
+    fn fiscal_quarters_starting_in_april() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let in_quarter = |d, q| is_date_in_selected_time_span(d, 2024, Some(q), None, 4);
+        assert!(in_quarter(date(2024, 4, 1), Quarter::Q1));
+        assert!(in_quarter(date(2024, 6, 30), Quarter::Q1));
+        assert!(!in_quarter(date(2024, 7, 1), Quarter::Q1));
+        assert!(!in_quarter(date(2024, 3, 31), Quarter::Q1));
+        assert!(in_quarter(date(2024, 7, 1), Quarter::Q2));
+        assert!(in_quarter(date(2024, 12, 31), Quarter::Q3));
+        assert!(!in_quarter(date(2025, 1, 1), Quarter::Q3));
+        assert!(in_quarter(date(2025, 1, 1), Quarter::Q4));
+        assert!(in_quarter(date(2025, 3, 31), Quarter::Q4));
+        assert!(!in_quarter(date(2024, 1, 1), Quarter::Q4));
+
+        assert_eq!(Quarter::from_month(4, 4), Quarter::Q1);
+        assert_eq!(Quarter::from_month(9, 4), Quarter::Q2);
+        assert_eq!(Quarter::from_month(12, 4), Quarter::Q3);
+        assert_eq!(Quarter::from_month(3, 4), Quarter::Q4);
+        assert_eq!(Quarter::from_month(3, 1), Quarter::Q1);
+    }
+
+    #[test]
+    fn fiscal_months_after_the_calendar_year_end() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        // January of fiscal year 2024 is in calendar year 2025
+        assert!(is_date_in_selected_time_span(
+            date(2025, 1, 15),
+            2024,
+            None,
+            Some(Month::January),
+            4,
+        ));
+        assert!(!is_date_in_selected_time_span(
+            date(2024, 1, 15),
+            2024,
+            None,
+            Some(Month::January),
+            4,
+        ));
+        assert!(is_date_in_selected_time_span(
+            date(2025, 2, 28),
+            2024,
+            None,
+            Some(Month::February),
+            4,
+        ));
+        assert!(is_date_in_selected_time_span(
+            date(2024, 4, 30),
+            2024,
+            None,
+            Some(Month::April),
+            4,
         ));
     }
 }